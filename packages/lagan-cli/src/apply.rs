@@ -0,0 +1,159 @@
+//! `lagan apply`, which sets the topics described by a configuration file.
+
+use std::{fs, time::Duration};
+
+use lagan::{channel::EventMask, nt_types::PubSubOptions, Instance, Value};
+
+use crate::{
+    args::Args,
+    config,
+    connection::{self, DEFAULT_TIMEOUT},
+    json::Json,
+    output::{json_value, plain_value, Output},
+};
+
+/// How long the current values get to arrive before they are compared against the configuration.
+const SETTLE_TIME: Duration = Duration::from_millis(500);
+
+pub const USAGE: &str = "\
+lagan apply <FILE> [--dry-run] [--address <ADDRESS>] [--timeout <DURATION>]
+    Sets the topics described by the JSON configuration FILE and prints every change,
+    or with --dry-run, only prints what would change. Topics that are neither persistent
+    nor retained disappear when lagan exits, unless something else publishes them.
+";
+
+pub fn run(args: impl IntoIterator<Item = String>) -> Result<(), String> {
+    let mut args = Args::parse(args, &["dry-run"])?;
+    let path = args
+        .positional()
+        .ok_or("apply needs a configuration file")?;
+    let dry_run = args.switch("dry-run");
+    let address = args.address()?;
+    let timeout = args.timeout(DEFAULT_TIMEOUT)?;
+    let output = args.output()?;
+    args.finish()?;
+
+    let text = fs::read_to_string(&path).map_err(|err| format!("Couldn't read {path}: {err}"))?;
+    let settings = config::parse(&text).map_err(|err| format!("{path}: {err}"))?;
+
+    let client = connection::connect(address, timeout)?;
+    // Clients only receive the current values of topics something subscribed to.
+    let names: Vec<&str> = settings
+        .iter()
+        .map(|setting| setting.name.as_str())
+        .collect();
+    let _values = client.event_channel(EventMask::VALUE, &names);
+    connection::wait_until(SETTLE_TIME, || {
+        names.iter().all(|name| client.entry(name).is_assigned())
+    });
+
+    let topics: Vec<_> = names.iter().map(|name| client.topic(name)).collect();
+    let mut publishers = Vec::new();
+    let mut changes = Vec::new();
+    let mut changed = 0;
+    let mut failed = 0;
+    for (setting, topic) in settings.iter().zip(&topics) {
+        let name = &setting.name;
+        let current = client.entry(name).value();
+        let value_changed = current != setting.value;
+        if value_changed {
+            match (output, current) {
+                (Output::Plain, Value::Unassigned) => println!(
+                    "+ {name} = {} ({})",
+                    plain_value(&setting.value),
+                    setting.type_string
+                ),
+                (Output::Plain, current) => println!(
+                    "~ {name}: {} -> {}",
+                    plain_value(&current),
+                    plain_value(&setting.value)
+                ),
+                (Output::Json, current) => changes.push(Json::object([
+                    ("topic", name.as_str().into()),
+                    ("from", json_value(&current)),
+                    ("to", json_value(&setting.value)),
+                    ("type", setting.type_string.as_str().into()),
+                ])),
+            }
+        }
+        let flags: Vec<(&str, bool)> = [
+            ("persistent", setting.persistent, topic.is_persistent()),
+            ("retained", setting.retained, topic.is_retained()),
+            ("cached", setting.cached, topic.is_cached()),
+        ]
+        .into_iter()
+        .filter_map(|(flag, wanted, current)| {
+            Some((flag, wanted.filter(|wanted| *wanted != current)?))
+        })
+        .collect();
+        for &(flag, wanted) in &flags {
+            match output {
+                Output::Plain => println!("~ {name}: {flag}={wanted}"),
+                Output::Json => changes.push(Json::object([
+                    ("topic", name.as_str().into()),
+                    ("flag", flag.into()),
+                    ("to", wanted.into()),
+                ])),
+            }
+        }
+        if value_changed || !flags.is_empty() {
+            changed += 1;
+        }
+        if dry_run {
+            continue;
+        }
+
+        if value_changed {
+            let published = topic
+                .publish(
+                    setting.value.value_type(),
+                    &setting.type_string,
+                    PubSubOptions::default(),
+                )
+                .and_then(|publisher| {
+                    publisher.set_value(setting.value.clone())?;
+                    Ok(publisher)
+                });
+            match published {
+                Ok(publisher) => publishers.push(publisher),
+                Err(err) => {
+                    eprintln!("{name}: {err}");
+                    failed += 1;
+                }
+            }
+        }
+        for (flag, wanted) in flags {
+            let result = match flag {
+                "persistent" => {
+                    topic.set_persistent(wanted);
+                    Ok(())
+                }
+                "retained" => topic.set_retained(wanted),
+                _ => topic.set_cached(wanted),
+            };
+            if let Err(err) = result {
+                eprintln!("{name}: {err}");
+                failed += 1;
+            }
+        }
+    }
+
+    match output {
+        Output::Plain if changed == 0 => println!("Nothing to change"),
+        Output::Plain => {}
+        Output::Json => println!(
+            "{}",
+            Json::object([
+                ("dry_run", dry_run.into()),
+                ("changes", Json::Array(changes))
+            ])
+        ),
+    }
+    if changed > 0 && !dry_run {
+        connection::flush(&client);
+    }
+    if failed > 0 {
+        return Err(format!("{failed} changes couldn't be made"));
+    }
+    Ok(())
+}
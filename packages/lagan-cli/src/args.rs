@@ -0,0 +1,137 @@
+//! A small parser for the arguments of subcommands.
+
+#[cfg(test)]
+use std::{cell::RefCell, collections::BTreeSet};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    net::SocketAddr,
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::output::Output;
+
+/// The address clients connect to when `--address` isn't given.
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:5810";
+
+#[cfg(test)]
+thread_local! {
+    /// The names of every option and switch subcommands asked for on this thread,
+    /// which the completion scripts are checked against.
+    pub static TAKEN: RefCell<BTreeSet<String>> = RefCell::default();
+}
+
+/// The arguments of a subcommand: positional arguments, `--name value` or `--name=value` options
+/// and `--name` switches.
+///
+/// Subcommands take the arguments they understand, then call [`Args::finish`] to reject the rest.
+#[derive(Debug, Default)]
+pub struct Args {
+    positional: VecDeque<String>,
+    options: HashMap<String, String>,
+    switches: HashSet<String>,
+}
+
+impl Args {
+    /// Sorts `args` into positional arguments, options and switches.
+    ///
+    /// `switches` are the names of the options that don't take a value.
+    pub fn parse(
+        args: impl IntoIterator<Item = String>,
+        switches: &[&str],
+    ) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                parsed.positional.push_back(arg);
+                continue;
+            };
+            if let Some((name, value)) = name.split_once('=') {
+                parsed.options.insert(name.to_owned(), value.to_owned());
+                continue;
+            }
+            if switches.contains(&name) {
+                parsed.switches.insert(name.to_owned());
+                continue;
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("--{name} needs a value"))?;
+            parsed.options.insert(name.to_owned(), value);
+        }
+        Ok(parsed)
+    }
+
+    /// Takes the next positional argument.
+    pub fn positional(&mut self) -> Option<String> {
+        self.positional.pop_front()
+    }
+
+    pub fn option(&mut self, name: &str) -> Option<String> {
+        #[cfg(test)]
+        TAKEN.with(|taken| taken.borrow_mut().insert(name.to_owned()));
+        self.options.remove(name)
+    }
+
+    /// Takes an option and parses its value.
+    pub fn parse_option<T: FromStr>(&mut self, name: &str) -> Result<Option<T>, String> {
+        self.option(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("{value:?} is not a valid value for --{name}"))
+            })
+            .transpose()
+    }
+
+    /// Whether the switch was given.
+    pub fn switch(&mut self, name: &str) -> bool {
+        #[cfg(test)]
+        TAKEN.with(|taken| taken.borrow_mut().insert(name.to_owned()));
+        self.switches.remove(name)
+    }
+
+    /// Takes the `--address` option, defaulting to [`DEFAULT_ADDRESS`].
+    pub fn address(&mut self) -> Result<SocketAddr, String> {
+        Ok(self
+            .parse_option("address")?
+            .unwrap_or_else(|| DEFAULT_ADDRESS.parse().unwrap()))
+    }
+
+    /// Takes the `--timeout` option, defaulting to `default`.
+    pub fn timeout(&mut self, default: Duration) -> Result<Duration, String> {
+        match self.option("timeout") {
+            Some(text) => parse_duration(&text)
+                .ok_or_else(|| format!("{text:?} is not a duration, e.g. 5s or 500ms")),
+            None => Ok(default),
+        }
+    }
+
+    /// Takes the `--output` option, defaulting to plain text.
+    pub fn output(&mut self) -> Result<Output, String> {
+        Ok(self.parse_option("output")?.unwrap_or_default())
+    }
+
+    /// Fails if any argument wasn't taken by the subcommand.
+    pub fn finish(self) -> Result<(), String> {
+        if let Some(arg) = self.positional.front() {
+            return Err(format!("Unexpected argument {arg:?}"));
+        }
+        if let Some(name) = self.options.keys().chain(&self.switches).next() {
+            return Err(format!("Unknown option --{name}"));
+        }
+        Ok(())
+    }
+}
+
+/// Parses a duration like `5s`, `500ms` or `2.5`, which is in seconds.
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let (number, scale) = if let Some(millis) = text.strip_suffix("ms") {
+        (millis, 1e-3)
+    } else {
+        (text.strip_suffix('s').unwrap_or(text), 1.0)
+    };
+    let seconds: f64 = number.trim().parse().ok()?;
+    Duration::try_from_secs_f64(seconds * scale).ok()
+}
@@ -0,0 +1,87 @@
+//! `lagan assert`, which checks the value of a topic, for scripted checklists and smoke tests.
+
+use std::time::Instant;
+
+use lagan::{channel::EventMask, Instance, Value};
+
+use crate::{
+    args::Args,
+    connection::{self, DEFAULT_TIMEOUT},
+    json::Json,
+    output::{json_value, plain_value, Output},
+};
+
+pub const USAGE: &str = "\
+lagan assert <TOPIC> [--equals <JSON>] [--address <ADDRESS>] [--timeout <DURATION>]
+    Waits until TOPIC has the value JSON, or any value if --equals is left out, and fails
+    if it doesn't within DURATION. Text that isn't JSON is compared as a string,
+    so --equals Auto works as well as --equals '\"Auto\"'.
+";
+
+pub fn run(args: impl IntoIterator<Item = String>) -> Result<(), String> {
+    let mut args = Args::parse(args, &[])?;
+    let topic_name = args.positional().ok_or("assert needs a topic")?;
+    let expected = args
+        .option("equals")
+        .map(|text| Json::parse(&text).unwrap_or(Json::String(text)));
+    let address = args.address()?;
+    let timeout = args.timeout(DEFAULT_TIMEOUT)?;
+    let output = args.output()?;
+    args.finish()?;
+
+    // The timeout covers connecting as well as waiting for the value.
+    let deadline = Instant::now() + timeout;
+    let client = connection::connect(address, timeout)?;
+    // Clients only receive the values of topics something subscribed to.
+    let _values = client.event_channel(EventMask::VALUE, [&topic_name]);
+    let entry = client.entry(&topic_name);
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    let passed = connection::wait_until(remaining, || {
+        let value = entry.value();
+        match &expected {
+            Some(expected) => matches(&json_value(&value), expected),
+            None => value != Value::Unassigned,
+        }
+    });
+
+    let value = entry.value();
+    match output {
+        Output::Plain if passed => println!("{topic_name} = {}", plain_value(&value)),
+        Output::Plain => {}
+        Output::Json => println!(
+            "{}",
+            Json::object([
+                ("topic", topic_name.as_str().into()),
+                ("value", json_value(&value)),
+                ("passed", passed.into()),
+            ])
+        ),
+    }
+    match expected {
+        _ if passed => Ok(()),
+        Some(expected) if value != Value::Unassigned => Err(format!(
+            "{topic_name} is {}, not {expected}",
+            plain_value(&value)
+        )),
+        _ => Err(format!(
+            "{topic_name} didn't get a value within {timeout:?}"
+        )),
+    }
+}
+
+/// Compares JSON by value, so numbers are equal however they are written, e.g. `1` and `1.0`.
+fn matches(actual: &Json, expected: &Json) -> bool {
+    match (actual, expected) {
+        (Json::Number(actual), Json::Number(expected)) => {
+            actual.parse::<f64>().ok() == expected.parse::<f64>().ok()
+        }
+        (Json::Array(actual), Json::Array(expected)) => {
+            actual.len() == expected.len()
+                && actual
+                    .iter()
+                    .zip(expected)
+                    .all(|(actual, expected)| matches(actual, expected))
+        }
+        (actual, expected) => actual == expected,
+    }
+}
@@ -0,0 +1,291 @@
+//! `lagan completions`, which prints shell completion scripts.
+
+use std::fmt::Write;
+
+use crate::args::Args;
+
+pub const USAGE: &str = "\
+lagan completions <SHELL>
+    Prints the completion script for SHELL, which is bash, zsh or fish,
+    e.g. `source <(lagan completions bash)` in .bashrc.
+";
+
+/// A subcommand, as far as completions are concerned.
+struct Command {
+    name: &'static str,
+    about: &'static str,
+    /// Words taken as the first positional argument.
+    actions: &'static [&'static str],
+    /// Options that take a value.
+    options: &'static [&'static str],
+    /// Options that don't take a value.
+    switches: &'static [&'static str],
+}
+
+/// Options every subcommand that talks to a server takes.
+const CONNECTION_OPTIONS: &[&str] = &["address", "timeout", "output"];
+
+const COMMANDS: &[Command] = &[
+    Command {
+        name: "record",
+        about: "Log the topics of a server to a data log",
+        actions: &[],
+        options: &["out", "prefix", "address", "duration", "output"],
+        switches: &[],
+    },
+    Command {
+        name: "replay",
+        about: "Publish the topics of a data log with their original timing",
+        actions: &[],
+        options: &["speed", "address", "output"],
+        switches: &["serve"],
+    },
+    Command {
+        name: "prop",
+        about: "Read or change the properties of a topic",
+        actions: &["get", "set", "delete"],
+        options: CONNECTION_OPTIONS,
+        switches: &[],
+    },
+    Command {
+        name: "flag",
+        about: "Change the persistent, retained and cached flags of a topic",
+        actions: &[],
+        options: &[
+            "persistent",
+            "retained",
+            "cached",
+            "address",
+            "timeout",
+            "output",
+        ],
+        switches: &[],
+    },
+    Command {
+        name: "apply",
+        about: "Set the topics described by a configuration file",
+        actions: &[],
+        options: CONNECTION_OPTIONS,
+        switches: &["dry-run"],
+    },
+    Command {
+        name: "assert",
+        about: "Check the value of a topic",
+        actions: &[],
+        options: &["equals", "address", "timeout", "output"],
+        switches: &[],
+    },
+    Command {
+        name: "wait-connected",
+        about: "Wait for a server to accept a connection",
+        actions: &[],
+        options: CONNECTION_OPTIONS,
+        switches: &[],
+    },
+    Command {
+        name: "completions",
+        about: "Print a shell completion script",
+        actions: &["bash", "zsh", "fish"],
+        options: &[],
+        switches: &[],
+    },
+    Command {
+        name: "help",
+        about: "Print usage",
+        actions: &[],
+        options: &[],
+        switches: &[],
+    },
+];
+
+/// The values offered for options that only take a few.
+const OPTION_VALUES: &[(&str, &str)] = &[
+    ("output", "plain json"),
+    ("persistent", "true false"),
+    ("retained", "true false"),
+    ("cached", "true false"),
+];
+
+pub fn run(args: impl IntoIterator<Item = String>) -> Result<(), String> {
+    let mut args = Args::parse(args, &[])?;
+    let shell = args.positional().ok_or("completions needs a shell")?;
+    args.finish()?;
+
+    let script = match shell.as_str() {
+        "bash" => bash(),
+        "zsh" => zsh(),
+        "fish" => fish(),
+        _ => {
+            return Err(format!(
+                "Can't complete for {shell:?}, only bash, zsh and fish"
+            ))
+        }
+    };
+    print!("{script}");
+    Ok(())
+}
+
+/// The words completed after a subcommand: its actions, options and switches.
+fn words(command: &Command) -> String {
+    let options = command
+        .options
+        .iter()
+        .chain(command.switches)
+        .map(|option| format!("--{option}"));
+    command
+        .actions
+        .iter()
+        .map(|action| action.to_string())
+        .chain(options)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn command_names() -> String {
+    COMMANDS
+        .iter()
+        .map(|command| command.name)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn bash() -> String {
+    let mut script = String::from(
+        "_lagan() {
+    local cur=${COMP_WORDS[COMP_CWORD]} prev=${COMP_WORDS[COMP_CWORD-1]}
+    if [ \"$COMP_CWORD\" -eq 1 ]; then
+",
+    );
+    let _ = writeln!(
+        script,
+        "        COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+        command_names()
+    );
+    script.push_str("        return\n    fi\n    case $prev in\n");
+    for (option, values) in OPTION_VALUES {
+        let _ = writeln!(
+            script,
+            "        --{option}) COMPREPLY=($(compgen -W \"{values}\" -- \"$cur\")); return ;;"
+        );
+    }
+    script.push_str("    esac\n    case ${COMP_WORDS[1]} in\n");
+    for command in COMMANDS {
+        let _ = writeln!(
+            script,
+            "        {}) COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")) ;;",
+            command.name,
+            words(command)
+        );
+    }
+    script.push_str("    esac\n}\ncomplete -o default -F _lagan lagan\n");
+    script
+}
+
+fn zsh() -> String {
+    let mut script = String::from(
+        "#compdef lagan
+_lagan() {
+    if (( CURRENT == 2 )); then
+",
+    );
+    let _ = writeln!(script, "        compadd {}", command_names());
+    script.push_str("        return\n    fi\n    case $words[CURRENT-1] in\n");
+    for (option, values) in OPTION_VALUES {
+        let _ = writeln!(script, "        --{option}) compadd {values}; return ;;");
+    }
+    script.push_str("    esac\n    case $words[2] in\n");
+    for command in COMMANDS {
+        let _ = writeln!(
+            script,
+            "        {}) compadd -- {} ;;",
+            command.name,
+            words(command)
+        );
+    }
+    script.push_str("    esac\n    _files\n}\ncompdef _lagan lagan\n");
+    script
+}
+
+fn fish() -> String {
+    let mut script = String::new();
+    for command in COMMANDS {
+        let _ = writeln!(
+            script,
+            "complete -c lagan -n __fish_use_subcommand -f -a {} -d '{}'",
+            command.name, command.about
+        );
+        let condition = format!("-n '__fish_seen_subcommand_from {}'", command.name);
+        if !command.actions.is_empty() {
+            let _ = writeln!(
+                script,
+                "complete -c lagan {condition} -f -a '{}'",
+                command.actions.join(" ")
+            );
+        }
+        for option in command.options {
+            let values = OPTION_VALUES
+                .iter()
+                .find(|(name, _)| name == option)
+                .map_or("-r".to_string(), |(_, values)| format!("-x -a '{values}'"));
+            let _ = writeln!(script, "complete -c lagan {condition} -l {option} {values}");
+        }
+        for switch in command.switches {
+            let _ = writeln!(script, "complete -c lagan {condition} -l {switch}");
+        }
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::COMMANDS;
+    use crate::args::TAKEN;
+
+    #[test]
+    fn every_command_is_completed() {
+        for (name, _) in crate::COMMANDS {
+            assert!(
+                COMMANDS.iter().any(|command| command.name == *name),
+                "{name} isn't completed"
+            );
+        }
+        for command in COMMANDS.iter().filter(|command| command.name != "help") {
+            assert!(
+                crate::COMMANDS
+                    .iter()
+                    .any(|(name, _)| *name == command.name),
+                "{} is completed but doesn't exist",
+                command.name
+            );
+        }
+    }
+
+    #[test]
+    fn every_option_is_completed() {
+        for (name, run) in crate::COMMANDS {
+            let command = COMMANDS
+                .iter()
+                .find(|command| command.name == *name)
+                .unwrap();
+
+            // The unknown option makes every subcommand fail once it took its own options, before doing anything.
+            TAKEN.with(|taken| taken.borrow_mut().clear());
+            let args = ["probe", "probe", "--out", "probe", "--unknown", "probe"];
+            assert!(run(args.map(String::from).to_vec()).is_err());
+            let taken = TAKEN.with(|taken| taken.take());
+
+            let completed = command
+                .options
+                .iter()
+                .chain(command.switches)
+                .map(|option| option.to_string())
+                .collect::<BTreeSet<_>>();
+            assert_eq!(
+                taken, completed,
+                "the options of {name} aren't all completed"
+            );
+        }
+    }
+}
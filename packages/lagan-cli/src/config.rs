@@ -0,0 +1,170 @@
+//! Configuration files for `lagan apply`, which describe topics as a JSON tree.
+//!
+//! Objects are tables, whose members are subtables or topics, e.g.
+//! `{"Shooter": {"Speed": 3000, "Enabled": true}}` sets `/Shooter/Speed` and `/Shooter/Enabled`.
+//! Booleans, strings, numbers and arrays of them set topics of the matching type,
+//! where numbers are doubles.
+//!
+//! An object with a `value` member sets a topic with more control: `type` picks its NetworkTables type,
+//! e.g. `int` or `float[]`, and `persistent`, `retained` and `cached` set its flags, e.g.
+//! `{"value": [0.1, 0, 0.01], "type": "float[]", "persistent": true}`.
+
+use lagan::Value;
+
+use crate::json::Json;
+
+/// A topic set by a configuration file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Setting {
+    pub name: String,
+    pub value: Value,
+    /// The NetworkTables type string of the value.
+    pub type_string: String,
+    pub persistent: Option<bool>,
+    pub retained: Option<bool>,
+    pub cached: Option<bool>,
+}
+
+/// Parses a configuration file into the topics it sets, in the order they are written.
+pub fn parse(text: &str) -> Result<Vec<Setting>, String> {
+    let Json::Object(members) = Json::parse(text)? else {
+        return Err("The configuration must be a JSON object".to_string());
+    };
+    let mut settings = Vec::new();
+    table("", members, &mut settings)?;
+    Ok(settings)
+}
+
+fn table(
+    path: &str,
+    members: Vec<(String, Json)>,
+    settings: &mut Vec<Setting>,
+) -> Result<(), String> {
+    for (key, json) in members {
+        let name = format!("{path}/{}", key.trim_matches('/'));
+        match json {
+            Json::Object(members) if !members.iter().any(|(key, _)| key == "value") => {
+                table(&name, members, settings)?
+            }
+            Json::Object(members) => settings.push(detailed(name, members)?),
+            json => {
+                let type_string = default_type(&json).ok_or_else(|| {
+                    format!(
+                        "{name}: can't tell the type of {}, set \"type\"",
+                        json.kind()
+                    )
+                })?;
+                let value = value(type_string, &json).map_err(|err| format!("{name}: {err}"))?;
+                settings.push(Setting {
+                    name,
+                    value,
+                    type_string: type_string.to_string(),
+                    persistent: None,
+                    retained: None,
+                    cached: None,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parses an object with a `value` member.
+fn detailed(name: String, members: Vec<(String, Json)>) -> Result<Setting, String> {
+    let mut json = Json::Null;
+    let mut type_string = None;
+    let mut flags = [None; 3];
+    for (key, member) in members {
+        match (key.as_str(), member) {
+            ("value", member) => json = member,
+            ("type", Json::String(type_name)) => type_string = Some(type_name),
+            ("persistent", Json::Bool(flag)) => flags[0] = Some(flag),
+            ("retained", Json::Bool(flag)) => flags[1] = Some(flag),
+            ("cached", Json::Bool(flag)) => flags[2] = Some(flag),
+            (key, member) => {
+                return Err(format!("{name}: unexpected {key:?} of {}", member.kind()));
+            }
+        }
+    }
+
+    let type_string = match type_string {
+        Some(type_string) => type_string,
+        None => default_type(&json)
+            .ok_or_else(|| {
+                format!(
+                    "{name}: can't tell the type of {}, set \"type\"",
+                    json.kind()
+                )
+            })?
+            .to_string(),
+    };
+    let value = value(&type_string, &json).map_err(|err| format!("{name}: {err}"))?;
+    let [persistent, retained, cached] = flags;
+    Ok(Setting {
+        name,
+        value,
+        type_string,
+        persistent,
+        retained,
+        cached,
+    })
+}
+
+/// The type of a value without an explicit type. Empty arrays have none.
+fn default_type(json: &Json) -> Option<&'static str> {
+    Some(match json {
+        Json::Bool(_) => "boolean",
+        Json::Number(_) => "double",
+        Json::String(_) => "string",
+        Json::Array(values) => match values.first()? {
+            Json::Bool(_) => "boolean[]",
+            Json::Number(_) => "double[]",
+            Json::String(_) => "string[]",
+            _ => return None,
+        },
+        Json::Null | Json::Object(_) => return None,
+    })
+}
+
+/// Converts JSON to a value of the given NetworkTables type.
+fn value(type_string: &str, json: &Json) -> Result<Value, String> {
+    fn items<T>(json: &Json, item: impl Fn(&Json) -> Option<T>) -> Option<Vec<T>> {
+        match json {
+            Json::Array(values) => values.iter().map(item).collect(),
+            _ => None,
+        }
+    }
+    fn boolean(json: &Json) -> Option<bool> {
+        match json {
+            Json::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+    fn number<T: std::str::FromStr>(json: &Json) -> Option<T> {
+        match json {
+            Json::Number(number) => number.parse().ok(),
+            _ => None,
+        }
+    }
+    fn string(json: &Json) -> Option<String> {
+        match json {
+            Json::String(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    let value = match type_string {
+        "boolean" => boolean(json).map(Value::Bool),
+        "int" => number(json).map(Value::I64),
+        "float" => number(json).map(Value::F32),
+        "double" => number(json).map(Value::F64),
+        "string" | "json" => string(json).map(Value::String),
+        "boolean[]" => items(json, boolean).map(Value::BoolArray),
+        "int[]" => items(json, number).map(Value::I64Array),
+        "float[]" => items(json, number).map(Value::F32Array),
+        "double[]" => items(json, number).map(Value::F64Array),
+        "string[]" => items(json, string).map(Value::StringArray),
+        _ => return Err(format!("type {type_string:?} isn't supported")),
+    };
+    value.ok_or_else(|| format!("{} is not a valid {type_string}", json.kind()))
+}
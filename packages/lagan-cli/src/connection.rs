@@ -0,0 +1,63 @@
+//! Connecting to servers, for the subcommands that read or change their topics.
+
+use std::{
+    net::SocketAddr,
+    thread,
+    time::{Duration, Instant},
+};
+
+use lagan::{
+    channel::{EventMask, EventReceiver},
+    Client, Instance,
+};
+
+/// How long to wait for the server when `--timeout` isn't given.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often conditions are checked while waiting.
+const POLL_PERIOD: Duration = Duration::from_millis(20);
+/// How long flushed changes get to reach the server before the client is dropped.
+const FLUSH_GRACE: Duration = Duration::from_millis(100);
+
+/// Checks `condition` until it holds, returning `false` if it doesn't within `timeout`.
+pub fn wait_until(timeout: Duration, mut condition: impl FnMut() -> bool) -> bool {
+    let started = Instant::now();
+    loop {
+        if condition() {
+            return true;
+        }
+        if started.elapsed() >= timeout {
+            return false;
+        }
+        thread::sleep(POLL_PERIOD);
+    }
+}
+
+/// Starts a client and waits for it to connect to the server at `address`.
+pub fn connect(address: SocketAddr, timeout: Duration) -> Result<Client, String> {
+    let client = Client::builder().address(address).build();
+    if !wait_until(timeout, || !client.connections().is_empty()) {
+        return Err(format!("Couldn't connect to {address} within {timeout:?}"));
+    }
+    Ok(client)
+}
+
+/// Waits until the server announces the topic with the given name.
+///
+/// Servers only announce topics to clients that subscribed to them,
+/// so the topic stays known to the client while the returned receiver lives.
+pub fn announced(client: &Client, name: &str, timeout: Duration) -> Result<EventReceiver, String> {
+    let announcements = client.event_channel(EventMask::TOPIC, [name]);
+    let topic = client.topic(name);
+    if !wait_until(timeout, || topic.is_existant()) {
+        return Err(format!(
+            "The server didn't announce {name} within {timeout:?}"
+        ));
+    }
+    Ok(announcements)
+}
+
+/// Sends the changes made through the client to the server before it is dropped.
+pub fn flush(client: &Client) {
+    client.flush();
+    thread::sleep(FLUSH_GRACE);
+}
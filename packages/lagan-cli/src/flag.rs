@@ -0,0 +1,67 @@
+//! `lagan flag`, which changes the persistent, retained and cached flags of a topic.
+
+use lagan::Instance;
+
+use crate::{
+    args::Args,
+    connection::{self, DEFAULT_TIMEOUT},
+    json::Json,
+    output::Output,
+};
+
+pub const USAGE: &str = "\
+lagan flag <TOPIC> [--persistent=<BOOL>] [--retained=<BOOL>] [--cached=<BOOL>]
+           [--address <ADDRESS>] [--timeout <DURATION>]
+    Sets the given flags of TOPIC, leaving the others as they are, then prints every flag.
+    E.g. --persistent=false stops the server from restoring a stale value after restarts.
+";
+
+pub fn run(args: impl IntoIterator<Item = String>) -> Result<(), String> {
+    let mut args = Args::parse(args, &[])?;
+    let topic_name = args.positional().ok_or("flag needs a topic")?;
+    let persistent: Option<bool> = args.parse_option("persistent")?;
+    let retained: Option<bool> = args.parse_option("retained")?;
+    let cached: Option<bool> = args.parse_option("cached")?;
+    let address = args.address()?;
+    let timeout = args.timeout(DEFAULT_TIMEOUT)?;
+    let output = args.output()?;
+    args.finish()?;
+
+    let client = connection::connect(address, timeout)?;
+    let _announcement = connection::announced(&client, &topic_name, timeout)?;
+    let topic = client.topic(&topic_name);
+    if let Some(persistent) = persistent {
+        topic.set_persistent(persistent);
+    }
+    if let Some(retained) = retained {
+        topic
+            .set_retained(retained)
+            .map_err(|err| err.to_string())?;
+    }
+    if let Some(cached) = cached {
+        topic.set_cached(cached).map_err(|err| err.to_string())?;
+    }
+    if persistent.is_some() || retained.is_some() || cached.is_some() {
+        connection::flush(&client);
+    }
+
+    let flags = [
+        ("persistent", topic.is_persistent()),
+        ("retained", topic.is_retained()),
+        ("cached", topic.is_cached()),
+    ];
+    match output {
+        Output::Plain => {
+            let flags: Vec<_> = flags
+                .iter()
+                .map(|(flag, value)| format!("{flag}={value}"))
+                .collect();
+            println!("{}", flags.join(" "));
+        }
+        Output::Json => println!(
+            "{}",
+            Json::object(flags.map(|(flag, value)| (flag, value.into())))
+        ),
+    }
+    Ok(())
+}
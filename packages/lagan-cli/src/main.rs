@@ -0,0 +1,70 @@
+//! `lagan`, a command line tool for recording, replaying and inspecting NetworkTables.
+
+use std::process::ExitCode;
+
+mod apply;
+mod args;
+mod assert;
+mod completions;
+mod config;
+mod connection;
+mod flag;
+mod json;
+mod output;
+mod prop;
+mod record;
+mod replay;
+mod wait_connected;
+
+fn usage() -> String {
+    [
+        "Usage:\n",
+        record::USAGE,
+        replay::USAGE,
+        prop::USAGE,
+        flag::USAGE,
+        apply::USAGE,
+        assert::USAGE,
+        wait_connected::USAGE,
+        completions::USAGE,
+        output::USAGE,
+    ]
+    .join("\n")
+}
+
+/// Runs a subcommand with the arguments after its name.
+type Run = fn(Vec<String>) -> Result<(), String>;
+
+/// Every subcommand by name, which the completion scripts are checked against.
+const COMMANDS: &[(&str, Run)] = &[
+    ("record", record::run),
+    ("replay", replay::run),
+    ("prop", prop::run),
+    ("flag", flag::run),
+    ("apply", apply::run),
+    ("assert", assert::run),
+    ("wait-connected", wait_connected::run),
+    ("completions", completions::run),
+];
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let result = match args.next().as_deref() {
+        Some("help" | "--help") | None => {
+            print!("{}", usage());
+            return ExitCode::SUCCESS;
+        }
+        Some(command) => match COMMANDS.iter().find(|(name, _)| *name == command) {
+            Some((_, run)) => run(args.collect()),
+            None => Err(format!("Unknown command {command:?}")),
+        },
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}\nRun `lagan help` for usage.");
+            ExitCode::FAILURE
+        }
+    }
+}
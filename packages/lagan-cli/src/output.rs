@@ -0,0 +1,84 @@
+//! How subcommands print their results: as plain text for people, or as JSON for scripts.
+
+use std::str::FromStr;
+
+use lagan::Value;
+
+use crate::json::Json;
+
+pub const USAGE: &str = "\
+Every subcommand but completions takes --output <FORMAT>, where FORMAT is plain (the default) or json.
+    With json, the result is printed to stdout as a single JSON document, e.g. for jq.
+    Progress and errors are still printed to stderr as text.
+";
+
+/// The format subcommands print their results in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Output {
+    #[default]
+    Plain,
+    Json,
+}
+
+impl FromStr for Output {
+    type Err = ();
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "plain" => Ok(Self::Plain),
+            "json" => Ok(Self::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Formats a value the way it would be written in a configuration file.
+pub fn plain_value(value: &Value) -> String {
+    match value {
+        Value::Unassigned => "nothing".to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::I64(value) => value.to_string(),
+        Value::F32(value) => value.to_string(),
+        Value::F64(value) => value.to_string(),
+        Value::String(value) => format!("{value:?}"),
+        Value::Raw(bytes) => format!("{} bytes", bytes.len()),
+        Value::BoolArray(values) => format!("{values:?}"),
+        Value::I64Array(values) => format!("{values:?}"),
+        Value::F32Array(values) => format!("{values:?}"),
+        Value::F64Array(values) => format!("{values:?}"),
+        Value::StringArray(values) => format!("{values:?}"),
+        Value::Unknown { type_bits, .. } => format!("a value of unknown type {type_bits}"),
+    }
+}
+
+/// Converts a value to JSON.
+///
+/// Raw values become arrays of bytes. Values JSON can't hold, like NaN or a value of unknown type, become null.
+pub fn json_value(value: &Value) -> Json {
+    fn float(value: impl Into<f64> + ToString) -> Json {
+        let text = value.to_string();
+        if value.into().is_finite() {
+            Json::Number(text)
+        } else {
+            Json::Null
+        }
+    }
+    fn array<T: Clone>(values: &[T], item: impl Fn(T) -> Json) -> Json {
+        Json::Array(values.iter().cloned().map(item).collect())
+    }
+
+    match value {
+        Value::Unassigned | Value::Unknown { .. } => Json::Null,
+        Value::Bool(value) => Json::Bool(*value),
+        Value::I64(value) => Json::Number(value.to_string()),
+        Value::F32(value) => float(*value),
+        Value::F64(value) => float(*value),
+        Value::String(value) => Json::String(value.clone()),
+        Value::Raw(bytes) => array(bytes, |byte| Json::Number(byte.to_string())),
+        Value::BoolArray(values) => array(values, Json::Bool),
+        Value::I64Array(values) => array(values, |value| Json::Number(value.to_string())),
+        Value::F32Array(values) => array(values, float),
+        Value::F64Array(values) => array(values, float),
+        Value::StringArray(values) => array(values, Json::String),
+    }
+}
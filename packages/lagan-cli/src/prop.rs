@@ -0,0 +1,65 @@
+//! `lagan prop`, which reads and changes the properties of a topic.
+
+use lagan::Instance;
+
+use crate::{
+    args::Args,
+    connection::{self, DEFAULT_TIMEOUT},
+};
+
+pub const USAGE: &str = "\
+lagan prop get <TOPIC> [NAME] [--address <ADDRESS>] [--timeout <DURATION>]
+    Prints the property NAME of TOPIC as JSON, or every property of TOPIC if NAME is left out.
+lagan prop set <TOPIC> <NAME> <JSON> [--address <ADDRESS>] [--timeout <DURATION>]
+    Sets the property NAME of TOPIC to a JSON value, e.g. true or '\"m/s\"'.
+lagan prop delete <TOPIC> <NAME> [--address <ADDRESS>] [--timeout <DURATION>]
+    Deletes the property NAME of TOPIC.
+";
+
+pub fn run(args: impl IntoIterator<Item = String>) -> Result<(), String> {
+    let mut args = Args::parse(args, &[])?;
+    let action = args.positional().unwrap_or_default();
+    let topic_name = args.positional().ok_or("prop needs a topic")?;
+    let name = args.positional();
+    let json = args.positional();
+    let address = args.address()?;
+    let timeout = args.timeout(DEFAULT_TIMEOUT)?;
+    // Properties are JSON already, so they print the same either way.
+    let _output = args.output()?;
+    args.finish()?;
+
+    let valid = match action.as_str() {
+        "get" => json.is_none(),
+        "set" => name.is_some() && json.is_some(),
+        "delete" => name.is_some() && json.is_none(),
+        _ => return Err("prop needs get, set or delete".to_string()),
+    };
+    if !valid {
+        return Err(format!("Wrong arguments for prop {action}"));
+    }
+
+    let client = connection::connect(address, timeout)?;
+    let _announcement = connection::announced(&client, &topic_name, timeout)?;
+    let topic = client.topic(&topic_name);
+    match (action.as_str(), name, json) {
+        ("get", None, _) => println!("{}", topic.properties()),
+        ("get", Some(name), _) => {
+            let value = topic
+                .property(&name)
+                .ok_or_else(|| format!("{topic_name} has no property {name:?}"))?;
+            println!("{value}");
+        }
+        ("set", Some(name), Some(json)) => {
+            topic
+                .set_property(name, json)
+                .map_err(|err| err.to_string())?;
+            connection::flush(&client);
+        }
+        (_, Some(name), _) => {
+            topic.delete_property(name).map_err(|err| err.to_string())?;
+            connection::flush(&client);
+        }
+        _ => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,87 @@
+//! `lagan record`, which connects to a server and logs its topics to a data log.
+
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use lagan::{channel::EventMask, datalog::DataLog, Client, Instance};
+
+use crate::{
+    args::{parse_duration, Args},
+    json::Json,
+    output::Output,
+};
+
+/// How often the data log is written to disk, which bounds what is lost if the recorder is killed.
+const FLUSH_PERIOD: Duration = Duration::from_millis(250);
+/// How often the number of recorded values is reported.
+const REPORT_PERIOD: Duration = Duration::from_secs(5);
+
+pub const USAGE: &str = "\
+lagan record --out <FILE> [--prefix <PREFIX>] [--address <ADDRESS>] [--duration <DURATION>]
+    Connects to the server at ADDRESS and logs every topic starting with PREFIX to FILE,
+    for DURATION (e.g. 150s) or until stopped. Topics are logged as NT: followed by their name, like
+    WPILib's DataLogManager, so the log opens in AdvantageScope and `lagan replay`.
+";
+
+pub fn run(args: impl IntoIterator<Item = String>) -> Result<(), String> {
+    let mut args = Args::parse(args, &[])?;
+    let out = args.option("out").ok_or("record needs --out <FILE>")?;
+    let prefix = args.option("prefix").unwrap_or_default();
+    let address = args.address()?;
+    let duration = args
+        .option("duration")
+        .map(|text| {
+            parse_duration(&text)
+                .ok_or_else(|| format!("{text:?} is not a duration, e.g. 150s or 500ms"))
+        })
+        .transpose()?;
+    let output = args.output()?;
+    args.finish()?;
+
+    let out = Path::new(&out);
+    let dir = match out.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_string_lossy(),
+        _ => ".".into(),
+    };
+    let filename = out
+        .file_name()
+        .ok_or_else(|| format!("{} is not a file", out.display()))?
+        .to_string_lossy();
+
+    let datalog = DataLog::new(dir, Some(&filename), FLUSH_PERIOD);
+    let client = Client::builder().address(address).build();
+    // Clients only receive the values of topics something subscribed to, and the data log only logs received values.
+    let values = client.event_channel(EventMask::VALUE, [&prefix]);
+    let _entries = client.log_entries_to(&datalog, &prefix);
+    let _connections = client.log_connections_to(&datalog, "NTConnection");
+    eprintln!("Recording {prefix}* from {address} to {}", out.display());
+
+    let started = Instant::now();
+    let mut recorded = 0;
+    let mut last_report = started;
+    while duration.is_none_or(|duration| started.elapsed() < duration) {
+        if values.recv_timeout(FLUSH_PERIOD).is_ok() {
+            recorded += 1;
+        }
+        recorded += values.try_iter().count();
+        if last_report.elapsed() >= REPORT_PERIOD {
+            eprintln!("{recorded} values recorded");
+            last_report = Instant::now();
+        }
+    }
+
+    datalog.flush();
+    match output {
+        Output::Plain => eprintln!("{recorded} values recorded to {}", out.display()),
+        Output::Json => println!(
+            "{}",
+            Json::object([
+                ("file", out.display().to_string().into()),
+                ("recorded", recorded.into()),
+            ])
+        ),
+    }
+    Ok(())
+}
@@ -0,0 +1,149 @@
+//! `lagan replay`, which publishes the NetworkTables entries of a data log with their original timing.
+
+use std::{
+    collections::BTreeMap,
+    thread,
+    time::{Duration, Instant},
+};
+
+use lagan::{
+    datalog::ENTRY_LOG_PREFIX, nt_types::PubSubOptions, wpilog::WpiLog, Client, Instance, Server,
+};
+
+use crate::{args::Args, json::Json, output::Output};
+
+/// How often the replay checks whether something connected yet.
+const CONNECT_POLL_PERIOD: Duration = Duration::from_millis(100);
+
+pub const USAGE: &str = "\
+lagan replay <FILE> [--speed <FACTOR>] [--serve] [--address <ADDRESS>]
+    Publishes the NetworkTables entries logged in FILE with their original timing, sped up by
+    FACTOR (1.0 by default). Connects to the server at ADDRESS, or with --serve, starts a server
+    on the port of ADDRESS instead. The replay starts once something is connected.
+";
+
+pub fn run(args: impl IntoIterator<Item = String>) -> Result<(), String> {
+    let mut args = Args::parse(args, &["serve"])?;
+    let path = args.positional().ok_or("replay needs a data log")?;
+    let speed: f64 = args.parse_option("speed")?.unwrap_or(1.0);
+    let serve = args.switch("serve");
+    let address = args.address()?;
+    let output = args.output()?;
+    args.finish()?;
+    if !(speed.is_finite() && speed > 0.0) {
+        return Err("--speed must be a positive number".to_string());
+    }
+
+    let log = WpiLog::open(&path).map_err(|err| err.to_string())?;
+    if serve {
+        // The replay isn't meant to outlive the log, so nothing is persisted where it could be picked up later.
+        let persist_filename = std::env::temp_dir().join("lagan-replay.json");
+        let server = Server::builder()
+            .persist_filename(persist_filename.to_string_lossy())
+            .nt4_port(address.port())
+            .build()
+            .map_err(|err| err.to_string())?;
+        eprintln!("Serving on port {}", address.port());
+        replay(&server, &log, speed, output)
+    } else {
+        let client = Client::builder().address(address).build();
+        eprintln!("Connecting to {address}");
+        replay(&client, &log, speed, output)
+    }
+}
+
+fn replay<I: Instance>(
+    instance: &I,
+    log: &WpiLog,
+    speed: f64,
+    output: Output,
+) -> Result<(), String> {
+    // Topics are published with the type of their first value, since entries don't carry NetworkTables types.
+    let mut types = BTreeMap::new();
+    for record in log.records() {
+        let Some(entry) = log.entry(record) else {
+            continue;
+        };
+        if let Some(name) = entry.name.strip_prefix(ENTRY_LOG_PREFIX) {
+            types
+                .entry(name)
+                .or_insert((record.value.value_type(), type_string(&entry.type_string)));
+        }
+    }
+    if types.is_empty() {
+        return Err("The log has no NetworkTables entries".to_string());
+    }
+
+    let topics: BTreeMap<&str, _> = types
+        .keys()
+        .map(|name| (*name, instance.topic(name)))
+        .collect();
+    let mut publishers = BTreeMap::new();
+    for (name, topic) in &topics {
+        let (value_type, type_string) = &types[name];
+        let publisher = topic
+            .publish(value_type.clone(), type_string, PubSubOptions::default())
+            .map_err(|err| format!("Couldn't publish {name}: {err}"))?;
+        publishers.insert(*name, publisher);
+    }
+
+    eprintln!("Waiting for a connection");
+    while instance.connections().is_empty() {
+        thread::sleep(CONNECT_POLL_PERIOD);
+    }
+
+    let (start, end) = log.time_range().unwrap_or_default();
+    eprintln!(
+        "Replaying {} topics over {:.1} s",
+        publishers.len(),
+        (end - start).as_secs_f64() / speed
+    );
+    let started = Instant::now();
+    let mut replayed = 0;
+    let mut failed = 0;
+    for record in log.records() {
+        let Some(publisher) = log
+            .entry(record)
+            .and_then(|entry| entry.name.strip_prefix(ENTRY_LOG_PREFIX))
+            .and_then(|name| publishers.get(name))
+        else {
+            continue;
+        };
+        let due = (record.timestamp - start).div_f64(speed);
+        if let Some(wait) = due.checked_sub(started.elapsed()) {
+            thread::sleep(wait);
+        }
+        // Values of another type than the first, e.g. after the robot program changed, can't be published.
+        match publisher.set_value(record.value.clone()) {
+            Ok(()) => replayed += 1,
+            Err(_) => failed += 1,
+        }
+    }
+
+    match output {
+        Output::Plain if failed > 0 => {
+            eprintln!("{failed} values didn't match the type of their topic and were skipped")
+        }
+        Output::Plain => {}
+        Output::Json => println!(
+            "{}",
+            Json::object([
+                ("topics", publishers.len().into()),
+                ("replayed", replayed.into()),
+                ("skipped", failed.into()),
+            ])
+        ),
+    }
+    Ok(())
+}
+
+/// Returns the NetworkTables type string of a data log entry type.
+///
+/// Data logs call integers `int64`, while NetworkTables calls them `int`.
+fn type_string(log_type: &str) -> &str {
+    match log_type {
+        "int64" => "int",
+        "int64[]" => "int[]",
+        other => other,
+    }
+}
@@ -0,0 +1,48 @@
+//! `lagan wait-connected`, which waits for a server to come up, e.g. while a robot boots.
+
+use std::time::Duration;
+
+use lagan::Instance;
+
+use crate::{args::Args, connection, json::Json, output::Output};
+
+/// How long to wait when `--timeout` isn't given, which is longer than other subcommands
+/// since robots take a while to boot.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub const USAGE: &str = "\
+lagan wait-connected [--address <ADDRESS>] [--timeout <DURATION>]
+    Waits until the server at ADDRESS accepts a connection, and fails if it doesn't
+    within DURATION (30s by default).
+";
+
+pub fn run(args: impl IntoIterator<Item = String>) -> Result<(), String> {
+    let mut args = Args::parse(args, &[])?;
+    let address = args.address()?;
+    let timeout = args.timeout(DEFAULT_TIMEOUT)?;
+    let output = args.output()?;
+    args.finish()?;
+
+    let client = connection::connect(address, timeout)?;
+    for info in client.connections() {
+        match output {
+            Output::Plain => println!(
+                "Connected to {} at {}:{}",
+                info.remote_id, info.remote_ip, info.remote_port
+            ),
+            Output::Json => println!(
+                "{}",
+                Json::object([
+                    ("remote_id", info.remote_id.into()),
+                    ("remote_ip", info.remote_ip.into()),
+                    ("remote_port", Json::Number(info.remote_port.to_string())),
+                    (
+                        "protocol_version",
+                        Json::Number(info.protocol_version.to_string())
+                    ),
+                ])
+            ),
+        }
+    }
+    Ok(())
+}
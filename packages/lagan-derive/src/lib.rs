@@ -0,0 +1,362 @@
+//! Derive macros for [`lagan`](https://docs.rs/lagan).
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+/// Derives `lagan::table::NtTable` for a struct with named fields.
+///
+/// See the documentation of `NtTable` for the supported attributes.
+#[proc_macro_derive(NtTable, attributes(nt))]
+pub fn derive_nt_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    nt_table(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `lagan::nt_types::StringEnum` for an enum with at least one variant and no fields.
+///
+/// Variants can be renamed with `#[nt(rename = "name")]`.
+#[proc_macro_derive(StringEnum, attributes(nt))]
+pub fn derive_string_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    string_enum(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct Field {
+    ident: syn::Ident,
+    vis: syn::Visibility,
+    ty: syn::Type,
+    /// The name of the child topic, without the `r#` of raw identifiers.
+    name: String,
+    skip: bool,
+}
+
+fn parse_fields(input: &DeriveInput) -> syn::Result<Vec<Field>> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "NtTable can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "NtTable can only be derived for structs with named fields",
+        ));
+    };
+
+    fields
+        .named
+        .iter()
+        .map(|field| {
+            let ident = field.ident.clone().unwrap();
+            let mut name = ident.unraw().to_string();
+            let mut skip = false;
+
+            for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("nt")) {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename") {
+                        name = meta.value()?.parse::<LitStr>()?.value();
+                        Ok(())
+                    } else if meta.path.is_ident("skip") {
+                        skip = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected `rename` or `skip`"))
+                    }
+                })?;
+            }
+
+            Ok(Field {
+                ident,
+                vis: field.vis.clone(),
+                ty: field.ty.clone(),
+                name,
+                skip,
+            })
+        })
+        .collect()
+}
+
+fn string_enum(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "StringEnum can only be derived for enums",
+        ));
+    };
+    // A value of an enum without variants can't exist, so it has no name to publish.
+    if data.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "StringEnum can't be derived for enums without variants",
+        ));
+    }
+
+    let mut variants = Vec::new();
+    let mut names = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "StringEnum variants cannot have fields",
+            ));
+        }
+
+        let mut name = variant.ident.unraw().to_string();
+        for attr in variant.attrs.iter().filter(|attr| attr.path().is_ident("nt")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    name = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `rename`"))
+                }
+            })?;
+        }
+
+        variants.push(&variant.ident);
+        names.push(name);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::lagan::nt_types::StringEnum for #ident #ty_generics #where_clause {
+            const VARIANTS: &'static [&'static str] = &[#(#names),*];
+
+            fn name(&self) -> &'static str {
+                match self {
+                    #(Self::#variants => #names),*
+                }
+            }
+
+            fn from_name(name: &str) -> ::core::option::Option<Self> {
+                match name {
+                    #(#names => ::core::option::Option::Some(Self::#variants),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    })
+}
+
+fn nt_table(input: DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "NtTable cannot be derived for generic structs",
+        ));
+    }
+
+    let fields = parse_fields(&input)?;
+    let ident = &input.ident;
+    let vis = &input.vis;
+    let tunables_ident = format_ident!("{}Tunables", ident);
+    let published = fields.iter().filter(|field| !field.skip).collect::<Vec<_>>();
+
+    let publish = published.iter().map(|Field { ident, ty, name, .. }| {
+        quote! {
+            instance
+                .entry(::lagan::table::child_name(table, #name))
+                .set_value(<#ty as ::lagan::nt_types::NetworkTablesValue>::into_value(
+                    ::core::clone::Clone::clone(&self.#ident),
+                ))?;
+        }
+    });
+
+    let read = fields.iter().map(|Field { ident, ty, name, skip, .. }| {
+        if *skip {
+            quote! { #ident: ::core::default::Default::default() }
+        } else {
+            quote! {
+                #ident: {
+                    let entry = instance.entry(::lagan::table::child_name(table, #name));
+                    let value = entry.value_of_type(
+                        <#ty as ::lagan::nt_types::NetworkTablesValue>::value_type(),
+                    )?;
+                    <#ty as ::lagan::nt_types::NetworkTablesValue>::from_value(value)?
+                }
+            }
+        }
+    });
+
+    let tunable_fields = published.iter().map(|Field { ident, vis, ty, .. }| {
+        quote! { #vis #ident: ::lagan::tunable::Tunable<'a, I, #ty> }
+    });
+    let tunable_inits = published.iter().map(|Field { ident, name, .. }| {
+        quote! {
+            #ident: ::lagan::tunable::Tunable::new(
+                instance,
+                ::lagan::table::child_name(table, #name),
+                ::core::clone::Clone::clone(&self.#ident),
+            )
+        }
+    });
+    let tunable_updates = published.iter().map(|Field { ident, .. }| {
+        quote! { changed |= self.#ident.update(); }
+    });
+
+    let tunables_doc = format!("Tunable versions of the fields of [`{ident}`].");
+
+    Ok(quote! {
+        impl ::lagan::table::NtTable for #ident {
+            #[allow(unused_variables)]
+            fn publish_all<I: ::lagan::Instance + ?Sized>(
+                &self,
+                instance: &I,
+                table: &str,
+            ) -> ::core::result::Result<(), ::lagan::NetworkTablesError> {
+                #(#publish)*
+                ::core::result::Result::Ok(())
+            }
+
+            #[allow(unused_variables)]
+            fn read_all<I: ::lagan::Instance + ?Sized>(
+                instance: &I,
+                table: &str,
+            ) -> ::core::option::Option<Self> {
+                ::core::option::Option::Some(Self {
+                    #(#read),*
+                })
+            }
+        }
+
+        #[doc = #tunables_doc]
+        #vis struct #tunables_ident<'a, I: ::lagan::Instance + ?Sized> {
+            #(#tunable_fields,)*
+            // Uses the parameters even if every field is skipped.
+            __marker: ::core::marker::PhantomData<(&'a (), fn(&I))>,
+        }
+
+        impl<I: ::lagan::Instance + ?Sized> #tunables_ident<'_, I> {
+            /// Applies every remote change to every field.
+            ///
+            /// Returns `true` if any field changed.
+            #[allow(unused_mut)]
+            #vis fn update(&mut self) -> bool {
+                let mut changed = false;
+                #(#tunable_updates)*
+                changed
+            }
+        }
+
+        impl #ident {
+            /// Creates a tunable for every field under `table`, using the current values as defaults.
+            #[allow(unused_variables)]
+            #vis fn tunables<'a, I: ::lagan::Instance + ?Sized>(
+                &self,
+                instance: &'a I,
+                table: &str,
+            ) -> #tunables_ident<'a, I> {
+                #tunables_ident {
+                    #(#tunable_inits,)*
+                    __marker: ::core::marker::PhantomData,
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::{nt_table, string_enum};
+
+    fn expand(input: syn::DeriveInput) -> String {
+        nt_table(input).unwrap().to_string()
+    }
+
+    #[test]
+    fn renamed_fields_use_their_new_name() {
+        let expanded = expand(parse_quote! {
+            struct Drive {
+                #[nt(rename = "Max Speed")]
+                max_speed: f64,
+            }
+        });
+        assert!(expanded.contains("\"Max Speed\""));
+        assert!(!expanded.contains("\"max_speed\""));
+    }
+
+    #[test]
+    fn skipped_fields_are_not_published() {
+        let expanded = expand(parse_quote! {
+            struct Drive {
+                max_speed: f64,
+                #[nt(skip)]
+                cache: Vec<f64>,
+            }
+        });
+        assert!(expanded.contains("\"max_speed\""));
+        assert!(!expanded.contains("\"cache\""));
+    }
+
+    #[test]
+    fn structs_with_every_field_skipped_use_the_tunables_parameters() {
+        let expanded = expand(parse_quote! {
+            struct Cache {
+                #[nt(skip)]
+                values: Vec<f64>,
+            }
+        });
+        assert!(!expanded.contains("\"values\""));
+        assert!(expanded.contains("PhantomData"));
+    }
+
+    #[test]
+    fn raw_identifiers_are_published_without_their_prefix() {
+        let expanded = expand(parse_quote! {
+            struct Auto {
+                r#type: String,
+            }
+        });
+        assert!(expanded.contains("\"type\""));
+        assert!(!expanded.contains("\"r#type\""));
+    }
+
+    fn string_enum_error(input: syn::DeriveInput) -> String {
+        string_enum(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn renamed_variants_use_their_new_name() {
+        let expanded = string_enum(parse_quote! {
+            enum Auto {
+                Left,
+                #[nt(rename = "Center Line")]
+                Center,
+            }
+        })
+        .unwrap()
+        .to_string();
+        assert!(expanded.contains("\"Left\""));
+        assert!(expanded.contains("\"Center Line\""));
+        assert!(!expanded.contains("\"Center\""));
+    }
+
+    #[test]
+    fn empty_enums_are_rejected() {
+        assert!(string_enum_error(parse_quote! { enum Never {} }).contains("without variants"));
+    }
+
+    #[test]
+    fn variants_with_fields_are_rejected() {
+        assert!(string_enum_error(parse_quote! { enum Auto { Left(u8) } })
+            .contains("cannot have fields"));
+    }
+
+    #[test]
+    fn structs_are_rejected() {
+        assert!(
+            string_enum_error(parse_quote! { struct Auto; }).contains("only be derived for enums")
+        );
+    }
+}
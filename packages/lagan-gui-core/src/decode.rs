@@ -0,0 +1,479 @@
+//! Decoding raw topic values into readable text.
+//!
+//! Raw topics carry WPILib structs, MessagePack or JSON more often than anything else,
+//! so these are the formats the GUI offers besides a hex dump.
+
+use std::fmt::{self, Display, Write};
+
+/// How deeply structs may nest, which also stops schemas that refer to themselves.
+const MAX_STRUCT_DEPTH: usize = 16;
+
+/// Why a raw value couldn't be decoded.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DecodeError {
+    pub message: String,
+}
+impl DecodeError {
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+impl std::error::Error for DecodeError {}
+
+/// Shows `bytes` as JSON text.
+///
+/// The text is not validated beyond being UTF-8.
+pub fn decode_json(bytes: &[u8]) -> Result<String, DecodeError> {
+    std::str::from_utf8(bytes)
+        .map(|text| text.trim().to_string())
+        .map_err(|err| DecodeError::new(format!("Not UTF-8: {err}")))
+}
+
+/// Decodes a single MessagePack value into JSON-like text.
+pub fn decode_msgpack(bytes: &[u8]) -> Result<String, DecodeError> {
+    let mut reader = Reader { bytes };
+    let mut text = String::new();
+    msgpack_value(&mut reader, &mut text)?;
+    if !reader.bytes.is_empty() {
+        return Err(DecodeError::new(format!(
+            "{} bytes left after the value",
+            reader.bytes.len()
+        )));
+    }
+    Ok(text)
+}
+
+/// Decodes WPILib structs described by `schema`, such as `double x;double y`.
+///
+/// If `array` is true, `bytes` holds any number of structs back to back.
+/// Nested struct types are resolved through `lookup`, which returns the schema of a struct by name.
+pub fn decode_struct(
+    bytes: &[u8],
+    schema: &str,
+    array: bool,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, DecodeError> {
+    let fields = parse_schema(schema, lookup, 0)?;
+    let size = fields_size(&fields)?;
+    let mut reader = Reader { bytes };
+    let mut text = String::new();
+
+    if array {
+        if size == 0 || bytes.len() % size != 0 {
+            return Err(DecodeError::new(format!(
+                "{} bytes is not a whole number of {size} byte structs",
+                bytes.len()
+            )));
+        }
+        text.push('[');
+        for index in 0..bytes.len() / size {
+            if index > 0 {
+                text.push_str(", ");
+            }
+            struct_value(&fields, &mut reader, &mut text)?;
+        }
+        text.push(']');
+    } else {
+        if bytes.len() != size {
+            return Err(DecodeError::new(format!(
+                "Expected {size} bytes, got {}",
+                bytes.len()
+            )));
+        }
+        struct_value(&fields, &mut reader, &mut text)?;
+    }
+    Ok(text)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < len {
+            return Err(DecodeError::new("Unexpected end of data"));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Reads a big-endian length of 1, 2 or 4 bytes, as MessagePack does.
+    fn length(&mut self, bytes: usize) -> Result<usize, DecodeError> {
+        let length = self
+            .take(bytes)?
+            .iter()
+            .fold(0, |length, &byte| (length << 8) | usize::from(byte));
+        Ok(length)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Container {
+    Str,
+    Bin,
+    Array,
+    Map,
+}
+
+fn msgpack_value(reader: &mut Reader, text: &mut String) -> Result<(), DecodeError> {
+    let marker = reader.u8()?;
+    let (container, len) = match marker {
+        0x80..=0x8f => (Container::Map, usize::from(marker & 0x0f)),
+        0x90..=0x9f => (Container::Array, usize::from(marker & 0x0f)),
+        0xa0..=0xbf => (Container::Str, usize::from(marker & 0x1f)),
+        0xc4 => (Container::Bin, reader.length(1)?),
+        0xc5 => (Container::Bin, reader.length(2)?),
+        0xc6 => (Container::Bin, reader.length(4)?),
+        0xd9 => (Container::Str, reader.length(1)?),
+        0xda => (Container::Str, reader.length(2)?),
+        0xdb => (Container::Str, reader.length(4)?),
+        0xdc => (Container::Array, reader.length(2)?),
+        0xdd => (Container::Array, reader.length(4)?),
+        0xde => (Container::Map, reader.length(2)?),
+        0xdf => (Container::Map, reader.length(4)?),
+        _ => return msgpack_scalar(marker, reader, text),
+    };
+
+    match container {
+        Container::Str => msgpack_str(reader, text, len),
+        Container::Bin => msgpack_bin(reader, text, len),
+        Container::Array => msgpack_array(reader, text, len),
+        Container::Map => msgpack_map(reader, text, len),
+    }
+}
+
+fn msgpack_scalar(marker: u8, reader: &mut Reader, text: &mut String) -> Result<(), DecodeError> {
+    // Writing to a string can't fail.
+    let _ = match marker {
+        0x00..=0x7f => write!(text, "{marker}"),
+        0xe0..=0xff => write!(text, "{}", marker as i8),
+        0xc0 => write!(text, "null"),
+        0xc2 => write!(text, "false"),
+        0xc3 => write!(text, "true"),
+        0xca => write!(text, "{}", f32::from_be_bytes(reader.array()?)),
+        0xcb => write!(text, "{}", f64::from_be_bytes(reader.array()?)),
+        0xcc => write!(text, "{}", reader.u8()?),
+        0xcd => write!(text, "{}", u16::from_be_bytes(reader.array()?)),
+        0xce => write!(text, "{}", u32::from_be_bytes(reader.array()?)),
+        0xcf => write!(text, "{}", u64::from_be_bytes(reader.array()?)),
+        0xd0 => write!(text, "{}", reader.u8()? as i8),
+        0xd1 => write!(text, "{}", i16::from_be_bytes(reader.array()?)),
+        0xd2 => write!(text, "{}", i32::from_be_bytes(reader.array()?)),
+        0xd3 => write!(text, "{}", i64::from_be_bytes(reader.array()?)),
+        0xc7..=0xc9 | 0xd4..=0xd8 => {
+            let len = match marker {
+                0xc7 => reader.length(1)?,
+                0xc8 => reader.length(2)?,
+                0xc9 => reader.length(4)?,
+                // Fixed size extensions hold 1, 2, 4, 8 or 16 bytes.
+                _ => 1 << (marker - 0xd4),
+            };
+            let kind = reader.u8()? as i8;
+            reader.take(len)?;
+            write!(text, "<extension {kind}, {len} bytes>")
+        }
+        _ => {
+            return Err(DecodeError::new(format!(
+                "Invalid MessagePack marker {marker:#04x}"
+            )))
+        }
+    };
+    Ok(())
+}
+
+fn msgpack_str(reader: &mut Reader, text: &mut String, len: usize) -> Result<(), DecodeError> {
+    let string = String::from_utf8_lossy(reader.take(len)?);
+    let _ = write!(text, "{string:?}");
+    Ok(())
+}
+
+fn msgpack_bin(reader: &mut Reader, text: &mut String, len: usize) -> Result<(), DecodeError> {
+    text.push_str("<");
+    for (index, byte) in reader.take(len)?.iter().enumerate() {
+        if index > 0 {
+            text.push(' ');
+        }
+        let _ = write!(text, "{byte:02x}");
+    }
+    text.push_str(">");
+    Ok(())
+}
+
+fn msgpack_array(reader: &mut Reader, text: &mut String, len: usize) -> Result<(), DecodeError> {
+    text.push('[');
+    for index in 0..len {
+        if index > 0 {
+            text.push_str(", ");
+        }
+        msgpack_value(reader, text)?;
+    }
+    text.push(']');
+    Ok(())
+}
+
+fn msgpack_map(reader: &mut Reader, text: &mut String, len: usize) -> Result<(), DecodeError> {
+    text.push('{');
+    for index in 0..len {
+        if index > 0 {
+            text.push_str(", ");
+        }
+        msgpack_value(reader, text)?;
+        text.push_str(": ");
+        msgpack_value(reader, text)?;
+    }
+    text.push('}');
+    Ok(())
+}
+
+#[derive(Debug)]
+enum FieldType {
+    Bool,
+    Char,
+    Int(usize),
+    Uint(usize),
+    F32,
+    F64,
+    Struct(Vec<Field>),
+}
+
+#[derive(Debug)]
+struct Field {
+    name: String,
+    ty: FieldType,
+    /// The length of array fields.
+    count: Option<usize>,
+}
+
+fn parse_schema(
+    schema: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    depth: usize,
+) -> Result<Vec<Field>, DecodeError> {
+    if depth > MAX_STRUCT_DEPTH {
+        return Err(DecodeError::new("Structs are nested too deeply"));
+    }
+
+    let mut fields = Vec::new();
+    for declaration in schema.split(';').map(str::trim) {
+        if declaration.is_empty() {
+            continue;
+        }
+        // Enum values are only names for integers, which are shown as they are.
+        let declaration = match declaration.strip_prefix("enum") {
+            Some(rest) if rest.trim_start().starts_with('{') => rest
+                .split_once('}')
+                .map(|(_, rest)| rest.trim())
+                .ok_or_else(|| DecodeError::new(format!("Unclosed enum in {declaration:?}")))?,
+            _ => declaration,
+        };
+        if declaration.contains(':') {
+            return Err(DecodeError::new(format!(
+                "Bit-fields are not supported: {declaration:?}"
+            )));
+        }
+
+        let (type_name, rest) = declaration
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| DecodeError::new(format!("Invalid declaration {declaration:?}")))?;
+        let rest = rest.trim();
+        let (name, count) = match rest.split_once('[') {
+            Some((name, count)) => {
+                let count = count
+                    .trim()
+                    .strip_suffix(']')
+                    .and_then(|count| count.trim().parse().ok())
+                    .ok_or_else(|| DecodeError::new(format!("Invalid array in {declaration:?}")))?;
+                (name.trim(), Some(count))
+            }
+            None => (rest, None),
+        };
+
+        let ty = match type_name {
+            "bool" => FieldType::Bool,
+            "char" => FieldType::Char,
+            "int8" => FieldType::Int(1),
+            "int16" => FieldType::Int(2),
+            "int32" => FieldType::Int(4),
+            "int64" => FieldType::Int(8),
+            "uint8" => FieldType::Uint(1),
+            "uint16" => FieldType::Uint(2),
+            "uint32" => FieldType::Uint(4),
+            "uint64" => FieldType::Uint(8),
+            "float" | "float32" => FieldType::F32,
+            "double" | "float64" => FieldType::F64,
+            name => {
+                let schema = lookup(name)
+                    .ok_or_else(|| DecodeError::new(format!("No schema for struct {name}")))?;
+                FieldType::Struct(parse_schema(&schema, lookup, depth + 1)?)
+            }
+        };
+
+        fields.push(Field {
+            name: name.to_string(),
+            ty,
+            count,
+        });
+    }
+    Ok(fields)
+}
+
+/// Returns the size of a struct with `fields` in bytes, failing if it doesn't fit in a `usize`,
+/// e.g. because a schema declares a huge array.
+fn fields_size(fields: &[Field]) -> Result<usize, DecodeError> {
+    fields.iter().try_fold(0usize, |total, field| {
+        let size = match &field.ty {
+            FieldType::Bool | FieldType::Char => 1,
+            FieldType::Int(size) | FieldType::Uint(size) => *size,
+            FieldType::F32 => 4,
+            FieldType::F64 => 8,
+            FieldType::Struct(fields) => fields_size(fields)?,
+        };
+        size.checked_mul(field.count.unwrap_or(1))
+            .and_then(|size| total.checked_add(size))
+            .ok_or_else(|| DecodeError::new(format!("Field {} is too large", field.name)))
+    })
+}
+
+fn struct_value(
+    fields: &[Field],
+    reader: &mut Reader,
+    text: &mut String,
+) -> Result<(), DecodeError> {
+    text.push('{');
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            text.push_str(", ");
+        }
+        let _ = write!(text, "{}: ", field.name);
+        match (field.count, &field.ty) {
+            // Char arrays hold strings, padded with zeros.
+            (Some(count), FieldType::Char) => {
+                let bytes = reader.take(count)?;
+                let end = bytes.iter().position(|&byte| byte == 0).unwrap_or(count);
+                let _ = write!(text, "{:?}", String::from_utf8_lossy(&bytes[..end]));
+            }
+            (Some(count), ty) => {
+                text.push('[');
+                for index in 0..count {
+                    if index > 0 {
+                        text.push_str(", ");
+                    }
+                    field_value(ty, reader, text)?;
+                }
+                text.push(']');
+            }
+            (None, ty) => field_value(ty, reader, text)?,
+        }
+    }
+    text.push('}');
+    Ok(())
+}
+
+fn field_value(ty: &FieldType, reader: &mut Reader, text: &mut String) -> Result<(), DecodeError> {
+    let _ = match ty {
+        FieldType::Bool => write!(text, "{}", reader.u8()? != 0),
+        FieldType::Char => write!(text, "{:?}", char::from(reader.u8()?)),
+        FieldType::Int(size) => {
+            let mut bytes = [0; 8];
+            bytes[..*size].copy_from_slice(reader.take(*size)?);
+            // Sign-extend by shifting the value into the top bytes and back.
+            let shift = 64 - 8 * *size as u32;
+            write!(text, "{}", (i64::from_le_bytes(bytes) << shift) >> shift)
+        }
+        FieldType::Uint(size) => {
+            let mut bytes = [0; 8];
+            bytes[..*size].copy_from_slice(reader.take(*size)?);
+            write!(text, "{}", u64::from_le_bytes(bytes))
+        }
+        FieldType::F32 => write!(text, "{}", f32::from_le_bytes(reader.array()?)),
+        FieldType::F64 => write!(text, "{}", f64::from_le_bytes(reader.array()?)),
+        FieldType::Struct(fields) => return struct_value(fields, reader, text),
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_schemas(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn structs_are_decoded() {
+        let bytes = [1.5f64.to_le_bytes(), (-2.0f64).to_le_bytes()].concat();
+        assert_eq!(
+            decode_struct(&bytes, "double x;double y", false, &no_schemas),
+            Ok("{x: 1.5, y: -2}".to_string())
+        );
+        assert_eq!(
+            decode_struct(&bytes, "double x", true, &no_schemas),
+            Ok("[{x: 1.5}, {x: -2}]".to_string())
+        );
+    }
+
+    #[test]
+    fn nested_structs_are_looked_up() {
+        let lookup = |name: &str| (name == "Point").then(|| "int8 x;uint8 y".to_string());
+        assert_eq!(
+            decode_struct(
+                &[0xff, 0xff, b'a', b'b', 0],
+                "Point p;char name[3]",
+                false,
+                &lookup
+            ),
+            Ok("{p: {x: -1, y: 255}, name: \"ab\"}".to_string())
+        );
+    }
+
+    #[test]
+    fn self_referencing_structs_are_rejected() {
+        let lookup = |_: &str| Some("Loop next".to_string());
+        assert!(decode_struct(&[], "Loop start", false, &lookup).is_err());
+    }
+
+    #[test]
+    fn oversized_arrays_are_rejected() {
+        let schema = format!("double values[{}]", usize::MAX);
+        assert!(decode_struct(&[], &schema, false, &no_schemas).is_err());
+        assert!(decode_struct(&[0; 8], &schema, true, &no_schemas).is_err());
+    }
+
+    #[test]
+    fn wrong_sizes_are_rejected() {
+        assert!(decode_struct(&[0; 7], "double x", false, &no_schemas).is_err());
+        assert!(decode_struct(&[0; 12], "double x", true, &no_schemas).is_err());
+    }
+
+    #[test]
+    fn msgpack_is_decoded() {
+        // {"a": [1, -1, true], "b": nil}
+        let bytes = [0x82, 0xa1, b'a', 0x93, 0x01, 0xff, 0xc3, 0xa1, b'b', 0xc0];
+        assert_eq!(
+            decode_msgpack(&bytes),
+            Ok("{\"a\": [1, -1, true], \"b\": null}".to_string())
+        );
+        assert!(decode_msgpack(&[0x92, 0x01]).is_err());
+        assert!(decode_msgpack(&[0x01, 0x02]).is_err());
+        assert!(decode_msgpack(&[0xc1]).is_err());
+    }
+}
@@ -0,0 +1,76 @@
+//! Turning text typed into the GUI into values to publish.
+
+use lagan::{nt_types::ValueType, Value};
+
+/// A write to a persistent or retained topic, waiting for the user to confirm it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingWrite {
+    pub name: String,
+    pub value: Value,
+}
+
+/// Parses `text` as a value of the given type.
+///
+/// Arrays are written as comma-separated elements, and raw values as hex bytes.
+pub fn parse_value(value_type: ValueType, text: &str) -> Result<Value, String> {
+    let text = text.trim();
+    let value = match value_type {
+        ValueType::Bool => Value::Bool(parse_bool(text)?),
+        ValueType::I64 => Value::I64(parse_number(text)?),
+        ValueType::F32 => Value::F32(parse_number(text)?),
+        ValueType::F64 => Value::F64(parse_number(text)?),
+        ValueType::String => Value::String(text.to_string()),
+        ValueType::Raw => Value::Raw(parse_hex(text)?),
+        ValueType::BoolArray => Value::BoolArray(parse_array(text, parse_bool)?),
+        ValueType::I64Array => Value::I64Array(parse_array(text, parse_number)?),
+        ValueType::F32Array => Value::F32Array(parse_array(text, parse_number)?),
+        ValueType::F64Array => Value::F64Array(parse_array(text, parse_number)?),
+        ValueType::StringArray => {
+            Value::StringArray(parse_array(text, |element| Ok(element.to_string()))?)
+        }
+        ValueType::Unassigned | ValueType::Unknown(_) => {
+            return Err("The topic's type can't be written".to_string())
+        }
+    };
+    Ok(value)
+}
+
+fn parse_bool(text: &str) -> Result<bool, String> {
+    match text.to_ascii_lowercase().as_str() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(format!("{text:?} is not a boolean")),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(text: &str) -> Result<T, String> {
+    text.parse()
+        .map_err(|_| format!("{text:?} is not a number"))
+}
+
+fn parse_array<T>(text: &str, parse: impl Fn(&str) -> Result<T, String>) -> Result<Vec<T>, String> {
+    let text = text
+        .strip_prefix('[')
+        .and_then(|text| text.strip_suffix(']'))
+        .unwrap_or(text);
+    if text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    text.split(',')
+        .map(|element| parse(element.trim()))
+        .collect()
+}
+
+fn parse_hex(text: &str) -> Result<Vec<u8>, String> {
+    let digits: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if digits.len() % 2 != 0 {
+        return Err("Hex bytes need two digits each".to_string());
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|index| {
+            u8::from_str_radix(&digits[index..index + 2], 16)
+                .map_err(|_| format!("{:?} is not a hex byte", &digits[index..index + 2]))
+        })
+        .collect()
+}
@@ -0,0 +1,189 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+
+use lagan::{
+    nt_types::{NetworkTablesInstant, RawValue},
+    Value,
+};
+
+/// The default number of values kept per topic.
+pub const DEFAULT_HISTORY_CAPACITY: usize = 512;
+
+/// A bounded history of the values seen on every tracked topic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct History {
+    capacity: usize,
+    values: HashMap<String, VecDeque<RawValue>>,
+}
+impl Default for History {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_CAPACITY)
+    }
+}
+
+impl History {
+    /// Creates a history that keeps at most `capacity` values per topic.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            values: HashMap::new(),
+        }
+    }
+
+    /// Appends a value to the history of a topic, dropping the oldest value if the history is full.
+    pub fn push(&mut self, name: impl AsRef<str>, value: RawValue) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let values = self.values.entry(name.as_ref().to_owned()).or_default();
+        if values.len() == self.capacity {
+            values.pop_front();
+        }
+        values.push_back(value);
+    }
+
+    /// Returns the history of a topic from oldest to newest.
+    pub fn get(&self, name: &str) -> impl Iterator<Item = &RawValue> {
+        self.values.get(name).into_iter().flatten()
+    }
+
+    /// Returns the numbers of a topic that changed at most `window` before its latest value, from newest to oldest.
+    ///
+    /// Values that aren't integers, floats or doubles are skipped.
+    fn window(&self, name: &str, window: Duration) -> impl Iterator<Item = f64> + '_ {
+        let values = self.values.get(name);
+        let latest = values
+            .and_then(VecDeque::back)
+            .map(|value| value.last_change);
+        values
+            .into_iter()
+            .flat_map(|values| values.iter().rev())
+            .take_while(move |value| {
+                latest.is_some_and(|latest| {
+                    latest.saturating_duration_since(value.last_change) <= window
+                })
+            })
+            .filter_map(|value| value.data.as_f64())
+    }
+
+    /// Returns the mean of the numbers of a topic within `window` of its latest value,
+    /// e.g. to smooth a noisy sensor before displaying it.
+    pub fn mean(&self, name: &str, window: Duration) -> Option<f64> {
+        let (sum, count) = self
+            .window(name, window)
+            .fold((0.0, 0usize), |(sum, count), value| {
+                (sum + value, count + 1)
+            });
+        (count > 0).then(|| sum / count as f64)
+    }
+
+    /// Returns the smallest number of a topic within `window` of its latest value.
+    pub fn min(&self, name: &str, window: Duration) -> Option<f64> {
+        self.window(name, window).reduce(f64::min)
+    }
+
+    /// Returns the largest number of a topic within `window` of its latest value.
+    pub fn max(&self, name: &str, window: Duration) -> Option<f64> {
+        self.window(name, window).reduce(f64::max)
+    }
+
+    /// Returns the value a topic had at `time`, interpolated between the values before and after it
+    /// with [`Value::lerp_between`].
+    ///
+    /// Returns `None` if `time` is outside of the history, or the values around it can't be interpolated.
+    pub fn value_at(&self, name: &str, time: NetworkTablesInstant) -> Option<Value> {
+        let values = self.values.get(name)?;
+        let next = values.partition_point(|value| value.last_change < time);
+        let after = values.get(next)?;
+        if after.last_change == time {
+            return Some(after.data.clone());
+        }
+
+        let before = values.get(next.checked_sub(1)?)?;
+        let elapsed = time.saturating_duration_since(before.last_change);
+        let span = after
+            .last_change
+            .saturating_duration_since(before.last_change);
+        Value::lerp_between(
+            &before.data,
+            &after.data,
+            elapsed.as_secs_f64() / span.as_secs_f64(),
+        )
+    }
+
+    pub fn remove(&mut self, name: impl AsRef<str>) {
+        self.values.remove(name.as_ref());
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(data: Value, millis: u64) -> RawValue {
+        RawValue {
+            data,
+            last_change: NetworkTablesInstant::from_micros(millis * 1000),
+            server_time: NetworkTablesInstant::from_micros(millis * 1000),
+        }
+    }
+
+    fn history(values: &[(f64, u64)]) -> History {
+        let mut history = History::default();
+        for &(data, millis) in values {
+            history.push("/x", value(Value::F64(data), millis));
+        }
+        history
+    }
+
+    #[test]
+    fn full_histories_drop_the_oldest_value() {
+        let mut history = History::new(2);
+        for millis in 0..3 {
+            history.push("/x", value(Value::I64(millis as i64), millis));
+        }
+        let values = history.get("/x").map(|value| value.data.clone());
+        assert_eq!(values.collect::<Vec<_>>(), [Value::I64(1), Value::I64(2)]);
+        assert_eq!(history.get("/y").count(), 0);
+    }
+
+    #[test]
+    fn empty_histories_keep_nothing() {
+        let mut history = History::new(0);
+        history.push("/x", value(Value::F64(1.0), 0));
+        assert_eq!(history.get("/x").count(), 0);
+        assert_eq!(history.mean("/x", Duration::from_secs(1)), None);
+    }
+
+    #[test]
+    fn statistics_only_cover_the_window() {
+        let mut history = history(&[(100.0, 0), (1.0, 900), (3.0, 950), (2.0, 1000)]);
+        history.push("/x", value(Value::String("skipped".to_string()), 1000));
+        let window = Duration::from_millis(100);
+        assert_eq!(history.mean("/x", window), Some(2.0));
+        assert_eq!(history.min("/x", window), Some(1.0));
+        assert_eq!(history.max("/x", window), Some(3.0));
+        assert_eq!(history.max("/x", Duration::from_secs(1)), Some(100.0));
+    }
+
+    #[test]
+    fn values_are_interpolated() {
+        let history = history(&[(0.0, 0), (10.0, 100)]);
+        let at = |millis| history.value_at("/x", NetworkTablesInstant::from_micros(millis * 1000));
+        assert_eq!(at(0), Some(Value::F64(0.0)));
+        assert_eq!(at(25), Some(Value::F64(2.5)));
+        assert_eq!(at(100), Some(Value::F64(10.0)));
+        assert_eq!(at(101), None);
+    }
+}
@@ -0,0 +1,126 @@
+//! Detail views of array and raw values, which don't fit on a single line.
+
+use std::fmt::Write;
+
+use lagan::{schema::TopicKind, Value};
+
+use crate::decode::{decode_json, decode_msgpack, decode_struct, DecodeError};
+
+/// The number of bytes shown per line of a hex dump.
+pub const HEX_DUMP_WIDTH: usize = 16;
+
+/// A line of a hex dump.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HexLine {
+    /// The offset of the first byte of the line.
+    pub offset: usize,
+    /// The bytes as space-separated hex, padded to [`HEX_DUMP_WIDTH`] bytes.
+    pub hex: String,
+    /// The bytes as ASCII, with unprintable bytes shown as `.`.
+    pub ascii: String,
+}
+
+/// Splits `bytes` into lines of [`HEX_DUMP_WIDTH`] bytes.
+pub fn hex_dump(bytes: &[u8]) -> Vec<HexLine> {
+    bytes
+        .chunks(HEX_DUMP_WIDTH)
+        .enumerate()
+        .map(|(line, chunk)| {
+            let mut hex = String::with_capacity(HEX_DUMP_WIDTH * 3);
+            for index in 0..HEX_DUMP_WIDTH {
+                if index > 0 {
+                    hex.push(' ');
+                }
+                match chunk.get(index) {
+                    Some(byte) => {
+                        let _ = write!(hex, "{byte:02x}");
+                    }
+                    None => hex.push_str("  "),
+                }
+            }
+            let ascii = chunk
+                .iter()
+                .map(|&byte| {
+                    if byte.is_ascii_graphic() || byte == b' ' {
+                        char::from(byte)
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+
+            HexLine {
+                offset: line * HEX_DUMP_WIDTH,
+                hex,
+                ascii,
+            }
+        })
+        .collect()
+}
+
+/// Formats every element of an array value, or returns `None` if `value` isn't an array.
+pub fn array_elements(value: &Value) -> Option<Vec<String>> {
+    fn format<T: std::fmt::Debug>(values: &[T]) -> Vec<String> {
+        values.iter().map(|value| format!("{value:?}")).collect()
+    }
+
+    match value {
+        Value::BoolArray(values) => Some(format(values)),
+        Value::I64Array(values) => Some(format(values)),
+        Value::F32Array(values) => Some(format(values)),
+        Value::F64Array(values) => Some(format(values)),
+        Value::StringArray(values) => Some(format(values)),
+        _ => None,
+    }
+}
+
+/// A format to decode a raw value as, instead of showing it as a hex dump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RawDecoding {
+    /// A WPILib struct, using the schema announced for the topic's struct type.
+    Struct,
+    MessagePack,
+    Json,
+}
+
+impl RawDecoding {
+    pub const ALL: [Self; 3] = [Self::Struct, Self::MessagePack, Self::Json];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Struct => "Struct",
+            Self::MessagePack => "MessagePack",
+            Self::Json => "JSON",
+        }
+    }
+}
+
+/// Decodes the raw value of a topic of the given kind.
+///
+/// Struct schemas are resolved through `schema`, which returns the schema of a struct by name.
+pub fn decode(
+    bytes: &[u8],
+    decoding: RawDecoding,
+    kind: &TopicKind,
+    schema: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, DecodeError> {
+    match decoding {
+        RawDecoding::Struct => {
+            let TopicKind::Struct(struct_type) = kind else {
+                return Err(DecodeError {
+                    message: "The topic doesn't hold structs".to_string(),
+                });
+            };
+            let struct_schema = struct_type
+                .schema
+                .clone()
+                .or_else(|| schema(&struct_type.name))
+                .ok_or_else(|| DecodeError {
+                    message: format!("No schema for struct {}", struct_type.name),
+                })?;
+            decode_struct(bytes, &struct_schema, struct_type.array, schema)
+        }
+        RawDecoding::MessagePack => decode_msgpack(bytes),
+        RawDecoding::Json => decode_json(bytes),
+    }
+}
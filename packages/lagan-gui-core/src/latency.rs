@@ -0,0 +1,74 @@
+use std::{collections::VecDeque, time::Duration};
+
+use lagan::{connection::TimeSync, nt_types::NetworkTablesInstant};
+
+/// The default number of time sync measurements kept.
+pub const DEFAULT_LATENCY_CAPACITY: usize = 300;
+
+/// A time sync measurement and when it was received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LatencySample {
+    /// When the measurement was received.
+    pub time: NetworkTablesInstant,
+    /// Half of the round trip time to the server.
+    pub rtt2: Duration,
+    /// Added to local time to get the estimated server time, in microseconds.
+    pub server_time_offset: i64,
+}
+
+/// A bounded history of the time sync measurements of a client, for spotting network degradation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyHistory {
+    capacity: usize,
+    samples: VecDeque<LatencySample>,
+}
+impl Default for LatencyHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_LATENCY_CAPACITY)
+    }
+}
+
+impl LatencyHistory {
+    /// Creates a history that keeps at most `capacity` measurements.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Appends a measurement, dropping the oldest one if the history is full.
+    ///
+    /// Invalid measurements, sent when the client disconnects, are ignored.
+    pub fn push(&mut self, time_sync: TimeSync) {
+        if !time_sync.valid || self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(LatencySample {
+            time: NetworkTablesInstant::now(),
+            rtt2: time_sync.rtt2,
+            server_time_offset: time_sync.server_time_offset,
+        });
+    }
+
+    /// Returns every measurement, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = &LatencySample> {
+        self.samples.iter()
+    }
+
+    pub fn latest(&self) -> Option<&LatencySample> {
+        self.samples.back()
+    }
+
+    /// Returns the largest round trip time divided by 2 in the history.
+    pub fn max_rtt2(&self) -> Option<Duration> {
+        self.samples.iter().map(|sample| sample.rtt2).max()
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+}
@@ -0,0 +1,310 @@
+//! The layout file, which keeps the dashboard between runs of the GUI.
+//!
+//! Every line holds a widget as tab-separated fields: its kind, its topic, then `key=value` settings,
+//! e.g. the fields `gauge`, `/Shooter/Speed`, `cell=0,0,2,1`, `min=0`, `max=5000` and `warning=4000`.
+//! The kinds are `boolean`, `gauge`, `value` and `alerts`, whose topic is the alert group.
+//! Every kind takes a `cell` setting of the column, row, width and height of the widget on the grid;
+//! widgets without one are put in the first free cell.
+//!
+//! Tabs don't appear in topic names in practice, unlike spaces. Empty lines and lines starting with `#` are ignored.
+
+use std::{fmt::Write, fs, path::Path};
+
+use crate::widgets::{AlertBanner, BooleanIndicator, Gauge, ValueDisplay, Widget};
+
+/// The number of columns of the dashboard grid. It has as many rows as its widgets need.
+pub const GRID_COLUMNS: usize = 8;
+
+/// A rectangle of cells on the dashboard grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub column: usize,
+    pub row: usize,
+    /// The number of columns covered, at least 1.
+    pub width: usize,
+    /// The number of rows covered, at least 1.
+    pub height: usize,
+}
+
+impl Cell {
+    /// A single cell.
+    pub fn at(column: usize, row: usize) -> Self {
+        Self {
+            column,
+            row,
+            width: 1,
+            height: 1,
+        }
+    }
+
+    pub fn overlaps(&self, other: &Cell) -> bool {
+        self.column < other.column + other.width
+            && other.column < self.column + self.width
+            && self.row < other.row + other.height
+            && other.row < self.row + self.height
+    }
+
+    /// Whether the cell is within the columns of the grid and covers at least one cell.
+    pub fn is_valid(&self) -> bool {
+        self.width > 0 && self.height > 0 && self.column + self.width <= GRID_COLUMNS
+    }
+}
+
+/// A widget and where it is on the grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacedWidget {
+    pub widget: Widget,
+    pub cell: Cell,
+}
+
+/// The widgets on the dashboard tab.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Layout {
+    pub widgets: Vec<PlacedWidget>,
+}
+
+impl Layout {
+    /// Parses the contents of a layout file.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut layout = Self::default();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (widget, cell) =
+                parse_widget(line).map_err(|err| format!("Line {}: {err}", index + 1))?;
+            let cell = cell.unwrap_or_else(|| layout.free_cell(1, 1));
+            layout.widgets.push(PlacedWidget { widget, cell });
+        }
+        Ok(layout)
+    }
+
+    /// Formats the layout as the contents of a layout file.
+    pub fn format(&self) -> String {
+        let mut text = String::new();
+        for PlacedWidget { widget, cell } in &self.widgets {
+            let (kind, topic) = match widget {
+                Widget::Boolean(indicator) => ("boolean", &indicator.topic),
+                Widget::Gauge(gauge) => ("gauge", &gauge.topic),
+                Widget::Value(display) => ("value", &display.topic),
+                Widget::Alerts(banner) => ("alerts", &banner.group),
+            };
+            let _ = write!(
+                text,
+                "{kind}\t{topic}\tcell={},{},{},{}",
+                cell.column, cell.row, cell.width, cell.height
+            );
+            match widget {
+                Widget::Boolean(indicator) => {
+                    let _ = write!(
+                        text,
+                        "\ttrue_color={}\tfalse_color={}",
+                        indicator.true_color, indicator.false_color
+                    );
+                }
+                Widget::Gauge(gauge) => {
+                    let _ = write!(text, "\tmin={}\tmax={}", gauge.min, gauge.max);
+                    if let Some(warning) = gauge.warning {
+                        let _ = write!(text, "\twarning={warning}");
+                    }
+                    if let Some(critical) = gauge.critical {
+                        let _ = write!(text, "\tcritical={critical}");
+                    }
+                }
+                Widget::Value(_) | Widget::Alerts(_) => {}
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Whether `cell` is on the grid and free, ignoring the widget at index `ignore`, e.g. the one being moved.
+    pub fn fits(&self, cell: &Cell, ignore: Option<usize>) -> bool {
+        cell.is_valid()
+            && self
+                .widgets
+                .iter()
+                .enumerate()
+                .all(|(index, placed)| Some(index) == ignore || !placed.cell.overlaps(cell))
+    }
+
+    /// Returns the first free cell of the given size, going row by row.
+    pub fn free_cell(&self, width: usize, height: usize) -> Cell {
+        let width = width.clamp(1, GRID_COLUMNS);
+        let height = height.max(1);
+        (0..)
+            .flat_map(|row| (0..=GRID_COLUMNS - width).map(move |column| (column, row)))
+            .map(|(column, row)| Cell {
+                column,
+                row,
+                width,
+                height,
+            })
+            .find(|cell| self.fits(cell, None))
+            .expect("the grid has unlimited rows")
+    }
+
+    /// Returns the number of rows the widgets cover.
+    pub fn rows(&self) -> usize {
+        self.widgets
+            .iter()
+            .map(|placed| placed.cell.row + placed.cell.height)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Reads a layout file, returning an empty layout if the file doesn't exist yet.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        match fs::read_to_string(path.as_ref()) {
+            Ok(text) => Self::parse(&text),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(format!("Couldn't read {}: {err}", path.as_ref().display())),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        fs::write(path.as_ref(), self.format())
+            .map_err(|err| format!("Couldn't write {}: {err}", path.as_ref().display()))
+    }
+}
+
+fn parse_widget(line: &str) -> Result<(Widget, Option<Cell>), String> {
+    let mut fields = line.split('\t');
+    let kind = fields.next().unwrap_or_default().trim();
+    let topic = fields
+        .next()
+        .filter(|topic| !topic.is_empty())
+        .ok_or("Missing topic")?;
+    let settings = fields
+        .map(|field| {
+            field
+                .split_once('=')
+                .ok_or_else(|| format!("{field:?} is not a key=value setting"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut widget = match kind {
+        "boolean" => Widget::Boolean(BooleanIndicator::new(topic)),
+        "gauge" => Widget::Gauge(Gauge::new(topic)),
+        "value" => Widget::Value(ValueDisplay::new(topic)),
+        "alerts" => Widget::Alerts(AlertBanner::new(topic)),
+        _ => return Err(format!("Unknown widget kind {kind:?}")),
+    };
+    let mut cell = None;
+    for (key, value) in settings {
+        match (&mut widget, key) {
+            (_, "cell") => cell = Some(parse_cell(value)?),
+            (Widget::Boolean(indicator), "true_color") => indicator.true_color = value.to_string(),
+            (Widget::Boolean(indicator), "false_color") => {
+                indicator.false_color = value.to_string()
+            }
+            (Widget::Gauge(gauge), "min") => gauge.min = parse_number(value)?,
+            (Widget::Gauge(gauge), "max") => gauge.max = parse_number(value)?,
+            (Widget::Gauge(gauge), "warning") => gauge.warning = Some(parse_number(value)?),
+            (Widget::Gauge(gauge), "critical") => gauge.critical = Some(parse_number(value)?),
+            _ => return Err(format!("Unknown {kind} setting {key:?}")),
+        }
+    }
+    Ok((widget, cell))
+}
+
+fn parse_cell(text: &str) -> Result<Cell, String> {
+    let numbers = text
+        .split(',')
+        .map(|number| number.trim().parse())
+        .collect::<Result<Vec<usize>, _>>()
+        .map_err(|_| format!("{text:?} is not a cell"))?;
+    let [column, row, width, height] = numbers[..] else {
+        return Err(format!(
+            "{text:?} is not a cell, expected column,row,width,height"
+        ));
+    };
+    let cell = Cell {
+        column,
+        row,
+        width,
+        height,
+    };
+    if !cell.is_valid() {
+        return Err(format!("{text:?} doesn't fit on the grid"));
+    }
+    Ok(cell)
+}
+
+fn parse_number(text: &str) -> Result<f64, String> {
+    text.trim()
+        .parse()
+        .map_err(|_| format!("{text:?} is not a number"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layouts_round_trip() {
+        let text = "gauge\t/Shooter/Speed\tcell=0,0,2,1\tmin=0\tmax=5000\twarning=4000\n\
+                    boolean\t/Intake/Has Note\tcell=2,0,1,1\ttrue_color=#ffffff\tfalse_color=#000000\n\
+                    alerts\t/SmartDashboard/Alerts\tcell=0,1,8,1\n";
+        let layout = Layout::parse(text).unwrap();
+        assert_eq!(layout.widgets.len(), 3);
+        assert_eq!(layout.format(), text);
+        assert_eq!(Layout::parse(&layout.format()), Ok(layout));
+    }
+
+    #[test]
+    fn comments_and_empty_lines_are_ignored() {
+        let layout = Layout::parse("# The dashboard\n\n  \nvalue\t/Pose\tcell=1,1,1,1\n").unwrap();
+        assert_eq!(
+            layout.widgets,
+            vec![PlacedWidget {
+                widget: Widget::Value(ValueDisplay::new("/Pose")),
+                cell: Cell::at(1, 1),
+            }]
+        );
+    }
+
+    #[test]
+    fn widgets_without_a_cell_fill_the_first_free_one() {
+        let layout = Layout::parse("value\t/A\tcell=0,0,7,1\nvalue\t/B\nvalue\t/C\n").unwrap();
+        assert_eq!(layout.widgets[1].cell, Cell::at(7, 0));
+        assert_eq!(layout.widgets[2].cell, Cell::at(0, 1));
+        assert_eq!(layout.rows(), 2);
+        assert_eq!(
+            layout.free_cell(2, 1),
+            Cell {
+                column: 1,
+                row: 1,
+                width: 2,
+                height: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_lines_are_reported() {
+        for line in [
+            "slider\t/A",
+            "value",
+            "value\t/A\tcell=0,0",
+            "value\t/A\tcell=7,0,2,1",
+            "value\t/A\tcell=0,0,0,1",
+            "value\t/A\tmin=0",
+            "gauge\t/A\tmax=fast",
+            "gauge\t/A\tmax",
+        ] {
+            assert!(Layout::parse(line).is_err(), "{line:?} was accepted");
+        }
+        assert!(Layout::parse("value\t/A\nslider\t/B")
+            .unwrap_err()
+            .starts_with("Line 2:"));
+    }
+
+    #[test]
+    fn fits_ignores_the_moved_widget() {
+        let layout = Layout::parse("value\t/A\tcell=0,0,2,2\n").unwrap();
+        let cell = Cell::at(1, 1);
+        assert!(!layout.fits(&cell, None));
+        assert!(layout.fits(&cell, Some(0)));
+    }
+}
@@ -0,0 +1,747 @@
+//! Frontend-agnostic state management for the Lagan GUI.
+//!
+//! All NetworkTables state lives in [`Core`], which is only ever modified by sending it a [`Message`].
+//! Frontends render what [`Core`] exposes and translate user input back into messages,
+//! so the same core can back the freya frontend or any other one.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::SocketAddr,
+    path::PathBuf,
+    time::Duration,
+};
+
+use decode::DecodeError;
+use edit::PendingWrite;
+use history::History;
+use inspect::RawDecoding;
+use lagan::{
+    annotation::Annotations,
+    channel::{Event, EventMask, EventReceiver},
+    client::Client,
+    conventions::sim::SimInput,
+    nt_types::{RawValue, ValueType},
+    schema::{TopicKind, SCHEMA_PREFIX},
+    server::Server,
+    stats::{TopicStats, UpdateStats},
+    Instance, NetworkTablesVersion, Value,
+};
+use latency::LatencyHistory;
+use layout::{Cell, Layout, PlacedWidget};
+use mirror::Mirror;
+use recording::Recorder;
+use replay::Replay;
+use schemas::Schema;
+use sim::{SimControl, SimPanel};
+use widgets::Widget;
+
+pub mod decode;
+pub mod edit;
+pub mod history;
+pub mod inspect;
+pub mod latency;
+pub mod layout;
+pub mod mirror;
+pub mod palette;
+pub mod recording;
+pub mod replay;
+pub mod schemas;
+pub mod sim;
+pub mod theme;
+pub mod widgets;
+
+/// How long a topic may go without updates before frontends should show it as stale.
+pub const STALE_AFTER: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum NetworkTablesState {
+    #[default]
+    None,
+    Client(NetworkTablesVersion),
+    Server,
+}
+
+/// Settings used when the core starts a client or server.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConnectionSettings {
+    /// The address clients connect to.
+    pub address: SocketAddr,
+    /// The file servers persist their data to.
+    pub persist_filename: String,
+    /// Refuses every write to the connection, so values can't be changed by accident during a match.
+    pub read_only: bool,
+}
+impl Default for ConnectionSettings {
+    fn default() -> Self {
+        Self {
+            address: "127.0.0.1:5810".parse().unwrap(),
+            persist_filename: "networktables.json".to_string(),
+            read_only: false,
+        }
+    }
+}
+
+/// The running NetworkTables instance owned by the core.
+#[derive(Debug)]
+pub enum Connection {
+    Client(Client),
+    Server(Server),
+}
+impl Connection {
+    fn start(state: NetworkTablesState, settings: &ConnectionSettings) -> Option<Self> {
+        match state {
+            NetworkTablesState::None => None,
+            NetworkTablesState::Client(version) => Some(Self::Client(
+                Client::builder()
+                    .address(settings.address)
+                    .version(version)
+                    .build(),
+            )),
+            // The old server is dropped before a new one is started, so starting it only fails
+            // if something else in this process runs a server.
+            NetworkTablesState::Server => Server::builder()
+                .persist_filename(&settings.persist_filename)
+                .build()
+                .ok()
+                .map(Self::Server),
+        }
+    }
+
+    /// Receives every value update, so update rates count the updates between polls too.
+    ///
+    /// Clients also receive their time sync measurements.
+    fn events(&self) -> EventReceiver {
+        match self {
+            Self::Client(client) => {
+                client.event_channel(EventMask::VALUE | EventMask::TIME_SYNC, [""])
+            }
+            Self::Server(server) => server.event_channel(EventMask::VALUE, [""]),
+        }
+    }
+
+    fn read(&self, name: &str) -> RawValue {
+        match self {
+            Self::Client(client) => client.entry(name).raw_value(),
+            Self::Server(server) => server.entry(name).raw_value(),
+        }
+    }
+
+    fn annotations(&self, name: &str) -> Annotations {
+        match self {
+            Self::Client(client) => client.topic(name).annotations(),
+            Self::Server(server) => server.topic(name).annotations(),
+        }
+    }
+
+    fn kind(&self, name: &str) -> TopicKind {
+        let type_string = match self {
+            Self::Client(client) => client.topic(name).value_type_string(),
+            Self::Server(server) => server.topic(name).value_type_string(),
+        };
+        TopicKind::parse(name, &type_string.unwrap_or_default())
+    }
+
+    /// Whether overwriting a topic could clobber configuration that outlives the robot program.
+    fn is_protected(&self, name: &str) -> bool {
+        let (persistent, retained) = match self {
+            Self::Client(client) => {
+                let topic = client.topic(name);
+                (topic.is_persistent(), topic.is_retained())
+            }
+            Self::Server(server) => {
+                let topic = server.topic(name);
+                (topic.is_persistent(), topic.is_retained())
+            }
+        };
+        persistent || retained
+    }
+
+    fn write(&self, name: &str, value: Value) -> Result<(), String> {
+        match self {
+            Self::Client(client) => client.entry(name).set_value(value),
+            Self::Server(server) => server.entry(name).set_value(value),
+        }
+        .map_err(|err| err.to_string())
+    }
+
+    fn publish_sim(&self, prefix: &str, control: &SimControl) -> Result<(), String> {
+        match self {
+            Self::Client(client) => control.publish(&SimInput::with_prefix(client, prefix)),
+            Self::Server(server) => control.publish(&SimInput::with_prefix(server, prefix)),
+        }
+        .map_err(|err| err.to_string())
+    }
+
+    /// Returns the name of every topic starting with `prefix`.
+    fn topic_names(&self, prefix: &str) -> Vec<String> {
+        match self {
+            Self::Client(client) => client
+                .topics_iter(prefix)
+                .map(|info| info.name.to_string())
+                .collect(),
+            Self::Server(server) => server
+                .topics_iter(prefix)
+                .map(|info| info.name.to_string())
+                .collect(),
+        }
+    }
+
+    /// Returns the schema published for the struct with the given name.
+    fn struct_schema(&self, struct_name: &str) -> Option<String> {
+        match self
+            .read(&format!("{SCHEMA_PREFIX}struct:{struct_name}"))
+            .data
+        {
+            Value::Raw(schema) => String::from_utf8(schema).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Every way a frontend can interact with the [`Core`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    /// Stops the current instance or replay and starts a new instance in the given state.
+    SetState(NetworkTablesState),
+    /// Changes the settings used the next time an instance is started.
+    SetConnectionSettings(ConnectionSettings),
+    /// Starts mirroring the topic with the given name.
+    Track(String),
+    /// Stops mirroring the topic with the given name and forgets its history.
+    Untrack(String),
+    /// Tracks the topic with the given name and lists it before unpinned topics.
+    Pin(String),
+    /// Lists the topic with the given name among the unpinned topics again.
+    Unpin(String),
+    /// Reads the latest value of every tracked topic, or while replaying, its value at the replay position.
+    Poll,
+    /// Stops the current instance and replays the data log at the given path instead.
+    OpenLog(PathBuf),
+    /// Moves the replay to the given time in the log, see [`Replay::position`].
+    Seek(Duration),
+    /// Starts recording every value change seen while polling.
+    StartRecording,
+    /// Stops the active recording, if any.
+    StopRecording,
+    /// Clears the value history of every topic.
+    ClearHistory,
+    /// Turns read-only mode on or off, see [`ConnectionSettings::read_only`].
+    SetReadOnly(bool),
+    /// Parses the text as a value of the topic's type and writes it.
+    ///
+    /// Writes to persistent or retained topics wait for [`Message::ConfirmWrite`].
+    Write {
+        name: String,
+        value: String,
+    },
+    /// Performs the pending write.
+    ConfirmWrite,
+    /// Drops the pending write.
+    CancelWrite,
+    /// Loads the dashboard from a layout file, and saves every later change to the dashboard to it.
+    OpenLayout(PathBuf),
+    /// Adds a widget to the first free cell of the dashboard and tracks its topics.
+    AddWidget(Widget),
+    /// Adds a widget to the dashboard at the given cell, unless the cell is taken.
+    PlaceWidget {
+        widget: Widget,
+        cell: Cell,
+    },
+    /// Moves or resizes the widget at the given index, unless the new cell is taken.
+    MoveWidget {
+        index: usize,
+        cell: Cell,
+    },
+    /// Replaces the widget at the given index, e.g. to change its settings.
+    UpdateWidget {
+        index: usize,
+        widget: Widget,
+    },
+    RemoveWidget(usize),
+    /// Moves the simulation inputs to a new prefix and publishes every one of them there.
+    SetSimPrefix(String),
+    /// Adds an input to the simulation panel and publishes it.
+    AddSimControl(SimControl),
+    /// Replaces the simulation input at the given index, e.g. to change its value, and publishes it.
+    UpdateSimControl {
+        index: usize,
+        control: SimControl,
+    },
+    /// Removes the simulation input at the given index. Its topics keep their last value.
+    RemoveSimControl(usize),
+}
+
+/// Headless GUI state.
+#[derive(Debug, Default)]
+pub struct Core {
+    state: NetworkTablesState,
+    settings: ConnectionSettings,
+    /// Declared before the connection so that it is dropped first.
+    events: Option<EventReceiver>,
+    connection: Option<Connection>,
+    tracked: BTreeSet<String>,
+    /// Tracked topics listed before the others.
+    pinned: BTreeSet<String>,
+    /// The name of every topic on the connection, as of the last poll.
+    topics: BTreeSet<String>,
+    mirror: Mirror,
+    /// The unit and description of every tracked topic, as of the last poll.
+    annotations: BTreeMap<String, Annotations>,
+    /// What kind of data every tracked topic holds, as of the last poll.
+    kinds: BTreeMap<String, TopicKind>,
+    stats: UpdateStats,
+    /// Every schema under [`SCHEMA_PREFIX`] and the value it was formatted from, as of the last poll.
+    schemas: BTreeMap<String, (Vec<u8>, Schema)>,
+    latency: LatencyHistory,
+    pending_write: Option<PendingWrite>,
+    /// Why the last write failed, until the next write succeeds.
+    write_error: Option<String>,
+    history: History,
+    recorder: Recorder,
+    layout: Layout,
+    /// The layout file changes to the dashboard are saved to.
+    layout_path: Option<PathBuf>,
+    /// Why the layout file couldn't be loaded or saved, until it next succeeds.
+    layout_error: Option<String>,
+    /// The data log driving the core instead of a connection.
+    replay: Option<Replay>,
+    /// Why the last data log couldn't be opened, until one is.
+    replay_error: Option<String>,
+    /// The inputs published for simulated robot code.
+    sim: SimPanel,
+}
+
+impl Core {
+    pub fn new(settings: ConnectionSettings) -> Self {
+        Self {
+            settings,
+            ..Default::default()
+        }
+    }
+
+    /// Applies a message to the core.
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::SetState(state) => {
+                if state == self.state && self.replay.is_none() {
+                    return;
+                }
+                self.replay = None;
+                // The old instance has to be stopped before a server can be started on the same ports.
+                self.events = None;
+                self.connection = None;
+                self.connection = Connection::start(state, &self.settings);
+                self.events = self.connection.as_ref().map(Connection::events);
+                self.stats = UpdateStats::default();
+                self.latency.clear();
+                self.state = state;
+                self.mirror.clear();
+                self.annotations.clear();
+                self.kinds.clear();
+                self.topics.clear();
+                self.schemas.clear();
+                self.pending_write = None;
+                // Simulated robot code shouldn't lose its inputs because the GUI reconnected.
+                self.publish_sim(0..self.sim.controls.len());
+            }
+            Message::SetConnectionSettings(settings) => self.settings = settings,
+            Message::Track(name) => {
+                self.tracked.insert(name);
+            }
+            Message::Untrack(name) => {
+                self.mirror.remove(&name);
+                self.annotations.remove(&name);
+                self.kinds.remove(&name);
+                self.history.remove(&name);
+                self.pinned.remove(&name);
+                self.tracked.remove(&name);
+            }
+            Message::Pin(name) => {
+                self.tracked.insert(name.clone());
+                self.pinned.insert(name);
+            }
+            Message::Unpin(name) => {
+                self.pinned.remove(&name);
+            }
+            Message::Poll => self.poll(),
+            Message::OpenLog(path) => match Replay::open(path) {
+                Ok(replay) => {
+                    self.update(Message::SetState(NetworkTablesState::None));
+                    self.replay = Some(replay);
+                    self.replay_error = None;
+                    self.poll();
+                }
+                Err(err) => self.replay_error = Some(err),
+            },
+            Message::Seek(position) => {
+                if let Some(replay) = &mut self.replay {
+                    replay.seek(position);
+                    self.poll();
+                }
+            }
+            Message::StartRecording => self.recorder.start(),
+            Message::StopRecording => self.recorder.stop(),
+            Message::ClearHistory => self.history.clear(),
+            Message::SetReadOnly(read_only) => {
+                self.settings.read_only = read_only;
+                // A write confirmed after turning read-only mode on would still clobber the topic.
+                if read_only {
+                    self.pending_write = None;
+                }
+            }
+            Message::Write { name, value } => self.write(name, &value),
+            Message::ConfirmWrite => {
+                if let Some(PendingWrite { name, value }) = self.pending_write.take() {
+                    self.finish_write(&name, value);
+                }
+            }
+            Message::CancelWrite => self.pending_write = None,
+            Message::OpenLayout(path) => {
+                match Layout::load(&path) {
+                    Ok(layout) => {
+                        self.layout = layout;
+                        self.layout_error = None;
+                    }
+                    Err(err) => self.layout_error = Some(err),
+                }
+                self.layout_path = Some(path);
+                self.track_widgets();
+            }
+            Message::AddWidget(widget) => {
+                let cell = self.layout.free_cell(1, 1);
+                self.layout.widgets.push(PlacedWidget { widget, cell });
+                self.layout_changed();
+            }
+            Message::PlaceWidget { widget, cell } => {
+                if self.layout.fits(&cell, None) {
+                    self.layout.widgets.push(PlacedWidget { widget, cell });
+                    self.layout_changed();
+                }
+            }
+            Message::MoveWidget { index, cell } => {
+                if index < self.layout.widgets.len() && self.layout.fits(&cell, Some(index)) {
+                    self.layout.widgets[index].cell = cell;
+                    self.layout_changed();
+                }
+            }
+            Message::UpdateWidget { index, widget } => {
+                if let Some(placed) = self.layout.widgets.get_mut(index) {
+                    placed.widget = widget;
+                    self.layout_changed();
+                }
+            }
+            Message::RemoveWidget(index) => {
+                if index < self.layout.widgets.len() {
+                    self.layout.widgets.remove(index);
+                    self.layout_changed();
+                }
+            }
+            Message::SetSimPrefix(prefix) => {
+                self.sim.prefix = prefix;
+                self.publish_sim(0..self.sim.controls.len());
+            }
+            Message::AddSimControl(control) => {
+                self.sim.controls.push(control);
+                self.publish_sim(self.sim.controls.len() - 1..self.sim.controls.len());
+            }
+            Message::UpdateSimControl { index, control } => {
+                if let Some(old) = self.sim.controls.get_mut(index) {
+                    *old = control;
+                    self.publish_sim(index..index + 1);
+                }
+            }
+            Message::RemoveSimControl(index) => {
+                if index < self.sim.controls.len() {
+                    self.sim.controls.remove(index);
+                }
+            }
+        }
+    }
+
+    /// Tracks the topics of every widget on the dashboard.
+    fn track_widgets(&mut self) {
+        for placed in &self.layout.widgets {
+            self.tracked.extend(placed.widget.topics());
+        }
+    }
+
+    fn layout_changed(&mut self) {
+        self.track_widgets();
+        if let Some(path) = &self.layout_path {
+            self.layout_error = self.layout.save(path).err();
+        }
+    }
+
+    fn write(&mut self, name: String, text: &str) {
+        let Some(connection) = &self.connection else {
+            return;
+        };
+        if self.settings.read_only {
+            self.write_error = Some("Read-only mode is on".to_string());
+            return;
+        }
+
+        let value_type = match self.mirror.get(&name) {
+            Some(value) => value.data.value_type(),
+            None => ValueType::Unassigned,
+        };
+        let value = match edit::parse_value(value_type, text) {
+            Ok(value) => value,
+            Err(err) => {
+                self.write_error = Some(err);
+                return;
+            }
+        };
+
+        if connection.is_protected(&name) {
+            self.pending_write = Some(PendingWrite { name, value });
+        } else {
+            self.finish_write(&name, value);
+        }
+    }
+
+    fn finish_write(&mut self, name: &str, value: Value) {
+        if let Some(connection) = &self.connection {
+            self.write_error = connection.write(name, value).err();
+        }
+    }
+
+    /// Publishes the simulation inputs in `indices`, unless read-only mode is on.
+    fn publish_sim(&mut self, indices: std::ops::Range<usize>) {
+        let Some(connection) = &self.connection else {
+            return;
+        };
+        if self.settings.read_only {
+            if !indices.is_empty() {
+                self.write_error = Some("Read-only mode is on".to_string());
+            }
+            return;
+        }
+        for control in &self.sim.controls[indices] {
+            if let Err(err) = connection.publish_sim(&self.sim.prefix, control) {
+                self.write_error = Some(err);
+                return;
+            }
+        }
+        self.write_error = None;
+    }
+
+    fn poll(&mut self) {
+        if self.replay.is_some() {
+            self.poll_replay();
+            return;
+        }
+
+        if let Some(events) = &self.events {
+            for event in events.try_iter() {
+                match event {
+                    Event::Value { name, value } => self.stats.record(name, &value),
+                    Event::TimeSync(time_sync) => self.latency.push(time_sync),
+                    _ => {}
+                }
+            }
+        }
+        self.stats.trim();
+
+        let Some(connection) = &self.connection else {
+            return;
+        };
+
+        self.topics = connection.topic_names("").into_iter().collect();
+
+        let schema_topics = connection.topic_names(SCHEMA_PREFIX);
+        self.schemas
+            .retain(|topic, _| schema_topics.contains(topic));
+        for topic in schema_topics {
+            let Value::Raw(bytes) = connection.read(&topic).data else {
+                continue;
+            };
+            // Protobuf descriptors are costly enough to format that unchanged ones are kept.
+            if self
+                .schemas
+                .get(&topic)
+                .is_some_and(|(old, _)| *old == bytes)
+            {
+                continue;
+            }
+            let schema = Schema::new(topic.clone(), &bytes);
+            self.schemas.insert(topic, (bytes, schema));
+        }
+
+        for name in &self.tracked {
+            // Properties change rarely, but can be set by any client at any time.
+            self.annotations
+                .insert(name.clone(), connection.annotations(name));
+            self.kinds.insert(name.clone(), connection.kind(name));
+
+            let value = connection.read(name);
+            if value.data.value_type() == ValueType::Unassigned {
+                continue;
+            }
+            if self.mirror.update(name, value.clone()) {
+                self.history.push(name, value.clone());
+                self.recorder.record(name, value);
+            }
+        }
+    }
+
+    /// Shows the tracked topics as they were at the replay position.
+    fn poll_replay(&mut self) {
+        let Some(replay) = &self.replay else {
+            return;
+        };
+        self.topics = replay.topics().map(str::to_owned).collect();
+
+        // Seeking backwards forgets values, so the mirror and history are rebuilt on every poll.
+        self.mirror.clear();
+        self.history.clear();
+        for name in &self.tracked {
+            if let Some(kind) = replay.kind(name) {
+                self.kinds.insert(name.clone(), kind.clone());
+            }
+            // Only the values the history keeps are read, so seeking stays fast in long logs.
+            let mut values: Vec<RawValue> = replay
+                .values(name)
+                .rev()
+                .take(self.history.capacity().max(1))
+                .collect();
+            if let Some(latest) = values.first() {
+                self.mirror.update(name, latest.clone());
+            }
+            values.reverse();
+            for value in values {
+                self.history.push(name, value);
+            }
+        }
+
+        self.schemas.clear();
+        for topic in self
+            .topics
+            .iter()
+            .filter(|topic| topic.starts_with(SCHEMA_PREFIX))
+        {
+            if let Some(Value::Raw(bytes)) =
+                replay.values(topic).next_back().map(|value| value.data)
+            {
+                let schema = Schema::new(topic.clone(), &bytes);
+                self.schemas.insert(topic.clone(), (bytes, schema));
+            }
+        }
+    }
+
+    pub fn state(&self) -> NetworkTablesState {
+        self.state
+    }
+    pub fn settings(&self) -> &ConnectionSettings {
+        &self.settings
+    }
+    pub fn connection(&self) -> Option<&Connection> {
+        self.connection.as_ref()
+    }
+    pub fn tracked(&self) -> impl Iterator<Item = &str> {
+        self.tracked.iter().map(String::as_str)
+    }
+    /// Returns the mirrored topics in the order frontends should list them, pinned topics first.
+    pub fn topic_order(&self) -> Vec<&str> {
+        let (pinned, unpinned): (Vec<&str>, Vec<&str>) = self
+            .mirror
+            .iter()
+            .map(|(name, _)| name)
+            .partition(|name| self.pinned.contains(*name));
+        pinned.into_iter().chain(unpinned).collect()
+    }
+    pub fn is_pinned(&self, name: impl AsRef<str>) -> bool {
+        self.pinned.contains(name.as_ref())
+    }
+    /// Returns the name of every topic on the connection, as of the last poll.
+    pub fn topics(&self) -> impl Iterator<Item = &str> {
+        self.topics.iter().map(String::as_str)
+    }
+    pub fn mirror(&self) -> &Mirror {
+        &self.mirror
+    }
+    /// Returns the unit and description of a tracked topic, as of the last poll.
+    pub fn annotations(&self, name: impl AsRef<str>) -> Option<&Annotations> {
+        self.annotations.get(name.as_ref())
+    }
+    /// Returns what kind of data a tracked topic holds, as of the last poll.
+    pub fn kind(&self, name: impl AsRef<str>) -> Option<&TopicKind> {
+        self.kinds.get(name.as_ref())
+    }
+    /// Decodes the mirrored value of a raw topic.
+    ///
+    /// Returns `None` if the topic has no mirrored raw value.
+    pub fn decode(
+        &self,
+        name: impl AsRef<str>,
+        decoding: RawDecoding,
+    ) -> Option<Result<String, DecodeError>> {
+        let Value::Raw(bytes) = &self.mirror.get(name.as_ref())?.data else {
+            return None;
+        };
+        let kind = self.kind(name.as_ref()).cloned().unwrap_or_default();
+        let schema = |struct_name: &str| match &self.connection {
+            Some(connection) => connection.struct_schema(struct_name),
+            // Replays have no connection, but keep the schemas logged with their raw values.
+            None => self
+                .schemas
+                .get(&format!("{SCHEMA_PREFIX}struct:{struct_name}"))
+                .and_then(|(bytes, _)| String::from_utf8(bytes.clone()).ok()),
+        };
+        Some(inspect::decode(bytes, decoding, &kind, &schema))
+    }
+    /// Returns the update rate and last update age of a topic, over the last few seconds.
+    ///
+    /// Returns `None` if the topic hasn't been updated since the connection started.
+    pub fn stats(&self, name: impl AsRef<str>) -> Option<TopicStats> {
+        self.stats.topic(name.as_ref())
+    }
+    /// Returns every schema published under [`SCHEMA_PREFIX`], ordered by topic name.
+    pub fn schemas(&self) -> impl Iterator<Item = &Schema> {
+        self.schemas.values().map(|(_, schema)| schema)
+    }
+    /// Returns the schema published to the given topic.
+    pub fn schema(&self, topic: impl AsRef<str>) -> Option<&Schema> {
+        self.schemas.get(topic.as_ref()).map(|(_, schema)| schema)
+    }
+    /// Returns the time sync measurements received since the client connected.
+    ///
+    /// Always empty when not running as a client.
+    pub fn latency(&self) -> &LatencyHistory {
+        &self.latency
+    }
+    pub fn is_read_only(&self) -> bool {
+        self.settings.read_only
+    }
+    /// Returns the write to a persistent or retained topic that is waiting for confirmation.
+    pub fn pending_write(&self) -> Option<&PendingWrite> {
+        self.pending_write.as_ref()
+    }
+    pub fn write_error(&self) -> Option<&str> {
+        self.write_error.as_deref()
+    }
+    /// Returns the widgets on the dashboard.
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+    pub fn layout_error(&self) -> Option<&str> {
+        self.layout_error.as_deref()
+    }
+    /// Returns the data log being replayed, if any.
+    pub fn replay(&self) -> Option<&Replay> {
+        self.replay.as_ref()
+    }
+    pub fn replay_error(&self) -> Option<&str> {
+        self.replay_error.as_deref()
+    }
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+    pub fn recorder(&self) -> &Recorder {
+        &self.recorder
+    }
+    /// Returns the inputs published for simulated robot code.
+    pub fn sim(&self) -> &SimPanel {
+        &self.sim
+    }
+}
@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+
+use lagan::nt_types::RawValue;
+
+/// The latest known value of every tracked topic.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mirror {
+    values: BTreeMap<String, RawValue>,
+}
+
+impl Mirror {
+    /// Stores the latest value of a topic.
+    ///
+    /// # Returns
+    ///
+    /// Returns true if the value differs from the previously mirrored value.
+    pub fn update(&mut self, name: impl AsRef<str>, value: RawValue) -> bool {
+        match self.values.get_mut(name.as_ref()) {
+            Some(current) if *current == value => false,
+            Some(current) => {
+                *current = value;
+                true
+            }
+            None => {
+                self.values.insert(name.as_ref().to_owned(), value);
+                true
+            }
+        }
+    }
+
+    pub fn get(&self, name: impl AsRef<str>) -> Option<&RawValue> {
+        self.values.get(name.as_ref())
+    }
+
+    pub fn remove(&mut self, name: impl AsRef<str>) -> Option<RawValue> {
+        self.values.remove(name.as_ref())
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    /// Returns every mirrored topic in name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &RawValue)> {
+        self.values
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
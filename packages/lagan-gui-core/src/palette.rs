@@ -0,0 +1,119 @@
+//! The command palette, which makes every common action reachable from the keyboard.
+
+use lagan::NetworkTablesVersion;
+
+use crate::{Core, Message, NetworkTablesState};
+
+/// An action offered by the command palette.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Command {
+    SetState(NetworkTablesState),
+    /// Tracks a topic found on the connection, so that it can be selected.
+    GoToTopic(String),
+    Pin(String),
+    Unpin(String),
+    StartRecording,
+    StopRecording,
+    ClearHistory,
+    SetReadOnly(bool),
+}
+
+impl Command {
+    /// The text the palette shows and matches queries against.
+    pub fn label(&self) -> String {
+        match self {
+            Self::SetState(NetworkTablesState::None) => "Disconnect".to_string(),
+            Self::SetState(NetworkTablesState::Server) => "Start server".to_string(),
+            Self::SetState(NetworkTablesState::Client(NetworkTablesVersion::V3)) => {
+                "Connect as client (V3)".to_string()
+            }
+            Self::SetState(NetworkTablesState::Client(NetworkTablesVersion::V4)) => {
+                "Connect as client (V4)".to_string()
+            }
+            Self::GoToTopic(name) => format!("Go to {name}"),
+            Self::Pin(name) => format!("Pin {name}"),
+            Self::Unpin(name) => format!("Unpin {name}"),
+            Self::StartRecording => "Start recording".to_string(),
+            Self::StopRecording => "Stop recording".to_string(),
+            Self::ClearHistory => "Clear history".to_string(),
+            Self::SetReadOnly(true) => "Turn read-only mode on".to_string(),
+            Self::SetReadOnly(false) => "Turn read-only mode off".to_string(),
+        }
+    }
+
+    /// The message that performs the command.
+    pub fn message(&self) -> Message {
+        match self {
+            Self::SetState(state) => Message::SetState(*state),
+            Self::GoToTopic(name) => Message::Track(name.clone()),
+            Self::Pin(name) => Message::Pin(name.clone()),
+            Self::Unpin(name) => Message::Unpin(name.clone()),
+            Self::StartRecording => Message::StartRecording,
+            Self::StopRecording => Message::StopRecording,
+            Self::ClearHistory => Message::ClearHistory,
+            Self::SetReadOnly(read_only) => Message::SetReadOnly(*read_only),
+        }
+    }
+
+    /// Returns the topic the command is about, which frontends should select after running it.
+    pub fn topic(&self) -> Option<&str> {
+        match self {
+            Self::GoToTopic(name) | Self::Pin(name) | Self::Unpin(name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+/// Returns every command that makes sense in the current state of the core.
+pub fn commands(core: &Core) -> Vec<Command> {
+    let mut commands: Vec<Command> = [
+        NetworkTablesState::Client(NetworkTablesVersion::V4),
+        NetworkTablesState::Client(NetworkTablesVersion::V3),
+        NetworkTablesState::Server,
+        NetworkTablesState::None,
+    ]
+    .into_iter()
+    .filter(|state| *state != core.state())
+    .map(Command::SetState)
+    .collect();
+
+    commands.push(if core.recorder().is_recording() {
+        Command::StopRecording
+    } else {
+        Command::StartRecording
+    });
+    commands.push(Command::ClearHistory);
+    commands.push(Command::SetReadOnly(!core.is_read_only()));
+
+    for name in core.topics() {
+        commands.push(Command::GoToTopic(name.to_owned()));
+        commands.push(if core.is_pinned(name) {
+            Command::Unpin(name.to_owned())
+        } else {
+            Command::Pin(name.to_owned())
+        });
+    }
+    commands
+}
+
+/// Returns the commands matching `query`, best match first.
+///
+/// A command matches if its label contains every character of the query in order, ignoring case.
+/// Labels containing the query as a whole rank first, then shorter labels.
+pub fn search(commands: Vec<Command>, query: &str) -> Vec<Command> {
+    let query = query.trim().to_lowercase();
+    let mut matches: Vec<((bool, usize), Command)> = commands
+        .into_iter()
+        .filter_map(|command| {
+            let label = command.label().to_lowercase();
+            let mut chars = label.chars();
+            if !query.chars().all(|wanted| chars.any(|c| c == wanted)) {
+                return None;
+            }
+            Some(((!label.contains(&query), label.len()), command))
+        })
+        .collect();
+    // Stable, so equally good matches keep the order of `commands`.
+    matches.sort_by_key(|(rank, _)| *rank);
+    matches.into_iter().map(|(_, command)| command).collect()
+}
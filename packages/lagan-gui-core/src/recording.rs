@@ -0,0 +1,67 @@
+use lagan::nt_types::{NetworkTablesInstant, RawValue};
+
+/// A single value change captured while recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub name: String,
+    pub value: RawValue,
+}
+
+/// Every value change seen between starting and stopping a recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    pub started: NetworkTablesInstant,
+    pub stopped: Option<NetworkTablesInstant>,
+    pub records: Vec<Record>,
+}
+
+/// Keeps track of the active recording and all finished recordings.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Recorder {
+    active: Option<Recording>,
+    finished: Vec<Recording>,
+}
+
+impl Recorder {
+    /// Starts a new recording if one is not already active.
+    pub fn start(&mut self) {
+        if self.active.is_some() {
+            return;
+        }
+
+        self.active = Some(Recording {
+            started: NetworkTablesInstant::now(),
+            stopped: None,
+            records: Vec::new(),
+        });
+    }
+
+    /// Stops the active recording, if any.
+    pub fn stop(&mut self) {
+        if let Some(mut recording) = self.active.take() {
+            recording.stopped = Some(NetworkTablesInstant::now());
+            self.finished.push(recording);
+        }
+    }
+
+    /// Adds a value change to the active recording.
+    /// Does nothing if there is no active recording.
+    pub fn record(&mut self, name: impl AsRef<str>, value: RawValue) {
+        if let Some(recording) = &mut self.active {
+            recording.records.push(Record {
+                name: name.as_ref().to_owned(),
+                value,
+            });
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.active.is_some()
+    }
+    pub fn active(&self) -> Option<&Recording> {
+        self.active.as_ref()
+    }
+    pub fn finished(&self) -> &[Recording] {
+        &self.finished
+    }
+}
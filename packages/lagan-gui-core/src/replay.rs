@@ -0,0 +1,119 @@
+//! Replaying data logs, which drives the GUI from a log file instead of a live connection.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use lagan::{
+    datalog::ENTRY_LOG_PREFIX,
+    nt_types::{NetworkTablesInstant, RawValue},
+    schema::TopicKind,
+    wpilog::WpiLog,
+};
+
+/// An open data log and the point in it being shown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    path: PathBuf,
+    log: WpiLog,
+    /// The indices of the records of every topic in the log, by topic name.
+    records: BTreeMap<String, Vec<usize>>,
+    /// The kind of every topic, from the type of the last entry logged for it.
+    kinds: BTreeMap<String, TopicKind>,
+    position: Duration,
+}
+
+impl Replay {
+    /// Reads a data log and starts replaying it from its first record.
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        let log = WpiLog::open(&path).map_err(|err| err.to_string())?;
+
+        let mut records: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        let mut kinds = BTreeMap::new();
+        for (index, record) in log.records().iter().enumerate() {
+            let Some(entry) = log.entry(record) else {
+                continue;
+            };
+            let topic = topic_name(&entry.name);
+            records.entry(topic.to_owned()).or_default().push(index);
+            kinds.insert(
+                topic.to_owned(),
+                TopicKind::parse(topic, &entry.type_string),
+            );
+        }
+
+        let position = log.time_range().map_or(Duration::ZERO, |(start, _)| start);
+        Ok(Self {
+            path,
+            log,
+            records,
+            kinds,
+            position,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    pub fn log(&self) -> &WpiLog {
+        &self.log
+    }
+
+    /// The time being shown, on the clock of the robot that wrote the log.
+    pub fn position(&self) -> Duration {
+        self.position
+    }
+    /// The timestamp of the first record, or zero if the log is empty.
+    pub fn start(&self) -> Duration {
+        self.log
+            .time_range()
+            .map_or(Duration::ZERO, |(start, _)| start)
+    }
+    /// The timestamp of the last record, or zero if the log is empty.
+    pub fn end(&self) -> Duration {
+        self.log.time_range().map_or(Duration::ZERO, |(_, end)| end)
+    }
+
+    /// Moves to `position`, clamped to the records of the log.
+    pub(crate) fn seek(&mut self, position: Duration) {
+        self.position = position.clamp(self.start(), self.end());
+    }
+
+    /// Returns the name of every topic in the log.
+    pub fn topics(&self) -> impl Iterator<Item = &str> {
+        self.records.keys().map(String::as_str)
+    }
+
+    /// Returns what kind of data a topic holds.
+    pub fn kind(&self, topic: &str) -> Option<&TopicKind> {
+        self.kinds.get(topic)
+    }
+
+    /// Returns the values of a topic logged up to the position, oldest first.
+    pub fn values(&self, topic: &str) -> impl DoubleEndedIterator<Item = RawValue> + '_ {
+        let indices = self.records.get(topic).map_or(&[][..], Vec::as_slice);
+        let records = self.log.records();
+        let end = indices.partition_point(|index| records[*index].timestamp <= self.position);
+        indices[..end].iter().map(move |index| {
+            let record = &records[*index];
+            let time = NetworkTablesInstant::from_micros(record.timestamp.as_micros() as u64);
+            RawValue {
+                data: record.value.clone(),
+                last_change: time,
+                server_time: time,
+            }
+        })
+    }
+}
+
+/// Returns the topic an entry of a log was logged from.
+///
+/// NetworkTables entries are logged with [`ENTRY_LOG_PREFIX`] before their topic name.
+/// Other entries, such as the ones logged for the driver station, keep their names.
+pub fn topic_name(entry_name: &str) -> &str {
+    entry_name
+        .strip_prefix(ENTRY_LOG_PREFIX)
+        .unwrap_or(entry_name)
+}
@@ -0,0 +1,303 @@
+//! Browsing the schemas published under [`SCHEMA_PREFIX`].
+//!
+//! Struct schemas are text, but protobuf schemas are serialized `FileDescriptorProto`s,
+//! so both are formatted into something closer to the source they were generated from.
+
+use std::fmt::Write;
+
+use lagan::schema::{StructType, SCHEMA_PREFIX};
+
+use crate::decode::DecodeError;
+
+/// How deeply protobuf messages may nest before a descriptor is considered malformed.
+const MAX_MESSAGE_DEPTH: usize = 16;
+
+/// What a schema topic describes, as told by its name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SchemaKind {
+    /// The schema of the named WPILib struct, e.g. `Pose2d`.
+    Struct(String),
+    /// The protobuf file with the given name, e.g. `geometry2d.proto`.
+    Protobuf(String),
+    /// A schema in a format the GUI doesn't know, named after the part of the topic name after the prefix.
+    Other(String),
+}
+
+impl SchemaKind {
+    /// Classifies a schema topic by its name.
+    pub fn parse(topic: &str) -> Self {
+        let name = topic.strip_prefix(SCHEMA_PREFIX).unwrap_or(topic);
+        if let Some(struct_name) = name.strip_prefix("struct:") {
+            Self::Struct(struct_name.to_string())
+        } else if let Some(file_name) = name.strip_prefix("proto:") {
+            Self::Protobuf(file_name.to_string())
+        } else {
+            Self::Other(name.to_string())
+        }
+    }
+}
+
+/// A schema published under [`SCHEMA_PREFIX`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Schema {
+    /// The name of the schema topic, e.g. `/.schema/struct:Pose2d`.
+    pub topic: String,
+    pub kind: SchemaKind,
+    /// The definition formatted for reading, or why it couldn't be formatted.
+    pub definition: Result<String, DecodeError>,
+}
+
+impl Schema {
+    /// Formats the value of a schema topic.
+    pub fn new(topic: impl Into<String>, bytes: &[u8]) -> Self {
+        let topic = topic.into();
+        let kind = SchemaKind::parse(&topic);
+        let definition = match &kind {
+            SchemaKind::Struct(_) => std::str::from_utf8(bytes)
+                .map(format_struct)
+                .map_err(|err| DecodeError::new(format!("Not UTF-8: {err}"))),
+            SchemaKind::Protobuf(_) => format_protobuf(bytes),
+            SchemaKind::Other(_) => String::from_utf8(bytes.to_vec())
+                .map_err(|err| DecodeError::new(format!("Not UTF-8: {err}"))),
+        };
+        Self {
+            topic,
+            kind,
+            definition,
+        }
+    }
+}
+
+/// Returns the name of the topic the schema of a struct type is published to.
+pub fn struct_schema_topic(struct_type: &StructType) -> String {
+    format!("{SCHEMA_PREFIX}struct:{}", struct_type.name)
+}
+
+/// Puts every declaration of a struct schema, such as `double x;double y`, on its own line.
+pub fn format_struct(schema: &str) -> String {
+    let mut text = String::new();
+    for declaration in schema.split(';').map(str::trim) {
+        if !declaration.is_empty() {
+            let _ = writeln!(text, "{declaration};");
+        }
+    }
+    text
+}
+
+/// Formats a serialized `FileDescriptorProto` as `.proto` source.
+///
+/// Only messages, their fields and enums are shown; options and services are left out.
+pub fn format_protobuf(bytes: &[u8]) -> Result<String, DecodeError> {
+    let mut text = String::new();
+    let mut package = None;
+    let mut dependencies = Vec::new();
+    let mut body = String::new();
+
+    for field in Fields::new(bytes) {
+        match field? {
+            (2, Wire::Bytes(name)) => package = Some(utf8(name)?),
+            (3, Wire::Bytes(name)) => dependencies.push(utf8(name)?),
+            (4, Wire::Bytes(message)) => format_message(message, 0, &mut body)?,
+            (5, Wire::Bytes(enumeration)) => format_enum(enumeration, 0, &mut body)?,
+            _ => {}
+        }
+    }
+
+    text.push_str("syntax = \"proto3\";\n");
+    if let Some(package) = package {
+        let _ = writeln!(text, "package {package};");
+    }
+    for dependency in dependencies {
+        let _ = writeln!(text, "import \"{dependency}\";");
+    }
+    text.push_str(&body);
+    Ok(text)
+}
+
+fn format_message(bytes: &[u8], depth: usize, text: &mut String) -> Result<(), DecodeError> {
+    if depth > MAX_MESSAGE_DEPTH {
+        return Err(DecodeError::new("Messages are nested too deeply"));
+    }
+
+    let mut name = "";
+    let mut body = String::new();
+    for field in Fields::new(bytes) {
+        match field? {
+            (1, Wire::Bytes(message_name)) => name = utf8(message_name)?,
+            (2, Wire::Bytes(field)) => format_field(field, depth + 1, &mut body)?,
+            (3, Wire::Bytes(message)) => format_message(message, depth + 1, &mut body)?,
+            (4, Wire::Bytes(enumeration)) => format_enum(enumeration, depth + 1, &mut body)?,
+            _ => {}
+        }
+    }
+
+    let indent = indent(depth);
+    let _ = write!(text, "\n{indent}message {name} {{\n{body}{indent}}}\n");
+    Ok(())
+}
+
+fn format_field(bytes: &[u8], depth: usize, text: &mut String) -> Result<(), DecodeError> {
+    let mut name = "";
+    let mut number = 0;
+    let mut repeated = false;
+    let mut type_number = 0;
+    let mut type_name = "";
+    for field in Fields::new(bytes) {
+        match field? {
+            (1, Wire::Bytes(field_name)) => name = utf8(field_name)?,
+            (3, Wire::Varint(field_number)) => number = field_number,
+            // LABEL_REPEATED
+            (4, Wire::Varint(label)) => repeated = label == 3,
+            (5, Wire::Varint(field_type)) => type_number = field_type,
+            (6, Wire::Bytes(field_type_name)) => type_name = utf8(field_type_name)?,
+            _ => {}
+        }
+    }
+
+    let type_name = match type_number {
+        1 => "double",
+        2 => "float",
+        3 => "int64",
+        4 => "uint64",
+        5 => "int32",
+        6 => "fixed64",
+        7 => "fixed32",
+        8 => "bool",
+        9 => "string",
+        12 => "bytes",
+        13 => "uint32",
+        15 => "sfixed32",
+        16 => "sfixed64",
+        17 => "sint32",
+        18 => "sint64",
+        // Messages, groups and enums are named by their fully qualified name.
+        _ => type_name.trim_start_matches('.'),
+    };
+    let label = if repeated { "repeated " } else { "" };
+    let _ = writeln!(
+        text,
+        "{}{label}{type_name} {name} = {number};",
+        indent(depth)
+    );
+    Ok(())
+}
+
+fn format_enum(bytes: &[u8], depth: usize, text: &mut String) -> Result<(), DecodeError> {
+    let mut name = "";
+    let mut body = String::new();
+    for field in Fields::new(bytes) {
+        match field? {
+            (1, Wire::Bytes(enum_name)) => name = utf8(enum_name)?,
+            (2, Wire::Bytes(value)) => {
+                let mut value_name = "";
+                let mut number = 0;
+                for field in Fields::new(value) {
+                    match field? {
+                        (1, Wire::Bytes(name)) => value_name = utf8(name)?,
+                        // Enum values are int32s, which are sign extended to 64 bits on the wire.
+                        (2, Wire::Varint(value_number)) => number = value_number as i32,
+                        _ => {}
+                    }
+                }
+                let _ = writeln!(body, "{}{value_name} = {number};", indent(depth + 1));
+            }
+            _ => {}
+        }
+    }
+
+    let indent = indent(depth);
+    let _ = write!(text, "\n{indent}enum {name} {{\n{body}{indent}}}\n");
+    Ok(())
+}
+
+fn indent(depth: usize) -> String {
+    "  ".repeat(depth)
+}
+
+fn utf8(bytes: &[u8]) -> Result<&str, DecodeError> {
+    std::str::from_utf8(bytes).map_err(|err| DecodeError::new(format!("Not UTF-8: {err}")))
+}
+
+/// The value of a protobuf field, as far as its wire type tells.
+enum Wire<'a> {
+    Varint(u64),
+    Fixed,
+    Bytes(&'a [u8]),
+}
+
+/// Iterates over the fields of a serialized protobuf message as pairs of field numbers and values.
+struct Fields<'a> {
+    bytes: &'a [u8],
+}
+impl<'a> Fields<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = self
+                .bytes
+                .split_first()
+                .ok_or_else(|| DecodeError::new("Unexpected end of data"))?;
+            self.bytes = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(DecodeError::new("Varint is too long"))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < len {
+            return Err(DecodeError::new("Unexpected end of data"));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn field(&mut self) -> Result<(u64, Wire<'a>), DecodeError> {
+        let key = self.varint()?;
+        let value = match key & 0x7 {
+            0 => Wire::Varint(self.varint()?),
+            1 => {
+                self.take(8)?;
+                Wire::Fixed
+            }
+            2 => {
+                let len = self.varint()?;
+                let len = usize::try_from(len)
+                    .map_err(|_| DecodeError::new(format!("Invalid length {len}")))?;
+                Wire::Bytes(self.take(len)?)
+            }
+            5 => {
+                self.take(4)?;
+                Wire::Fixed
+            }
+            wire_type => {
+                return Err(DecodeError::new(format!(
+                    "Unsupported wire type {wire_type}"
+                )))
+            }
+        };
+        Ok((key >> 3, value))
+    }
+}
+impl<'a> Iterator for Fields<'a> {
+    type Item = Result<(u64, Wire<'a>), DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        let field = self.field();
+        // Stop after an error, since the rest of the message can't be framed anymore.
+        if field.is_err() {
+            self.bytes = &[];
+        }
+        Some(field)
+    }
+}
@@ -0,0 +1,84 @@
+//! Driving the inputs of simulated robot code, see [`lagan::conventions::sim`].
+
+use lagan::{
+    conventions::sim::{Joystick, SimInput, DEFAULT_PREFIX, JOYSTICK_PORTS},
+    Instance, NetworkTablesError,
+};
+
+/// The number of axes of joysticks added to the panel.
+pub const JOYSTICK_AXES: usize = 6;
+/// The number of buttons of joysticks added to the panel.
+pub const JOYSTICK_BUTTONS: usize = 12;
+
+/// An input on the simulation panel.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimControl {
+    Bool { name: String, value: bool },
+    Number { name: String, value: f64 },
+    Joystick { port: u8, joystick: Joystick },
+}
+
+impl SimControl {
+    /// Creates a joystick with every axis centered, every button released and its POV centered.
+    pub fn joystick(port: u8) -> Self {
+        Self::Joystick {
+            port,
+            joystick: Joystick {
+                axes: vec![0.0; JOYSTICK_AXES],
+                buttons: vec![false; JOYSTICK_BUTTONS],
+                povs: vec![-1],
+            },
+        }
+    }
+
+    /// The name the control is listed under.
+    pub fn title(&self) -> String {
+        match self {
+            Self::Bool { name, .. } | Self::Number { name, .. } => name.clone(),
+            Self::Joystick { port, .. } => format!("Joystick {port}"),
+        }
+    }
+
+    pub(crate) fn publish<I: Instance + ?Sized>(
+        &self,
+        input: &SimInput<'_, I>,
+    ) -> Result<(), NetworkTablesError> {
+        match self {
+            Self::Bool { name, value } => input.set_bool(name, *value),
+            Self::Number { name, value } => input.set_number(name, *value),
+            Self::Joystick { port, joystick } => input.set_joystick(*port, joystick),
+        }
+    }
+}
+
+/// The inputs the simulation panel publishes, which are published again whenever a connection starts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimPanel {
+    /// The prefix the inputs are published under.
+    pub prefix: String,
+    pub controls: Vec<SimControl>,
+}
+
+impl Default for SimPanel {
+    fn default() -> Self {
+        Self {
+            prefix: DEFAULT_PREFIX.to_string(),
+            controls: Vec::new(),
+        }
+    }
+}
+
+impl SimPanel {
+    /// Returns the first joystick port without a joystick on the panel, if any is left.
+    pub fn free_port(&self) -> Option<u8> {
+        let taken: Vec<u8> = self
+            .controls
+            .iter()
+            .filter_map(|control| match control {
+                SimControl::Joystick { port, .. } => Some(*port),
+                _ => None,
+            })
+            .collect();
+        (0..JOYSTICK_PORTS).find(|port| !taken.contains(port))
+    }
+}
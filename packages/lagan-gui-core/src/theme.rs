@@ -0,0 +1,192 @@
+//! The colors frontends draw with, from built-in themes or a user's theme file.
+//!
+//! A theme file holds `key = value` lines. `base` names the built-in theme the file starts from
+//! (`macchiato`, `latte` or `high-contrast`), `name` names the result, and every other key overrides
+//! a color of [`Palette`] by its field name, e.g. `error = #ff0000`.
+//! Empty lines and lines starting with `#` are ignored.
+
+use std::{fs, path::Path};
+
+/// The colors of a theme, as `#rrggbb` or `#rrggbbaa`, named by what they are used for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Palette {
+    pub name: String,
+    /// Whether the background is dark, so frontends can pick matching defaults for anything not covered here.
+    pub dark: bool,
+    pub background: String,
+    /// Panels and plots drawn on the background.
+    pub panel: String,
+    /// Drawn on panels, e.g. the arrow button of a dropdown.
+    pub inset: String,
+    /// Selected rows and buttons.
+    pub selection: String,
+    pub hover: String,
+    pub border: String,
+    pub text: String,
+    /// Headers, hints and other secondary text.
+    pub subtext: String,
+    /// Text drawn on the status colors below.
+    pub on_status: String,
+    pub error: String,
+    pub success: String,
+    pub warning: String,
+    pub info: String,
+    /// Data drawn next to data in the info color, e.g. the second plot of a graph.
+    pub accent: String,
+}
+
+impl Palette {
+    /// Catppuccin Macchiato, the default dark theme.
+    pub fn macchiato() -> Self {
+        Self {
+            name: "Catppuccin Macchiato".to_string(),
+            dark: true,
+            background: "#24273a".to_string(),
+            panel: "#1e2030".to_string(),
+            inset: "#181926".to_string(),
+            selection: "#363a4f".to_string(),
+            hover: "#494d64".to_string(),
+            border: "#8087a2".to_string(),
+            text: "#cad3f5".to_string(),
+            subtext: "#a5adcb".to_string(),
+            on_status: "#181926".to_string(),
+            error: "#ed8796".to_string(),
+            success: "#a6da95".to_string(),
+            warning: "#eed49f".to_string(),
+            info: "#8aadf4".to_string(),
+            accent: "#c6a0f6".to_string(),
+        }
+    }
+
+    /// Catppuccin Latte, a light theme.
+    pub fn latte() -> Self {
+        Self {
+            name: "Catppuccin Latte".to_string(),
+            dark: false,
+            background: "#eff1f5".to_string(),
+            panel: "#e6e9ef".to_string(),
+            inset: "#dce0e8".to_string(),
+            selection: "#ccd0da".to_string(),
+            hover: "#bcc0cc".to_string(),
+            border: "#8c8fa1".to_string(),
+            text: "#4c4f69".to_string(),
+            subtext: "#6c6f85".to_string(),
+            on_status: "#eff1f5".to_string(),
+            error: "#d20f39".to_string(),
+            success: "#40a02b".to_string(),
+            warning: "#df8e1d".to_string(),
+            info: "#1e66f5".to_string(),
+            accent: "#8839ef".to_string(),
+        }
+    }
+
+    /// Black on white with saturated status colors, readable in direct sunlight at outdoor venues.
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "High contrast".to_string(),
+            dark: false,
+            background: "#ffffff".to_string(),
+            panel: "#f0f0f0".to_string(),
+            inset: "#d0d0d0".to_string(),
+            selection: "#ffd700".to_string(),
+            hover: "#ffe766".to_string(),
+            border: "#000000".to_string(),
+            text: "#000000".to_string(),
+            subtext: "#000000".to_string(),
+            on_status: "#ffffff".to_string(),
+            error: "#c00000".to_string(),
+            success: "#007000".to_string(),
+            warning: "#a05000".to_string(),
+            info: "#0000c0".to_string(),
+            accent: "#6000a0".to_string(),
+        }
+    }
+
+    /// Returns every built-in theme, the default first.
+    pub fn presets() -> [Self; 3] {
+        [Self::macchiato(), Self::latte(), Self::high_contrast()]
+    }
+
+    /// Returns the built-in theme with the given name in theme files.
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "macchiato" => Some(Self::macchiato()),
+            "latte" => Some(Self::latte()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// Parses the contents of a theme file.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let settings = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|(index, line)| {
+                line.split_once('=')
+                    .map(|(key, value)| (key.trim(), value.trim()))
+                    .ok_or_else(|| format!("Line {}: {line:?} is not a key = value setting", index + 1))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut palette = match settings.iter().find(|(key, _)| *key == "base") {
+            Some((_, base)) => {
+                Self::preset(base).ok_or_else(|| format!("Unknown base theme {base:?}"))?
+            }
+            None => Self::macchiato(),
+        };
+        palette.name = "Custom".to_string();
+
+        for (key, value) in settings {
+            let color = match key {
+                "base" => continue,
+                "name" => {
+                    palette.name = value.to_string();
+                    continue;
+                }
+                "background" => &mut palette.background,
+                "panel" => &mut palette.panel,
+                "inset" => &mut palette.inset,
+                "selection" => &mut palette.selection,
+                "hover" => &mut palette.hover,
+                "border" => &mut palette.border,
+                "text" => &mut palette.text,
+                "subtext" => &mut palette.subtext,
+                "on_status" => &mut palette.on_status,
+                "error" => &mut palette.error,
+                "success" => &mut palette.success,
+                "warning" => &mut palette.warning,
+                "info" => &mut palette.info,
+                "accent" => &mut palette.accent,
+                _ => return Err(format!("Unknown theme setting {key:?}")),
+            };
+            if !is_color(value) {
+                return Err(format!("{value:?} is not a #rrggbb color"));
+            }
+            *color = value.to_string();
+        }
+        Ok(palette)
+    }
+
+    /// Reads a theme file, returning `None` if it doesn't exist.
+    pub fn load(path: impl AsRef<Path>) -> Result<Option<Self>, String> {
+        match fs::read_to_string(path.as_ref()) {
+            Ok(text) => Self::parse(&text).map(Some),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(format!("Couldn't read {}: {err}", path.as_ref().display())),
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::macchiato()
+    }
+}
+
+fn is_color(text: &str) -> bool {
+    text.strip_prefix('#').is_some_and(|digits| {
+        matches!(digits.len(), 6 | 8) && digits.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
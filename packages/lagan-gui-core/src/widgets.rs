@@ -0,0 +1,210 @@
+//! Dashboard widgets, which show a few topics in a way readable from across a room.
+
+use lagan::{
+    conventions::alerts::{self, Alerts},
+    nt_types::ValueType,
+    Value,
+};
+
+use crate::mirror::Mirror;
+
+/// The group WPILib publishes alerts to by default.
+pub const DEFAULT_ALERTS_GROUP: &str = alerts::DEFAULT_GROUP;
+
+/// A widget on the dashboard tab.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Widget {
+    Boolean(BooleanIndicator),
+    Gauge(Gauge),
+    Value(ValueDisplay),
+    Alerts(AlertBanner),
+}
+
+impl Widget {
+    /// Returns the topics the widget shows, which have to be tracked for it to work.
+    pub fn topics(&self) -> Vec<String> {
+        match self {
+            Self::Boolean(indicator) => vec![indicator.topic.clone()],
+            Self::Gauge(gauge) => vec![gauge.topic.clone()],
+            Self::Value(display) => vec![display.topic.clone()],
+            Self::Alerts(banner) => banner.topics().to_vec(),
+        }
+    }
+
+    /// The name the widget is listed under.
+    pub fn title(&self) -> &str {
+        match self {
+            Self::Boolean(indicator) => &indicator.topic,
+            Self::Gauge(gauge) => &gauge.topic,
+            Self::Value(display) => &display.topic,
+            Self::Alerts(banner) => &banner.group,
+        }
+    }
+}
+
+/// The kinds of widget that show a single topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WidgetKind {
+    Boolean,
+    Gauge,
+    Value,
+}
+
+impl WidgetKind {
+    pub const ALL: [Self; 3] = [Self::Boolean, Self::Gauge, Self::Value];
+
+    /// Returns the kinds of widget that can show a topic of the given type, best fitting first.
+    pub fn for_type(value_type: ValueType) -> Vec<Self> {
+        match value_type {
+            ValueType::Bool => vec![Self::Boolean, Self::Value],
+            ValueType::I64 | ValueType::F32 | ValueType::F64 => vec![Self::Gauge, Self::Value],
+            // Topics that aren't mirrored yet could hold anything.
+            ValueType::Unassigned => Self::ALL.to_vec(),
+            _ => vec![Self::Value],
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Boolean => "Indicator",
+            Self::Gauge => "Gauge",
+            Self::Value => "Value",
+        }
+    }
+
+    /// Creates a widget of this kind with default settings.
+    pub fn create(self, topic: impl Into<String>) -> Widget {
+        match self {
+            Self::Boolean => Widget::Boolean(BooleanIndicator::new(topic)),
+            Self::Gauge => Widget::Gauge(Gauge::new(topic)),
+            Self::Value => Widget::Value(ValueDisplay::new(topic)),
+        }
+    }
+}
+
+/// A large block colored by the value of a boolean topic.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BooleanIndicator {
+    pub topic: String,
+    /// The color shown while the topic is true, as `#rrggbb`.
+    pub true_color: String,
+    /// The color shown while the topic is false, as `#rrggbb`.
+    pub false_color: String,
+}
+
+impl BooleanIndicator {
+    pub fn new(topic: impl Into<String>) -> Self {
+        Self {
+            topic: topic.into(),
+            true_color: "#a6da95".to_string(),
+            false_color: "#ed8796".to_string(),
+        }
+    }
+
+    /// Returns the value of the topic, or `None` if it isn't a mirrored boolean.
+    pub fn value(&self, mirror: &Mirror) -> Option<bool> {
+        match mirror.get(&self.topic)?.data {
+            Value::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// How close the value of a [`Gauge`] is to its thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GaugeLevel {
+    Normal,
+    Warning,
+    Critical,
+}
+
+/// A bar showing a numeric topic between a minimum and a maximum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gauge {
+    pub topic: String,
+    pub min: f64,
+    pub max: f64,
+    /// The value at and above which the gauge shows a warning.
+    pub warning: Option<f64>,
+    /// The value at and above which the gauge shows it is critical.
+    pub critical: Option<f64>,
+}
+
+impl Gauge {
+    pub fn new(topic: impl Into<String>) -> Self {
+        Self {
+            topic: topic.into(),
+            min: 0.0,
+            max: 1.0,
+            warning: None,
+            critical: None,
+        }
+    }
+
+    /// Returns the value of the topic, or `None` if it isn't a mirrored number.
+    pub fn value(&self, mirror: &Mirror) -> Option<f64> {
+        mirror.get(&self.topic)?.data.as_f64()
+    }
+
+    /// Returns how far `value` is between the minimum and the maximum, clamped to `0.0..=1.0`.
+    pub fn fraction(&self, value: f64) -> f64 {
+        if self.max <= self.min {
+            return 0.0;
+        }
+        ((value - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+    }
+
+    pub fn level(&self, value: f64) -> GaugeLevel {
+        if self.critical.is_some_and(|critical| value >= critical) {
+            GaugeLevel::Critical
+        } else if self.warning.is_some_and(|warning| value >= warning) {
+            GaugeLevel::Warning
+        } else {
+            GaugeLevel::Normal
+        }
+    }
+}
+
+/// The value of any topic as text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ValueDisplay {
+    pub topic: String,
+}
+
+impl ValueDisplay {
+    pub fn new(topic: impl Into<String>) -> Self {
+        Self {
+            topic: topic.into(),
+        }
+    }
+}
+
+/// Banners for the alerts of a WPILib alert group, see [`lagan::conventions::alerts`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AlertBanner {
+    /// The topic of the group, e.g. [`DEFAULT_ALERTS_GROUP`].
+    pub group: String,
+}
+
+impl AlertBanner {
+    pub fn new(group: impl Into<String>) -> Self {
+        Self {
+            group: group.into(),
+        }
+    }
+
+    /// Returns the topics of the errors, warnings and infos of the group.
+    pub fn topics(&self) -> [String; 3] {
+        alerts::topics(&self.group)
+    }
+
+    /// Returns the active alerts of the group, leaving out severities that aren't mirrored.
+    pub fn alerts(&self, mirror: &Mirror) -> Alerts {
+        let [errors, warnings, infos] = self.topics();
+        Alerts::from_values([
+            mirror.get(errors).map(|value| &value.data),
+            mirror.get(warnings).map(|value| &value.data),
+            mirror.get(infos).map(|value| &value.data),
+        ])
+    }
+}
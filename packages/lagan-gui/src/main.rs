@@ -1,61 +1,215 @@
 use freya::prelude::*;
-use lagan::NetworkTablesVersion;
+use lagan::{
+    conventions::sim::Joystick, nt_types::ValueType, schema::TopicKind, NetworkTablesVersion,
+    Value,
+};
+use lagan_gui_core::{
+    inspect::{self, RawDecoding},
+    palette, schemas,
+    theme::Palette,
+    layout::{Cell, PlacedWidget, GRID_COLUMNS},
+    sim::SimControl,
+    widgets::{
+        AlertBanner, BooleanIndicator, Gauge, GaugeLevel, Widget, WidgetKind,
+        DEFAULT_ALERTS_GROUP,
+    },
+    Core, Message, NetworkTablesState, STALE_AFTER,
+};
 
-// Catppuccin Machiatto
-const THEME: Theme = {
-    use Cow::Borrowed;
+/// The file a user's theme is read from, relative to the working directory.
+const THEME_FILE: &str = "lagan-theme.txt";
+
+/// Builds the theme of freya's components from a palette.
+fn freya_theme(palette: &Palette) -> Theme {
+    let base = if palette.dark { DARK_THEME } else { LIGHT_THEME };
+    let color = |color: &String| Cow::Owned(color.clone());
 
     Theme {
-        name: "Catppuccino Machiatto",
+        name: "Lagan",
         body: BodyTheme {
-            background: Borrowed("#24273a"),
-            color: Borrowed("#cad3f5"),
-            ..DARK_THEME.body
+            background: color(&palette.background),
+            color: color(&palette.text),
+            ..base.body
         },
         menu_container: MenuContainerTheme {
-            background: Borrowed("#1e2030"),
-            ..DARK_THEME.menu_container
+            background: color(&palette.panel),
+            ..base.menu_container
         },
         menu_item: MenuItemTheme {
             font_theme: FontTheme {
-                color: Borrowed("#cad3f5"),
+                color: color(&palette.text),
             },
-            hover_background: Borrowed("#363a4f"),
-            ..DARK_THEME.menu_item
+            hover_background: color(&palette.selection),
+            ..base.menu_item
         },
         button: ButtonTheme {
             font_theme: FontTheme {
-                color: Borrowed("#cad3f5"),
+                color: color(&palette.text),
             },
-            background: Borrowed("#363a4f"),
-            hover_background: Borrowed("#45475a"),
-            border_fill: Borrowed("#8087a2"),
-            ..DARK_THEME.button
+            background: color(&palette.selection),
+            hover_background: color(&palette.hover),
+            border_fill: color(&palette.border),
+            ..base.button
         },
-        ..DARK_THEME
+        ..base
     }
-};
+}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum NetworkTablesState {
-    None,
-    Client(NetworkTablesVersion),
-    Server,
+/// Returns the palette of the theme picked in the [`ThemeSelector`].
+fn use_palette() -> Palette {
+    use_context::<Signal<Palette, UnsyncStorage>>().read().clone()
 }
 
 fn main() {
     launch_with_props(app, "Lagan", (1280.0, 720.0));
 }
 
+/// The most commands the command palette lists at once.
+const PALETTE_LENGTH: usize = 20;
+/// The file the dashboard is kept in, relative to the working directory.
+const LAYOUT_FILE: &str = "lagan-layout.txt";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Tab {
+    Topics,
+    Dashboard,
+    Simulation,
+}
+
 fn app() -> Element {
-    let nt_state = use_signal(|| NetworkTablesState::None);
+    let mut core = use_signal(Core::default);
+    let mut selected = use_signal(|| None::<String>);
+    // The schema topic shown by the schema browser.
+    let selected_schema = use_signal(|| None::<String>);
+    let mut palette_open = use_signal(|| false);
+    let mut tab = use_signal(|| Tab::Topics);
+    use_hook(move || core.write().update(Message::OpenLayout(LAYOUT_FILE.into())));
+    // The theme file starts out picked, since writing one is a clear sign of wanting it.
+    let theme_file = use_hook(|| Palette::load(THEME_FILE));
+    let palette = use_context_provider(|| {
+        Signal::new(match &theme_file {
+            Ok(Some(palette)) => palette.clone(),
+            _ => Palette::default(),
+        })
+    });
+    use_init_theme(|| freya_theme(&palette.read()));
+
+    // Moves through the listed topics, so the dashboard can be used without a mouse.
+    let onglobalkeydown = move |event: KeyboardEvent| {
+        if event.modifiers.contains(Modifiers::CONTROL) && event.code == Code::KeyP {
+            palette_open.toggle();
+            return;
+        }
+        if palette_open() {
+            return;
+        }
+
+        let order: Vec<String> = core
+            .read()
+            .topic_order()
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
+        let position = selected
+            .read()
+            .as_ref()
+            .and_then(|name| order.iter().position(|topic| topic == name));
+        match event.key {
+            Key::ArrowDown if !order.is_empty() => {
+                let next = position.map_or(0, |position| (position + 1).min(order.len() - 1));
+                selected.set(Some(order[next].clone()));
+            }
+            Key::ArrowUp if !order.is_empty() => {
+                let previous = position.map_or(0, |position| position.saturating_sub(1));
+                selected.set(Some(order[previous].clone()));
+            }
+            Key::Delete => {
+                if let Some(name) = selected.take() {
+                    core.write().update(Message::Untrack(name));
+                }
+            }
+            _ => {}
+        }
+    };
 
     rsx! {
-        ThemeProvider {
-            theme: THEME,
-            Body {
+        Body {
+            rect {
+                width: "100%",
+                height: "100%",
+                onglobalkeydown,
                 NetworkTablesStateSelector {
-                    state: nt_state
+                    core
+                }
+                ReplayControls {
+                    core
+                }
+                ThemeSelector {
+                    theme_file
+                }
+                rect {
+                    direction: "horizontal",
+                    padding: "0 70 0 70",
+                    Button {
+                        onclick: move |_| tab.set(Tab::Topics),
+                        label {
+                            "Topics"
+                        }
+                    }
+                    Button {
+                        onclick: move |_| tab.set(Tab::Dashboard),
+                        label {
+                            "Dashboard"
+                        }
+                    }
+                    Button {
+                        onclick: move |_| tab.set(Tab::Simulation),
+                        label {
+                            "Simulation"
+                        }
+                    }
+                }
+                if tab() == Tab::Dashboard {
+                    Dashboard {
+                        core
+                    }
+                } else if tab() == Tab::Simulation {
+                    SimulationPanel {
+                        core
+                    }
+                } else {
+                    TrackedTopics {
+                        core,
+                        selected
+                    }
+                    ValueDetail {
+                        core,
+                        selected,
+                        selected_schema
+                    }
+                    AddWidget {
+                        core,
+                        selected
+                    }
+                    EditValue {
+                        core,
+                        selected
+                    }
+                    LatencyGraph {
+                        core
+                    }
+                    SchemaBrowser {
+                        core,
+                        selected_schema
+                    }
+                }
+                WriteConfirmation {
+                    core
+                }
+                CommandPalette {
+                    core,
+                    selected,
+                    open: palette_open
                 }
             }
         }
@@ -64,6 +218,7 @@ fn app() -> Element {
 
 #[component]
 fn DropDownMenu(label: String, children: Element) -> Element {
+    let palette = use_palette();
     let mut show_menu = use_signal(|| false);
     let animations = use_animation(|ctx| {
         ctx.with(
@@ -81,7 +236,7 @@ fn DropDownMenu(label: String, children: Element) -> Element {
         rect {
             direction: "horizontal",
             cross_align: "center",
-            background: "#1e2030",
+            background: "{palette.panel}",
             corner_radius: "{theme.button.corner_radius}",
 
             rect {
@@ -92,7 +247,7 @@ fn DropDownMenu(label: String, children: Element) -> Element {
             }
             Button {
                 theme: theme_with!(ButtonTheme {
-                    background: "#181926".into(),
+                    background: palette.inset.clone().into(),
                     border_fill: "transparent".into(),
                     margin: "0".into(),
                     height: "40".into(),
@@ -126,18 +281,18 @@ fn DropDownMenu(label: String, children: Element) -> Element {
 }
 
 #[component]
-fn NetworkTablesStateSelector(state: Signal<NetworkTablesState, UnsyncStorage>) -> Element {
-    let displayed_state = match state() {
+fn NetworkTablesStateSelector(core: Signal<Core, UnsyncStorage>) -> Element {
+    let displayed_state = match core.read().state() {
         NetworkTablesState::None => "None",
         NetworkTablesState::Client(NetworkTablesVersion::V3) => "Client (V3)",
         NetworkTablesState::Client(NetworkTablesVersion::V4) => "Client (V4)",
         NetworkTablesState::Server => "Server",
     };
+    let read_only = core.read().is_read_only();
 
     rsx! {
         rect {
             width: "100%",
-            height: "100%",
             padding: "10 70 10 70",
             DropDownMenu {
                 label: "State: {displayed_state}",
@@ -145,25 +300,1428 @@ fn NetworkTablesStateSelector(state: Signal<NetworkTablesState, UnsyncStorage>)
                     label {
                         "None"
                     }
-                    onclick: move |_| state.set(NetworkTablesState::None)
+                    onclick: move |_| core.write().update(Message::SetState(NetworkTablesState::None))
                 }
                 MenuButton {
                     label {
                         "Server"
                     }
-                    onclick: move |_| state.set(NetworkTablesState::Server)
+                    onclick: move |_| core.write().update(Message::SetState(NetworkTablesState::Server))
                 }
                 MenuButton {
                     label {
                         "Client (V3)"
                     }
-                    onclick: move |_| state.set(NetworkTablesState::Client(NetworkTablesVersion::V3))
+                    onclick: move |_| core.write().update(Message::SetState(NetworkTablesState::Client(NetworkTablesVersion::V3)))
                 }
                 MenuButton {
                     label {
                         "Client (V4)"
                     }
-                    onclick: move |_| state.set(NetworkTablesState::Client(NetworkTablesVersion::V4))
+                    onclick: move |_| core.write().update(Message::SetState(NetworkTablesState::Client(NetworkTablesVersion::V4)))
+                }
+            }
+            Button {
+                onclick: move |_| core.write().update(Message::SetReadOnly(!read_only)),
+                label {
+                    if read_only { "Read-only: On" } else { "Read-only: Off" }
+                }
+            }
+        }
+    }
+}
+
+/// Opens data logs, and while one is replayed, scrubs through it.
+#[component]
+fn ReplayControls(core: Signal<Core, UnsyncStorage>) -> Element {
+    let palette = use_palette();
+    let mut path = use_signal(String::new);
+    let replay_error = core.read().replay_error().map(str::to_owned);
+    let timeline = core.read().replay().map(|replay| {
+        (
+            replay.path().display().to_string(),
+            replay.start(),
+            replay.end(),
+            replay.position(),
+        )
+    });
+
+    rsx! {
+        rect {
+            width: "100%",
+            padding: "0 70 10 70",
+            if let Some((file, start, end, position)) = timeline {
+                rect {
+                    direction: "horizontal",
+                    cross_align: "center",
+                    label {
+                        "Replaying {file}"
+                    }
+                    Button {
+                        onclick: move |_| core.write().update(Message::SetState(NetworkTablesState::None)),
+                        label {
+                            "Close log"
+                        }
+                    }
+                }
+                rect {
+                    direction: "horizontal",
+                    cross_align: "center",
+                    Slider {
+                        value: (if end > start { (position - start).as_secs_f64() / (end - start).as_secs_f64() * 100.0 } else { 0.0 }),
+                        onmoved: move |percent: f64| {
+                            let time = start + (end - start).mul_f64(percent / 100.0);
+                            core.write().update(Message::Seek(time));
+                        }
+                    }
+                    label {
+                        "{position.as_secs_f64():.2} s / {end.as_secs_f64():.2} s"
+                    }
+                }
+            } else {
+                rect {
+                    direction: "horizontal",
+                    cross_align: "center",
+                    Input {
+                        value: path.read().clone(),
+                        placeholder: "Data log (.wpilog)",
+                        onchange: move |value| path.set(value)
+                    }
+                    Button {
+                        onclick: move |_| core.write().update(Message::OpenLog(path.read().clone().into())),
+                        label {
+                            "Open log"
+                        }
+                    }
+                }
+            }
+            if let Some(replay_error) = replay_error {
+                label {
+                    color: "{palette.error}",
+                    "{replay_error}"
+                }
+            }
+        }
+    }
+}
+
+/// Picks the theme from the built-in ones and the user's theme file.
+#[component]
+fn ThemeSelector(theme_file: Result<Option<Palette>, String>) -> Element {
+    let mut palette = use_context::<Signal<Palette, UnsyncStorage>>();
+    let mut theme = use_theme();
+    let current = palette.read().clone();
+    let choices: Vec<Palette> = theme_file
+        .clone()
+        .ok()
+        .flatten()
+        .into_iter()
+        .chain(Palette::presets())
+        .collect();
+
+    rsx! {
+        rect {
+            width: "100%",
+            padding: "0 70 10 70",
+            DropDownMenu {
+                label: "Theme: {current.name}",
+                for choice in choices {
+                    MenuButton {
+                        key: "{choice.name}",
+                        onclick: move |_| {
+                            theme.set(freya_theme(&choice));
+                            palette.set(choice.clone());
+                        },
+                        label {
+                            "{choice.name}"
+                        }
+                    }
+                }
+            }
+            if let Err(err) = theme_file {
+                label {
+                    color: "{current.error}",
+                    "{THEME_FILE}: {err}"
+                }
+            }
+        }
+    }
+}
+
+/// A row of [`TrackedTopics`].
+struct TopicRow {
+    name: String,
+    value: String,
+    unit: String,
+    description: Option<String>,
+    /// Updates per second, or `None` if the topic hasn't been updated since connecting.
+    update_rate: Option<f64>,
+    last_update_age: Option<f64>,
+    stale: bool,
+    pinned: bool,
+}
+
+#[component]
+fn TrackedTopics(
+    core: Signal<Core, UnsyncStorage>,
+    selected: Signal<Option<String>, UnsyncStorage>,
+) -> Element {
+    let palette = use_palette();
+    let core = core.read();
+    let selected_name = selected.read().clone();
+    let rows = core.topic_order().into_iter().filter_map(|name| {
+        let value = core.mirror().get(name)?;
+        let annotations = core.annotations(name).cloned().unwrap_or_default();
+        let stats = core.stats(name);
+        Some(TopicRow {
+            name: name.to_owned(),
+            value: format!("{:?}", value.data),
+            unit: annotations.unit.unwrap_or_default(),
+            description: annotations.description,
+            update_rate: stats.as_ref().map(|stats| stats.update_rate),
+            last_update_age: stats
+                .as_ref()
+                .map(|stats| stats.last_update_age.as_secs_f64()),
+            stale: !stats.is_some_and(|stats| stats.last_update_age <= STALE_AFTER),
+            pinned: core.is_pinned(name),
+        })
+    });
+
+    rsx! {
+        rect {
+            width: "100%",
+            padding: "10 70 10 70",
+            rect {
+                direction: "horizontal",
+                color: "{palette.subtext}",
+                label {
+                    width: "fill",
+                    "Topic"
+                }
+                label {
+                    width: "90",
+                    "Rate"
+                }
+                label {
+                    width: "90",
+                    "Last update"
+                }
+            }
+            for row in rows {
+                rect {
+                    key: "{row.name}",
+                    padding: "5 0 5 0",
+                    background: (if selected_name.as_ref() == Some(&row.name) { palette.selection.clone() } else { "transparent".to_string() }),
+                    onclick: {
+                        let name = row.name.clone();
+                        move |_| selected.set(Some(name.clone()))
+                    },
+                    rect {
+                        direction: "horizontal",
+                        label {
+                            width: "fill",
+                            {if row.pinned { "* " } else { "" }}
+                            "{row.name}: {row.value} {row.unit}"
+                        }
+                        label {
+                            width: "90",
+                            {row.update_rate.map_or("-".to_string(), |rate| format!("{rate:.1} Hz"))}
+                        }
+                        label {
+                            width: "90",
+                            color: (if row.stale { palette.error.clone() } else { palette.success.clone() }),
+                            {row.last_update_age.map_or("never".to_string(), |age| format!("{age:.1} s ago"))}
+                        }
+                    }
+                    if let Some(description) = row.description {
+                        label {
+                            font_size: "12",
+                            color: "{palette.subtext}",
+                            "{description}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ValueDetail(
+    core: Signal<Core, UnsyncStorage>,
+    selected: Signal<Option<String>, UnsyncStorage>,
+    selected_schema: Signal<Option<String>, UnsyncStorage>,
+) -> Element {
+    let palette = use_palette();
+    // `None` shows raw values as a hex dump.
+    let mut decoding = use_signal(|| None::<RawDecoding>);
+    let core = core.read();
+    let name = selected.read().clone()?;
+    let value = core.mirror().get(&name)?;
+
+    if let Some(elements) = inspect::array_elements(&value.data) {
+        return rsx! {
+            rect {
+                width: "100%",
+                padding: "10 70 10 70",
+                label {
+                    "{name} ({elements.len()} elements)"
+                }
+                ScrollView {
+                    height: "300",
+                    for (index, element) in elements.into_iter().enumerate() {
+                        rect {
+                            key: "{index}",
+                            direction: "horizontal",
+                            label {
+                                width: "60",
+                                color: "{palette.subtext}",
+                                "{index}"
+                            }
+                            label {
+                                "{element}"
+                            }
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    let Value::Raw(bytes) = &value.data else {
+        return None;
+    };
+    let hex_lines = inspect::hex_dump(bytes);
+    let decoded = decoding().and_then(|decoding| core.decode(&name, decoding));
+    let schema_topic = match core.kind(&name) {
+        Some(TopicKind::Struct(struct_type)) => Some(schemas::struct_schema_topic(struct_type)),
+        _ => None,
+    };
+
+    rsx! {
+        rect {
+            width: "100%",
+            padding: "10 70 10 70",
+            label {
+                "{name} ({bytes.len()} bytes)"
+            }
+            rect {
+                direction: "horizontal",
+                Button {
+                    onclick: move |_| decoding.set(None),
+                    label {
+                        "Hex"
+                    }
+                }
+                for option in RawDecoding::ALL {
+                    Button {
+                        key: "{option.name()}",
+                        onclick: move |_| decoding.set(Some(option)),
+                        label {
+                            "{option.name()}"
+                        }
+                    }
+                }
+                if let Some(schema_topic) = schema_topic {
+                    Button {
+                        onclick: move |_| selected_schema.set(Some(schema_topic.clone())),
+                        label {
+                            "Show schema"
+                        }
+                    }
+                }
+            }
+            ScrollView {
+                height: "300",
+                {match decoded {
+                    Some(Ok(text)) => rsx! {
+                        label {
+                            font_family: "monospace",
+                            "{text}"
+                        }
+                    },
+                    Some(Err(err)) => rsx! {
+                        label {
+                            color: "{palette.error}",
+                            "{err}"
+                        }
+                    },
+                    None => rsx! {
+                        for line in hex_lines {
+                            label {
+                                key: "{line.offset}",
+                                font_family: "monospace",
+                                "{line.offset:08x}  {line.hex}  {line.ascii}"
+                            }
+                        }
+                    },
+                }}
+            }
+        }
+    }
+}
+
+#[component]
+fn EditValue(
+    core: Signal<Core, UnsyncStorage>,
+    selected: Signal<Option<String>, UnsyncStorage>,
+) -> Element {
+    let palette = use_palette();
+    let mut text = use_signal(String::new);
+    let name = selected.read().clone()?;
+    if core.read().is_read_only() {
+        return None;
+    }
+    let write_error = core.read().write_error().map(str::to_owned);
+
+    rsx! {
+        rect {
+            width: "100%",
+            padding: "10 70 10 70",
+            rect {
+                direction: "horizontal",
+                cross_align: "center",
+                Input {
+                    value: text.read().clone(),
+                    placeholder: "New value",
+                    onchange: move |value| text.set(value)
+                }
+                Button {
+                    onclick: move |_| core.write().update(Message::Write {
+                        name: name.clone(),
+                        value: text.read().clone(),
+                    }),
+                    label {
+                        "Set"
+                    }
+                }
+            }
+            if let Some(write_error) = write_error {
+                label {
+                    color: "{palette.error}",
+                    "{write_error}"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn WriteConfirmation(core: Signal<Core, UnsyncStorage>) -> Element {
+    let pending = core.read().pending_write().cloned()?;
+
+    rsx! {
+        Popup {
+            oncloserequest: move |_| core.write().update(Message::CancelWrite),
+            PopupTitle {
+                label {
+                    "Overwrite {pending.name}?"
+                }
+            }
+            PopupContent {
+                label {
+                    "The topic is persistent or retained, so the new value {pending.value:?} outlives the robot program."
+                }
+                rect {
+                    direction: "horizontal",
+                    Button {
+                        onclick: move |_| core.write().update(Message::ConfirmWrite),
+                        label {
+                            "Overwrite"
+                        }
+                    }
+                    Button {
+                        onclick: move |_| core.write().update(Message::CancelWrite),
+                        label {
+                            "Cancel"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The height of each plot in [`LatencyGraph`].
+const GRAPH_HEIGHT: f64 = 60.0;
+
+/// Plots RTT/2 and the server time offset of the client, oldest measurement first.
+#[component]
+fn LatencyGraph(core: Signal<Core, UnsyncStorage>) -> Element {
+    let palette = use_palette();
+    let core = core.read();
+    if !matches!(core.state(), NetworkTablesState::Client(_)) {
+        return None;
+    }
+    let latency = core.latency();
+    let latest = latency.latest().copied();
+
+    let max_rtt2 = latency.max_rtt2().unwrap_or_default().as_secs_f64();
+    let rtt2_heights: Vec<f64> = latency
+        .samples()
+        .map(|sample| {
+            if max_rtt2 == 0.0 {
+                0.0
+            } else {
+                sample.rtt2.as_secs_f64() / max_rtt2 * GRAPH_HEIGHT
+            }
+        })
+        .collect();
+
+    // The offset is plotted against its own range, since its absolute value depends on when each node started.
+    let offsets = latency.samples().map(|sample| sample.server_time_offset);
+    let min_offset = offsets.clone().min().unwrap_or_default();
+    let offset_range = (offsets.clone().max().unwrap_or_default() - min_offset) as f64;
+    let offset_heights: Vec<f64> = offsets
+        .map(|offset| {
+            if offset_range == 0.0 {
+                GRAPH_HEIGHT / 2.0
+            } else {
+                (offset - min_offset) as f64 / offset_range * GRAPH_HEIGHT
+            }
+        })
+        .collect();
+
+    rsx! {
+        rect {
+            width: "100%",
+            padding: "10 70 10 70",
+            {match latest {
+                Some(latest) => rsx! {
+                    label {
+                        "RTT/2: {latest.rtt2.as_secs_f64() * 1000.0:.1} ms (max {max_rtt2 * 1000.0:.1} ms)"
+                    }
+                },
+                None => rsx! {
+                    label {
+                        color: "{palette.subtext}",
+                        "No time sync measurements yet"
+                    }
+                },
+            }}
+            rect {
+                direction: "horizontal",
+                main_align: "end",
+                cross_align: "end",
+                width: "100%",
+                height: "{GRAPH_HEIGHT}",
+                background: "{palette.panel}",
+                for (index, height) in rtt2_heights.into_iter().enumerate() {
+                    rect {
+                        key: "{index}",
+                        width: "2",
+                        height: "{height}",
+                        background: "{palette.info}"
+                    }
+                }
+            }
+            if let Some(latest) = latest {
+                label {
+                    "Server time offset: {latest.server_time_offset} us (range {offset_range} us)"
+                }
+            }
+            rect {
+                direction: "horizontal",
+                main_align: "end",
+                cross_align: "end",
+                width: "100%",
+                height: "{GRAPH_HEIGHT}",
+                background: "{palette.panel}",
+                for (index, height) in offset_heights.into_iter().enumerate() {
+                    rect {
+                        key: "{index}",
+                        width: "2",
+                        height: "{height}",
+                        background: "{palette.accent}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Lists the schemas published under `/.schema/` and shows the definition of the selected one.
+#[component]
+fn SchemaBrowser(
+    core: Signal<Core, UnsyncStorage>,
+    selected_schema: Signal<Option<String>, UnsyncStorage>,
+) -> Element {
+    let palette = use_palette();
+    let core = core.read();
+    let topics: Vec<String> = core.schemas().map(|schema| schema.topic.clone()).collect();
+    if topics.is_empty() {
+        return None;
+    }
+    let selected = selected_schema.read().clone();
+    let definition = selected.as_ref().map(|topic| match core.schema(topic) {
+        Some(schema) => schema.definition.clone().map_err(|err| err.to_string()),
+        None => Err(format!("{topic} hasn't been published")),
+    });
+
+    rsx! {
+        rect {
+            width: "100%",
+            padding: "10 70 10 70",
+            direction: "horizontal",
+            ScrollView {
+                width: "300",
+                height: "300",
+                for topic in topics {
+                    rect {
+                        key: "{topic}",
+                        padding: "2 0 2 0",
+                        background: (if selected.as_ref() == Some(&topic) { palette.selection.clone() } else { "transparent".to_string() }),
+                        onclick: {
+                            let topic = topic.clone();
+                            move |_| selected_schema.set(Some(topic.clone()))
+                        },
+                        label {
+                            "{topic}"
+                        }
+                    }
+                }
+            }
+            ScrollView {
+                width: "fill",
+                height: "300",
+                {match definition {
+                    Some(Ok(text)) => rsx! {
+                        label {
+                            font_family: "monospace",
+                            "{text}"
+                        }
+                    },
+                    Some(Err(err)) => rsx! {
+                        label {
+                            color: "{palette.error}",
+                            "{err}"
+                        }
+                    },
+                    None => rsx! {
+                        label {
+                            color: "{palette.subtext}",
+                            "Select a schema"
+                        }
+                    },
+                }}
+            }
+        }
+    }
+}
+
+/// A Ctrl+P palette that runs commands by name, filtered by typing.
+#[component]
+fn CommandPalette(
+    core: Signal<Core, UnsyncStorage>,
+    selected: Signal<Option<String>, UnsyncStorage>,
+    open: Signal<bool, UnsyncStorage>,
+) -> Element {
+    let palette = use_palette();
+    let mut query = use_signal(String::new);
+    let mut highlighted = use_signal(|| 0usize);
+    if !open() {
+        return None;
+    }
+
+    let mut matches = palette::search(palette::commands(&core.read()), &query.read());
+    matches.truncate(PALETTE_LENGTH);
+    let labels: Vec<String> = matches.iter().map(palette::Command::label).collect();
+
+    let mut close = move || {
+        open.set(false);
+        query.set(String::new());
+        highlighted.set(0);
+    };
+    let onglobalkeydown = move |event: KeyboardEvent| match &event.key {
+        Key::Escape => close(),
+        Key::ArrowDown => highlighted.set((highlighted() + 1).min(matches.len().saturating_sub(1))),
+        Key::ArrowUp => highlighted.set(highlighted().saturating_sub(1)),
+        Key::Enter => {
+            if let Some(command) = matches.get(highlighted()) {
+                core.write().update(command.message());
+                if let Some(topic) = command.topic() {
+                    selected.set(Some(topic.to_owned()));
+                }
+            }
+            close();
+        }
+        Key::Backspace => {
+            query.write().pop();
+            highlighted.set(0);
+        }
+        Key::Character(text) if !event.modifiers.contains(Modifiers::CONTROL) => {
+            query.write().push_str(text);
+            highlighted.set(0);
+        }
+        _ => {}
+    };
+
+    rsx! {
+        Popup {
+            oncloserequest: move |_| close(),
+            PopupTitle {
+                label {
+                    "> {query}"
+                }
+            }
+            PopupContent {
+                rect {
+                    onglobalkeydown,
+                    if labels.is_empty() {
+                        label {
+                            color: "{palette.subtext}",
+                            "No matching commands"
+                        }
+                    }
+                    for (index, label) in labels.into_iter().enumerate() {
+                        rect {
+                            key: "{index}",
+                            padding: "2 5 2 5",
+                            background: (if index == highlighted() { palette.selection.clone() } else { "transparent".to_string() }),
+                            label {
+                                "{label}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Buttons pinning the selected topic to the dashboard as a widget fitting its type.
+#[component]
+fn AddWidget(
+    core: Signal<Core, UnsyncStorage>,
+    selected: Signal<Option<String>, UnsyncStorage>,
+) -> Element {
+    let name = selected.read().clone()?;
+    let value_type = core.read().mirror().get(&name)?.data.value_type();
+
+    rsx! {
+        rect {
+            width: "100%",
+            padding: "0 70 0 70",
+            direction: "horizontal",
+            for kind in WidgetKind::for_type(value_type) {
+                Button {
+                    key: "{kind.name()}",
+                    onclick: {
+                        let name = name.clone();
+                        move |_| core.write().update(Message::AddWidget(kind.create(name.clone())))
+                    },
+                    label {
+                        "Add {kind.name()} to dashboard"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The side of a cell of the dashboard grid, in pixels.
+const CELL_SIZE: usize = 140;
+/// Empty rows shown below the widgets, so there is always room to drop new ones.
+const SPARE_ROWS: usize = 2;
+
+/// What is being dragged onto the dashboard grid.
+#[derive(Debug, Clone, PartialEq)]
+enum DragData {
+    Topic(String),
+    /// The widget at the given index of the layout.
+    Widget(usize),
+}
+
+/// A grid of widgets that topics are dragged onto from a side list, and that are rearranged by dragging.
+#[component]
+fn Dashboard(core: Signal<Core, UnsyncStorage>) -> Element {
+    let palette = use_palette();
+    // A topic dropped on a cell, waiting for the kind of widget to show it with.
+    let mut dropped = use_signal(|| None::<(String, Cell)>);
+    let widgets = core.read().layout().widgets.clone();
+    let rows = core.read().layout().rows() + SPARE_ROWS;
+    let topics: Vec<String> = core.read().topics().map(str::to_owned).collect();
+    let layout_error = core.read().layout_error().map(str::to_owned);
+
+    rsx! {
+        DragProvider::<DragData> {
+            rect {
+                width: "100%",
+                height: "fill",
+                padding: "10 70 10 70",
+                if let Some(layout_error) = layout_error {
+                    label {
+                        color: "{palette.error}",
+                        "{layout_error}"
+                    }
+                }
+                rect {
+                    direction: "horizontal",
+                    height: "fill",
+                    rect {
+                        width: "250",
+                        height: "fill",
+                        label {
+                            color: "{palette.subtext}",
+                            "Drag topics onto the grid"
+                        }
+                        ScrollView {
+                            height: "fill",
+                            for topic in topics {
+                                DragZone {
+                                    key: "{topic}",
+                                    data: DragData::Topic(topic.clone()),
+                                    drag_element: rsx! {
+                                        label {
+                                            "{topic}"
+                                        }
+                                    },
+                                    label {
+                                        "{topic}"
+                                    }
+                                }
+                            }
+                        }
+                        Button {
+                            onclick: move |_| core.write().update(Message::AddWidget(Widget::Alerts(AlertBanner::new(DEFAULT_ALERTS_GROUP)))),
+                            label {
+                                "Add alerts"
+                            }
+                        }
+                    }
+                    ScrollView {
+                        width: "fill",
+                        height: "fill",
+                        rect {
+                            width: "{GRID_COLUMNS * CELL_SIZE}",
+                            height: "{rows * CELL_SIZE}",
+                            for row in 0..rows {
+                                rect {
+                                    key: "{row}",
+                                    direction: "horizontal",
+                                    for column in 0..GRID_COLUMNS {
+                                        DropZone {
+                                            key: "{column}",
+                                            ondrop: move |data: DragData| match data {
+                                                DragData::Topic(topic) => dropped.set(Some((topic, Cell::at(column, row)))),
+                                                DragData::Widget(index) => {
+                                                    let current = core.read().layout().widgets.get(index).map(|placed| placed.cell);
+                                                    if let Some(current) = current {
+                                                        let cell = Cell { column, row, ..current };
+                                                        core.write().update(Message::MoveWidget { index, cell });
+                                                    }
+                                                }
+                                            },
+                                            rect {
+                                                width: "{CELL_SIZE}",
+                                                height: "{CELL_SIZE}",
+                                                border: "1 solid {palette.selection}"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            for (index, placed) in widgets.into_iter().enumerate() {
+                                DashboardWidget {
+                                    key: "{index}",
+                                    core,
+                                    index,
+                                    placed
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        if let Some((topic, cell)) = dropped() {
+            WidgetKindPicker {
+                core,
+                topic,
+                cell,
+                dropped
+            }
+        }
+    }
+}
+
+/// Asks which kind of widget a topic dropped on the grid should be shown with.
+#[component]
+fn WidgetKindPicker(
+    core: Signal<Core, UnsyncStorage>,
+    topic: String,
+    cell: Cell,
+    dropped: Signal<Option<(String, Cell)>, UnsyncStorage>,
+) -> Element {
+    let value_type = core
+        .read()
+        .mirror()
+        .get(&topic)
+        .map_or(ValueType::Unassigned, |value| value.data.value_type());
+
+    rsx! {
+        Popup {
+            oncloserequest: move |_| dropped.set(None),
+            PopupTitle {
+                label {
+                    "Show {topic} as"
+                }
+            }
+            PopupContent {
+                rect {
+                    direction: "horizontal",
+                    for kind in WidgetKind::for_type(value_type) {
+                        Button {
+                            key: "{kind.name()}",
+                            onclick: {
+                                let topic = topic.clone();
+                                move |_| {
+                                    let widget = kind.create(topic.clone());
+                                    core.write().update(Message::PlaceWidget { widget, cell });
+                                    dropped.set(None);
+                                }
+                            },
+                            label {
+                                "{kind.name()}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A widget on the dashboard grid, dragged by its title to move it.
+#[component]
+fn DashboardWidget(core: Signal<Core, UnsyncStorage>, index: usize, placed: PlacedWidget) -> Element {
+    let palette = use_palette();
+    let mut configuring = use_signal(|| false);
+    let PlacedWidget { widget, cell } = placed;
+    let title = widget.title().to_owned();
+
+    let body = {
+        let core = core.read();
+        match &widget {
+            Widget::Boolean(indicator) => {
+                let (color, text) = match indicator.value(core.mirror()) {
+                    Some(true) => (indicator.true_color.clone(), "True"),
+                    Some(false) => (indicator.false_color.clone(), "False"),
+                    None => (palette.hover.clone(), "No value"),
+                };
+                rsx! {
+                    rect {
+                        width: "100%",
+                        height: "fill",
+                        main_align: "center",
+                        cross_align: "center",
+                        background: "{color}",
+                        corner_radius: "8",
+                        label {
+                            font_size: "24",
+                            color: "{palette.on_status}",
+                            "{text}"
+                        }
+                    }
+                }
+            }
+            Widget::Gauge(gauge) => match gauge.value(core.mirror()) {
+                Some(value) => {
+                    let color = match gauge.level(value) {
+                        GaugeLevel::Normal => &palette.success,
+                        GaugeLevel::Warning => &palette.warning,
+                        GaugeLevel::Critical => &palette.error,
+                    };
+                    let percent = gauge.fraction(value) * 100.0;
+                    rsx! {
+                        label {
+                            font_size: "24",
+                            "{value:.2}"
+                        }
+                        rect {
+                            width: "100%",
+                            height: "20",
+                            background: "{palette.panel}",
+                            corner_radius: "4",
+                            rect {
+                                width: "{percent}%",
+                                height: "100%",
+                                background: "{color}",
+                                corner_radius: "4"
+                            }
+                        }
+                    }
+                }
+                None => rsx! {
+                    label {
+                        color: "{palette.subtext}",
+                        "No value"
+                    }
+                },
+            },
+            Widget::Value(display) => {
+                let text = core
+                    .mirror()
+                    .get(&display.topic)
+                    .map_or("No value".to_string(), |value| format!("{:?}", value.data));
+                rsx! {
+                    label {
+                        font_size: "18",
+                        "{text}"
+                    }
+                }
+            }
+            Widget::Alerts(banner) => {
+                let alerts = banner.alerts(core.mirror());
+                let banners = alerts
+                    .errors
+                    .into_iter()
+                    .map(|text| (palette.error.clone(), text))
+                    .chain(alerts.warnings.into_iter().map(|text| (palette.warning.clone(), text)))
+                    .chain(alerts.infos.into_iter().map(|text| (palette.info.clone(), text)))
+                    .enumerate();
+                rsx! {
+                    ScrollView {
+                        for (index, (color, text)) in banners {
+                            rect {
+                                key: "{index}",
+                                width: "100%",
+                                padding: "5 10 5 10",
+                                margin: "2 0 2 0",
+                                background: "{color}",
+                                corner_radius: "4",
+                                label {
+                                    color: "{palette.on_status}",
+                                    "{text}"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    rsx! {
+        rect {
+            position: "absolute",
+            position_left: "{cell.column * CELL_SIZE}",
+            position_top: "{cell.row * CELL_SIZE}",
+            width: "{cell.width * CELL_SIZE}",
+            height: "{cell.height * CELL_SIZE}",
+            padding: "5",
+            background: "{palette.background}",
+            border: "1 solid {palette.border}",
+            rect {
+                direction: "horizontal",
+                cross_align: "center",
+                DragZone {
+                    data: DragData::Widget(index),
+                    drag_element: rsx! {
+                        label {
+                            "{title}"
+                        }
+                    },
+                    label {
+                        width: "fill",
+                        "{title}"
+                    }
+                }
+                Button {
+                    onclick: move |_| configuring.toggle(),
+                    label {
+                        "..."
+                    }
+                }
+            }
+            {body}
+        }
+        if configuring() {
+            Popup {
+                oncloserequest: move |_| configuring.set(false),
+                PopupTitle {
+                    label {
+                        "{title}"
+                    }
+                }
+                PopupContent {
+                    WidgetSettings {
+                        core,
+                        index,
+                        widget
+                    }
+                    rect {
+                        direction: "horizontal",
+                        for (text, width, height) in [("Wider", 1, 0), ("Narrower", -1, 0), ("Taller", 0, 1), ("Shorter", 0, -1)] {
+                            Button {
+                                key: "{text}",
+                                onclick: move |_| {
+                                    let cell = Cell {
+                                        width: cell.width.saturating_add_signed(width),
+                                        height: cell.height.saturating_add_signed(height),
+                                        ..cell
+                                    };
+                                    core.write().update(Message::MoveWidget { index, cell });
+                                },
+                                label {
+                                    "{text}"
+                                }
+                            }
+                        }
+                    }
+                    Button {
+                        onclick: move |_| {
+                            configuring.set(false);
+                            core.write().update(Message::RemoveWidget(index));
+                        },
+                        label {
+                            "Remove"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Inputs for the settings of a widget, which update it as soon as they hold a valid value.
+#[component]
+fn WidgetSettings(core: Signal<Core, UnsyncStorage>, index: usize, widget: Widget) -> Element {
+    let palette = use_palette();
+    let mut update = move |widget: Widget| core.write().update(Message::UpdateWidget { index, widget });
+    let optional = |text: &str| -> Option<Option<f64>> {
+        if text.trim().is_empty() {
+            Some(None)
+        } else {
+            text.trim().parse().ok().map(Some)
+        }
+    };
+
+    match widget {
+        Widget::Boolean(indicator) => rsx! {
+            SettingInput {
+                name: "True color",
+                value: indicator.true_color.clone(),
+                onchange: {
+                    let indicator = indicator.clone();
+                    move |text: String| update(Widget::Boolean(BooleanIndicator { true_color: text, ..indicator.clone() }))
+                }
+            }
+            SettingInput {
+                name: "False color",
+                value: indicator.false_color.clone(),
+                onchange: move |text: String| update(Widget::Boolean(BooleanIndicator { false_color: text, ..indicator.clone() }))
+            }
+        },
+        Widget::Gauge(gauge) => rsx! {
+            SettingInput {
+                name: "Minimum",
+                value: gauge.min.to_string(),
+                onchange: {
+                    let gauge = gauge.clone();
+                    move |text: String| if let Ok(min) = text.trim().parse() {
+                        update(Widget::Gauge(Gauge { min, ..gauge.clone() }));
+                    }
+                }
+            }
+            SettingInput {
+                name: "Maximum",
+                value: gauge.max.to_string(),
+                onchange: {
+                    let gauge = gauge.clone();
+                    move |text: String| if let Ok(max) = text.trim().parse() {
+                        update(Widget::Gauge(Gauge { max, ..gauge.clone() }));
+                    }
+                }
+            }
+            SettingInput {
+                name: "Warning at",
+                value: gauge.warning.map(|warning| warning.to_string()).unwrap_or_default(),
+                onchange: {
+                    let gauge = gauge.clone();
+                    move |text: String| if let Some(warning) = optional(&text) {
+                        update(Widget::Gauge(Gauge { warning, ..gauge.clone() }));
+                    }
+                }
+            }
+            SettingInput {
+                name: "Critical at",
+                value: gauge.critical.map(|critical| critical.to_string()).unwrap_or_default(),
+                onchange: move |text: String| if let Some(critical) = optional(&text) {
+                    update(Widget::Gauge(Gauge { critical, ..gauge.clone() }));
+                }
+            }
+        },
+        Widget::Value(_) => rsx! {
+            label {
+                color: "{palette.subtext}",
+                "Value widgets have no settings"
+            }
+        },
+        Widget::Alerts(banner) => rsx! {
+            SettingInput {
+                name: "Group",
+                value: banner.group,
+                onchange: move |group: String| update(Widget::Alerts(AlertBanner::new(group)))
+            }
+        },
+    }
+}
+
+/// POV angles offered by [`SimControlEditor`], with `-1` for centered.
+const POV_ANGLES: [i64; 9] = [-1, 0, 45, 90, 135, 180, 225, 270, 315];
+
+/// Inputs for simulated robot code, which are published as soon as they change.
+#[component]
+fn SimulationPanel(core: Signal<Core, UnsyncStorage>) -> Element {
+    let palette = use_palette();
+    let mut name = use_signal(String::new);
+    let sim = core.read().sim().clone();
+    let mut prefix = use_signal(|| sim.prefix.clone());
+    let free_port = sim.free_port();
+    let write_error = core.read().write_error().map(str::to_owned);
+    let mut add = move |control: fn(String) -> SimControl| {
+        let name = name.read().trim().to_string();
+        if !name.is_empty() {
+            core.write().update(Message::AddSimControl(control(name)));
+        }
+    };
+
+    rsx! {
+        rect {
+            width: "100%",
+            height: "fill",
+            padding: "10 70 10 70",
+            rect {
+                direction: "horizontal",
+                cross_align: "center",
+                label {
+                    width: "120",
+                    "Prefix"
+                }
+                Input {
+                    value: prefix.read().clone(),
+                    onchange: move |value| prefix.set(value)
+                }
+                // Applied on demand, so typing a prefix doesn't publish the inputs under every partial one.
+                Button {
+                    onclick: move |_| core.write().update(Message::SetSimPrefix(prefix.read().clone())),
+                    label {
+                        "Move inputs"
+                    }
+                }
+            }
+            rect {
+                direction: "horizontal",
+                cross_align: "center",
+                Input {
+                    value: name.read().clone(),
+                    placeholder: "Input name",
+                    onchange: move |value| name.set(value)
+                }
+                Button {
+                    onclick: move |_| add(|name| SimControl::Bool { name, value: false }),
+                    label {
+                        "Add boolean"
+                    }
+                }
+                Button {
+                    onclick: move |_| add(|name| SimControl::Number { name, value: 0.0 }),
+                    label {
+                        "Add number"
+                    }
+                }
+                if let Some(port) = free_port {
+                    Button {
+                        onclick: move |_| core.write().update(Message::AddSimControl(SimControl::joystick(port))),
+                        label {
+                            "Add joystick {port}"
+                        }
+                    }
+                }
+            }
+            if let Some(write_error) = write_error {
+                label {
+                    color: "{palette.error}",
+                    "{write_error}"
+                }
+            }
+            ScrollView {
+                height: "fill",
+                for (index, control) in sim.controls.into_iter().enumerate() {
+                    SimControlEditor {
+                        key: "{index}",
+                        core,
+                        index,
+                        control
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Changes a single simulation input.
+#[component]
+fn SimControlEditor(core: Signal<Core, UnsyncStorage>, index: usize, control: SimControl) -> Element {
+    let palette = use_palette();
+    let mut update = move |control: SimControl| core.write().update(Message::UpdateSimControl { index, control });
+    let title = control.title();
+
+    let body = match control {
+        SimControl::Bool { name, value } => {
+            let state = if value { "On" } else { "Off" };
+            rsx! {
+                Button {
+                    onclick: move |_| update(SimControl::Bool { name: name.clone(), value: !value }),
+                    label {
+                        "{state}"
+                    }
+                }
+            }
+        }
+        SimControl::Number { name, value } => rsx! {
+            SettingInput {
+                name: "Value",
+                value: value.to_string(),
+                onchange: move |text: String| if let Ok(value) = text.trim().parse() {
+                    update(SimControl::Number { name: name.clone(), value });
+                }
+            }
+        },
+        SimControl::Joystick { port, joystick } => {
+            let axes: Vec<(usize, f64)> = joystick.axes.iter().copied().enumerate().collect();
+            let buttons: Vec<(usize, String)> = joystick
+                .buttons
+                .iter()
+                .enumerate()
+                .map(|(button, pressed)| {
+                    let color = if *pressed { &palette.success } else { &palette.subtext };
+                    (button, color.clone())
+                })
+                .collect();
+            let pov = joystick.povs.first().copied().unwrap_or(-1);
+            let pov_angles = POV_ANGLES.map(|angle| {
+                let text = if angle < 0 { "Centered".to_string() } else { angle.to_string() };
+                (angle, text)
+            });
+            rsx! {
+                for (axis, value) in axes {
+                    rect {
+                        key: "axis-{axis}",
+                        direction: "horizontal",
+                        cross_align: "center",
+                        label {
+                            width: "120",
+                            "Axis {axis}"
+                        }
+                        Slider {
+                            value: (value + 1.0) * 50.0,
+                            onmoved: {
+                                let joystick = joystick.clone();
+                                move |percent: f64| {
+                                    let mut joystick = joystick.clone();
+                                    if let Some(value) = joystick.axes.get_mut(axis) {
+                                        *value = percent / 50.0 - 1.0;
+                                    }
+                                    update(SimControl::Joystick { port, joystick });
+                                }
+                            }
+                        }
+                        label {
+                            "{value:.2}"
+                        }
+                    }
+                }
+                rect {
+                    direction: "horizontal",
+                    for (button, color) in buttons {
+                        Button {
+                            key: "button-{button}",
+                            onclick: {
+                                let joystick = joystick.clone();
+                                move |_| {
+                                    let mut joystick = joystick.clone();
+                                    if let Some(pressed) = joystick.buttons.get_mut(button) {
+                                        *pressed = !*pressed;
+                                    }
+                                    update(SimControl::Joystick { port, joystick });
+                                }
+                            },
+                            label {
+                                color: "{color}",
+                                "{button + 1}"
+                            }
+                        }
+                    }
+                }
+                rect {
+                    direction: "horizontal",
+                    cross_align: "center",
+                    label {
+                        width: "120",
+                        "POV {pov}"
+                    }
+                    for (angle, text) in pov_angles {
+                        Button {
+                            key: "pov-{angle}",
+                            onclick: {
+                                let joystick = joystick.clone();
+                                move |_| update(SimControl::Joystick { port, joystick: Joystick { povs: vec![angle], ..joystick.clone() } })
+                            },
+                            label {
+                                "{text}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    rsx! {
+        rect {
+            width: "100%",
+            padding: "5",
+            margin: "5 0 5 0",
+            border: "1 solid {palette.border}",
+            rect {
+                direction: "horizontal",
+                cross_align: "center",
+                label {
+                    width: "fill",
+                    "{title}"
+                }
+                Button {
+                    onclick: move |_| core.write().update(Message::RemoveSimControl(index)),
+                    label {
+                        "Remove"
+                    }
+                }
+            }
+            {body}
+        }
+    }
+}
+
+/// A labeled input that keeps its own text, so invalid intermediate text isn't reset while typing.
+#[component]
+fn SettingInput(name: String, value: String, onchange: EventHandler<String>) -> Element {
+    let mut text = use_signal(|| value);
+
+    rsx! {
+        rect {
+            direction: "horizontal",
+            cross_align: "center",
+            label {
+                width: "120",
+                "{name}"
+            }
+            Input {
+                value: text.read().clone(),
+                onchange: move |value: String| {
+                    text.set(value.clone());
+                    onchange.call(value);
                 }
             }
         }
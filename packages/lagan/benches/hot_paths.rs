@@ -0,0 +1,145 @@
+//! Benchmarks of the paths that run for every value written or read.
+//!
+//! Everything runs on the default instance without networking, so only lagan and ntcore's local
+//! bookkeeping are measured.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use lagan::{
+    nt_types::{PubSubOptions, RawValue, Value, ValueType},
+    prelude::*,
+};
+use ntcore_sys::{NT_Type, NT_Value, NT_ValueData, NT_ValueDataArray};
+
+const ARRAY_SIZES: [usize; 3] = [1, 256, 65536];
+
+fn f64_array(size: usize) -> Value {
+    Value::F64Array((0..size).map(|i| i as f64).collect())
+}
+
+fn entry_set_value(c: &mut Criterion) {
+    let instance = DefaultInstance::new();
+    let entry = instance.entry("/bench/entry_set_value");
+
+    let mut group = c.benchmark_group("Entry::set_value");
+    group.bench_function("f64", |b| {
+        b.iter(|| entry.set_value(Value::F64(black_box(1.0))).unwrap())
+    });
+    for size in ARRAY_SIZES {
+        let value = f64_array(size);
+        group.bench_with_input(BenchmarkId::new("f64 array", size), &value, |b, value| {
+            b.iter_batched(
+                || value.clone(),
+                |value| entry.set_value(value).unwrap(),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn publisher_set_value(c: &mut Criterion) {
+    let instance = DefaultInstance::new();
+    let scalar_topic = instance.topic("/bench/publisher_set_value/f64");
+    let scalar = scalar_topic
+        .publish(ValueType::F64, "double", PubSubOptions::default())
+        .unwrap();
+    let array_topic = instance.topic("/bench/publisher_set_value/f64_array");
+    let array = array_topic
+        .publish(ValueType::F64Array, "double[]", PubSubOptions::default())
+        .unwrap();
+
+    let mut group = c.benchmark_group("TopicPublisher::set_value");
+    group.bench_function("f64", |b| {
+        b.iter(|| scalar.set_value(Value::F64(black_box(1.0))).unwrap())
+    });
+    group.bench_function("f64 without the type check", |b| {
+        b.iter(|| scalar.set_f64_fast(black_box(1.0)))
+    });
+    for size in ARRAY_SIZES {
+        let value = f64_array(size);
+        group.bench_with_input(BenchmarkId::new("f64 array", size), &value, |b, value| {
+            b.iter_batched(
+                || value.clone(),
+                |value| array.set_value(value).unwrap(),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn raw_value_from_nt_value(c: &mut Criterion) {
+    let mut group = c.benchmark_group("RawValue::from(NT_Value)");
+    group.bench_function("f64", |b| {
+        let value = NT_Value {
+            r#type: NT_Type::NT_DOUBLE,
+            last_change: 0,
+            server_time: 0,
+            data: NT_ValueData { v_double: 1.0 },
+        };
+        b.iter(|| RawValue::from(black_box(value)))
+    });
+    for size in ARRAY_SIZES {
+        let data = (0..size).map(|i| i as f64).collect::<Vec<_>>();
+        let value = NT_Value {
+            r#type: NT_Type::NT_DOUBLE_ARRAY,
+            last_change: 0,
+            server_time: 0,
+            data: NT_ValueData {
+                arr_double: NT_ValueDataArray {
+                    arr: data.as_ptr(),
+                    size: data.len(),
+                },
+            },
+        };
+        group.bench_function(BenchmarkId::new("f64 array", size), |b| {
+            b.iter(|| RawValue::from(black_box(value)))
+        });
+    }
+    group.finish();
+}
+
+fn drain_queue(c: &mut Criterion) {
+    const QUEUE_LENGTH: u32 = 100;
+
+    let instance = DefaultInstance::new();
+    let options = PubSubOptions::builder()
+        .send_all_updates(true)
+        .queue_length(QUEUE_LENGTH)
+        .ignore_duplicates(false)
+        .build();
+
+    let mut group = c.benchmark_group("TopicSubscriber::try_read_update_queue");
+    for size in ARRAY_SIZES {
+        let topic = instance.topic(format!("/bench/drain_queue/{size}"));
+        let subscriber = topic
+            .subscribe(ValueType::F64Array, "double[]", options)
+            .unwrap();
+        let publisher = topic
+            .publish(ValueType::F64Array, "double[]", options)
+            .unwrap();
+        let value = f64_array(size);
+
+        group.bench_function(BenchmarkId::new("f64 array", size), |b| {
+            b.iter_batched(
+                || {
+                    for _ in 0..QUEUE_LENGTH {
+                        publisher.set_value(value.clone()).unwrap();
+                    }
+                },
+                |()| subscriber.try_read_update_queue(),
+                BatchSize::PerIteration,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    entry_set_value,
+    publisher_set_value,
+    raw_value_from_nt_value,
+    drain_queue
+);
+criterion_main!(benches);
@@ -19,7 +19,8 @@ fn main() {
 
     let server = Server::builder()
         .persist_filename("networktables.json")
-        .build();
+        .build()
+        .unwrap();
 
     let foo_server = server.entry("/data");
     foo_server.set_value_string("aa").unwrap();
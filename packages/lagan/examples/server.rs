@@ -13,7 +13,8 @@ fn main() {
 
     let server = Server::builder()
         .persist_filename("networktables.json")
-        .build();
+        .build()
+        .unwrap();
 
     loop {
         std::thread::sleep(std::time::Duration::from_secs(1));
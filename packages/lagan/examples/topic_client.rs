@@ -19,9 +19,13 @@ fn main() {
         .build();
 
     let topic = client.topic("/sin");
-    let topic_subscriber = topic.subscribe(ValueType::F64, "double", Default::default());
+    let topic_subscriber = topic
+        .subscribe(ValueType::F64, "double", Default::default())
+        .unwrap();
     let topic2 = client.topic("/iCanPublish");
-    let topic_publisher = topic2.publish(ValueType::F64, "double", PubSubOptions::default());
+    let topic_publisher = topic2
+        .publish(ValueType::F64, "double", PubSubOptions::default())
+        .unwrap();
 
     let entry = client.entry("/sinRecieved");
 
@@ -0,0 +1,115 @@
+//! Converts arbitrary bytes laid out as every kind of `NT_Value` into a `RawValue`.
+//!
+//! Run with `cargo fuzz run value_conversion` from `packages/lagan`.
+
+#![no_main]
+
+use lagan::nt_types::{RawValue, Value};
+use libfuzzer_sys::fuzz_target;
+use ntcore_sys::{NT_Bool, NT_Type, NT_Value, NT_ValueData, NT_ValueDataArray, WPI_String};
+
+const TYPES: [NT_Type; 13] = [
+    NT_Type::NT_UNASSIGNED,
+    NT_Type::NT_BOOLEAN,
+    NT_Type::NT_DOUBLE,
+    NT_Type::NT_STRING,
+    NT_Type::NT_RAW,
+    NT_Type::NT_BOOLEAN_ARRAY,
+    NT_Type::NT_DOUBLE_ARRAY,
+    NT_Type::NT_STRING_ARRAY,
+    NT_Type::NT_RPC,
+    NT_Type::NT_INTEGER,
+    NT_Type::NT_FLOAT,
+    NT_Type::NT_FLOAT_ARRAY,
+    NT_Type::NT_INTEGER_ARRAY,
+];
+
+/// Reinterprets `data` as elements of `T`, copied so that they are properly aligned.
+fn elements<T: Copy>(data: &[u8], from_bytes: impl Fn(&[u8]) -> T) -> Vec<T> {
+    data.chunks_exact(std::mem::size_of::<T>())
+        .map(from_bytes)
+        .collect()
+}
+
+fn array<T>(elements: &[T]) -> NT_ValueDataArray<T> {
+    NT_ValueDataArray {
+        arr: if elements.is_empty() {
+            std::ptr::null()
+        } else {
+            elements.as_ptr()
+        },
+        size: elements.len(),
+    }
+}
+
+fuzz_target!(|input: &[u8]| {
+    let Some((&selector, data)) = input.split_first() else {
+        return;
+    };
+    let r#type = TYPES[selector as usize % TYPES.len()];
+
+    let bools: Vec<NT_Bool> = elements(data, |b| NT_Bool::from_le_bytes(b.try_into().unwrap()));
+    let f64s: Vec<f64> = elements(data, |b| f64::from_le_bytes(b.try_into().unwrap()));
+    let f32s: Vec<f32> = elements(data, |b| f32::from_le_bytes(b.try_into().unwrap()));
+    let i64s: Vec<i64> = elements(data, |b| i64::from_le_bytes(b.try_into().unwrap()));
+    let strings: Vec<WPI_String> = data
+        .split(|byte| *byte == b'|')
+        .map(|s| WPI_String {
+            str: s.as_ptr().cast(),
+            len: s.len(),
+        })
+        .collect();
+    let string = WPI_String {
+        str: data.as_ptr().cast(),
+        len: data.len(),
+    };
+
+    let value_data = match r#type {
+        NT_Type::NT_BOOLEAN => NT_ValueData {
+            v_boolean: bools.first().copied().unwrap_or_default(),
+        },
+        NT_Type::NT_INTEGER => NT_ValueData {
+            v_int: i64s.first().copied().unwrap_or_default(),
+        },
+        NT_Type::NT_FLOAT => NT_ValueData {
+            v_float: f32s.first().copied().unwrap_or_default(),
+        },
+        NT_Type::NT_DOUBLE => NT_ValueData {
+            v_double: f64s.first().copied().unwrap_or_default(),
+        },
+        NT_Type::NT_STRING => NT_ValueData { v_string: string },
+        NT_Type::NT_RAW => NT_ValueData { v_raw: array(data) },
+        NT_Type::NT_BOOLEAN_ARRAY => NT_ValueData {
+            arr_boolean: array(&bools),
+        },
+        NT_Type::NT_DOUBLE_ARRAY => NT_ValueData {
+            arr_double: array(&f64s),
+        },
+        NT_Type::NT_FLOAT_ARRAY => NT_ValueData {
+            arr_float: array(&f32s),
+        },
+        NT_Type::NT_INTEGER_ARRAY => NT_ValueData {
+            arr_int: array(&i64s),
+        },
+        NT_Type::NT_STRING_ARRAY => NT_ValueData {
+            arr_string: array(&strings),
+        },
+        _ => NT_ValueData { v_int: 0 },
+    };
+
+    let value = RawValue::from(NT_Value {
+        r#type,
+        last_change: 0,
+        server_time: 0,
+        data: value_data,
+    });
+
+    match value.data {
+        Value::Raw(raw) => assert_eq!(raw, data),
+        Value::String(converted) => assert_eq!(converted, String::from_utf8_lossy(data)),
+        Value::F64Array(converted) => assert_eq!(converted.len(), f64s.len()),
+        Value::I64Array(converted) => assert_eq!(converted, i64s),
+        Value::StringArray(converted) => assert_eq!(converted.len(), strings.len()),
+        _ => {}
+    }
+});
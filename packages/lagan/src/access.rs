@@ -0,0 +1,219 @@
+//! Restricting how topics can be written.
+
+use std::fmt::Debug;
+
+#[cfg(feature = "async")]
+use crate::topic::TopicSubscriberReadQueueRawFuture;
+use crate::{
+    nt_types::{NetworkTablesValue, PubSubOptions, RawValue, Value, ValueFlags, ValueType},
+    topic::{Topic, TopicPublisher, TopicSubscriber},
+    Instance, NetworkTablesError, ValidationFailedSnafu,
+};
+
+/// A publisher that validates every value before publishing it.
+///
+/// The validator returns the value to publish, which may be adjusted (e.g. clamped to a range),
+/// or `None` to reject the value.
+pub struct ValidatedPublisher<'a, I: Instance + ?Sized, T: NetworkTablesValue> {
+    publisher: TopicPublisher<'a, I>,
+    validator: Box<dyn Fn(T) -> Option<T> + Send + Sync + 'a>,
+}
+
+impl<'a, I: Instance + ?Sized, T: NetworkTablesValue> ValidatedPublisher<'a, I, T> {
+    pub fn new(
+        publisher: TopicPublisher<'a, I>,
+        validator: impl Fn(T) -> Option<T> + Send + Sync + 'a,
+    ) -> Self {
+        Self {
+            publisher,
+            validator: Box::new(validator),
+        }
+    }
+
+    /// Validates the value and publishes the result.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::ValidationFailed`] if the validator rejected the value.
+    /// - Any error from [`TopicPublisher::set_value`].
+    pub fn set(&self, value: T) -> Result<(), NetworkTablesError> {
+        let value = (self.validator)(value).ok_or_else(|| ValidationFailedSnafu.build())?;
+        self.publisher.set_value(value.into_value())
+    }
+
+    pub fn publisher(&self) -> &TopicPublisher<'a, I> {
+        &self.publisher
+    }
+}
+
+impl<'a, I: Instance + ?Sized, T: NetworkTablesValue + PartialOrd + Clone + Send + Sync + 'a>
+    ValidatedPublisher<'a, I, T>
+{
+    /// Creates a publisher that clamps every value to `min..=max`.
+    pub fn clamped(publisher: TopicPublisher<'a, I>, min: T, max: T) -> Self {
+        Self::new(publisher, move |value| {
+            Some(if value < min {
+                min.clone()
+            } else if value > max {
+                max.clone()
+            } else {
+                value
+            })
+        })
+    }
+}
+
+impl<I: Instance + ?Sized + Debug, T: NetworkTablesValue> Debug for ValidatedPublisher<'_, I, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValidatedPublisher")
+            .field("publisher", &self.publisher)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A view of a topic that can be read and subscribed to, but not published to or reconfigured.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ReadOnlyTopic<'t, 'a, I: Instance + ?Sized> {
+    topic: &'t Topic<'a, I>,
+}
+
+impl<'t, 'a, I: Instance + ?Sized> ReadOnlyTopic<'t, 'a, I> {
+    pub fn new(topic: &'t Topic<'a, I>) -> Self {
+        Self { topic }
+    }
+
+    /// See [`Topic::subscribe`].
+    ///
+    /// The subscriber only gives access to the topic as a [`ReadOnlyTopic`] as well.
+    pub fn subscribe(
+        &self,
+        expected_type: ValueType,
+        expected_type_string: impl AsRef<str>,
+        options: PubSubOptions,
+    ) -> Result<ReadOnlySubscriber<'t, I>, NetworkTablesError> {
+        self.topic
+            .subscribe(expected_type, expected_type_string, options)
+            .map(|subscriber| ReadOnlySubscriber { subscriber })
+    }
+
+    pub fn value_type(&self) -> ValueType {
+        self.topic.value_type()
+    }
+    pub fn value_type_string(&self) -> Option<String> {
+        self.topic.value_type_string()
+    }
+    pub fn flags(&self) -> ValueFlags {
+        self.topic.flags()
+    }
+    pub fn property(&self, name: impl AsRef<str>) -> Option<String> {
+        self.topic.property(name)
+    }
+    pub fn properties(&self) -> String {
+        self.topic.properties()
+    }
+    pub fn is_existant(&self) -> bool {
+        self.topic.is_existant()
+    }
+    pub fn name(&self) -> &str {
+        self.topic.name()
+    }
+}
+
+impl<'t, 'a, I: Instance + ?Sized> From<&'t Topic<'a, I>> for ReadOnlyTopic<'t, 'a, I> {
+    fn from(topic: &'t Topic<'a, I>) -> Self {
+        Self::new(topic)
+    }
+}
+
+#[cfg(feature = "async")]
+macro_rules! forward_typed_reader {
+    {$($ident:ident => $ty:ty),*} => {
+        $(
+            /// See [`TopicSubscriber::value`].
+            pub async fn $ident(&self) -> Option<$ty> {
+                self.subscriber.$ident().await
+            }
+        )*
+    };
+}
+
+/// A subscriber created through a [`ReadOnlyTopic`], which only gives access to its topic as a [`ReadOnlyTopic`].
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ReadOnlySubscriber<'t, I: Instance + ?Sized> {
+    subscriber: TopicSubscriber<'t, I>,
+}
+
+impl<'t, I: Instance + ?Sized> ReadOnlySubscriber<'t, I> {
+    /// See [`TopicSubscriber::try_read_update_queue_raw`].
+    pub fn try_read_update_queue_raw(&self) -> Option<Vec<RawValue>> {
+        self.subscriber.try_read_update_queue_raw()
+    }
+    /// See [`TopicSubscriber::recycle`].
+    pub fn recycle(&self, values: Vec<RawValue>) {
+        self.subscriber.recycle(values)
+    }
+    /// See [`TopicSubscriber::try_read_update_queue_into`].
+    pub fn try_read_update_queue_into(&self, values: &mut Vec<RawValue>) -> usize {
+        self.subscriber.try_read_update_queue_into(values)
+    }
+    pub fn try_read_update_queue(&self) -> Option<Vec<Value>> {
+        self.subscriber.try_read_update_queue()
+    }
+    /// See [`TopicSubscriber::try_read_update_queue_raw_of_type`].
+    pub fn try_read_update_queue_raw_of_type(
+        &self,
+        value_type: ValueType,
+    ) -> Option<Vec<RawValue>> {
+        self.subscriber
+            .try_read_update_queue_raw_of_type(value_type)
+    }
+    /// See [`TopicSubscriber::try_read_update_queue_of_type_into`].
+    pub fn try_read_update_queue_of_type_into(
+        &self,
+        value_type: ValueType,
+        values: &mut Vec<RawValue>,
+    ) -> usize {
+        self.subscriber
+            .try_read_update_queue_of_type_into(value_type, values)
+    }
+    pub fn try_read_update_queue_of_type(&self, value_type: ValueType) -> Option<Vec<Value>> {
+        self.subscriber.try_read_update_queue_of_type(value_type)
+    }
+
+    #[cfg(feature = "async")]
+    pub fn update_queue_raw(&self) -> TopicSubscriberReadQueueRawFuture<'_, I> {
+        self.subscriber.update_queue_raw()
+    }
+    #[cfg(feature = "async")]
+    pub async fn update_queue(&self) -> Vec<Value> {
+        self.subscriber.update_queue().await
+    }
+    #[cfg(feature = "async")]
+    pub async fn value(&self) -> Value {
+        self.subscriber.value().await
+    }
+
+    #[cfg(feature = "async")]
+    forward_typed_reader! {
+        value_bool => bool,
+        value_i64 => i64,
+        value_f32 => f32,
+        value_f64 => f64,
+        value_string => String,
+        value_raw => Vec<u8>,
+        value_bool_array => Vec<bool>,
+        value_f64_array => Vec<f64>,
+        value_f32_array => Vec<f32>,
+        value_i64_array => Vec<i64>,
+        value_string_array => Vec<String>
+    }
+
+    pub fn topic(&self) -> ReadOnlyTopic<'t, 't, I> {
+        ReadOnlyTopic::new(self.subscriber.topic())
+    }
+
+    /// The type string this subscriber was created with.
+    pub fn type_string(&self) -> &str {
+        self.subscriber.type_string()
+    }
+}
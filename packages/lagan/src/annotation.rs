@@ -0,0 +1,90 @@
+//! Units and descriptions of topics, stored in topic properties.
+//!
+//! Every team otherwise invents its own metadata keys, so lagan settles on a convention:
+//! the `unit` property holds the unit of the topic's values (e.g. `"m/s"`)
+//! and the `desc` property a human-readable description, both as JSON strings.
+
+use crate::{access::ReadOnlyTopic, json, topic::Topic, Instance, NetworkTablesError};
+
+/// The property holding the unit of a topic's values.
+pub const UNIT_PROPERTY: &str = "unit";
+/// The property holding the description of a topic.
+pub const DESCRIPTION_PROPERTY: &str = "desc";
+
+/// The unit and description of a topic.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Annotations {
+    pub unit: Option<String>,
+    pub description: Option<String>,
+}
+
+impl<I: Instance + ?Sized> Topic<'_, I> {
+    /// Returns the unit of the topic's values.
+    ///
+    /// Returns `None` if the unit is not set or is not a JSON string.
+    pub fn unit(&self) -> Option<String> {
+        self.string_property(UNIT_PROPERTY)
+    }
+
+    /// Sets the unit of the topic's values, e.g. `"m/s"`.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::Nt4Only`] on a NetworkTables 3 client.
+    pub fn set_unit(&self, unit: impl AsRef<str>) -> Result<(), NetworkTablesError> {
+        self.set_property(UNIT_PROPERTY, json::encode_string(unit.as_ref()))
+    }
+
+    /// Returns the description of the topic.
+    ///
+    /// Returns `None` if the description is not set or is not a JSON string.
+    pub fn description(&self) -> Option<String> {
+        self.string_property(DESCRIPTION_PROPERTY)
+    }
+
+    /// Sets the human-readable description of the topic.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::Nt4Only`] on a NetworkTables 3 client.
+    pub fn set_description(&self, description: impl AsRef<str>) -> Result<(), NetworkTablesError> {
+        self.set_property(
+            DESCRIPTION_PROPERTY,
+            json::encode_string(description.as_ref()),
+        )
+    }
+
+    /// Returns both the unit and the description of the topic.
+    pub fn annotations(&self) -> Annotations {
+        Annotations {
+            unit: self.unit(),
+            description: self.description(),
+        }
+    }
+
+    fn string_property(&self, name: &str) -> Option<String> {
+        json::decode_string(&self.property(name)?)
+    }
+}
+
+impl<I: Instance + ?Sized> ReadOnlyTopic<'_, '_, I> {
+    /// See [`Topic::unit`].
+    pub fn unit(&self) -> Option<String> {
+        self.property(UNIT_PROPERTY)
+            .and_then(|unit| json::decode_string(&unit))
+    }
+
+    /// See [`Topic::description`].
+    pub fn description(&self) -> Option<String> {
+        self.property(DESCRIPTION_PROPERTY)
+            .and_then(|description| json::decode_string(&description))
+    }
+
+    /// See [`Topic::annotations`].
+    pub fn annotations(&self) -> Annotations {
+        Annotations {
+            unit: self.unit(),
+            description: self.description(),
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Subscribing to every topic under a prefix as topics are announced and unannounced.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use ntcore_sys::{
+    NT_EventFlags, NT_Inst, NT_Release, NT_Subscribe, NT_Subscriber, NT_Topic, WPI_String,
+};
+
+use crate::{
+    channel::TopicEventKind, listener::ListenerHandle, lock, nt_types::PubSubOptions,
+    schema::TopicKind, shutdown::InstanceGuard, topic::TopicInfo,
+};
+
+type Subscribers = HashMap<NT_Topic, (Arc<str>, NT_Subscriber)>;
+
+/// Subscribers to every topic under a prefix, created with [`Instance::auto_subscribe`](crate::Instance::auto_subscribe).
+///
+/// Dropping it stops following announcements and releases every subscriber.
+#[derive(Debug)]
+#[must_use = "the topics are unsubscribed from when this is dropped"]
+pub struct AutoSubscription {
+    // Taken and dropped before the subscribers are released in `Drop`.
+    listener: Option<ListenerHandle>,
+    subscribers: Arc<Mutex<Subscribers>>,
+    guard: InstanceGuard,
+}
+
+impl AutoSubscription {
+    pub(crate) fn new(
+        instance: NT_Inst,
+        prefix: &str,
+        options: PubSubOptions,
+        callback: impl Fn(TopicEventKind, TopicInfo) + Send + Sync + 'static,
+    ) -> Self {
+        let guard = InstanceGuard::new(instance);
+        let subscribers = Arc::new(Mutex::new(Subscribers::new()));
+        let raw_prefix = [WPI_String::from(prefix)];
+        let raw_options = options.into();
+
+        // Topics that already exist are reported right away as if they were just published.
+        let mask = NT_EventFlags::NT_EVENT_PUBLISH
+            | NT_EventFlags::NT_EVENT_UNPUBLISH
+            | NT_EventFlags::NT_EVENT_IMMEDIATE;
+        let listener = ListenerHandle::multiple(instance, &raw_prefix, mask, {
+            let subscribers = subscribers.clone();
+            move |event| {
+                let flags = NT_EventFlags::from_bits_retain(event.flags);
+                // Safety: publish and unpublish events always carry topic info.
+                let raw_info = unsafe { &event.data.topicInfo };
+                let mut info = unsafe { TopicInfo::from_raw(instance, raw_info) };
+                let kind = if flags.contains(NT_EventFlags::NT_EVENT_PUBLISH) {
+                    let mut subscribers = lock(&subscribers);
+                    if subscribers.contains_key(&raw_info.topic) {
+                        return;
+                    }
+                    let subscriber = unsafe {
+                        NT_Subscribe(
+                            raw_info.topic,
+                            raw_info.r#type,
+                            &raw const raw_info.type_str,
+                            &raw const raw_options,
+                        )
+                    };
+                    subscribers.insert(raw_info.topic, (info.name.clone(), subscriber));
+                    TopicEventKind::Published
+                } else if flags.contains(NT_EventFlags::NT_EVENT_UNPUBLISH) {
+                    let Some((_, subscriber)) = lock(&subscribers).remove(&raw_info.topic) else {
+                        return;
+                    };
+                    unsafe { NT_Release(subscriber) };
+                    TopicEventKind::Unpublished
+                } else {
+                    return;
+                };
+
+                info.kind = TopicKind::parse(&info.name, &info.type_string);
+                callback(kind, info);
+            }
+        });
+
+        Self {
+            listener: Some(listener),
+            subscribers,
+            guard,
+        }
+    }
+
+    /// Returns the names of the topics currently subscribed to, in no particular order.
+    pub fn topics(&self) -> Vec<Arc<str>> {
+        lock(&self.subscribers)
+            .values()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
+impl Drop for AutoSubscription {
+    fn drop(&mut self) {
+        // Removing the listener first waits for a running callback, so no subscriber is created after this.
+        drop(self.listener.take());
+        let subscribers = std::mem::take(&mut *lock(&self.subscribers));
+        self.guard.release(|| {
+            for (_, subscriber) in subscribers.into_values() {
+                unsafe { NT_Release(subscriber) };
+            }
+        });
+    }
+}
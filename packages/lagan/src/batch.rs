@@ -0,0 +1,67 @@
+//! Publishing related values together.
+
+use ntcore_sys::{NT_Flush, NT_Now};
+
+use crate::{nt_types::Value, topic::TopicPublisher, Instance, NetworkTablesError};
+
+/// A set of publisher writes that are applied together.
+///
+/// Every value in a batch gets the same timestamp and is flushed to the network at once,
+/// so related values such as the x, y and rotation of a pose arrive in the same network frame.
+#[derive(Debug)]
+pub struct Batch<'p, 'a, I: Instance + ?Sized> {
+    writes: Vec<(&'p TopicPublisher<'a, I>, Value)>,
+}
+
+impl<'p, 'a, I: Instance + ?Sized> Batch<'p, 'a, I> {
+    pub fn new() -> Self {
+        Self { writes: Vec::new() }
+    }
+
+    /// Queues a write to `publisher`. Nothing is published until [`Self::flush`] is called.
+    pub fn set(&mut self, publisher: &'p TopicPublisher<'a, I>, value: Value) -> &mut Self {
+        self.writes.push((publisher, value));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.writes.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    /// Publishes every queued write with a single timestamp and flushes every instance they were published to.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if any value doesn't match its topic's type.
+    ///   In that case nothing is published.
+    pub fn flush(&mut self) -> Result<(), NetworkTablesError> {
+        for (publisher, value) in &self.writes {
+            publisher.ensure_type(value)?;
+        }
+
+        // The publishers may belong to different instances, e.g. clients of two different robots.
+        let mut instances = Vec::new();
+        let timestamp = unsafe { NT_Now() };
+        for (publisher, value) in self.writes.drain(..) {
+            let instance = unsafe { publisher.topic().instance.handle() };
+            if !instances.contains(&instance) {
+                instances.push(instance);
+            }
+            publisher.set_value_at(value, timestamp)?;
+        }
+
+        for instance in instances {
+            unsafe { NT_Flush(instance) };
+        }
+        Ok(())
+    }
+}
+
+impl<I: Instance + ?Sized> Default for Batch<'_, '_, I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,281 @@
+//! Waiting for values from plain threads, without an async runtime.
+//!
+//! Waits block on ntcore listener pollers, so they don't spin while nothing changes.
+//! Every wait has a variant taking a [`CancelToken`], which lets another thread
+//! (e.g. a Ctrl-C handler or a closing GUI) abort it.
+
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use ntcore_sys::{
+    wpiutil::{WPI_SetSignalObject, WPI_WaitForObject, WPI_WaitForObjectTimeout},
+    NT_AddPolledListener, NT_CreateListenerPoller, NT_DestroyListenerPoller, NT_EventFlags,
+    NT_Handle, NT_Inst, NT_Listener, NT_ListenerPoller, NT_ReadListenerQueue, NT_RemoveListener,
+    NtEventArray,
+};
+
+use crate::{
+    entry::Entry, lock, nt_types::Value, shutdown::InstanceGuard, topic::TopicSubscriber, Instance,
+};
+
+/// A token that cancels blocking waits from another thread.
+///
+/// Clones share the same state, so one clone can be handed to the waiting thread
+/// and another kept to cancel it. Once cancelled, a token stays cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<CancelTokenInner>,
+}
+
+#[derive(Debug, Default)]
+struct CancelTokenInner {
+    cancelled: AtomicBool,
+    /// Pollers currently being waited on with this token.
+    waiting: Mutex<Vec<NT_ListenerPoller>>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels every wait using this token, including ones that are already blocked.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        let waiting = lock(&self.inner.waiting);
+        for poller in waiting.iter() {
+            unsafe { WPI_SetSignalObject(*poller) };
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    fn register(&self, poller: NT_ListenerPoller) {
+        lock(&self.inner.waiting).push(poller);
+    }
+
+    fn unregister(&self, poller: NT_ListenerPoller) {
+        let mut waiting = lock(&self.inner.waiting);
+        if let Some(index) = waiting.iter().position(|waiting| *waiting == poller) {
+            waiting.swap_remove(index);
+        }
+    }
+}
+
+/// A listener poller that is notified of value changes on a single handle.
+#[derive(Debug)]
+pub(crate) struct Poller {
+    poller: NT_ListenerPoller,
+    listener: NT_Listener,
+    guard: InstanceGuard,
+}
+
+impl Poller {
+    pub(crate) fn new(instance: NT_Inst, handle: NT_Handle) -> Self {
+        unsafe {
+            let poller = NT_CreateListenerPoller(instance);
+            let listener =
+                NT_AddPolledListener(poller, handle, NT_EventFlags::NT_EVENT_VALUE_ALL.bits());
+            Self {
+                poller,
+                listener,
+                guard: InstanceGuard::new(instance),
+            }
+        }
+    }
+
+    /// Blocks until there are new events or `timeout` elapses, discarding the events.
+    ///
+    /// Returns `false` if the wait timed out, was cancelled or the poller was destroyed.
+    pub(crate) fn wait(&self, timeout: Option<Duration>, cancel: Option<&CancelToken>) -> bool {
+        // Registering before checking the token means a concurrent cancel either is seen here
+        // or signals the poller, so the wait below can't miss it.
+        if let Some(cancel) = cancel {
+            cancel.register(self.poller);
+            if cancel.is_cancelled() {
+                cancel.unregister(self.poller);
+                return false;
+            }
+        }
+
+        let signaled = match timeout {
+            Some(timeout) => {
+                let mut timed_out = 0;
+                unsafe {
+                    WPI_WaitForObjectTimeout(self.poller, timeout.as_secs_f64(), &raw mut timed_out)
+                }
+            }
+            None => unsafe { WPI_WaitForObject(self.poller) },
+        } != 0;
+
+        if let Some(cancel) = cancel {
+            cancel.unregister(self.poller);
+        }
+
+        let mut count = 0;
+        drop(unsafe {
+            NtEventArray::from_raw(NT_ReadListenerQueue(self.poller, &raw mut count), count)
+        });
+
+        signaled && !cancel.is_some_and(CancelToken::is_cancelled)
+    }
+}
+
+impl Drop for Poller {
+    fn drop(&mut self) {
+        self.guard.release(|| unsafe {
+            NT_RemoveListener(self.listener);
+            NT_DestroyListenerPoller(self.poller);
+        });
+    }
+}
+
+fn remaining(deadline: Option<Instant>) -> Option<Option<Duration>> {
+    match deadline {
+        Some(deadline) => {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            (!remaining.is_zero()).then_some(Some(remaining))
+        }
+        None => Some(None),
+    }
+}
+
+impl<'a, I: Instance + ?Sized> TopicSubscriber<'a, I> {
+    fn poller(&self) -> Poller {
+        unsafe { Poller::new(self.topic().instance.handle(), self.handle()) }
+    }
+
+    /// Blocks until the topic has a new value and returns the latest one.
+    ///
+    /// Waits forever if `timeout` is `None`. Returns `None` if the timeout elapses first.
+    pub fn next_value_blocking(&self, timeout: Option<Duration>) -> Option<Value> {
+        self.wait_next_value(timeout, None)
+    }
+
+    /// Like [`next_value_blocking`](Self::next_value_blocking), but also returns `None`
+    /// once `cancel` is cancelled.
+    pub fn next_value_blocking_cancellable(
+        &self,
+        timeout: Option<Duration>,
+        cancel: &CancelToken,
+    ) -> Option<Value> {
+        self.wait_next_value(timeout, Some(cancel))
+    }
+
+    fn wait_next_value(
+        &self,
+        timeout: Option<Duration>,
+        cancel: Option<&CancelToken>,
+    ) -> Option<Value> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        // The poller has to exist before checking the queue so that no update is missed in between.
+        let poller = self.poller();
+        loop {
+            if let Some(mut values) = self.try_read_update_queue() {
+                if let Some(value) = values.pop() {
+                    return Some(value);
+                }
+            }
+            if !poller.wait(remaining(deadline)?, cancel) {
+                return None;
+            }
+        }
+    }
+
+    /// Returns a blocking iterator over every new value of the topic.
+    pub fn changes_iter(&self) -> Changes<'_, 'a, I> {
+        Changes {
+            poller: self.poller(),
+            subscriber: self,
+            pending: VecDeque::new(),
+            cancel: None,
+        }
+    }
+
+    /// Like [`changes_iter`](Self::changes_iter), but the iterator ends once `cancel` is cancelled.
+    pub fn changes_iter_cancellable(&self, cancel: CancelToken) -> Changes<'_, 'a, I> {
+        Changes {
+            cancel: Some(cancel),
+            ..self.changes_iter()
+        }
+    }
+}
+
+/// A blocking iterator over the new values of a topic, created with [`TopicSubscriber::changes_iter`].
+#[derive(Debug)]
+pub struct Changes<'s, 'a, I: Instance + ?Sized> {
+    subscriber: &'s TopicSubscriber<'a, I>,
+    poller: Poller,
+    pending: VecDeque<Value>,
+    cancel: Option<CancelToken>,
+}
+
+impl<I: Instance + ?Sized> Iterator for Changes<'_, '_, I> {
+    type Item = Value;
+
+    fn next(&mut self) -> Option<Value> {
+        loop {
+            if let Some(value) = self.pending.pop_front() {
+                return Some(value);
+            }
+            if let Some(values) = self.subscriber.try_read_update_queue() {
+                self.pending.extend(values);
+                continue;
+            }
+            if !self.poller.wait(None, self.cancel.as_ref()) {
+                return None;
+            }
+        }
+    }
+}
+
+impl<I: Instance + ?Sized> Entry<'_, I> {
+    /// Blocks until the value of the entry satisfies `predicate` and returns it.
+    ///
+    /// The current value is checked first. Waits forever if `timeout` is `None`.
+    /// Returns `None` if the timeout elapses first.
+    pub fn wait_for(
+        &self,
+        predicate: impl FnMut(&Value) -> bool,
+        timeout: Option<Duration>,
+    ) -> Option<Value> {
+        self.wait_for_inner(predicate, timeout, None)
+    }
+
+    /// Like [`wait_for`](Self::wait_for), but also returns `None` once `cancel` is cancelled.
+    pub fn wait_for_cancellable(
+        &self,
+        predicate: impl FnMut(&Value) -> bool,
+        timeout: Option<Duration>,
+        cancel: &CancelToken,
+    ) -> Option<Value> {
+        self.wait_for_inner(predicate, timeout, Some(cancel))
+    }
+
+    fn wait_for_inner(
+        &self,
+        mut predicate: impl FnMut(&Value) -> bool,
+        timeout: Option<Duration>,
+        cancel: Option<&CancelToken>,
+    ) -> Option<Value> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let poller = unsafe { Poller::new(self.instance.handle(), self.handle()) };
+        loop {
+            let value = self.value();
+            if predicate(&value) {
+                return Some(value);
+            }
+            if !poller.wait(remaining(deadline)?, cancel) {
+                return None;
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! Which optional ntcore functions the linked library provides.
+//!
+//! lagan is written against the latest ntcore headers, but with ntcore-sys' `system` feature
+//! the library it is linked against may be older. The functions that newer releases added are resolved
+//! at runtime (see [`ntcore_sys::probe`]), and lagan degrades to a fallback or an error
+//! instead of failing to link or load when they are missing.
+
+use ntcore_sys::probe::optional_functions;
+
+/// The optional features of the linked ntcore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    /// The cached topic property, i.e. `NT_SetTopicCached` and `NT_GetTopicCached`.
+    ///
+    /// Without it, topics are never reported as cached and setting the flag fails.
+    pub cached_property: bool,
+    /// Reading values of a single type without decoding others,
+    /// i.e. `NT_GetEntryValueType` and `NT_ReadQueueValueType`.
+    ///
+    /// Without it, values of every type are read and filtered afterwards.
+    pub typed_reads: bool,
+    /// Registering and looking up struct and protobuf schemas,
+    /// i.e. `NT_AddSchema` and `NT_HasSchema`.
+    pub schemas: bool,
+}
+
+impl Capabilities {
+    /// Probes the linked ntcore. The functions are resolved once and then reused.
+    pub fn probe() -> Self {
+        let functions = optional_functions();
+        Self {
+            cached_property: functions.NT_SetTopicCached.is_some()
+                && functions.NT_GetTopicCached.is_some(),
+            typed_reads: functions.NT_GetEntryValueType.is_some()
+                && functions.NT_ReadQueueValueType.is_some(),
+            schemas: functions.NT_AddSchema.is_some() && functions.NT_HasSchema.is_some(),
+        }
+    }
+}
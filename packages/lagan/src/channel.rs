@@ -0,0 +1,244 @@
+//! Receiving NetworkTables events over a channel instead of through callbacks.
+//!
+//! [`EventReceiver`] owns a background thread that drains a listener poller and sends every event
+//! over a [`std::sync::mpsc`] channel, so synchronous applications can handle events in their existing receive loops.
+
+use std::{
+    collections::HashMap,
+    ops::Deref,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use bitflags::bitflags;
+use ntcore_sys::{
+    wpiutil::WPI_WaitForObject, NT_AddPolledListener, NT_AddPolledListenerMultiple,
+    NT_AddPolledLogger, NT_CreateListenerPoller, NT_DestroyListenerPoller, NT_Event, NT_EventFlags,
+    NT_GetTopicName, NT_Inst, NT_ListenerPoller, NT_ReadListenerQueue, NT_Topic, NtEventArray,
+    WPI_String, WpiStringBuf,
+};
+
+use crate::{
+    connection::{ConnectionEvent, ConnectionInfo, TimeSync},
+    intern,
+    logging::{LogLevel, LogMessage},
+    nt_types::RawValue,
+    schema::TopicKind,
+    shutdown::InstanceGuard,
+    topic::TopicInfo,
+};
+
+bitflags! {
+    /// The kinds of events an [`EventReceiver`] receives.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct EventMask: u32 {
+        /// Remote nodes connecting and disconnecting.
+        const CONNECTION = 1 << 0;
+        /// Topics being published, unpublished or having their properties changed.
+        const TOPIC = 1 << 1;
+        /// New values, local or remote.
+        const VALUE = 1 << 2;
+        /// ntcore log messages of level [`LogLevel::Info`] and above.
+        const LOG = 1 << 3;
+        /// Time synchronization measurements, on clients only.
+        const TIME_SYNC = 1 << 4;
+    }
+}
+
+/// What happened to a topic in an [`Event::Topic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TopicEventKind {
+    Published,
+    Unpublished,
+    PropertiesChanged,
+}
+
+/// An event received by an [`EventReceiver`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Connection(ConnectionEvent),
+    /// The [`TopicInfo::kind`] of the topic is classified without resolving struct schemas.
+    Topic {
+        kind: TopicEventKind,
+        info: TopicInfo,
+    },
+    Value {
+        name: Arc<str>,
+        value: RawValue,
+    },
+    Log(LogMessage),
+    TimeSync(TimeSync),
+}
+
+/// The receiving end of an event channel, created with [`Instance::event_channel`](crate::Instance::event_channel).
+///
+/// Dereferences to the [`Receiver`], so it can be used with `recv`, `recv_timeout`, `try_iter` and friends.
+/// Dropping it stops the background thread.
+#[derive(Debug)]
+pub struct EventReceiver {
+    receiver: Receiver<Event>,
+    poller: NT_ListenerPoller,
+    guard: InstanceGuard,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl EventReceiver {
+    /// Starts receiving the events in `mask`.
+    /// Topic and value events are limited to topics whose name starts with one of `prefixes`.
+    ///
+    /// If the background thread can't be started, the channel is disconnected right away.
+    pub(crate) fn new<P: AsRef<str>>(
+        instance: NT_Inst,
+        mask: EventMask,
+        prefixes: impl IntoIterator<Item = P>,
+    ) -> Self {
+        let raw_prefixes: Vec<WPI_String> = prefixes
+            .into_iter()
+            .map(|prefix| WPI_String::from(prefix.as_ref()))
+            .collect();
+
+        let mut instance_mask = NT_EventFlags::NT_EVENT_NONE;
+        if mask.contains(EventMask::CONNECTION) {
+            instance_mask |= NT_EventFlags::NT_EVENT_CONNECTION;
+        }
+        if mask.contains(EventMask::TIME_SYNC) {
+            instance_mask |= NT_EventFlags::NT_EVENT_TIMESYNC;
+        }
+
+        let mut topic_mask = NT_EventFlags::NT_EVENT_NONE;
+        if mask.contains(EventMask::TOPIC) {
+            topic_mask |= NT_EventFlags::NT_EVENT_TOPIC;
+        }
+        if mask.contains(EventMask::VALUE) {
+            topic_mask |= NT_EventFlags::NT_EVENT_VALUE_ALL;
+        }
+
+        let poller = unsafe {
+            let poller = NT_CreateListenerPoller(instance);
+            if !instance_mask.is_empty() {
+                NT_AddPolledListener(poller, instance, instance_mask.bits());
+            }
+            if !topic_mask.is_empty() {
+                NT_AddPolledListenerMultiple(
+                    poller,
+                    raw_prefixes.as_ptr(),
+                    raw_prefixes.len(),
+                    topic_mask.bits(),
+                );
+            }
+            if mask.contains(EventMask::LOG) {
+                NT_AddPolledLogger(poller, LogLevel::Info.to_raw(), u32::MAX);
+            }
+            poller
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("lagan-events".to_string())
+            .spawn(move || pump(instance, poller, sender))
+            .ok();
+
+        Self {
+            receiver,
+            poller,
+            guard: InstanceGuard::new(instance),
+            thread,
+        }
+    }
+}
+
+impl Deref for EventReceiver {
+    type Target = Receiver<Event>;
+
+    fn deref(&self) -> &Receiver<Event> {
+        &self.receiver
+    }
+}
+
+impl Drop for EventReceiver {
+    fn drop(&mut self) {
+        // Destroying the poller, or the instance along with it, wakes the background thread, which then returns.
+        self.guard
+            .release(|| unsafe { NT_DestroyListenerPoller(self.poller) });
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Sends the events of `poller` until it is destroyed or the receiver is dropped.
+fn pump(instance: NT_Inst, poller: NT_ListenerPoller, sender: Sender<Event>) {
+    let mut names = HashMap::<NT_Topic, Arc<str>>::new();
+
+    while unsafe { WPI_WaitForObject(poller) } != 0 {
+        let mut count = 0;
+        let raw_events =
+            unsafe { NtEventArray::from_raw(NT_ReadListenerQueue(poller, &raw mut count), count) };
+
+        for event in raw_events.iter() {
+            let Some(event) = convert(instance, event, &mut names) else {
+                continue;
+            };
+            if sender.send(event).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn convert(
+    instance: NT_Inst,
+    event: &NT_Event,
+    names: &mut HashMap<NT_Topic, Arc<str>>,
+) -> Option<Event> {
+    let flags = NT_EventFlags::from_bits_retain(event.flags);
+
+    if flags.intersects(NT_EventFlags::NT_EVENT_CONNECTION) {
+        let info = ConnectionInfo::from(unsafe { &event.data.connInfo });
+        Some(Event::Connection(
+            if flags.contains(NT_EventFlags::NT_EVENT_CONNECTED) {
+                ConnectionEvent::Connected(info)
+            } else {
+                ConnectionEvent::Disconnected(info)
+            },
+        ))
+    } else if flags.intersects(NT_EventFlags::NT_EVENT_TOPIC) {
+        let kind = if flags.contains(NT_EventFlags::NT_EVENT_PUBLISH) {
+            TopicEventKind::Published
+        } else if flags.contains(NT_EventFlags::NT_EVENT_UNPUBLISH) {
+            TopicEventKind::Unpublished
+        } else {
+            TopicEventKind::PropertiesChanged
+        };
+        let mut info = unsafe { TopicInfo::from_raw(instance, &event.data.topicInfo) };
+        info.kind = TopicKind::parse(&info.name, &info.type_string);
+        Some(Event::Topic { kind, info })
+    } else if flags.intersects(NT_EventFlags::NT_EVENT_VALUE_ALL) {
+        let data = unsafe { event.data.valueData };
+        let name = names
+            .entry(data.topic)
+            .or_insert_with(|| {
+                let mut name = WpiStringBuf::new();
+                unsafe { NT_GetTopicName(data.topic, name.as_mut_ptr()) };
+                intern::intern(instance, &name.to_str_lossy())
+            })
+            .clone();
+        Some(Event::Value {
+            name,
+            value: RawValue::from(data.value),
+        })
+    } else if flags.contains(NT_EventFlags::NT_EVENT_TIMESYNC) {
+        Some(Event::TimeSync(TimeSync::from(unsafe {
+            &event.data.timeSyncData
+        })))
+    } else if flags.contains(NT_EventFlags::NT_EVENT_LOGMESSAGE) {
+        Some(Event::Log(LogMessage::from(unsafe {
+            &event.data.logMessage
+        })))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,157 @@
+//! Publishing raw payloads too large for a single value.
+//!
+//! A chunked payload named `name` is stored as raw topics `name/chunks/0`, `name/chunks/1`, ...
+//! and an integer array manifest at `name/manifest` holding
+//! `[generation, length, chunk count, checksum]`.
+//! Every chunk starts with the generation it belongs to as 8 little-endian bytes,
+//! so readers can tell when they have read chunks of different payloads.
+
+use snafu::ensure;
+
+use crate::{
+    nt_types::{Value, ValueType},
+    CorruptChunksSnafu, Instance, InvalidChunkSizeSnafu, NetworkTablesError,
+};
+
+/// The chunk size used by [`publish_chunked`] by default.
+pub const DEFAULT_CHUNK_SIZE: usize = 16 * 1024;
+
+const GENERATION_SIZE: usize = std::mem::size_of::<u64>();
+
+fn manifest_name(name: &str) -> String {
+    format!("{}/manifest", name.trim_end_matches('/'))
+}
+fn chunk_name(name: &str, index: usize) -> String {
+    format!("{}/chunks/{index}", name.trim_end_matches('/'))
+}
+
+/// 64-bit FNV-1a, used to check the integrity of reassembled payloads.
+fn checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Manifest {
+    generation: u64,
+    length: usize,
+    chunks: usize,
+    checksum: u64,
+}
+
+impl Manifest {
+    fn read<I: Instance + ?Sized>(instance: &I, name: &str) -> Option<Self> {
+        let manifest = instance
+            .entry(manifest_name(name))
+            .value_of_type(ValueType::I64Array)?;
+        let Value::I64Array(manifest) = manifest else {
+            return None;
+        };
+        let [generation, length, chunks, checksum] = manifest[..] else {
+            return None;
+        };
+
+        Some(Self {
+            generation: generation as u64,
+            length: length as usize,
+            chunks: chunks as usize,
+            checksum: checksum as u64,
+        })
+    }
+
+    fn to_array(self) -> Vec<i64> {
+        vec![
+            self.generation as i64,
+            self.length as i64,
+            self.chunks as i64,
+            self.checksum as i64,
+        ]
+    }
+}
+
+/// Publishes `data` under `name` in chunks of at most `chunk_size` bytes.
+///
+/// Chunks are published before the manifest, so readers never see a manifest for chunks that don't exist yet.
+/// Chunks of the previous payload beyond the new chunk count are unpublished after the manifest.
+///
+/// # Errors
+///
+/// - [`NetworkTablesError::InvalidChunkSize`] if `chunk_size` is zero.
+pub fn publish_chunked<I: Instance + ?Sized>(
+    instance: &I,
+    name: &str,
+    data: &[u8],
+    chunk_size: usize,
+) -> Result<(), NetworkTablesError> {
+    ensure!(chunk_size > 0, InvalidChunkSizeSnafu);
+
+    let previous = Manifest::read(instance, name);
+    let generation = previous.map_or(0, |manifest| manifest.generation + 1);
+
+    let mut chunks = 0;
+    for (index, chunk) in data.chunks(chunk_size).enumerate() {
+        let mut raw = Vec::with_capacity(GENERATION_SIZE + chunk.len());
+        raw.extend_from_slice(&generation.to_le_bytes());
+        raw.extend_from_slice(chunk);
+        instance.entry(chunk_name(name, index)).set_value_raw(raw)?;
+        chunks += 1;
+    }
+
+    let manifest = Manifest {
+        generation,
+        length: data.len(),
+        chunks,
+        checksum: checksum(data),
+    };
+    instance
+        .entry(manifest_name(name))
+        .set_value_i64_array(manifest.to_array())?;
+
+    // Readers only read as many chunks as the manifest lists, so the old ones can go once it is published.
+    for index in chunks..previous.map_or(0, |manifest| manifest.chunks) {
+        instance.entry(chunk_name(name, index)).unpublish();
+    }
+    Ok(())
+}
+
+/// Reassembles a payload published with [`publish_chunked`].
+///
+/// Returns `Ok(None)` if nothing has been published under `name`.
+///
+/// # Errors
+///
+/// - [`NetworkTablesError::CorruptChunks`] if chunks are missing, belong to a different payload
+///   (e.g. because a new one is being published), or the reassembled payload fails its integrity check.
+///   Reading again once the new payload has fully arrived will succeed.
+pub fn read_chunked<I: Instance + ?Sized>(
+    instance: &I,
+    name: &str,
+) -> Result<Option<Vec<u8>>, NetworkTablesError> {
+    let Some(manifest) = Manifest::read(instance, name) else {
+        return Ok(None);
+    };
+
+    let mut data = Vec::with_capacity(manifest.length);
+    for index in 0..manifest.chunks {
+        let chunk = instance
+            .entry(chunk_name(name, index))
+            .value_raw()
+            .ok_or_else(|| CorruptChunksSnafu.build())?;
+
+        let Some((generation, chunk)) = chunk.split_first_chunk::<GENERATION_SIZE>() else {
+            return CorruptChunksSnafu.fail();
+        };
+        let generation = u64::from_le_bytes(*generation);
+        if generation != manifest.generation {
+            return CorruptChunksSnafu.fail();
+        }
+        data.extend_from_slice(chunk);
+    }
+
+    if data.len() != manifest.length || checksum(&data) != manifest.checksum {
+        return CorruptChunksSnafu.fail();
+    }
+
+    Ok(Some(data))
+}
@@ -1,16 +1,32 @@
-use std::{ffi::CString, net::SocketAddr};
+use std::{
+    hash::{Hash, Hasher},
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 use ntcore_sys::{
-    NT_AddLogger, NT_CreateInstance, NT_DestroyInstance, NT_Inst, NT_SetServer,
+    NT_CreateInstance, NT_Inst, NT_SetServer,
     NT_StartClient3, NT_StartClient4, NT_StopClient, WPI_String,
 };
+#[cfg(feature = "builder")]
 use typed_builder::TypedBuilder;
 
-use crate::{Instance, NetworkTablesVersion};
+use crate::{
+    datalog::{ConnectionDataLogger, DataLog, EntryDataLogger, ENTRY_LOG_PREFIX},
+    shutdown, Instance, NetworkTablesVersion,
+};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A NetworkTables client on its own instance.
+///
+/// Clones share the instance, which is destroyed once every clone is dropped.
+#[derive(Debug)]
 pub struct Client {
     instance: NT_Inst,
+    /// Shared with clones, so stopping any of them stops them all.
+    stopped: Arc<AtomicBool>,
 }
 
 impl Client {
@@ -20,41 +36,83 @@ impl Client {
         server_name: Option<impl AsRef<str>>,
     ) -> Self {
         let instance = unsafe { NT_CreateInstance() };
+        shutdown::acquire_instance(instance);
 
         //TODO: Are these WPI_String pointers supposed to be static?
         //TODO: When can the identity and name safely be dropped?
         unsafe {
-            NT_AddLogger(
-                instance,
-                0,
-                u32::MAX,
-                std::ptr::null_mut(),
-                crate::default_log_callback,
-            );
-
-            let identity = CString::new(address.ip().to_string()).unwrap();
-            let identity = WPI_String::from(identity.as_c_str());
+            crate::add_default_logger(instance);
+
+            let identity = address.ip().to_string();
+            let identity = WPI_String::from(identity.as_str());
             match version {
                 NetworkTablesVersion::V4 => NT_StartClient4(instance, &raw const identity),
                 NetworkTablesVersion::V3 => NT_StartClient3(instance, &raw const identity),
             }
 
-            let server_name = server_name
-                .map(|name| CString::new(name.as_ref()).unwrap())
-                .unwrap_or_else(|| CString::new("").unwrap());
-            let server_name = WPI_String::from(server_name.as_c_str());
+            let server_name = server_name.as_ref().map_or("", |name| name.as_ref());
+            let server_name = WPI_String::from(server_name);
             NT_SetServer(instance, &raw const server_name, address.port() as _);
         }
 
-        Self { instance }
+        Self {
+            instance,
+            stopped: Arc::default(),
+        }
+    }
+
+    /// Stops the client without destroying the instance.
+    ///
+    /// Entries and topics obtained from this client remain valid, but no longer communicate over the network.
+    /// Clones of the client are stopped as well. Stopping an already stopped client does nothing.
+    pub fn stop(&mut self) {
+        if !self.stopped.swap(true, Ordering::SeqCst) {
+            unsafe { NT_StopClient(self.instance) };
+        }
+    }
+
+    /// Returns `true` if [`Client::stop`] has been called on this client or one of its clones.
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Logs the connection to the server to `datalog` under the entry `name` until the returned logger is dropped.
+    pub fn log_connections_to<'l>(
+        &self,
+        datalog: &'l DataLog,
+        name: impl AsRef<str>,
+    ) -> ConnectionDataLogger<'l> {
+        ConnectionDataLogger::new(self.instance, datalog, name.as_ref())
+    }
+
+    /// Logs the value of every entry whose name starts with `prefix` to `datalog` until the returned logger is dropped.
+    ///
+    /// Like WPILib's `DataLogManager`, entries are logged as `NT:` followed by their full name.
+    pub fn log_entries_to<'l>(
+        &self,
+        datalog: &'l DataLog,
+        prefix: impl AsRef<str>,
+    ) -> EntryDataLogger<'l> {
+        let prefix = prefix.as_ref();
+        // ntcore strips `prefix` from the logged names, so it is added back.
+        let log_prefix = format!("{ENTRY_LOG_PREFIX}{prefix}");
+        EntryDataLogger::new(self.instance, datalog, prefix, &log_prefix)
     }
 
+    #[cfg(feature = "builder")]
     pub fn builder() -> ClientOptionsBuilder {
         ClientOptions::builder()
     }
 }
 
 impl Instance for Client {
+    unsafe fn from_handle(handle: NT_Inst) -> Self {
+        shutdown::acquire_instance(handle);
+        Self {
+            instance: handle,
+            stopped: Arc::default(),
+        }
+    }
     unsafe fn handle(&self) -> NT_Inst {
         self.instance
     }
@@ -63,22 +121,43 @@ impl Instance for Client {
     }
 }
 
+impl Clone for Client {
+    fn clone(&self) -> Self {
+        shutdown::acquire_instance(self.instance);
+        Self {
+            instance: self.instance,
+            stopped: Arc::clone(&self.stopped),
+        }
+    }
+}
+
+impl PartialEq for Client {
+    fn eq(&self, other: &Self) -> bool {
+        self.instance == other.instance
+    }
+}
+impl Eq for Client {}
+impl Hash for Client {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.instance.hash(state);
+    }
+}
+
 impl Drop for Client {
     fn drop(&mut self) {
-        unsafe {
-            NT_StopClient(self.instance);
-            NT_DestroyInstance(self.instance);
-        }
+        // Listeners, subscribers and publishers that outlive the instance stop releasing their handles first.
+        let instance = self.instance;
+        shutdown::release_instance(instance, || self.stop());
     }
 }
 
-#[derive(Debug, Clone, TypedBuilder)]
-#[builder(build_method(into = Client))]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "builder", derive(TypedBuilder), builder(build_method(into = Client)))]
 pub struct ClientOptions {
-    #[builder(default = None, setter(transform = |name: impl AsRef<str>| Some(name.as_ref().to_string())))]
+    #[cfg_attr(feature = "builder", builder(default = None, setter(transform = |name: impl AsRef<str>| Some(name.as_ref().to_string()))))]
     pub server_name: Option<String>,
     pub address: SocketAddr,
-    #[builder(default)]
+    #[cfg_attr(feature = "builder", builder(default))]
     pub version: NetworkTablesVersion,
 }
 impl From<ClientOptions> for Client {
@@ -0,0 +1,133 @@
+//! Coalescing bursts of values into at most one callback per topic per interval.
+//!
+//! The first value of a topic is delivered right away. Values arriving within the interval after a delivery are held back,
+//! and only the latest of them is delivered once the interval has passed, along with how many were skipped.
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    lock,
+    nt_types::RawValue,
+    runtime::{DefaultRuntime, PeriodicTask, Runtime},
+    unwind,
+};
+
+type Callback<K> = Box<dyn Fn(&K, RawValue, u64) + Send + Sync>;
+
+#[derive(Debug)]
+struct Slot {
+    last_delivery: Instant,
+    pending: Option<RawValue>,
+    skipped: u64,
+}
+
+struct Shared<K> {
+    slots: Mutex<HashMap<K, Slot>>,
+    callback: Callback<K>,
+}
+
+impl<K: Clone + Eq + Hash> Shared<K> {
+    /// Delivers every held back value whose interval has passed.
+    fn flush(&self, interval: Duration) {
+        let now = Instant::now();
+        let due: Vec<(K, RawValue, u64)> = lock(&self.slots)
+            .iter_mut()
+            .filter_map(|(key, slot)| {
+                if now.saturating_duration_since(slot.last_delivery) < interval {
+                    return None;
+                }
+                let value = slot.pending.take()?;
+                slot.last_delivery = now;
+                Some((key.clone(), value, std::mem::take(&mut slot.skipped)))
+            })
+            .collect();
+
+        // The callback runs without the lock, so a slow callback doesn't hold up incoming values.
+        // A panic is caught so the periodic work keeps delivering the other topics.
+        for (key, value, skipped) in due {
+            unwind::catch("NetworkTables listener callback", || {
+                (self.callback)(&key, value, skipped)
+            });
+        }
+    }
+}
+
+/// Coalesces the values of any number of topics, told apart by `K`.
+pub(crate) struct Coalescer<K> {
+    shared: Arc<Shared<K>>,
+    interval: Duration,
+}
+
+impl<K: Clone + Eq + Hash + Send + Sync + 'static> Coalescer<K> {
+    /// Creates a coalescer that calls `callback` with each delivered value and the number of values skipped before it.
+    ///
+    /// Held back values are delivered by periodic work on the [`DefaultRuntime`], which runs until the returned task is dropped.
+    /// Dropping the task drops the values still held back.
+    pub(crate) fn new(
+        interval: Duration,
+        callback: impl Fn(&K, RawValue, u64) + Send + Sync + 'static,
+    ) -> (Self, PeriodicTask) {
+        let shared = Arc::new(Shared {
+            slots: Mutex::new(HashMap::new()),
+            callback: Box::new(callback),
+        });
+
+        let flusher = DefaultRuntime::default().spawn_periodic(interval, {
+            let shared = shared.clone();
+            move || shared.flush(interval)
+        });
+
+        // Without periodic work to deliver held back values, nothing may be held back.
+        let interval = if flusher.is_stopped() {
+            Duration::ZERO
+        } else {
+            interval
+        };
+
+        (Self { shared, interval }, flusher)
+    }
+
+    /// Delivers `value` right away if the interval since the last delivery for `key` has passed,
+    /// or holds it back otherwise.
+    pub(crate) fn push(&self, key: K, value: RawValue) {
+        let now = Instant::now();
+        let deliver = {
+            let mut slots = lock(&self.shared.slots);
+            match slots.get_mut(&key) {
+                Some(slot)
+                    if slot.pending.is_some()
+                        || now.saturating_duration_since(slot.last_delivery) < self.interval =>
+                {
+                    if slot.pending.replace(value).is_some() {
+                        slot.skipped += 1;
+                    }
+                    None
+                }
+                Some(slot) => {
+                    slot.last_delivery = now;
+                    Some(value)
+                }
+                None => {
+                    slots.insert(
+                        key.clone(),
+                        Slot {
+                            last_delivery: now,
+                            pending: None,
+                            skipped: 0,
+                        },
+                    );
+                    Some(value)
+                }
+            }
+        };
+
+        if let Some(value) = deliver {
+            (self.shared.callback)(&key, value, 0);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Transparent compression for raw topics.
+//!
+//! Compressed topics are raw topics whose type string is the type string of the uncompressed data
+//! followed by `+` and the name of the compression algorithm, e.g. `json+zstd`.
+//! Subscribers use the suffix to decide how to decompress values, so both ends always agree.
+
+use crate::{
+    nt_types::{PubSubOptions, Value, ValueType},
+    topic::{Topic, TopicPublisher, TopicSubscriber},
+    Instance, NetworkTablesError,
+};
+
+/// A compression algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compression {
+    #[cfg(feature = "deflate")]
+    Deflate,
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// The type string suffix that marks a topic as compressed with this algorithm.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "deflate")]
+            Self::Deflate => "deflate",
+            #[cfg(feature = "zstd")]
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Returns the type string for data of type `base` compressed with this algorithm.
+    pub fn type_string(&self, base: &str) -> String {
+        format!("{base}+{}", self.suffix())
+    }
+
+    /// Splits a type string into the type string of the uncompressed data and its compression.
+    ///
+    /// Returns `None` if the type string doesn't end with the suffix of an enabled algorithm.
+    pub fn from_type_string(type_string: &str) -> Option<(&str, Self)> {
+        let (base, suffix) = type_string.rsplit_once('+')?;
+        let compression = match suffix {
+            #[cfg(feature = "deflate")]
+            "deflate" => Self::Deflate,
+            #[cfg(feature = "zstd")]
+            "zstd" => Self::Zstd,
+            _ => return None,
+        };
+        Some((base, compression))
+    }
+
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::Compression`] if the compressor failed.
+    pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>, NetworkTablesError> {
+        let result = match self {
+            #[cfg(feature = "deflate")]
+            Self::Deflate => {
+                use std::io::Write;
+
+                let mut encoder =
+                    flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data).and_then(|()| encoder.finish())
+            }
+            #[cfg(feature = "zstd")]
+            Self::Zstd => zstd::encode_all(data, zstd::DEFAULT_COMPRESSION_LEVEL),
+        };
+        result.map_err(|err| NetworkTablesError::Compression {
+            message: err.to_string(),
+        })
+    }
+
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::Decompression`] if `data` is not valid compressed data.
+    pub fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, NetworkTablesError> {
+        let result = match self {
+            #[cfg(feature = "deflate")]
+            Self::Deflate => {
+                use std::io::Read;
+
+                let mut decompressed = Vec::new();
+                flate2::read::DeflateDecoder::new(data)
+                    .read_to_end(&mut decompressed)
+                    .map(|_| decompressed)
+            }
+            #[cfg(feature = "zstd")]
+            Self::Zstd => zstd::decode_all(data),
+        };
+        result.map_err(|err| NetworkTablesError::Decompression {
+            message: err.to_string(),
+        })
+    }
+}
+
+/// A raw topic publisher that compresses every value.
+#[derive(Debug)]
+pub struct CompressedPublisher<'a, I: Instance + ?Sized> {
+    publisher: TopicPublisher<'a, I>,
+    compression: Compression,
+}
+
+impl<'a, I: Instance + ?Sized> CompressedPublisher<'a, I> {
+    /// Starts publishing compressed values of type `base_type_string` to `topic`.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the topic already exists with a type other than raw.
+    pub fn new(
+        topic: &'a Topic<'a, I>,
+        base_type_string: &str,
+        compression: Compression,
+        options: PubSubOptions,
+    ) -> Result<Self, NetworkTablesError> {
+        let publisher = topic.publish(
+            ValueType::Raw,
+            compression.type_string(base_type_string),
+            options,
+        )?;
+        Ok(Self {
+            publisher,
+            compression,
+        })
+    }
+
+    pub fn set(&self, data: &[u8]) -> Result<(), NetworkTablesError> {
+        self.publisher
+            .set_value_raw(self.compression.compress(data)?)
+    }
+
+    pub fn set_string(&self, string: &str) -> Result<(), NetworkTablesError> {
+        self.set(string.as_bytes())
+    }
+
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    pub fn publisher(&self) -> &TopicPublisher<'a, I> {
+        &self.publisher
+    }
+}
+
+/// A raw topic subscriber that decompresses values according to the topic's type string.
+///
+/// Values of topics without a compression suffix are passed through unchanged.
+#[derive(Debug)]
+pub struct CompressedSubscriber<'a, I: Instance + ?Sized> {
+    subscriber: TopicSubscriber<'a, I>,
+}
+
+impl<'a, I: Instance + ?Sized> CompressedSubscriber<'a, I> {
+    /// Subscribes to compressed values on `topic`.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the topic already exists with a type other than raw.
+    pub fn new(topic: &'a Topic<'a, I>, options: PubSubOptions) -> Result<Self, NetworkTablesError> {
+        let subscriber = topic.subscribe(ValueType::Raw, "raw", options)?;
+        Ok(Self { subscriber })
+    }
+
+    /// Returns the compression of the topic, if it has a compression suffix.
+    pub fn compression(&self) -> Option<Compression> {
+        let type_string = self.subscriber.topic().value_type_string()?;
+        Compression::from_type_string(&type_string).map(|(_, compression)| compression)
+    }
+
+    /// Returns all of the new decompressed values since the last read.
+    ///
+    /// If there have been no new updates, None is returned.
+    pub fn try_read_update_queue(&self) -> Option<Vec<Result<Vec<u8>, NetworkTablesError>>> {
+        let values = self.subscriber.try_read_update_queue_of_type(ValueType::Raw)?;
+        let compression = self.compression();
+
+        let values = values
+            .into_iter()
+            .filter_map(|value| match value {
+                Value::Raw(data) => Some(match compression {
+                    Some(compression) => compression.decompress(&data),
+                    None => Ok(data),
+                }),
+                _ => None,
+            })
+            .collect();
+        Some(values)
+    }
+
+    pub fn subscriber(&self) -> &TopicSubscriber<'a, I> {
+        &self.subscriber
+    }
+}
@@ -0,0 +1,175 @@
+//! Information about the remote nodes an instance is connected to.
+
+use std::time::Duration;
+
+use ntcore_sys::{
+    NT_AddPolledListener, NT_ConnectionInfo, NT_CreateListenerPoller, NT_DestroyListenerPoller,
+    NT_EventFlags, NT_GetConnections, NT_Listener, NT_ListenerPoller, NT_ReadListenerQueue,
+    NT_RemoveListener, NT_TimeSyncEventData, NtConnectionInfoArray, NtEventArray,
+};
+
+use crate::{
+    nt_types::NetworkTablesInstant, shutdown::InstanceGuard, Instance, NetworkTablesVersion,
+};
+
+/// A connection to a remote NetworkTables node.
+///
+/// On a server this describes a connected client, on a client it describes the server.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConnectionInfo {
+    /// The identity the remote node gave when it connected.
+    pub remote_id: String,
+    /// The IP address of the remote node.
+    pub remote_ip: String,
+    /// The port of the remote node.
+    pub remote_port: u32,
+    /// The last time any update was received from the remote node.
+    pub last_update: NetworkTablesInstant,
+    /// The protocol version in use, where `0x0300` is 3.0 and `0x0400` is 4.0.
+    pub protocol_version: u32,
+}
+
+impl ConnectionInfo {
+    /// Returns the NetworkTables version used by the connection if it is a known one.
+    pub fn version(&self) -> Option<NetworkTablesVersion> {
+        match self.protocol_version >> 8 {
+            3 => Some(NetworkTablesVersion::V3),
+            4 => Some(NetworkTablesVersion::V4),
+            _ => None,
+        }
+    }
+}
+
+impl From<&NT_ConnectionInfo> for ConnectionInfo {
+    fn from(info: &NT_ConnectionInfo) -> Self {
+        unsafe {
+            Self {
+                remote_id: info.remote_id.to_str_lossy().into_owned(),
+                remote_ip: info.remote_ip.to_str_lossy().into_owned(),
+                remote_port: info.remote_port,
+                last_update: NetworkTablesInstant::from_micros(info.last_update),
+                protocol_version: info.protocol_version,
+            }
+        }
+    }
+}
+
+/// A time synchronization measurement of a client against its server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeSync {
+    /// Added to local time to get the estimated server time, in microseconds.
+    pub server_time_offset: i64,
+    /// Half of the measured round trip time.
+    pub rtt2: Duration,
+    /// False when the client disconnected, in which case the other fields are meaningless.
+    pub valid: bool,
+}
+
+impl From<&NT_TimeSyncEventData> for TimeSync {
+    fn from(data: &NT_TimeSyncEventData) -> Self {
+        Self {
+            server_time_offset: data.serverTimeOffset,
+            rtt2: Duration::from_micros(data.rtt2.max(0) as u64),
+            valid: data.valid != 0,
+        }
+    }
+}
+
+/// Returns every currently connected remote node.
+pub(crate) fn connections<I: Instance + ?Sized>(instance: &I) -> Vec<ConnectionInfo> {
+    let mut count = 0;
+    let raw_connections = unsafe {
+        NtConnectionInfoArray::from_raw(NT_GetConnections(instance.handle(), &raw mut count), count)
+    };
+
+    raw_connections.iter().map(ConnectionInfo::from).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ConnectionEvent {
+    Connected(ConnectionInfo),
+    Disconnected(ConnectionInfo),
+}
+
+impl ConnectionEvent {
+    pub fn info(&self) -> &ConnectionInfo {
+        match self {
+            Self::Connected(info) | Self::Disconnected(info) => info,
+        }
+    }
+}
+
+/// A queue of connect and disconnect events for an instance.
+///
+/// Remote nodes that are already connected when the queue is created are reported as [`ConnectionEvent::Connected`].
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ConnectionEvents<'a, I: Instance + ?Sized> {
+    instance: &'a I,
+    guard: InstanceGuard,
+    poller: NT_ListenerPoller,
+    listener: NT_Listener,
+}
+
+impl<'a, I: Instance + ?Sized> ConnectionEvents<'a, I> {
+    pub(crate) fn new(instance: &'a I) -> Self {
+        let mask = NT_EventFlags::NT_EVENT_CONNECTION | NT_EventFlags::NT_EVENT_IMMEDIATE;
+        let (poller, listener) = unsafe {
+            let poller = NT_CreateListenerPoller(instance.handle());
+            let listener = NT_AddPolledListener(poller, instance.handle(), mask.bits());
+            (poller, listener)
+        };
+
+        Self {
+            instance,
+            guard: InstanceGuard::new(unsafe { instance.handle() }),
+            poller,
+            listener,
+        }
+    }
+
+    /// Returns all of the connection events since the last read.
+    ///
+    /// If there have been no new events, None is returned.
+    pub fn try_read_queue(&self) -> Option<Vec<ConnectionEvent>> {
+        let mut events = Vec::new();
+        (self.try_read_queue_into(&mut events) > 0).then_some(events)
+    }
+
+    /// Appends all of the connection events since the last read to `events`.
+    ///
+    /// Reusing the same `Vec` for every read avoids allocating a new one each time.
+    /// Returns the number of events appended.
+    pub fn try_read_queue_into(&self, events: &mut Vec<ConnectionEvent>) -> usize {
+        let mut count = 0;
+        let raw_events = unsafe {
+            NtEventArray::from_raw(NT_ReadListenerQueue(self.poller, &raw mut count), count)
+        };
+
+        let before = events.len();
+        events.extend(raw_events.iter().filter_map(|event| {
+            let flags = NT_EventFlags::from_bits_retain(event.flags);
+            let info = ConnectionInfo::from(unsafe { &event.data.connInfo });
+            if flags.contains(NT_EventFlags::NT_EVENT_CONNECTED) {
+                Some(ConnectionEvent::Connected(info))
+            } else if flags.contains(NT_EventFlags::NT_EVENT_DISCONNECTED) {
+                Some(ConnectionEvent::Disconnected(info))
+            } else {
+                None
+            }
+        }));
+        events.len() - before
+    }
+
+    pub fn instance(&self) -> &'a I {
+        self.instance
+    }
+}
+
+impl<I: Instance + ?Sized> Drop for ConnectionEvents<'_, I> {
+    fn drop(&mut self) {
+        self.guard.release(|| unsafe {
+            NT_RemoveListener(self.listener);
+            NT_DestroyListenerPoller(self.poller);
+        });
+    }
+}
@@ -0,0 +1,9 @@
+//! Topic layouts that robot code, co-processors and dashboards agree on,
+//! so each program doesn't have to spell out the topic names of the other side by hand.
+
+pub mod alerts;
+pub mod camera;
+pub mod field2d;
+pub mod fms;
+pub mod mechanism2d;
+pub mod sim;
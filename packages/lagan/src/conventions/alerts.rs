@@ -0,0 +1,224 @@
+//! WPILib's alerts, which Elastic, Shuffleboard and lagan-gui show as banners.
+//!
+//! The active alerts of a group are published under the group's topic as the string arrays `errors`, `warnings`
+//! and `infos`, newest first, along with a `.type` of `Alerts` so dashboards recognize the group.
+
+use crate::{entry::Entry, Instance, NetworkTablesError, Value};
+
+/// The group WPILib publishes alerts to by default.
+pub const DEFAULT_GROUP: &str = "/SmartDashboard/Alerts";
+/// The `.type` of alert groups.
+pub const GROUP_TYPE: &str = "Alerts";
+
+/// How severe an alert is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertLevel {
+    Error,
+    Warning,
+    Info,
+}
+
+impl AlertLevel {
+    /// Every level, most severe first.
+    pub const ALL: [Self; 3] = [Self::Error, Self::Warning, Self::Info];
+
+    /// The name of the topic holding the alerts of this level.
+    pub fn topic_name(self) -> &'static str {
+        match self {
+            Self::Error => "errors",
+            Self::Warning => "warnings",
+            Self::Info => "infos",
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Self::Error => 0,
+            Self::Warning => 1,
+            Self::Info => 2,
+        }
+    }
+}
+
+/// Returns the topics of the errors, warnings and infos of `group`.
+pub fn topics(group: &str) -> [String; 3] {
+    let group = group.trim_end_matches('/');
+    AlertLevel::ALL.map(|level| format!("{group}/{}", level.topic_name()))
+}
+
+/// The active alerts of a group, by level.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Alerts {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub infos: Vec<String>,
+}
+
+impl Alerts {
+    /// Decodes the values of the errors, warnings and infos topics of a group.
+    ///
+    /// Missing values and values that aren't string arrays count as no alerts.
+    pub fn from_values(values: [Option<&Value>; 3]) -> Self {
+        let [errors, warnings, infos] = values.map(|value| match value {
+            Some(Value::StringArray(alerts)) => alerts.clone(),
+            _ => Vec::new(),
+        });
+        Self {
+            errors,
+            warnings,
+            infos,
+        }
+    }
+
+    /// Returns the alerts of the given level, newest first.
+    pub fn get(&self, level: AlertLevel) -> &[String] {
+        match level {
+            AlertLevel::Error => &self.errors,
+            AlertLevel::Warning => &self.warnings,
+            AlertLevel::Info => &self.infos,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty() && self.infos.is_empty()
+    }
+}
+
+/// Raises and clears the alerts of a group.
+///
+/// The alerts stay published until the group is dropped.
+#[derive(Debug)]
+pub struct AlertGroup<'a, I: Instance + ?Sized> {
+    group: String,
+    // Keeps the type published along with the alerts.
+    _type: Entry<'a, I>,
+    entries: [Entry<'a, I>; 3],
+    active: [Vec<String>; 3],
+}
+
+impl<'a, I: Instance + ?Sized> AlertGroup<'a, I> {
+    /// Publishes an alert group without any active alerts to `group`, e.g. [`DEFAULT_GROUP`].
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if one of the group's topics exists with another type.
+    pub fn new(instance: &'a I, group: impl AsRef<str>) -> Result<Self, NetworkTablesError> {
+        let group = group.as_ref().trim_end_matches('/').to_string();
+        let type_entry = instance.entry(format!("{group}/.type"));
+        type_entry.set_value_string(GROUP_TYPE)?;
+        let entries = topics(&group).map(|topic| instance.entry(topic));
+        for entry in &entries {
+            entry.set_value_string_array(Vec::new())?;
+        }
+
+        Ok(Self {
+            group,
+            _type: type_entry,
+            entries,
+            active: Default::default(),
+        })
+    }
+
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    /// Raises or clears an alert. Raised alerts are listed before older ones of the same level.
+    ///
+    /// Nothing is published if the alert already was in that state.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the level's topic was republished with another type.
+    pub fn set(
+        &mut self,
+        level: AlertLevel,
+        text: impl AsRef<str>,
+        active: bool,
+    ) -> Result<(), NetworkTablesError> {
+        let text = text.as_ref();
+        let alerts = &mut self.active[level.index()];
+        let position = alerts.iter().position(|alert| alert == text);
+        match (position, active) {
+            (None, true) => alerts.insert(0, text.to_string()),
+            (Some(position), false) => {
+                alerts.remove(position);
+            }
+            _ => return Ok(()),
+        }
+        self.publish(level)
+    }
+
+    /// Raises an alert, see [`Self::set`].
+    pub fn raise(
+        &mut self,
+        level: AlertLevel,
+        text: impl AsRef<str>,
+    ) -> Result<(), NetworkTablesError> {
+        self.set(level, text, true)
+    }
+
+    /// Clears an alert, see [`Self::set`].
+    pub fn clear(
+        &mut self,
+        level: AlertLevel,
+        text: impl AsRef<str>,
+    ) -> Result<(), NetworkTablesError> {
+        self.set(level, text, false)
+    }
+
+    /// Clears every alert of the group.
+    pub fn clear_all(&mut self) -> Result<(), NetworkTablesError> {
+        for level in AlertLevel::ALL {
+            if !self.active[level.index()].is_empty() {
+                self.active[level.index()].clear();
+                self.publish(level)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the active alerts of the group.
+    pub fn alerts(&self) -> Alerts {
+        let [errors, warnings, infos] = self.active.clone();
+        Alerts {
+            errors,
+            warnings,
+            infos,
+        }
+    }
+
+    fn publish(&self, level: AlertLevel) -> Result<(), NetworkTablesError> {
+        self.entries[level.index()].set_value_string_array(self.active[level.index()].clone())
+    }
+}
+
+/// Reads the active alerts of a group, e.g. to show them on a dashboard.
+#[derive(Debug)]
+pub struct AlertReader<'a, I: Instance + ?Sized> {
+    group: String,
+    entries: [Entry<'a, I>; 3],
+}
+
+impl<'a, I: Instance + ?Sized> AlertReader<'a, I> {
+    /// Subscribes to the alerts of `group`, e.g. [`DEFAULT_GROUP`].
+    pub fn new(instance: &'a I, group: impl AsRef<str>) -> Self {
+        let group = group.as_ref().trim_end_matches('/').to_string();
+        let entries = topics(&group).map(|topic| instance.entry(topic));
+        Self { group, entries }
+    }
+
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    /// Returns the active alerts of the group.
+    pub fn alerts(&self) -> Alerts {
+        let [errors, warnings, infos] = &self.entries;
+        Alerts::from_values([
+            Some(&errors.value()),
+            Some(&warnings.value()),
+            Some(&infos.value()),
+        ])
+    }
+}
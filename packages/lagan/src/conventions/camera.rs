@@ -0,0 +1,127 @@
+//! CameraServer's stream announcements, which dashboards use to find the streams of every camera.
+//!
+//! Every camera has a table under [`CAMERA_PUBLISHER_TABLE`] named after it, whose `streams` string array lists its
+//! stream URLs prefixed with their format, e.g. `mjpg:http://10.0.0.11:1181/?action=stream`.
+
+use crate::{entry::Entry, Instance, NetworkTablesError};
+
+/// The table cameras are announced under.
+pub const CAMERA_PUBLISHER_TABLE: &str = "/CameraPublisher";
+/// The source of cameras whose frames are produced by a program, e.g. annotated frames of a vision pipeline.
+pub const PROGRAM_SOURCE: &str = "cv:";
+
+/// Prefixes an MJPEG stream URL with its format, unless it already has the prefix.
+pub fn mjpeg_stream(url: &str) -> String {
+    if url.starts_with("mjpg:") {
+        url.to_string()
+    } else {
+        format!("mjpg:{url}")
+    }
+}
+
+/// Announces the streams of a camera.
+///
+/// The camera stays announced until the announcer is dropped.
+#[derive(Debug)]
+pub struct CameraAnnouncer<'a, I: Instance + ?Sized> {
+    name: String,
+    streams: Entry<'a, I>,
+    source: Entry<'a, I>,
+    description: Entry<'a, I>,
+    connected: Entry<'a, I>,
+}
+
+impl<'a, I: Instance + ?Sized> CameraAnnouncer<'a, I> {
+    /// Announces a connected camera with the given MJPEG stream URLs, e.g. `http://10.0.0.11:1181/?action=stream`.
+    ///
+    /// The camera's source is [`PROGRAM_SOURCE`] and its description its name, until they are changed.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if one of the camera's topics exists with another type.
+    pub fn new<S: AsRef<str>>(
+        instance: &'a I,
+        name: impl Into<String>,
+        urls: impl IntoIterator<Item = S>,
+    ) -> Result<Self, NetworkTablesError> {
+        let name = name.into();
+        let table = format!("{CAMERA_PUBLISHER_TABLE}/{name}");
+        let announcer = Self {
+            streams: instance.entry(format!("{table}/streams")),
+            source: instance.entry(format!("{table}/source")),
+            description: instance.entry(format!("{table}/description")),
+            connected: instance.entry(format!("{table}/connected")),
+            name,
+        };
+
+        announcer.set_streams(urls)?;
+        announcer.set_source(PROGRAM_SOURCE)?;
+        announcer.set_description(&announcer.name)?;
+        announcer.set_connected(true)?;
+        Ok(announcer)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Replaces the announced MJPEG stream URLs, e.g. after the stream moved to another port.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the `streams` topic exists with another type.
+    pub fn set_streams<S: AsRef<str>>(
+        &self,
+        urls: impl IntoIterator<Item = S>,
+    ) -> Result<(), NetworkTablesError> {
+        let streams = urls
+            .into_iter()
+            .map(|url| mjpeg_stream(url.as_ref()))
+            .collect();
+        self.streams.set_value_string_array(streams)
+    }
+
+    /// Sets where the camera's frames come from, e.g. `usb:/dev/video0` or [`PROGRAM_SOURCE`].
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the `source` topic exists with another type.
+    pub fn set_source(&self, source: &str) -> Result<(), NetworkTablesError> {
+        self.source.set_value_string(source)
+    }
+
+    /// Sets the human-readable description of the camera.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the `description` topic exists with another type.
+    pub fn set_description(&self, description: &str) -> Result<(), NetworkTablesError> {
+        self.description.set_value_string(description)
+    }
+
+    /// Sets whether the camera is producing frames, which dashboards show instead of a frozen stream.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the `connected` topic exists with another type.
+    pub fn set_connected(&self, connected: bool) -> Result<(), NetworkTablesError> {
+        self.connected.set_value_bool(connected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mjpeg_stream;
+
+    #[test]
+    fn streams_are_prefixed_once() {
+        assert_eq!(
+            mjpeg_stream("http://10.0.0.11:1181/?action=stream"),
+            "mjpg:http://10.0.0.11:1181/?action=stream"
+        );
+        assert_eq!(
+            mjpeg_stream("mjpg:http://10.0.0.11:1181/?action=stream"),
+            "mjpg:http://10.0.0.11:1181/?action=stream"
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Glass's `Field2d`, which shows the poses of the robot and other objects on a picture of the field.
+//!
+//! Every object of a field is a double array under the field's table, holding `x, y, rotation` for each of its poses
+//! in meters and degrees. The robot is the object named [`ROBOT_OBJECT`].
+
+use std::collections::HashMap;
+
+use crate::{entry::Entry, Instance, NetworkTablesError};
+
+/// The `.type` of fields.
+pub const FIELD_TYPE: &str = "Field2d";
+/// The object holding the pose of the robot.
+pub const ROBOT_OBJECT: &str = "Robot";
+
+/// A position on the field and the direction something is facing there.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Pose2d {
+    /// Meters from the blue alliance wall.
+    pub x: f64,
+    /// Meters from the right side of the field, seen from the blue alliance wall.
+    pub y: f64,
+    /// Radians counterclockwise from facing the red alliance wall.
+    pub rotation: f64,
+}
+
+impl Pose2d {
+    pub fn new(x: f64, y: f64, rotation: f64) -> Self {
+        Self { x, y, rotation }
+    }
+}
+
+/// Encodes poses as the double array of a field object.
+pub fn encode_poses(poses: &[Pose2d]) -> Vec<f64> {
+    poses
+        .iter()
+        .flat_map(|pose| [pose.x, pose.y, pose.rotation.to_degrees()])
+        .collect()
+}
+
+/// Publishes the objects of a field.
+///
+/// Objects stay published until the field is dropped or they are removed.
+#[derive(Debug)]
+pub struct Field2d<'a, I: Instance + ?Sized> {
+    instance: &'a I,
+    table: String,
+    // Keeps the type published along with the objects.
+    _type: Entry<'a, I>,
+    objects: HashMap<String, Entry<'a, I>>,
+}
+
+impl<'a, I: Instance + ?Sized> Field2d<'a, I> {
+    /// Publishes a field without any objects to `table`, e.g. `/SmartDashboard/Field`.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the field's `.type` exists with another type.
+    pub fn new(instance: &'a I, table: impl AsRef<str>) -> Result<Self, NetworkTablesError> {
+        let table = table.as_ref().trim_end_matches('/').to_string();
+        let type_entry = instance.entry(format!("{table}/.type"));
+        type_entry.set_value_string(FIELD_TYPE)?;
+
+        Ok(Self {
+            instance,
+            table,
+            _type: type_entry,
+            objects: HashMap::new(),
+        })
+    }
+
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Publishes the pose of the robot.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the robot's topic exists with another type.
+    pub fn set_robot_pose(&mut self, pose: Pose2d) -> Result<(), NetworkTablesError> {
+        self.set_object(ROBOT_OBJECT, &[pose])
+    }
+
+    /// Publishes the poses of an object, e.g. the path the robot is following or game pieces seen by a camera.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the object's topic exists with another type.
+    pub fn set_object(&mut self, name: &str, poses: &[Pose2d]) -> Result<(), NetworkTablesError> {
+        let entry = self
+            .objects
+            .entry(name.to_string())
+            .or_insert_with(|| self.instance.entry(format!("{}/{name}", self.table)));
+        entry.set_value_f64_array(encode_poses(poses))
+    }
+
+    /// Stops publishing an object.
+    pub fn remove_object(&mut self, name: &str) {
+        self.objects.remove(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::{encode_poses, Pose2d};
+
+    #[test]
+    fn poses_are_encoded_in_degrees() {
+        let poses = [Pose2d::new(1.0, 2.0, FRAC_PI_2), Pose2d::new(3.0, 4.0, 0.0)];
+        assert_eq!(encode_poses(&poses), vec![1.0, 2.0, 90.0, 3.0, 4.0, 0.0]);
+        assert!(encode_poses(&[]).is_empty());
+    }
+}
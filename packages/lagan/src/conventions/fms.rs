@@ -0,0 +1,253 @@
+//! The match information the driver station publishes under [`FMS_INFO_TABLE`], e.g. to record data only during matches.
+//!
+//! `FMSInfo` has no match time, so robot code that wants co-processors to know it has to publish it itself,
+//! see [`FmsReader::with_match_time_topic`].
+
+use std::sync::Mutex;
+
+use bitflags::bitflags;
+
+use crate::{entry::Entry, listener::ListenerHandle, lock, Instance, Value};
+
+/// The table the driver station's match information is published to.
+pub const FMS_INFO_TABLE: &str = "/FMSInfo";
+
+/// The topics of [`FMS_INFO_TABLE`] read by [`FmsReader`].
+const KEYS: [&str; 8] = [
+    "EventName",
+    "GameSpecificMessage",
+    "MatchNumber",
+    "ReplayNumber",
+    "MatchType",
+    "IsRedAlliance",
+    "StationNumber",
+    "FMSControlData",
+];
+
+bitflags! {
+    /// The state of the robot as reported by the driver station.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub struct ControlWord: u32 {
+        const ENABLED = 0x01;
+        const AUTONOMOUS = 0x02;
+        const TEST = 0x04;
+        const EMERGENCY_STOP = 0x08;
+        const FMS_ATTACHED = 0x10;
+        const DS_ATTACHED = 0x20;
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MatchType {
+    #[default]
+    None,
+    Practice,
+    Qualification,
+    Elimination,
+}
+
+impl MatchType {
+    fn from_number(number: i64) -> Self {
+        match number {
+            1 => Self::Practice,
+            2 => Self::Qualification,
+            3 => Self::Elimination,
+            _ => Self::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alliance {
+    Red,
+    Blue,
+}
+
+/// The match information published by the driver station.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FmsInfo {
+    pub event_name: String,
+    pub game_specific_message: String,
+    pub match_number: i64,
+    pub replay_number: i64,
+    pub match_type: MatchType,
+    /// `None` until the driver station published the alliance.
+    pub alliance: Option<Alliance>,
+    /// The driver station number from 1 to 3, or `None` if it is unknown.
+    pub station: Option<u8>,
+    pub control: ControlWord,
+    /// The seconds left in the current period of the match, if a match time topic was configured and published.
+    pub match_time: Option<f64>,
+}
+
+impl FmsInfo {
+    /// Applies the value of the topic `key` of [`FMS_INFO_TABLE`], and returns `true` if the information changed.
+    ///
+    /// Unknown topics and values of unexpected types are ignored.
+    /// Numbers may be integers or doubles, since NetworkTables 3 only has doubles.
+    pub fn apply(&mut self, key: &str, value: &Value) -> bool {
+        let number = || value.as_f64().map(|number| number as i64);
+        let old = self.clone();
+        match (key, value) {
+            ("EventName", Value::String(name)) => self.event_name.clone_from(name),
+            ("GameSpecificMessage", Value::String(message)) => {
+                self.game_specific_message.clone_from(message);
+            }
+            ("MatchNumber", _) => self.match_number = number().unwrap_or(self.match_number),
+            ("ReplayNumber", _) => self.replay_number = number().unwrap_or(self.replay_number),
+            ("MatchType", _) => {
+                if let Some(number) = number() {
+                    self.match_type = MatchType::from_number(number);
+                }
+            }
+            ("IsRedAlliance", Value::Bool(red)) => {
+                self.alliance = Some(if *red { Alliance::Red } else { Alliance::Blue });
+            }
+            ("StationNumber", _) => {
+                if let Some(number) = number() {
+                    self.station = u8::try_from(number)
+                        .ok()
+                        .filter(|station| (1..=3).contains(station));
+                }
+            }
+            ("FMSControlData", _) => {
+                if let Some(number) = number() {
+                    self.control = ControlWord::from_bits_truncate(number as u32);
+                }
+            }
+            _ => return false,
+        }
+        *self != old
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.control.contains(ControlWord::ENABLED)
+    }
+
+    pub fn is_autonomous(&self) -> bool {
+        self.control.contains(ControlWord::AUTONOMOUS)
+    }
+
+    /// Returns `true` while the robot is enabled in teleop, i.e. neither in autonomous nor in test.
+    pub fn is_teleop(&self) -> bool {
+        self.is_enabled()
+            && !self
+                .control
+                .intersects(ControlWord::AUTONOMOUS | ControlWord::TEST)
+    }
+
+    /// Returns `true` while connected to a field, i.e. during a real match.
+    pub fn is_fms_attached(&self) -> bool {
+        self.control.contains(ControlWord::FMS_ATTACHED)
+    }
+}
+
+/// Reads the match information published by the driver station.
+#[derive(Debug)]
+pub struct FmsReader<'a, I: Instance + ?Sized> {
+    instance: &'a I,
+    // Keep the topics subscribed, so their values are there when read.
+    entries: Vec<(&'static str, Entry<'a, I>)>,
+    match_time: Option<Entry<'a, I>>,
+}
+
+impl<'a, I: Instance + ?Sized> FmsReader<'a, I> {
+    pub fn new(instance: &'a I) -> Self {
+        let entries = KEYS
+            .iter()
+            .map(|&key| (key, instance.entry(format!("{FMS_INFO_TABLE}/{key}"))))
+            .collect();
+        Self {
+            instance,
+            entries,
+            match_time: None,
+        }
+    }
+
+    /// Reads the match time from the given number topic, e.g. one robot code publishes `DriverStation.getMatchTime()` to.
+    pub fn with_match_time_topic(mut self, topic: impl AsRef<str>) -> Self {
+        self.match_time = Some(self.instance.entry(topic));
+        self
+    }
+
+    /// Returns the latest match information.
+    pub fn info(&self) -> FmsInfo {
+        let mut info = FmsInfo::default();
+        for (key, entry) in &self.entries {
+            info.apply(key, &entry.value());
+        }
+        info.match_time = self
+            .match_time
+            .as_ref()
+            .and_then(|entry| entry.value().as_f64());
+        info
+    }
+
+    /// Calls `callback` with the new match information whenever it changes, until the returned handle is dropped.
+    ///
+    /// The callback runs on ntcore's listener thread, so it should return quickly.
+    pub fn on_change(&self, callback: impl Fn(&FmsInfo) + Send + Sync + 'static) -> ListenerHandle {
+        let info = Mutex::new(self.info());
+        let match_time = self.match_time.as_ref().map(|entry| entry.name_arc());
+
+        let mut prefixes = vec![format!("{FMS_INFO_TABLE}/")];
+        prefixes.extend(match_time.as_deref().map(str::to_string));
+        self.instance.watch(prefixes, move |name, value| {
+            let changed = {
+                let mut info = lock(&info);
+                let changed = if match_time.as_deref() == Some(&*name) {
+                    let time = value.data.as_f64();
+                    let changed = info.match_time != time;
+                    info.match_time = time;
+                    changed
+                } else {
+                    name.strip_prefix(FMS_INFO_TABLE)
+                        .and_then(|key| key.strip_prefix('/'))
+                        .is_some_and(|key| info.apply(key, &value.data))
+                };
+                changed.then(|| info.clone())
+            };
+            // The callback runs without the lock, so it can't deadlock by reading the information again.
+            if let Some(info) = changed {
+                callback(&info);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Value;
+
+    use super::{Alliance, ControlWord, FmsInfo, MatchType};
+
+    #[test]
+    fn fms_topics_are_applied() {
+        let mut info = FmsInfo::default();
+        assert!(info.apply("EventName", &Value::String("CASJ".to_string())));
+        assert!(info.apply("MatchType", &Value::I64(2)));
+        assert!(info.apply("MatchNumber", &Value::F64(42.0)));
+        assert!(info.apply("IsRedAlliance", &Value::Bool(false)));
+        assert!(info.apply("StationNumber", &Value::I64(3)));
+        assert!(info.apply("FMSControlData", &Value::I64(0x33)));
+
+        assert_eq!(info.event_name, "CASJ");
+        assert_eq!(info.match_type, MatchType::Qualification);
+        assert_eq!(info.match_number, 42);
+        assert_eq!(info.alliance, Some(Alliance::Blue));
+        assert_eq!(info.station, Some(3));
+        assert_eq!(
+            info.control,
+            ControlWord::ENABLED
+                | ControlWord::AUTONOMOUS
+                | ControlWord::FMS_ATTACHED
+                | ControlWord::DS_ATTACHED
+        );
+        assert!(info.is_autonomous() && info.is_fms_attached() && !info.is_teleop());
+
+        // Repeated values and unknown topics don't count as changes.
+        assert!(!info.apply("MatchNumber", &Value::I64(42)));
+        assert!(!info.apply("Unknown", &Value::Bool(true)));
+        assert!(!info.apply("EventName", &Value::I64(1)));
+    }
+}
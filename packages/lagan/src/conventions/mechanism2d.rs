@@ -0,0 +1,234 @@
+//! Glass's `Mechanism2d`, which draws a mechanism as a tree of line segments, e.g. an arm on an elevator.
+//!
+//! A mechanism's table holds its `dims` and `backgroundColor`. Every root is a subtable with its `x` and `y`,
+//! and every ligament a subtable of its parent with its `angle`, `length`, `weight` and `color`.
+//! Lengths and positions are in the same units as the dimensions, angles in degrees.
+
+use std::collections::HashMap;
+
+use crate::{entry::Entry, Instance, NetworkTablesError, Value};
+
+/// The `.type` of mechanisms.
+pub const MECHANISM_TYPE: &str = "Mechanism2d";
+/// The `.type` of ligaments.
+pub const LIGAMENT_TYPE: &str = "line";
+
+/// A mechanism drawn on a canvas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mechanism2d {
+    pub width: f64,
+    pub height: f64,
+    /// The color of the canvas, as `#rrggbb`.
+    pub background_color: String,
+    pub roots: Vec<MechanismRoot>,
+}
+
+impl Mechanism2d {
+    /// Creates a mechanism without any roots, on WPILib's dark blue background.
+    pub fn new(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            background_color: "#000020".to_string(),
+            roots: Vec::new(),
+        }
+    }
+
+    /// Returns every topic of the mechanism under `table` and its value.
+    pub fn values(&self, table: &str) -> Vec<(String, Value)> {
+        let table = table.trim_end_matches('/');
+        let mut values = vec![
+            (
+                format!("{table}/.type"),
+                Value::String(MECHANISM_TYPE.to_string()),
+            ),
+            (
+                format!("{table}/dims"),
+                Value::F64Array(vec![self.width, self.height]),
+            ),
+            (
+                format!("{table}/backgroundColor"),
+                Value::String(self.background_color.clone()),
+            ),
+        ];
+        for root in &self.roots {
+            let path = format!("{table}/{}", root.name);
+            values.push((format!("{path}/x"), Value::F64(root.x)));
+            values.push((format!("{path}/y"), Value::F64(root.y)));
+            for ligament in &root.ligaments {
+                ligament.push_values(&path, &mut values);
+            }
+        }
+        values
+    }
+}
+
+/// The point of the canvas a tree of ligaments starts from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MechanismRoot {
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+    pub ligaments: Vec<Ligament>,
+}
+
+impl MechanismRoot {
+    pub fn new(name: impl Into<String>, x: f64, y: f64) -> Self {
+        Self {
+            name: name.into(),
+            x,
+            y,
+            ligaments: Vec::new(),
+        }
+    }
+
+    pub fn with_ligament(mut self, ligament: Ligament) -> Self {
+        self.ligaments.push(ligament);
+        self
+    }
+}
+
+/// A line segment of a mechanism, which starts at the end of its parent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ligament {
+    pub name: String,
+    pub length: f64,
+    /// Degrees counterclockwise from the direction of the parent ligament, or from the x axis for ligaments of roots.
+    pub angle: f64,
+    /// The width of the line, in pixels.
+    pub weight: f64,
+    /// The color of the line, as `#rrggbb`.
+    pub color: String,
+    pub ligaments: Vec<Ligament>,
+}
+
+impl Ligament {
+    /// Creates a ligament in WPILib's default orange.
+    pub fn new(name: impl Into<String>, length: f64, angle: f64) -> Self {
+        Self {
+            name: name.into(),
+            length,
+            angle,
+            weight: 10.0,
+            color: "#eb8934".to_string(),
+            ligaments: Vec::new(),
+        }
+    }
+
+    pub fn with_ligament(mut self, ligament: Ligament) -> Self {
+        self.ligaments.push(ligament);
+        self
+    }
+
+    fn push_values(&self, parent: &str, values: &mut Vec<(String, Value)>) {
+        let path = format!("{parent}/{}", self.name);
+        values.push((
+            format!("{path}/.type"),
+            Value::String(LIGAMENT_TYPE.to_string()),
+        ));
+        values.push((format!("{path}/length"), Value::F64(self.length)));
+        values.push((format!("{path}/angle"), Value::F64(self.angle)));
+        values.push((format!("{path}/weight"), Value::F64(self.weight)));
+        values.push((format!("{path}/color"), Value::String(self.color.clone())));
+        for ligament in &self.ligaments {
+            ligament.push_values(&path, values);
+        }
+    }
+}
+
+/// Publishes a mechanism to a table.
+///
+/// The topics stay published until the publisher is dropped.
+/// Roots and ligaments that are left out of a later publish are unpublished.
+#[derive(Debug)]
+pub struct MechanismPublisher<'a, I: Instance + ?Sized> {
+    instance: &'a I,
+    table: String,
+    entries: HashMap<String, Entry<'a, I>>,
+}
+
+impl<'a, I: Instance + ?Sized> MechanismPublisher<'a, I> {
+    /// Publishes mechanisms to `table`, e.g. `/SmartDashboard/Arm`.
+    pub fn new(instance: &'a I, table: impl AsRef<str>) -> Self {
+        Self {
+            instance,
+            table: table.as_ref().trim_end_matches('/').to_string(),
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Publishes every part of `mechanism`.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if one of the mechanism's topics exists with another type,
+    ///   e.g. because a root and a ligament share a name. The other topics are still published.
+    pub fn publish(&mut self, mechanism: &Mechanism2d) -> Result<(), NetworkTablesError> {
+        let values = mechanism.values(&self.table);
+        self.entries
+            .retain(|topic, _| values.iter().any(|(name, _)| name == topic));
+
+        let mut result = Ok(());
+        for (topic, value) in values {
+            let entry = self
+                .entries
+                .entry(topic)
+                .or_insert_with_key(|topic| self.instance.entry(topic));
+            if let Err(err) = entry.set_value(value) {
+                result = Err(err);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Value;
+
+    use super::{Ligament, Mechanism2d, MechanismRoot};
+
+    #[test]
+    fn mechanisms_flatten_into_topics() {
+        let mut mechanism = Mechanism2d::new(3.0, 2.0);
+        mechanism
+            .roots
+            .push(
+                MechanismRoot::new("base", 1.5, 0.0).with_ligament(
+                    Ligament::new("elevator", 1.0, 90.0)
+                        .with_ligament(Ligament::new("arm", 0.5, -45.0)),
+                ),
+            );
+
+        let values = mechanism.values("/SmartDashboard/Arm/");
+        let value = |topic: &str| {
+            values
+                .iter()
+                .find(|(name, _)| name == topic)
+                .map(|(_, value)| value.clone())
+        };
+        assert_eq!(
+            value("/SmartDashboard/Arm/.type"),
+            Some(Value::String("Mechanism2d".to_string()))
+        );
+        assert_eq!(
+            value("/SmartDashboard/Arm/dims"),
+            Some(Value::F64Array(vec![3.0, 2.0]))
+        );
+        assert_eq!(value("/SmartDashboard/Arm/base/x"), Some(Value::F64(1.5)));
+        assert_eq!(
+            value("/SmartDashboard/Arm/base/elevator/.type"),
+            Some(Value::String("line".to_string()))
+        );
+        assert_eq!(
+            value("/SmartDashboard/Arm/base/elevator/arm/angle"),
+            Some(Value::F64(-45.0))
+        );
+        // 3 for the mechanism, 2 for the root and 5 for each ligament.
+        assert_eq!(values.len(), 15);
+    }
+}
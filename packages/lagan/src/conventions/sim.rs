@@ -0,0 +1,132 @@
+//! Inputs of robot code running in desktop simulation, driven over NetworkTables, e.g. from lagan-gui.
+//!
+//! A [`SimInput`] keeps every input under one prefix, [`DEFAULT_PREFIX`] unless configured otherwise:
+//!
+//! - `<prefix>/Joystick<port>/axes`, `buttons` and `povs` hold a joystick as double, boolean and integer arrays.
+//!   POVs are angles in degrees, or `-1` while centered.
+//! - `<prefix>/<name>` holds a named boolean or number input.
+//!
+//! The program driving the simulation publishes the inputs, and the simulated robot code reads them with the same helper.
+//! Input topics are retained, so the simulation keeps the last inputs when the program driving it exits.
+
+use crate::{Instance, NetworkTablesError, Value};
+
+/// The prefix inputs are published under by default.
+pub const DEFAULT_PREFIX: &str = "/SimInput";
+/// The number of joystick ports the driver station has.
+pub const JOYSTICK_PORTS: u8 = 6;
+
+/// The state of a simulated joystick.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Joystick {
+    /// Axis values, usually from `-1.0` to `1.0`.
+    pub axes: Vec<f64>,
+    pub buttons: Vec<bool>,
+    /// POV angles in degrees, or `-1` while centered.
+    pub povs: Vec<i64>,
+}
+
+/// Publishes and reads simulation inputs under a prefix.
+#[derive(Debug)]
+pub struct SimInput<'a, I: Instance + ?Sized> {
+    instance: &'a I,
+    prefix: String,
+}
+
+impl<'a, I: Instance + ?Sized> SimInput<'a, I> {
+    /// Uses the inputs under [`DEFAULT_PREFIX`].
+    pub fn new(instance: &'a I) -> Self {
+        Self::with_prefix(instance, DEFAULT_PREFIX)
+    }
+
+    /// Uses the inputs under `prefix`, e.g. to simulate several robots on one server.
+    pub fn with_prefix(instance: &'a I, prefix: impl Into<String>) -> Self {
+        let prefix = prefix.into().trim_end_matches('/').to_string();
+        Self { instance, prefix }
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Returns the topic of the input with the given name.
+    pub fn topic_name(&self, name: &str) -> String {
+        format!("{}/{}", self.prefix, name.trim_start_matches('/'))
+    }
+
+    /// Returns the topics of the axes, buttons and POVs of the joystick on `port`.
+    pub fn joystick_topics(&self, port: u8) -> [String; 3] {
+        ["axes", "buttons", "povs"].map(|part| self.topic_name(&format!("Joystick{port}/{part}")))
+    }
+
+    /// Publishes the state of the joystick on `port`.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if one of the joystick's topics exists with another type.
+    pub fn set_joystick(&self, port: u8, joystick: &Joystick) -> Result<(), NetworkTablesError> {
+        let [axes, buttons, povs] = self.joystick_topics(port);
+        self.publish(axes, Value::F64Array(joystick.axes.clone()))?;
+        self.publish(buttons, Value::BoolArray(joystick.buttons.clone()))?;
+        self.publish(povs, Value::I64Array(joystick.povs.clone()))
+    }
+
+    /// Returns the state of the joystick on `port`.
+    ///
+    /// Returns `None` if nothing was published for the joystick. Parts that weren't published are empty.
+    pub fn joystick(&self, port: u8) -> Option<Joystick> {
+        let [axes, buttons, povs] = self.joystick_topics(port);
+        let axes = self.instance.entry(axes).value_f64_array();
+        let buttons = self.instance.entry(buttons).value_bool_array();
+        let povs = self.instance.entry(povs).value_i64_array();
+        if axes.is_none() && buttons.is_none() && povs.is_none() {
+            return None;
+        }
+
+        Some(Joystick {
+            axes: axes.unwrap_or_default(),
+            buttons: buttons.unwrap_or_default(),
+            povs: povs.unwrap_or_default(),
+        })
+    }
+
+    /// Publishes the boolean input with the given name.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the input exists with another type.
+    pub fn set_bool(&self, name: &str, value: bool) -> Result<(), NetworkTablesError> {
+        self.publish(self.topic_name(name), Value::Bool(value))
+    }
+
+    /// Returns the boolean input with the given name, or `None` if it isn't a published boolean.
+    pub fn bool(&self, name: &str) -> Option<bool> {
+        self.instance.entry(self.topic_name(name)).value_bool()
+    }
+
+    /// Publishes the number input with the given name.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the input exists with another type.
+    pub fn set_number(&self, name: &str, value: f64) -> Result<(), NetworkTablesError> {
+        self.publish(self.topic_name(name), Value::F64(value))
+    }
+
+    /// Returns the number input with the given name, or `None` if it isn't a published number.
+    pub fn number(&self, name: &str) -> Option<f64> {
+        self.instance.entry(self.topic_name(name)).value().as_f64()
+    }
+    /// Publishes `value` to `topic` and marks the topic retained.
+    ///
+    /// The entry stops publishing once it is dropped, which would otherwise remove the input from the server.
+    fn publish(&self, topic: String, value: Value) -> Result<(), NetworkTablesError> {
+        let entry = self.instance.entry(&topic);
+        entry.set_value(value)?;
+        // NetworkTables 3 servers keep values without publishers anyway.
+        if self.instance.version().has_properties() {
+            self.instance.topic(topic).set_retained(true)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+//! Plugging topics into tokio dataflow graphs.
+//!
+//! [`TopicSubscriber::into_watch`] turns a subscriber into a [`watch::Receiver`] holding the latest value of its topic,
+//! and [`TopicPublisher::publish_from`] publishes whatever arrives on an [`mpsc::Receiver`].
+
+use std::sync::Arc;
+
+use ntcore_sys::{NT_DisposeValue, NT_GetEntryValue, NT_Release};
+use tokio::sync::{mpsc, watch};
+
+use crate::{
+    listener::ListenerHandle,
+    nt_types::RawValue,
+    topic::{TopicPublisher, TopicSubscriber},
+    Instance, NetworkTablesError, Value,
+};
+
+impl<I: Instance + ?Sized> TopicSubscriber<'_, I> {
+    /// Turns the subscriber into a [`watch::Receiver`] that always holds the latest value of the topic,
+    /// starting with the current one.
+    ///
+    /// The subscription stays alive until every clone of the receiver is dropped, even past the topic itself.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub fn into_watch(self) -> watch::Receiver<Value> {
+        // The instance may be destroyed before the last receiver is dropped, and the handle must not be released then.
+        let guard = self.topic().guard;
+        let handle = self.into_raw();
+
+        let mut raw_value = unsafe { std::mem::zeroed() };
+        unsafe { NT_GetEntryValue(handle, &raw mut raw_value) };
+        let current = RawValue::from(raw_value).data;
+        unsafe { NT_DisposeValue(&raw mut raw_value) };
+
+        let (sender, receiver) = watch::channel(current);
+        let sender = Arc::new(sender);
+        let listener = ListenerHandle::on_value(handle, {
+            let sender = sender.clone();
+            move |value| {
+                sender.send_replace(value.data);
+            }
+        });
+
+        tokio::spawn(async move {
+            sender.closed().await;
+            // Removing the listener waits for its callback to finish, which must not block the runtime.
+            let _ = tokio::task::spawn_blocking(move || {
+                drop(listener);
+                guard.release(|| unsafe { NT_Release(handle) });
+            })
+            .await;
+        });
+
+        receiver
+    }
+}
+
+impl<I: Instance + ?Sized> TopicPublisher<'_, I> {
+    /// Publishes every value that arrives on `receiver` until all of its senders are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first value that can't be published, with the error of [`TopicPublisher::set_value`].
+    pub async fn publish_from(
+        &self,
+        mut receiver: mpsc::Receiver<Value>,
+    ) -> Result<(), NetworkTablesError> {
+        while let Some(value) = receiver.recv().await {
+            self.set_value(value)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+//! Recording NetworkTables traffic to WPILib data logs (`.wpilog` files).
+//!
+//! A [`DataLog`] is the file being written. Loggers such as the ones returned by
+//! [`Client::log_entries_to`](crate::client::Client::log_entries_to) write to it until they are dropped.
+
+use std::{marker::PhantomData, time::Duration};
+
+use ntcore_sys::{
+    wpiutil::{WPI_DataLog_CreateBackgroundWriter, WPI_DataLog_Flush, WPI_DataLog_Release},
+    NT_ConnectionDataLogger, NT_DataLogger, NT_Inst, NT_StartConnectionDataLog,
+    NT_StartEntryDataLog, NT_StopConnectionDataLog, NT_StopEntryDataLog, WPI_DataLog, WPI_String,
+};
+
+use crate::shutdown::InstanceGuard;
+
+/// The prefix WPILib's `DataLogManager` gives NetworkTables entries in data logs.
+pub const ENTRY_LOG_PREFIX: &str = "NT:";
+
+/// A data log that is written to disk in the background.
+#[derive(Debug)]
+pub struct DataLog {
+    log: *mut WPI_DataLog,
+}
+
+// The data log is internally synchronized.
+unsafe impl Send for DataLog {}
+unsafe impl Sync for DataLog {}
+
+impl DataLog {
+    /// Creates a data log in `dir`.
+    ///
+    /// If `filename` is `None`, a random name is used.
+    /// The log is flushed to disk every `period`, which trades the data lost on a crash against disk writes.
+    pub fn new(dir: impl AsRef<str>, filename: Option<&str>, period: Duration) -> Self {
+        let dir = WPI_String::from(dir.as_ref());
+        let filename = WPI_String::from(filename.unwrap_or(""));
+        let extra_header = WPI_String::from("");
+
+        let log = unsafe {
+            WPI_DataLog_CreateBackgroundWriter(
+                &raw const dir,
+                &raw const filename,
+                period.as_secs_f64(),
+                &raw const extra_header,
+            )
+        };
+        Self { log }
+    }
+
+    /// Writes everything logged so far to disk right away.
+    pub fn flush(&self) {
+        unsafe { WPI_DataLog_Flush(self.log) }
+    }
+
+    /// # Safety
+    ///
+    /// The data log must not be released through the returned pointer.
+    pub unsafe fn handle(&self) -> *mut WPI_DataLog {
+        self.log
+    }
+}
+
+impl Drop for DataLog {
+    fn drop(&mut self) {
+        unsafe { WPI_DataLog_Release(self.log) }
+    }
+}
+
+/// Logs the values of entries to a [`DataLog`] until dropped.
+///
+/// The logger may outlive its instance, which stops logging itself when destroyed.
+#[derive(Debug)]
+#[must_use = "logging stops when the logger is dropped"]
+pub struct EntryDataLogger<'l> {
+    logger: NT_DataLogger,
+    instance: InstanceGuard,
+    _log: PhantomData<&'l DataLog>,
+}
+
+impl<'l> EntryDataLogger<'l> {
+    /// Logs every entry of `instance` whose name starts with `prefix`,
+    /// named after the entry with `prefix` replaced by `log_prefix`.
+    pub(crate) fn new(
+        instance: NT_Inst,
+        datalog: &'l DataLog,
+        prefix: &str,
+        log_prefix: &str,
+    ) -> Self {
+        let instance_guard = InstanceGuard::new(instance);
+        let prefix = WPI_String::from(prefix);
+        let log_prefix = WPI_String::from(log_prefix);
+
+        let logger = unsafe {
+            NT_StartEntryDataLog(
+                instance,
+                datalog.log,
+                &raw const prefix,
+                &raw const log_prefix,
+            )
+        };
+        Self {
+            logger,
+            instance: instance_guard,
+            _log: PhantomData,
+        }
+    }
+}
+
+impl Drop for EntryDataLogger<'_> {
+    fn drop(&mut self) {
+        self.instance
+            .release(|| unsafe { NT_StopEntryDataLog(self.logger) });
+    }
+}
+
+/// Logs connections and disconnections to a [`DataLog`] until dropped.
+///
+/// The logger may outlive its instance, which stops logging itself when destroyed.
+#[derive(Debug)]
+#[must_use = "logging stops when the logger is dropped"]
+pub struct ConnectionDataLogger<'l> {
+    logger: NT_ConnectionDataLogger,
+    instance: InstanceGuard,
+    _log: PhantomData<&'l DataLog>,
+}
+
+impl<'l> ConnectionDataLogger<'l> {
+    /// Logs the connections of `instance` to the data log entry `name`.
+    pub(crate) fn new(instance: NT_Inst, datalog: &'l DataLog, name: &str) -> Self {
+        let instance_guard = InstanceGuard::new(instance);
+        let name = WPI_String::from(name);
+
+        let logger = unsafe { NT_StartConnectionDataLog(instance, datalog.log, &raw const name) };
+        Self {
+            logger,
+            instance: instance_guard,
+            _log: PhantomData,
+        }
+    }
+}
+
+impl Drop for ConnectionDataLogger<'_> {
+    fn drop(&mut self) {
+        self.instance
+            .release(|| unsafe { NT_StopConnectionDataLog(self.logger) });
+    }
+}
@@ -0,0 +1,34 @@
+use ntcore_sys::{NT_GetDefaultInstance, NT_GetNetworkMode, NT_Inst, NT_NetworkMode};
+
+use crate::Instance;
+
+/// The default NetworkTables instance.
+///
+/// Unlike [`Client`](crate::client::Client) and [`Server`](crate::server::Server),
+/// this type does not start or stop anything; it simply accesses whatever networking
+/// has already been configured on the default instance, for example by WPILib robot code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DefaultInstance;
+
+impl DefaultInstance {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Instance for DefaultInstance {
+    /// # Safety
+    ///
+    /// Caller must ensure that `handle` is the default instance handle.
+    unsafe fn from_handle(handle: NT_Inst) -> Self {
+        debug_assert_eq!(handle, unsafe { NT_GetDefaultInstance() });
+        Self
+    }
+    unsafe fn handle(&self) -> NT_Inst {
+        unsafe { NT_GetDefaultInstance() }
+    }
+    fn is_server(&self) -> bool {
+        let mode = unsafe { NT_GetNetworkMode(self.handle()) };
+        mode & NT_NetworkMode::NT_NET_MODE_SERVER.bits() != 0
+    }
+}
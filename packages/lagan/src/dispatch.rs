@@ -0,0 +1,364 @@
+//! Choosing where listener callbacks run.
+//!
+//! ntcore calls every listener of an instance from a single internal thread, so a callback that blocks
+//! holds up every other listener, and a slow enough one stalls the whole client.
+//! A [`Dispatcher`] moves callbacks off that thread, onto a pool of lagan threads or into a [`CallbackQueue`]
+//! that the program drains from its own event loop, and decides what happens when callbacks arrive faster than they run.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Condvar, Mutex, MutexGuard, PoisonError,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{lock, unwind};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// What happens to a callback that arrives while the queue of a [`Dispatcher`] is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Overflow {
+    /// Waits on ntcore's listener thread until there is room, holding up other listeners of the instance.
+    #[default]
+    Block,
+    /// Drops the callback that just arrived.
+    DropNewest,
+    /// Drops the callback that has waited the longest to make room, so the latest values are always delivered.
+    DropOldest,
+}
+
+#[derive(Default)]
+struct Jobs {
+    queued: VecDeque<Job>,
+    /// Set once every [`Dispatcher`] feeding the queue is dropped.
+    closed: bool,
+}
+
+struct Queue {
+    jobs: Mutex<Jobs>,
+    available: Condvar,
+    space: Condvar,
+    capacity: usize,
+    overflow: Overflow,
+    dropped: AtomicU64,
+}
+
+impl Queue {
+    fn new(capacity: usize, overflow: Overflow) -> Self {
+        Self {
+            jobs: Mutex::default(),
+            available: Condvar::new(),
+            space: Condvar::new(),
+            capacity: capacity.max(1),
+            overflow,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    fn push(&self, job: Job) {
+        let mut jobs = lock(&self.jobs);
+        while jobs.queued.len() >= self.capacity && !jobs.closed {
+            match self.overflow {
+                Overflow::Block => jobs = wait(&self.space, jobs),
+                Overflow::DropNewest => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                Overflow::DropOldest => {
+                    jobs.queued.pop_front();
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        if jobs.closed {
+            return;
+        }
+        jobs.queued.push_back(job);
+        self.available.notify_one();
+    }
+
+    /// Takes the next job, waiting until `deadline` if there is none yet.
+    ///
+    /// Returns `None` if the deadline passed, or the queue is closed and empty.
+    fn pop(&self, deadline: Option<Instant>) -> Option<Job> {
+        let mut jobs = lock(&self.jobs);
+        loop {
+            if let Some(job) = jobs.queued.pop_front() {
+                self.space.notify_one();
+                return Some(job);
+            }
+            if jobs.closed {
+                return None;
+            }
+            jobs = match deadline {
+                Some(deadline) => {
+                    let timeout = deadline.checked_duration_since(Instant::now())?;
+                    self.available
+                        .wait_timeout(jobs, timeout)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+                None => wait(&self.available, jobs),
+            };
+        }
+    }
+
+    fn try_pop(&self) -> Option<Job> {
+        let job = lock(&self.jobs).queued.pop_front()?;
+        self.space.notify_one();
+        Some(job)
+    }
+
+    fn close(&self) {
+        lock(&self.jobs).closed = true;
+        self.available.notify_all();
+        self.space.notify_all();
+    }
+}
+
+fn wait<'g, T>(condvar: &Condvar, guard: MutexGuard<'g, T>) -> MutexGuard<'g, T> {
+    condvar.wait(guard).unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Closes the queue once the last [`Dispatcher`] feeding it is dropped, which lets pool threads exit.
+struct Sender {
+    queue: Arc<Queue>,
+}
+
+impl Drop for Sender {
+    fn drop(&mut self) {
+        self.queue.close();
+    }
+}
+
+/// Where callbacks run, shared by every listener it wraps.
+///
+/// Cloning a dispatcher is cheap, and clones share the same threads or queue.
+/// Pool threads exit once every clone is dropped, after running the callbacks still queued.
+#[derive(Clone, Default)]
+pub struct Dispatcher {
+    sender: Option<Arc<Sender>>,
+}
+
+impl Dispatcher {
+    /// Runs callbacks right away on ntcore's listener thread, which is what listeners do without a dispatcher.
+    pub fn listener_thread() -> Self {
+        Self::default()
+    }
+
+    /// Runs callbacks on `threads` lagan threads, queueing up to `capacity` callbacks that haven't started yet.
+    ///
+    /// Callbacks may run concurrently and out of order when there is more than one thread.
+    /// A panicking callback is logged as an error and doesn't take its thread down.
+    /// If no thread could be spawned, callbacks run on ntcore's listener thread instead.
+    pub fn pool(threads: usize, capacity: usize, overflow: Overflow) -> Self {
+        let queue = Arc::new(Queue::new(capacity, overflow));
+        let spawned = (0..threads.max(1))
+            .filter(|index| {
+                let queue = queue.clone();
+                thread::Builder::new()
+                    .name(format!("lagan-callback-{index}"))
+                    .spawn(move || {
+                        while let Some(job) = queue.pop(None) {
+                            unwind::catch("NetworkTables listener callback", job);
+                        }
+                    })
+                    .is_ok()
+            })
+            .count();
+
+        if spawned == 0 {
+            return Self::listener_thread();
+        }
+        Self {
+            sender: Some(Arc::new(Sender { queue })),
+        }
+    }
+
+    /// Queues callbacks, up to `capacity` of them, until the returned [`CallbackQueue`] runs them,
+    /// e.g. from the event loop of a GUI or the periodic loop of a robot program.
+    pub fn queue(capacity: usize, overflow: Overflow) -> (Self, CallbackQueue) {
+        let queue = Arc::new(Queue::new(capacity, overflow));
+        let dispatcher = Self {
+            sender: Some(Arc::new(Sender {
+                queue: queue.clone(),
+            })),
+        };
+        (dispatcher, CallbackQueue { queue })
+    }
+
+    /// Runs `job` wherever this dispatcher runs callbacks.
+    pub fn dispatch(&self, job: impl FnOnce() + Send + 'static) {
+        match &self.sender {
+            Some(sender) => sender.queue.push(Box::new(job)),
+            None => job(),
+        }
+    }
+
+    /// Wraps a listener callback so it runs wherever this dispatcher runs callbacks.
+    ///
+    /// Callbacks taking more than one argument can call [`Dispatcher::dispatch`] themselves.
+    pub fn wrap<A: Send + 'static>(
+        &self,
+        callback: impl Fn(A) + Send + Sync + 'static,
+    ) -> impl Fn(A) + Send + Sync + 'static {
+        let dispatcher = self.clone();
+        let callback = Arc::new(callback);
+        move |argument| {
+            let callback = callback.clone();
+            dispatcher.dispatch(move || callback(argument));
+        }
+    }
+
+    /// Returns the number of callbacks dropped because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.sender
+            .as_ref()
+            .map_or(0, |sender| sender.queue.dropped.load(Ordering::Relaxed))
+    }
+}
+
+impl fmt::Debug for Dispatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Dispatcher");
+        if let Some(sender) = &self.sender {
+            debug
+                .field("capacity", &sender.queue.capacity)
+                .field("overflow", &sender.queue.overflow)
+                .field("dropped", &self.dropped());
+        }
+        debug.finish_non_exhaustive()
+    }
+}
+
+/// Callbacks queued by a [`Dispatcher`] created with [`Dispatcher::queue`], waiting for the program to run them.
+pub struct CallbackQueue {
+    queue: Arc<Queue>,
+}
+
+impl CallbackQueue {
+    /// Runs every callback queued so far without waiting for more, and returns how many ran.
+    pub fn run_pending(&self) -> usize {
+        let mut ran = 0;
+        // Callbacks queued while these run are left for the next call, so a busy topic can't keep this running forever.
+        for _ in 0..self.len() {
+            let Some(job) = self.queue.try_pop() else {
+                break;
+            };
+            job();
+            ran += 1;
+        }
+        ran
+    }
+
+    /// Waits for the next callback and runs it.
+    ///
+    /// Returns `false` if `timeout` passed first, or every dispatcher feeding the queue was dropped and it is empty.
+    pub fn run_next(&self, timeout: Option<Duration>) -> bool {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        match self.queue.pop(deadline) {
+            Some(job) => {
+                job();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the number of callbacks waiting to run.
+    pub fn len(&self) -> usize {
+        lock(&self.queue.jobs).queued.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl fmt::Debug for CallbackQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackQueue")
+            .field("len", &self.len())
+            .field("capacity", &self.queue.capacity)
+            .field("overflow", &self.queue.overflow)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    fn received(overflow: Overflow) -> Vec<u32> {
+        let (dispatcher, queue) = Dispatcher::queue(2, overflow);
+        let (sender, receiver) = mpsc::channel();
+        let callback = dispatcher.wrap(move |value: u32| sender.send(value).unwrap());
+        for value in 0..4 {
+            callback(value);
+        }
+        assert_eq!(dispatcher.dropped(), 2);
+        assert_eq!(queue.run_pending(), 2);
+        receiver.try_iter().collect()
+    }
+
+    #[test]
+    fn full_queues_drop_callbacks() {
+        assert_eq!(received(Overflow::DropNewest), vec![0, 1]);
+        assert_eq!(received(Overflow::DropOldest), vec![2, 3]);
+    }
+
+    #[test]
+    fn listener_thread_runs_callbacks_right_away() {
+        let (sender, receiver) = mpsc::channel();
+        Dispatcher::listener_thread()
+            .dispatch(move || sender.send(thread::current().id()).unwrap());
+        assert_eq!(receiver.try_recv(), Ok(thread::current().id()));
+    }
+
+    #[test]
+    fn pools_run_callbacks_on_their_own_threads() {
+        let dispatcher = Dispatcher::pool(2, 16, Overflow::Block);
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let callback = dispatcher
+            .wrap(move |value: u32| lock(&sender).send((value, thread::current().id())).unwrap());
+        for value in 0..8 {
+            callback(value);
+        }
+
+        let mut values: Vec<u32> = (0..8)
+            .map(|_| {
+                let (value, thread) = receiver.recv_timeout(Duration::from_secs(1)).unwrap();
+                assert_ne!(thread, thread::current().id());
+                value
+            })
+            .collect();
+        values.sort_unstable();
+        assert_eq!(values, (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn panics_dont_stop_pools() {
+        let dispatcher = Dispatcher::pool(1, 16, Overflow::Block);
+        let (sender, receiver) = mpsc::channel();
+        dispatcher.dispatch(|| panic!("callback failed"));
+        dispatcher.dispatch(move || sender.send(()).unwrap());
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(1)), Ok(()));
+    }
+
+    #[test]
+    fn queues_close_with_their_dispatchers() {
+        let (dispatcher, queue) = Dispatcher::queue(4, Overflow::Block);
+        dispatcher.dispatch(|| {});
+        drop(dispatcher);
+        assert!(queue.run_next(None));
+        assert!(!queue.run_next(None));
+    }
+}
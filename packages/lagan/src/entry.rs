@@ -1,19 +1,20 @@
-use std::ffi::CString;
+use std::{sync::Arc, time::Duration};
 
 use ntcore_sys::{
-    NT_Entry, NT_EntryFlags, NT_GetEntryType, NT_GetEntryValue, NT_Now, NT_Release, NT_SetEntryFlags, NT_SetEntryValue, NT_Value, NT_ValueData, NT_ValueDataArray, WPI_String
+    NT_DisposeValue, NT_Entry, NT_EntryFlags, NT_GetEntryType, NT_GetEntryValue, NT_Now, NT_ReadQueueValue, NT_Release, NT_Unpublish, NT_Type, NT_GetEntryFlags, NT_SetDefaultEntryValue, NT_SetEntryFlags, NT_SetEntryValue, NtValueArray, probe::optional_functions
 };
 use snafu::ensure;
 
 use crate::{
-    nt_types::{RawValue, ValueFlags, ValueType}, Instance, NetworkTablesError, SetToUnassignedSnafu, UnassignedFlagsSnafu, Value
+    listener::ListenerHandle, nt_types::{ensure_known, with_nt_value, RawValue, ValueFlags, ValueRef, ValueType}, pool, shutdown::InstanceGuard, Instance, NetworkTablesError, ProtocolSnafu, SetToUnassignedSnafu, UnassignedFlagsSnafu, Value
 };
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct Entry<'a, I: Instance + ?Sized> {
     pub(crate) instance: &'a I,
+    pub(crate) guard: InstanceGuard,
     pub(crate) handle: NT_Entry,
-    pub(crate) name: String,
+    pub(crate) name: Arc<str>,
 }
 
 macro_rules! typed_value_getter {
@@ -38,6 +39,7 @@ macro_rules! typed_value_setter {
             /// # Errors
             ///
             /// - [`NetworkTablesEntryError::InvalidType`] if the type of the entry is not of the specified type.
+            /// - [`NetworkTablesError::ProtocolError`] if ntcore rejected the value.
             pub fn $ident(&self, value: $ty) -> Result<(), NetworkTablesError> {
                 self.set_value(Value::$variant(value))
             }
@@ -80,94 +82,47 @@ impl<I: Instance + ?Sized> Entry<'_, I> {
             });
         }
 
+        if value == Value::Unassigned {
+            return SetToUnassignedSnafu.fail();
+        }
+        ensure_known(&value)?;
+
         let timestamp = unsafe { NT_Now() };
-        let mut new_value = NT_Value {
-            r#type: value.value_type().into(),
-            last_change: timestamp,
-            server_time: current_value.server_time.as_micros() as _,
-            data: unsafe { std::mem::zeroed() },
+        let server_time = if self.instance.is_server() {
+            timestamp
+        } else {
+            current_value.server_time.as_micros() as _
         };
-        if self.instance.is_server() {
-            new_value.server_time = timestamp;
-        }
 
-        macro_rules! set_simple_array {
-            ($field:ident => $union:ident) => {{
-                new_value.data = NT_ValueData {
-                    $union: NT_ValueDataArray {
-                        arr: $field.as_ptr(),
-                        size: $field.len() as _,
-                    },
-                };
-                let status = unsafe { NT_SetEntryValue(self.handle(), &raw const new_value) };
-                debug_assert_eq!(status, 1);
-                return Ok(());
-            }};
-        }
+        let status = with_nt_value(&value, timestamp, server_time, |new_value| unsafe {
+            NT_SetEntryValue(self.handle(), new_value)
+        });
+        ensure!(status == 1, ProtocolSnafu { message: format!("ntcore rejected a value set on {}", self.name) });
 
-        //Safety: This raw data cannot be used after the values it points to are dropped.
-        //Safety: for this reason, the types that store pointers have to be used inside the match arms.
-        let raw_value_data = match value {
-            Value::Unassigned => return SetToUnassignedSnafu.fail(),
-            Value::Bool(value) => NT_ValueData {
-                v_boolean: value as _,
-            },
-            Value::I64(value) => NT_ValueData { v_int: value },
-            Value::F32(value) => NT_ValueData { v_float: value },
-            Value::F64(value) => NT_ValueData { v_double: value },
-            Value::String(string) => {
-                let string = CString::new(string).unwrap();
-                let wpi_string = WPI_String::from(string.as_c_str());
-                new_value.data = NT_ValueData {
-                    v_string: wpi_string,
-                };
-                let status = unsafe { NT_SetEntryValue(self.handle(), &raw const new_value) };
-                debug_assert_eq!(status, 1);
-                return Ok(());
-            }
-            Value::Raw(data) => set_simple_array!(data => v_raw),
-            Value::F64Array(array) => set_simple_array!(array => arr_double),
-            Value::F32Array(array) => set_simple_array!(array => arr_float),
-            Value::I64Array(array) => set_simple_array!(array => arr_int),
-            Value::BoolArray(array) => {
-                let bools = array.into_iter().map(|b| b.into()).collect::<Vec<_>>();
-                new_value.data = NT_ValueData {
-                    arr_boolean: NT_ValueDataArray {
-                        arr: bools.as_ptr(),
-                        size: bools.len() as _,
-                    },
-                };
-                let status = unsafe { NT_SetEntryValue(self.handle(), &raw const new_value) };
-                debug_assert_eq!(status, 1);
-                return Ok(());
-            }
-            Value::StringArray(array) => {
-                let c_strings = array
-                    .into_iter()
-                    .map(|s| CString::new(s).unwrap())
-                    .collect::<Vec<_>>();
-                let wpi_strings = c_strings
-                    .iter()
-                    .map(|s| WPI_String::from(s.as_c_str()))
-                    .collect::<Vec<_>>();
-                new_value.data = NT_ValueData {
-                    arr_string: NT_ValueDataArray {
-                        arr: wpi_strings.as_ptr(),
-                        size: wpi_strings.len() as _,
-                    },
-                };
-                let status = unsafe { NT_SetEntryValue(self.handle(), &raw const new_value) };
-                debug_assert_eq!(status, 1);
-                return Ok(());
-            }
-        };
+        Ok(())
+    }
 
-        new_value.data = raw_value_data;
+    /// Returns the value of this entry, initializing it to `default` and marking it persistent if it is unassigned.
+    ///
+    /// This is meant for configuration that should survive reboots: the first boot publishes the default,
+    /// every later boot uses the persisted (and possibly remotely changed) value.
+    /// Initialization is atomic, so it is safe to call from several places or retry.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::SetToUnassigned`] if `default` is [`Value::Unassigned`].
+    pub fn get_or_init(&self, default: Value) -> Result<Value, NetworkTablesError> {
+        ensure!(default != Value::Unassigned, SetToUnassignedSnafu);
+        ensure_known(&default)?;
 
-        let status = unsafe { NT_SetEntryValue(self.handle(), &raw const new_value) };
-        debug_assert_eq!(status, 1);
+        with_nt_value(&default, 0, 0, |default| unsafe {
+            NT_SetDefaultEntryValue(self.handle(), default)
+        });
+        // The entry is assigned now, so setting flags can't fail.
+        let flags = unsafe { NT_GetEntryFlags(self.handle()) } | NT_EntryFlags::NT_PERSISTENT;
+        unsafe { NT_SetEntryFlags(self.handle(), flags) };
 
-        Ok(())
+        Ok(self.value())
     }
 
     typed_value_setter! {
@@ -200,6 +155,13 @@ impl<I: Instance + ?Sized> Entry<'_, I> {
         Ok(())
     }
 
+    /// Stops publishing values through this entry.
+    ///
+    /// The topic disappears from the network once nothing else publishes it. Setting a value publishes it again.
+    pub fn unpublish(&self) {
+        unsafe { NT_Unpublish(self.handle()) }
+    }
+
     pub fn is_assigned(&self) -> bool {
         !matches!(self.value_type(), ValueType::Unassigned)
     }
@@ -211,12 +173,148 @@ impl<I: Instance + ?Sized> Entry<'_, I> {
         &self.name
     }
 
+    /// Returns the name of this entry, shared with every other handle to it.
+    ///
+    /// Cloning the returned name doesn't allocate, which makes it cheap to use as e.g. a map key.
+    pub fn name_arc(&self) -> Arc<str> {
+        self.name.clone()
+    }
+
+    /// Calls `callback` with every new value of this entry, local or remote, until the returned handle is dropped.
+    ///
+    /// The callback runs on ntcore's listener thread, so it should return quickly.
+    pub fn on_change(&self, callback: impl Fn(RawValue) + Send + Sync + 'static) -> ListenerHandle {
+        ListenerHandle::on_value(self.handle, callback)
+    }
+
+    /// Like [`Self::on_change`], but calls `callback` at most once per `interval` with the latest value
+    /// and the number of values skipped before it, e.g. to repaint a GUI for a 250 Hz topic.
+    ///
+    /// The first value after a quiet period is delivered right away, later ones once `interval` has passed
+    /// since the last delivery, from a background thread.
+    pub fn on_change_coalesced(&self, interval: Duration, callback: impl Fn(RawValue, u64) + Send + Sync + 'static) -> ListenerHandle {
+        ListenerHandle::on_value_coalesced(self.handle, interval, callback)
+    }
+
     pub fn raw_value(&self) -> RawValue {
         let mut raw_value = unsafe { std::mem::zeroed() };
         unsafe {
             NT_GetEntryValue(self.handle(), &raw mut raw_value);
         }
-        raw_value.into()
+        // The value is copied, so ntcore's strings and arrays can be freed right away.
+        let value = RawValue::from(raw_value);
+        unsafe { NT_DisposeValue(&raw mut raw_value) };
+        value
+    }
+
+    /// Calls `f` with the value of this entry borrowed from ntcore, instead of copying it into a [`Value`].
+    ///
+    /// Strings and arrays are only copied if `f` copies them, which matters for e.g. a dashboard
+    /// inspecting a large string array every frame.
+    pub fn with_value<R>(&self, f: impl FnOnce(ValueRef<'_>) -> R) -> R {
+        let mut raw_value = unsafe { std::mem::zeroed() };
+        unsafe {
+            NT_GetEntryValue(self.handle(), &raw mut raw_value);
+        }
+        // Safety: ntcore wrote the value, and it is disposed of only after `f` is done with it.
+        let result = f(unsafe { ValueRef::from_raw(&raw_value) });
+        unsafe { NT_DisposeValue(&raw mut raw_value) };
+        result
+    }
+
+    /// Returns the value of this entry in its raw form if it is of the given type.
+    ///
+    /// Unlike the typed getters, values of other types are never decoded.
+    /// Returns `None` if the entry is unassigned or of a different type.
+    /// Passing [`ValueType::Unassigned`] accepts values of any type.
+    pub fn raw_value_of_type(&self, value_type: ValueType) -> Option<RawValue> {
+        let Some(get_value_of_type) = optional_functions().NT_GetEntryValueType else {
+            // Older ntcore can only read values of every type, so the others are decoded and dropped.
+            let value = self.raw_value();
+            let matches = value_type == ValueType::Unassigned || value.data.value_type() == value_type;
+            return (matches && value.data != Value::Unassigned).then_some(value);
+        };
+        let mut raw_value = unsafe { std::mem::zeroed() };
+        unsafe {
+            get_value_of_type(self.handle(), value_type.into(), &raw mut raw_value);
+        }
+        let value = RawValue::from(raw_value);
+        unsafe { NT_DisposeValue(&raw mut raw_value) };
+
+        match value.data {
+            Value::Unassigned => None,
+            _ => Some(value),
+        }
+    }
+
+    /// Returns the value of this entry if it is of the given type.
+    ///
+    /// See [`Self::raw_value_of_type`].
+    pub fn value_of_type(&self, value_type: ValueType) -> Option<Value> {
+        self.raw_value_of_type(value_type).map(|value| value.data)
+    }
+
+    /// Returns all of the new entry values of the given type since the last read in their raw form.
+    ///
+    /// Passing [`ValueType::Unassigned`] accepts values of any type.
+    /// If there have been no new updates of the given type, None is returned.
+    ///
+    /// The returned `Vec` is taken from a pool of buffers on this thread. Give it back with [`Self::recycle`]
+    /// once done with it, so later reads reuse its capacity.
+    pub fn try_read_update_queue_raw_of_type(&self, value_type: ValueType) -> Option<Vec<RawValue>> {
+        let mut values = pool::RAW_VALUES.take();
+        if self.try_read_update_queue_of_type_into(value_type, &mut values) > 0 {
+            Some(values)
+        } else {
+            pool::RAW_VALUES.give(values);
+            None
+        }
+    }
+
+    /// Gives a `Vec` returned by [`Self::try_read_update_queue_raw_of_type`] back to the pool,
+    /// so later reads on this thread reuse its capacity. The values still in it are dropped.
+    pub fn recycle(&self, values: Vec<RawValue>) {
+        pool::RAW_VALUES.give(values);
+    }
+
+    /// Appends all of the new entry values of the given type since the last read to `values`.
+    ///
+    /// Reusing the same `Vec` for every read avoids allocating a new one each time.
+    /// Returns the number of values appended.
+    pub fn try_read_update_queue_of_type_into(&self, value_type: ValueType, values: &mut Vec<RawValue>) -> usize {
+        let before = values.len();
+        self.read_update_queue_of_type(value_type, |value| values.push(value));
+        values.len() - before
+    }
+
+    pub fn try_read_update_queue_of_type(&self, value_type: ValueType) -> Option<Vec<Value>> {
+        let mut values = Vec::new();
+        self.read_update_queue_of_type(value_type, |value| values.push(value.data));
+        (!values.is_empty()).then_some(values)
+    }
+
+    /// Calls `f` with every new value of the given type since the last read.
+    fn read_update_queue_of_type(&self, value_type: ValueType, mut f: impl FnMut(RawValue)) {
+        let mut count = 0;
+        let Some(read_queue_of_type) = optional_functions().NT_ReadQueueValueType else {
+            // Older ntcore can only read values of every type, so the others are decoded and dropped.
+            let raw_values = unsafe {
+                NtValueArray::from_raw(NT_ReadQueueValue(self.handle(), &raw mut count), count)
+            };
+            raw_values
+                .iter()
+                .map(|v| RawValue::from(*v))
+                .filter(|v| value_type == ValueType::Unassigned || v.data.value_type() == value_type)
+                .for_each(f);
+            return;
+        };
+        let types = NT_Type::from(value_type).bits();
+        let raw_values = unsafe {
+            NtValueArray::from_raw(read_queue_of_type(self.handle(), types, &raw mut count), count)
+        };
+        for v in raw_values.iter() {
+            f(RawValue::from(*v));
+        }
     }
 
     /// # Safety
@@ -229,8 +327,6 @@ impl<I: Instance + ?Sized> Entry<'_, I> {
 
 impl<I: Instance + ?Sized> Drop for Entry<'_, I> {
     fn drop(&mut self) {
-        unsafe {
-            NT_Release(self.handle());
-        }
+        self.guard.release(|| unsafe { NT_Release(self.handle) });
     }
 }
@@ -0,0 +1,40 @@
+//! Shared topic names.
+//!
+//! Each instance interns the names of its topics and entries, so every [`Topic`](crate::topic::Topic),
+//! [`Entry`](crate::entry::Entry) and statistic for the same name shares one allocation.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use ntcore_sys::NT_Inst;
+
+use crate::lock;
+
+type Interners = Mutex<HashMap<NT_Inst, HashSet<Arc<str>>>>;
+
+fn interners() -> &'static Interners {
+    static INTERNERS: OnceLock<Interners> = OnceLock::new();
+    INTERNERS.get_or_init(Default::default)
+}
+
+/// Returns the shared name `name` of `instance`, allocating it the first time it is used.
+pub(crate) fn intern(instance: NT_Inst, name: &str) -> Arc<str> {
+    let mut interners = lock(interners());
+    let names = interners.entry(instance).or_default();
+    if let Some(name) = names.get(name) {
+        return name.clone();
+    }
+
+    let name = Arc::<str>::from(name);
+    names.insert(name.clone());
+    name
+}
+
+/// Forgets every name interned for `instance`. Called when the instance is destroyed.
+///
+/// Names that are still in use stay valid; they are just no longer shared with new ones.
+pub(crate) fn forget_instance(instance: NT_Inst) {
+    lock(interners()).remove(&instance);
+}
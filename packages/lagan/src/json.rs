@@ -0,0 +1,234 @@
+//! The little JSON lagan reads and writes itself, mostly topic properties.
+
+/// Encodes `value` as a JSON string, including the quotes.
+pub(crate) fn encode_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            character if u32::from(character) < 0x20 => {
+                json.push_str(&format!("\\u{:04x}", u32::from(character)));
+            }
+            character => json.push(character),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Decodes a JSON string, including the quotes.
+///
+/// Returns `None` if `json` is anything but a single valid JSON string.
+pub(crate) fn decode_string(json: &str) -> Option<String> {
+    let mut characters = json.trim().strip_prefix('"')?.chars();
+    let mut value = String::new();
+    loop {
+        match characters.next()? {
+            '"' => break,
+            '\\' => match characters.next()? {
+                '"' => value.push('"'),
+                '\\' => value.push('\\'),
+                '/' => value.push('/'),
+                'b' => value.push('\u{8}'),
+                'f' => value.push('\u{c}'),
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                'u' => {
+                    let high = hex_escape(&mut characters)?;
+                    let code = if (0xd800..0xdc00).contains(&high) {
+                        // Characters outside the basic plane are escaped as a surrogate pair.
+                        if characters.next()? != '\\' || characters.next()? != 'u' {
+                            return None;
+                        }
+                        let low = hex_escape(&mut characters)?;
+                        if !(0xdc00..0xe000).contains(&low) {
+                            return None;
+                        }
+                        0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                    } else {
+                        high
+                    };
+                    value.push(char::from_u32(code)?);
+                }
+                _ => return None,
+            },
+            character if u32::from(character) < 0x20 => return None,
+            character => value.push(character),
+        }
+    }
+    characters.as_str().is_empty().then_some(value)
+}
+
+fn hex_escape(characters: &mut std::str::Chars) -> Option<u32> {
+    (0..4).try_fold(0, |code, _| {
+        Some((code << 4) | characters.next()?.to_digit(16)?)
+    })
+}
+
+/// Checks that `json` is a single valid JSON document, returning what's wrong with it otherwise.
+pub(crate) fn validate(json: &str) -> Result<(), String> {
+    let mut validator = Validator {
+        bytes: json.as_bytes(),
+        position: 0,
+    };
+    validator.value(0)?;
+    validator.whitespace();
+    if validator.position < validator.bytes.len() {
+        return Err(validator.error("trailing characters"));
+    }
+    Ok(())
+}
+
+/// How deeply arrays and objects may nest before a document is rejected, so the recursion can't overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+struct Validator<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Validator<'_> {
+    fn error(&self, message: &str) -> String {
+        format!("{message} at byte {}", self.position)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), String> {
+        self.whitespace();
+        if self.peek() != Some(expected) {
+            return Err(self.error(&format!("expected {:?}", char::from(expected))));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn value(&mut self, depth: usize) -> Result<(), String> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+        self.whitespace();
+        match self.peek() {
+            Some(b'[') => self.sequence(b']', |validator| validator.value(depth + 1)),
+            Some(b'{') => self.sequence(b'}', |validator| {
+                validator.whitespace();
+                validator.string()?;
+                validator.expect(b':')?;
+                validator.value(depth + 1)
+            }),
+            Some(b'"') => self.string(),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            _ => {
+                for word in ["true", "false", "null"] {
+                    if self.bytes[self.position..].starts_with(word.as_bytes()) {
+                        self.position += word.len();
+                        return Ok(());
+                    }
+                }
+                Err(self.error("expected a value"))
+            }
+        }
+    }
+
+    /// Reads an array or object, whose opening bracket is next, reading each element with `element`.
+    fn sequence(
+        &mut self,
+        close: u8,
+        mut element: impl FnMut(&mut Self) -> Result<(), String>,
+    ) -> Result<(), String> {
+        self.position += 1;
+        self.whitespace();
+        if self.peek() == Some(close) {
+            self.position += 1;
+            return Ok(());
+        }
+        loop {
+            element(self)?;
+            self.whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(byte) if byte == close => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                _ => return Err(self.error(&format!("expected ',' or {:?}", char::from(close)))),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<(), String> {
+        let start = self.position;
+        if self.peek() != Some(b'"') {
+            return Err(self.error("expected a string"));
+        }
+        self.position += 1;
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.position += 1;
+                    break;
+                }
+                Some(b'\\') => self.position += 2,
+                Some(_) => self.position += 1,
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+        // `json` is a str, so the quotes are on character boundaries.
+        decode_string(&String::from_utf8_lossy(&self.bytes[start..self.position]))
+            .map(|_| ())
+            .ok_or_else(|| self.error("invalid string"))
+    }
+
+    fn number(&mut self) -> Result<(), String> {
+        let start = self.position;
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.position += 1,
+            Some(b'1'..=b'9') => self.digits(),
+            _ => return Err(self.error("invalid number")),
+        }
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            self.required_digits(start)?;
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.position += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.position += 1;
+            }
+            self.required_digits(start)?;
+        }
+        Ok(())
+    }
+
+    fn digits(&mut self) {
+        while self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+            self.position += 1;
+        }
+    }
+
+    fn required_digits(&mut self, start: usize) -> Result<(), String> {
+        if !self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+            self.position = start;
+            return Err(self.error("invalid number"));
+        }
+        self.digits();
+        Ok(())
+    }
+}
@@ -1,26 +1,134 @@
-use std::{ffi::CString, fmt::Debug};
+// Robot code must not be taken down by its telemetry, so runtime paths report errors instead of panicking.
+#![cfg_attr(not(test), deny(clippy::unwrap_used, clippy::expect_used, clippy::unreachable))]
 
-use entry::Entry;
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use auto_subscribe::AutoSubscription;
+use capabilities::Capabilities;
+use channel::{EventMask, EventReceiver, TopicEventKind};
+use connection::{ConnectionEvents, ConnectionInfo};
+#[cfg(feature = "log")]
 use log::{log, Level};
-use nt_types::{Value, ValueType};
+use logging::{LogLevel, LogMessages};
+use nt_types::NetworkTablesInstant;
 use ntcore_sys::{
-    NT_Event, NT_GetEntry, NT_GetTopic, NT_Inst, NT_LogLevel, NT_LogMessage, WPI_String,
+    NT_Flush, NT_GetEntry, NT_GetEntryEx, NT_GetInstanceFromHandle, NT_GetNetworkMode, NT_GetTopic, NT_Handle, NT_Inst,
+    NT_NetworkMode, NT_Release, WPI_String,
 };
+#[cfg(feature = "log")]
+use ntcore_sys::{NT_AddLogger, NT_Event, NT_LogMessage};
 use snafu::Snafu;
-use topic::Topic;
+use schema::SchemaSubscription;
+use shutdown::InstanceGuard;
+use topic::{TopicInfo, TopicsIter};
+#[cfg(feature = "async")]
+use watch::WatchStream;
 
+pub mod access;
+pub mod annotation;
+pub mod auto_subscribe;
+pub mod batch;
+pub mod blocking;
+pub mod capabilities;
+pub mod channel;
+pub mod chunked;
 pub mod client;
+mod coalesce;
+#[cfg(any(feature = "deflate", feature = "zstd"))]
+pub mod compression;
+pub mod connection;
+pub mod conventions;
+#[cfg(all(feature = "async", feature = "tokio"))]
+pub mod dataflow;
+pub mod datalog;
+mod default_instance;
+pub mod dispatch;
 pub mod entry;
+mod intern;
+mod json;
+pub mod listener;
+pub mod logging;
+mod migrate;
 pub mod nt_types;
+mod persistence;
+mod pool;
+pub mod portforward;
+#[cfg(feature = "uom")]
+pub mod quantity;
+pub mod runtime;
+pub mod schema;
 pub mod server;
+mod shutdown;
+pub mod stats;
+pub mod table;
+pub mod time_aligned;
 pub mod topic;
+pub mod tunable;
+pub mod typed;
+mod unwind;
+pub mod validation;
+pub mod version;
+pub mod watch;
+pub mod wpilog;
+
+pub use client::Client;
+pub use default_instance::DefaultInstance;
+pub use entry::Entry;
+pub use listener::ListenerHandle;
+pub use nt_types::{PubSubOptions, RawValue, Value, ValueFlags, ValueRef, ValueType};
+pub use server::Server;
+pub use table::NtTable;
+pub use topic::{Topic, TopicPublisher, TopicSubscriber};
+pub use version::ntcore_version;
+
+/// Everything needed to publish and subscribe to topics.
+pub mod pubsub {
+    pub use crate::{
+        annotation::Annotations,
+        auto_subscribe::AutoSubscription,
+        entry::Entry,
+        nt_types::{PubSubOptions, PublisherId},
+        schema::{SchemaSubscription, StructType, TopicKind},
+        topic::{Topic, TopicInfo, TopicPublisher, TopicSubscriber, TopicsIter},
+        typed::{TypeChanged, TypedSubscriber, TypedUpdate},
+    };
+}
+
+/// Listeners and the events they report.
+pub mod event {
+    pub use crate::{
+        blocking::CancelToken,
+        channel::{Event, EventMask, EventReceiver, TopicEventKind},
+        connection::{ConnectionEvent, ConnectionEvents, ConnectionInfo, TimeSync},
+        listener::ListenerHandle,
+        logging::{LogContext, LogLevel, LogMessage, LogMessages},
+    };
+    #[cfg(feature = "async")]
+    pub use crate::watch::WatchStream;
+}
+
+/// The NetworkTables clock.
+pub mod time {
+    pub use crate::{
+        nt_types::{set_simulated_time, NetworkTablesInstant},
+        time_aligned::TimeAligned,
+    };
+}
 
 pub mod prelude {
     pub use crate::{
         client::Client,
-        nt_types::{Value, ValueFlags, ValueType},
+        entry::Entry,
+        listener::ListenerHandle,
+        nt_types::{PubSubOptions, RawValue, Value, ValueFlags, ValueType},
         server::Server,
-        Instance, NetworkTablesVersion,
+        table::NtTable,
+        topic::{Topic, TopicPublisher, TopicSubscriber},
+        DefaultInstance, Instance, NetworkTablesVersion,
     };
 }
 
@@ -31,29 +139,48 @@ pub enum NetworkTablesVersion {
     V3,
 }
 
+impl NetworkTablesVersion {
+    /// The port servers listen on for this version by default.
+    pub fn default_port(self) -> u16 {
+        match self {
+            Self::V4 => 5810,
+            Self::V3 => 1735,
+        }
+    }
+
+    /// Returns `true` if topics of this version have properties, i.e. type strings, JSON properties and
+    /// the cached and retained flags.
+    ///
+    /// NetworkTables 3 only knows the persistent flag.
+    pub fn has_properties(self) -> bool {
+        self == Self::V4
+    }
+
+    /// Returns `true` if this version has integer and float types.
+    ///
+    /// NetworkTables 3 stores every number as a double.
+    pub fn has_numeric_types(self) -> bool {
+        self == Self::V4
+    }
+}
+
 /// # Safety
 ///
 /// Caller must ensure that this function is only used as a listener callback for a logger.
+#[cfg(feature = "log")]
 pub unsafe extern "C" fn default_log_callback(
     _data: *mut std::ffi::c_void,
     message: *const NT_Event,
 ) {
     let message = unsafe { (*message).data.logMessage };
-    log_callback_inner(message);
+    // The logger is user code, and a panic must not unwind into ntcore.
+    // It can't report its own panic, which the panic hook has printed already.
+    let _ = std::panic::catch_unwind(|| log_callback_inner(message));
 }
 
+#[cfg(feature = "log")]
 fn log_callback_inner(message: NT_LogMessage) {
-    let level = if message.level >= NT_LogLevel::NT_LOG_ERROR.bits() {
-        Level::Error
-    } else if message.level >= NT_LogLevel::NT_LOG_WARNING.bits() {
-        Level::Warn
-    } else if message.level >= NT_LogLevel::NT_LOG_INFO.bits() {
-        Level::Info
-    } else if message.level >= NT_LogLevel::NT_LOG_DEBUG2.bits() {
-        Level::Debug
-    } else if message.level >= NT_LogLevel::NT_LOG_DEBUG3.bits() {
-        Level::Trace
-    } else {
+    let Some(level) = LogLevel::from_raw(message.level).map(Level::from) else {
         return;
     };
 
@@ -74,44 +201,244 @@ fn log_callback_inner(message: NT_LogMessage) {
     }
 }
 
+/// Forwards the log messages of `instance` to the `log` crate, if the `log` feature is enabled.
+pub(crate) fn add_default_logger(instance: NT_Inst) {
+    #[cfg(feature = "log")]
+    unsafe {
+        NT_AddLogger(instance, 0, u32::MAX, std::ptr::null_mut(), default_log_callback);
+    }
+    #[cfg(not(feature = "log"))]
+    let _ = instance;
+}
+
 pub trait Instance {
     fn entry(&self, name: impl AsRef<str>) -> Entry<'_, Self> {
-        let raw_name = CString::new(name.as_ref()).unwrap();
-        let raw_name = WPI_String::from(raw_name.as_c_str());
+        let raw_name = WPI_String::from(name.as_ref());
 
         let handle = unsafe { NT_GetEntry(self.handle(), &raw const raw_name) };
 
         Entry {
             instance: self,
+            guard: InstanceGuard::new(unsafe { self.handle() }),
             handle,
-            name: name.as_ref().to_owned(),
+            name: intern::intern(unsafe { self.handle() }, name.as_ref()),
+        }
+    }
+
+    /// Creates an entry that uses the given options for its subscriber and publisher,
+    /// for example to receive every value change instead of only the latest one.
+    fn entry_with_options(
+        &self,
+        name: impl AsRef<str>,
+        value_type: ValueType,
+        options: PubSubOptions,
+    ) -> Entry<'_, Self> {
+        let raw_name = WPI_String::from(name.as_ref());
+        let raw_type_str = WPI_String::from(value_type.type_string());
+        let raw_options = options.into();
+
+        let handle = unsafe {
+            let topic = NT_GetTopic(self.handle(), &raw const raw_name);
+            let entry = NT_GetEntryEx(
+                topic,
+                value_type.into(),
+                &raw const raw_type_str,
+                &raw const raw_options,
+            );
+            // The entry keeps the topic alive on its own.
+            NT_Release(topic);
+            entry
+        };
+
+        Entry {
+            instance: self,
+            guard: InstanceGuard::new(unsafe { self.handle() }),
+            handle,
+            name: intern::intern(unsafe { self.handle() }, name.as_ref()),
         }
     }
 
     fn topic(&self, name: impl AsRef<str>) -> Topic<'_, Self> {
-        let raw_name = CString::new(name.as_ref()).unwrap();
-        let raw_name = WPI_String::from(raw_name.as_c_str());
+        let raw_name = WPI_String::from(name.as_ref());
 
         let handle = unsafe { NT_GetTopic(self.handle(), &raw const raw_name) };
 
         Topic {
             instance: self,
+            guard: InstanceGuard::new(unsafe { self.handle() }),
             handle,
-            name: name.as_ref().to_owned(),
+            name: intern::intern(unsafe { self.handle() }, name.as_ref()),
         }
     }
 
+    /// Returns a lazy iterator over every topic whose name starts with `prefix`.
+    ///
+    /// Unlike fetching the information of every topic at once, this only holds one topic's information at a time,
+    /// which matters for instances with thousands of topics.
+    fn topics_iter(&self, prefix: impl AsRef<str>) -> TopicsIter<'_, Self> {
+        TopicsIter::new(self, prefix.as_ref())
+    }
+
+    /// Subscribes to every schema topic, so that [`TopicInfo::kind`](topic::TopicInfo::kind) can resolve the schema of struct topics.
+    ///
+    /// The values of schema topics published by other nodes only reach this instance while something subscribes to them.
+    fn subscribe_schemas(&self) -> SchemaSubscription<'_, Self> {
+        SchemaSubscription::new(self)
+    }
+
+    /// Copies the value and properties of every topic of the table `from` to the same topic of the table `to`,
+    /// e.g. to rename a dashboard's table without losing the settings persisted under the old name.
+    ///
+    /// `from` and `to` are table names, with or without a trailing slash, so migrating `/Old` leaves `/Older` alone.
+    /// Topics without a value are skipped. On NetworkTables 3 clients, only the persistent flag is copied.
+    /// If `delete_source` is set, the old topics stop being persistent and retained, so the server drops them
+    /// once nothing publishes them anymore.
+    ///
+    /// Returns the entries of the copies. Copies that are neither persistent nor retained are unpublished when they're dropped.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if a copy exists with another type. The topics before it are already migrated.
+    fn migrate(&self, from: impl AsRef<str>, to: impl AsRef<str>, delete_source: bool) -> Result<Vec<Entry<'_, Self>>, NetworkTablesError> {
+        migrate::migrate(self, from.as_ref(), to.as_ref(), delete_source)
+    }
+
+    /// Calls `callback` with the name and value of every new value of a topic whose name starts with one of `prefixes`,
+    /// until the returned handle is dropped.
+    ///
+    /// All prefixes share a single listener. The callback runs on ntcore's listener thread,
+    /// so it should hand values off (e.g. to a channel or state store) and return quickly.
+    fn watch<P: AsRef<str>>(&self, prefixes: impl IntoIterator<Item = P>, callback: impl Fn(Arc<str>, RawValue) + Send + Sync + 'static) -> ListenerHandle {
+        watch::watch(unsafe { self.handle() }, prefixes, callback)
+    }
+
+    /// Subscribes to every topic whose name starts with `prefix` when it is announced and unsubscribes when it is unannounced,
+    /// until the returned handle is dropped. Topics that already exist are subscribed to right away.
+    ///
+    /// `callback` is called with [`TopicEventKind::Published`] after subscribing to a topic
+    /// and [`TopicEventKind::Unpublished`] after unsubscribing. Like [`Instance::watch`], it runs on ntcore's listener thread.
+    /// This saves mirrors and recorders from keeping a map of subscribers in step with the announced topics.
+    fn auto_subscribe(&self, prefix: impl AsRef<str>, options: PubSubOptions, callback: impl Fn(TopicEventKind, TopicInfo) + Send + Sync + 'static) -> AutoSubscription {
+        AutoSubscription::new(unsafe { self.handle() }, prefix.as_ref(), options, callback)
+    }
+
+    /// Starts a background thread that sends the events in `mask` over a channel until the returned receiver is dropped.
+    ///
+    /// Topic and value events are limited to topics whose name starts with one of `prefixes`.
+    /// Unlike callbacks, this lets synchronous applications handle events in their existing receive loops.
+    fn event_channel<P: AsRef<str>>(&self, mask: EventMask, prefixes: impl IntoIterator<Item = P>) -> EventReceiver {
+        EventReceiver::new(unsafe { self.handle() }, mask, prefixes)
+    }
+
+    /// Like [`Instance::watch`], but calls `callback` at most once per topic per `interval`
+    /// with the latest value and the number of values skipped before it.
+    ///
+    /// Use this when reacting to every value is wasted work, e.g. repainting a GUI for 250 Hz odometry.
+    fn watch_coalesced<P: AsRef<str>>(&self, prefixes: impl IntoIterator<Item = P>, interval: Duration, callback: impl Fn(Arc<str>, RawValue, u64) + Send + Sync + 'static) -> ListenerHandle {
+        watch::watch_coalesced(unsafe { self.handle() }, prefixes, interval, callback)
+    }
+
+    /// Like [`Instance::watch`], but queues the values to be read asynchronously instead of calling a callback.
+    #[cfg(feature = "async")]
+    fn watch_stream<P: AsRef<str>>(&self, prefixes: impl IntoIterator<Item = P>) -> WatchStream {
+        WatchStream::new(unsafe { self.handle() }, prefixes)
+    }
+
+    /// Returns every remote node currently connected to this instance.
+    fn connections(&self) -> Vec<ConnectionInfo> {
+        connection::connections(self)
+    }
+
+    /// Creates a queue of connect and disconnect events for this instance.
+    fn connection_events(&self) -> ConnectionEvents<'_, Self> {
+        ConnectionEvents::new(self)
+    }
+
+    /// Sends pending value and property changes to the network now instead of with the next periodic update.
+    ///
+    /// Short-lived programs should call this before exiting, so their last changes aren't lost.
+    /// ntcore rate limits flushes, so calling this often doesn't flood the network.
+    fn flush(&self) {
+        unsafe { NT_Flush(self.handle()) }
+    }
+
+    /// Creates a queue of the messages this instance logs at `min_level` or above,
+    /// with the topic and remote node each message is about where it can be told.
+    fn log_messages(&self, min_level: LogLevel) -> LogMessages<'_, Self> {
+        LogMessages::new(self, min_level)
+    }
+
+    /// Returns the current time of the NetworkTables clock.
+    fn now(&self) -> NetworkTablesInstant {
+        NetworkTablesInstant::now()
+    }
+
+    /// Switches the NetworkTables clock between the system clock and simulated time.
+    ///
+    /// The clock is shared by every instance, so this affects the whole process.
+    /// See [`nt_types::set_simulated_time`].
+    fn set_simulated_time(&self, enabled: bool) {
+        nt_types::set_simulated_time(enabled)
+    }
+
+    /// The protocol version this instance speaks.
+    ///
+    /// Servers accept both versions and use NetworkTables 4 internally, so only running NT3 clients return [`NetworkTablesVersion::V3`].
+    fn version(&self) -> NetworkTablesVersion {
+        let mode = unsafe { NT_GetNetworkMode(self.handle()) };
+        if mode & NT_NetworkMode::NT_NET_MODE_CLIENT3.bits() != 0 {
+            NetworkTablesVersion::V3
+        } else {
+            NetworkTablesVersion::V4
+        }
+    }
+
+    /// The optional features of the linked ntcore, see [`capabilities`].
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::probe()
+    }
+
     fn is_server(&self) -> bool;
     fn is_client(&self) -> bool {
         !self.is_server()
     }
 
+    /// Adopts an existing NetworkTables instance, such as one created by other ntcore-based code.
+    ///
+    /// # Safety
+    ///
+    /// Caller must ensure that `handle` is a valid instance handle in the mode this type expects.
+    /// The returned value takes ownership of the instance and tears it down when dropped,
+    /// so the instance must not be stopped or destroyed by anything else.
+    unsafe fn from_handle(handle: NT_Inst) -> Self
+    where
+        Self: Sized;
+
     /// # Safety
     ///
     /// Caller must ensure that the returned handle is only used while the instance is valid.
     unsafe fn handle(&self) -> NT_Inst;
 }
 
+/// Returns the handle of the instance that owns the given entry, topic, subscriber, publisher or listener handle.
+///
+/// Returns `None` if the handle is invalid.
+/// This is useful for adopting instances created by other ntcore-based code with [`Instance::from_handle`].
+pub fn instance_of(handle: NT_Handle) -> Option<NT_Inst> {
+    match unsafe { NT_GetInstanceFromHandle(handle) } {
+        0 => None,
+        instance => Some(instance),
+    }
+}
+
+
+/// Locks `mutex`, recovering the data if a thread panicked while holding the lock.
+///
+/// Every lock in lagan guards data that stays consistent even if an update was interrupted,
+/// so a panic elsewhere must not cascade into every later call.
+pub(crate) fn lock<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
 
 /// Errors that can occur when interacting with NetworkTables.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Snafu)]
@@ -127,5 +454,65 @@ pub enum NetworkTablesError {
     UnassignedFlags,
 
     /// Attempted to set an entry or topic to a value of unassigned.
-    SetToUnassigned
+    SetToUnassigned,
+
+    /// Attempted to set topic properties to something other than valid JSON.
+    InvalidProperties,
+
+    /// A chunked payload was incomplete, mixed chunks of different payloads or failed its integrity check.
+    CorruptChunks,
+
+    /// Attempted to publish a chunked payload in chunks of zero bytes.
+    InvalidChunkSize,
+
+    /// A compressed value could not be decompressed.
+    #[snafu(display("Failed to decompress value: {message}"))]
+    Decompression { message: String },
+
+    /// A validated publisher rejected a value.
+    ValidationFailed,
+
+    /// Attempted to read quantities from a topic whose unit is a different one.
+    #[snafu(display("Expected values in {expected:?}, but the topic's unit is {found:?}."))]
+    UnitMismatch { expected: String, found: String },
+
+    /// A value could not be compressed.
+    #[snafu(display("Failed to compress value: {message}"))]
+    Compression { message: String },
+
+    /// Attempted to use a NetworkTables 4 feature on a NetworkTables 3 client, where it would silently do nothing.
+    #[snafu(display("{feature} requires NetworkTables 4, but this is a NetworkTables 3 client."))]
+    Nt4Only { feature: &'static str },
+
+    /// Attempted to use a feature the linked ntcore is too old to provide, see [`Capabilities`].
+    #[snafu(display("{feature} is not supported by the linked ntcore."))]
+    Unsupported { feature: &'static str },
+
+    /// Attempted to start a server on an instance another [`Server`] or [`Client`] already owns.
+    #[snafu(display("Another server or client already owns this NetworkTables instance."))]
+    InstanceInUse,
+
+    /// Attempted to forward a local port that another [`PortForward`](portforward::PortForward) already forwards.
+    #[snafu(display("Port {port} is already being forwarded."))]
+    PortForwarded { port: u16 },
+
+    /// The server's persistent file could not be loaded or saved.
+    #[snafu(display("Persistent file {filename:?} could not be used: {message}"))]
+    Persistence { filename: String, message: String },
+
+    /// Attempted to restart or save a server adopted with [`Instance::from_handle`], whose options are unknown.
+    #[snafu(display("The server was not started by lagan, so the options it was started with are unknown."))]
+    UnknownServerOptions,
+
+    /// Attempted something that only works while the server is stopped.
+    #[snafu(display("The server must be stopped first."))]
+    ServerRunning,
+
+    /// ntcore rejected a request or reported something lagan doesn't understand.
+    #[snafu(display("NetworkTables protocol error: {message}"))]
+    ProtocolError { message: String },
+
+    /// A data log could not be read, or isn't a valid WPILib data log.
+    #[snafu(display("Data log could not be read: {message}"))]
+    InvalidDataLog { message: String },
 }
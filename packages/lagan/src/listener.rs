@@ -0,0 +1,217 @@
+//! Callbacks that ntcore calls on its own thread whenever something happens to a topic or entry.
+
+use ntcore_sys::{
+    callback::Trampoline, NT_AddListener, NT_AddListenerMultiple, NT_Event, NT_EventFlags,
+    NT_Handle, NT_Inst, NT_Listener, NT_RemoveListener, NT_WaitForListenerQueue, WPI_String,
+};
+
+use std::{
+    cell::Cell,
+    mem::ManuallyDrop,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crate::{
+    coalesce::Coalescer, nt_types::RawValue, runtime::PeriodicTask, shutdown::InstanceGuard, unwind,
+};
+
+thread_local! {
+    /// How many listener callbacks are running on this thread, i.e. whether this is one of ntcore's listener threads.
+    static CALLBACK_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A listener that is removed when this handle is dropped.
+///
+/// Dropping the handle waits until ntcore has called every callback of the instance that was already queued,
+/// since the callback may still be running. Dropping it inside a listener callback can't wait for that,
+/// so the callback's closure is leaked instead of freed.
+/// Callbacks share ntcore's listener thread with every other listener of the instance,
+/// so callbacks that may block should be wrapped with a [`Dispatcher`](crate::dispatch::Dispatcher).
+/// Coalescing listeners hold back values that were not delivered yet, and those are dropped with the handle.
+/// The handle may outlive its instance, which removes the listener itself when destroyed.
+#[derive(Debug)]
+#[must_use = "the listener is removed when the handle is dropped"]
+pub struct ListenerHandle {
+    listener: NT_Listener,
+    instance: InstanceGuard,
+    // Dropped after the listener is removed in `Drop`, unless it may still be running.
+    trampoline: ManuallyDrop<Trampoline>,
+    _flusher: Option<PeriodicTask>,
+    guard: Arc<PanicGuard>,
+}
+
+/// Keeps panics of a callback from unwinding into ntcore, and records them for [`ListenerHandle::has_panicked`].
+#[derive(Debug, Default)]
+pub(crate) struct PanicGuard {
+    panicked: AtomicBool,
+    remove_on_panic: AtomicBool,
+}
+
+impl PanicGuard {
+    /// Calls `callback`, catching and recording its panics.
+    ///
+    /// Once it panicked, `callback` isn't called anymore if [`ListenerHandle::remove_on_panic`] was requested.
+    pub(crate) fn call(&self, callback: impl FnOnce()) {
+        if self.panicked.load(Ordering::Acquire) && self.remove_on_panic.load(Ordering::Relaxed) {
+            return;
+        }
+        if unwind::catch("NetworkTables listener callback", callback).is_none() {
+            self.panicked.store(true, Ordering::Release);
+        }
+    }
+
+    /// Wraps `callback` in a trampoline for ntcore that calls it through the returned guard.
+    fn wrap(callback: impl Fn(&NT_Event) + Send + Sync + 'static) -> (Arc<Self>, Trampoline) {
+        let guard = Arc::new(Self::default());
+        let trampoline = Trampoline::new({
+            let guard = guard.clone();
+            move |event| {
+                CALLBACK_DEPTH.with(|depth| depth.set(depth.get() + 1));
+                guard.call(|| callback(event));
+                CALLBACK_DEPTH.with(|depth| depth.set(depth.get() - 1));
+            }
+        });
+        (guard, trampoline)
+    }
+}
+
+impl ListenerHandle {
+    /// Adds a listener for the events in `mask` of `handle`, which may be an instance, topic, subscriber or entry.
+    pub(crate) fn new(
+        handle: NT_Handle,
+        mask: NT_EventFlags,
+        callback: impl Fn(&NT_Event) + Send + Sync + 'static,
+    ) -> Self {
+        let instance = InstanceGuard::of(handle);
+        let (guard, trampoline) = PanicGuard::wrap(callback);
+        let listener = unsafe {
+            NT_AddListener(
+                handle,
+                mask.bits(),
+                trampoline.data(),
+                trampoline.callback(),
+            )
+        };
+        Self {
+            listener,
+            instance,
+            trampoline: ManuallyDrop::new(trampoline),
+            _flusher: None,
+            guard,
+        }
+    }
+
+    /// Adds a listener for the events in `mask` of every topic of `instance` whose name starts with one of `prefixes`.
+    pub(crate) fn multiple(
+        instance: NT_Inst,
+        prefixes: &[WPI_String],
+        mask: NT_EventFlags,
+        callback: impl Fn(&NT_Event) + Send + Sync + 'static,
+    ) -> Self {
+        let instance_guard = InstanceGuard::new(instance);
+        let (guard, trampoline) = PanicGuard::wrap(callback);
+        let listener = unsafe {
+            NT_AddListenerMultiple(
+                instance,
+                prefixes.as_ptr(),
+                prefixes.len(),
+                mask.bits(),
+                trampoline.data(),
+                trampoline.callback(),
+            )
+        };
+        Self {
+            listener,
+            instance: instance_guard,
+            trampoline: ManuallyDrop::new(trampoline),
+            _flusher: None,
+            guard,
+        }
+    }
+
+    /// Adds a listener that calls `callback` with every new value of `handle`, local or remote.
+    pub(crate) fn on_value(
+        handle: NT_Handle,
+        callback: impl Fn(RawValue) + Send + Sync + 'static,
+    ) -> Self {
+        Self::new(handle, NT_EventFlags::NT_EVENT_VALUE_ALL, move |event| {
+            if event.flags & NT_EventFlags::NT_EVENT_VALUE_ALL.bits() == 0 {
+                return;
+            }
+            // Safety: value events always carry value data.
+            let value = unsafe { event.data.valueData.value };
+            callback(RawValue::from(value));
+        })
+    }
+
+    /// Like [`ListenerHandle::on_value`], but calls `callback` at most once per `interval`
+    /// with the latest value and the number of values skipped before it.
+    pub(crate) fn on_value_coalesced(
+        handle: NT_Handle,
+        interval: Duration,
+        callback: impl Fn(RawValue, u64) + Send + Sync + 'static,
+    ) -> Self {
+        let guard = Arc::new(PanicGuard::default());
+        let (coalescer, flusher) = Coalescer::new(interval, {
+            let guard = guard.clone();
+            move |(): &(), value, skipped| guard.call(|| callback(value, skipped))
+        });
+        Self::on_value(handle, move |value| coalescer.push((), value)).with_flusher(flusher, guard)
+    }
+
+    /// Keeps `flusher` running until the listener is removed.
+    ///
+    /// Coalesced values are delivered on the flusher's thread as well as ntcore's, so the callback is called through
+    /// `guard` on both, and that guard replaces the listener's own for [`Self::has_panicked`] and [`Self::remove_on_panic`].
+    pub(crate) fn with_flusher(mut self, flusher: PeriodicTask, guard: Arc<PanicGuard>) -> Self {
+        self._flusher = Some(flusher);
+        self.guard = guard;
+        self
+    }
+
+    /// Stops calling the callback once it panicked, so a callback left in a broken state isn't called again.
+    ///
+    /// The listener stays registered with ntcore until the handle is dropped, but ignores every later event.
+    pub fn remove_on_panic(self) -> Self {
+        self.guard.remove_on_panic.store(true, Ordering::Relaxed);
+        self
+    }
+
+    /// Returns `true` if the callback panicked at least once.
+    pub fn has_panicked(&self) -> bool {
+        self.guard.panicked.load(Ordering::Acquire)
+    }
+
+    /// # Safety
+    ///
+    /// Caller must ensure that the returned handle is only used while this listener handle is alive.
+    pub unsafe fn handle(&self) -> NT_Listener {
+        self.listener
+    }
+}
+
+impl Drop for ListenerHandle {
+    fn drop(&mut self) {
+        let removed = self
+            .instance
+            .release(|| unsafe { NT_RemoveListener(self.listener) });
+
+        // On a listener thread, the callback may be the one dropping the handle, or be queued behind it,
+        // and waiting for the queue would never return.
+        if CALLBACK_DEPTH.with(Cell::get) > 0 {
+            return;
+        }
+        if removed {
+            // The callback may still be running on ntcore's listener thread, and it must not outlive the trampoline.
+            // A negative timeout waits for as long as it takes.
+            unsafe { NT_WaitForListenerQueue(self.listener, -1.0) };
+        }
+        // Safety: the listener is removed and no callback is running anymore, or the instance was destroyed,
+        // which stops its listener thread.
+        unsafe { ManuallyDrop::drop(&mut self.trampoline) };
+    }
+}
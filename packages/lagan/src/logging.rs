@@ -0,0 +1,215 @@
+//! ntcore's log messages as structured events.
+//!
+//! ntcore reports problems such as type mismatches only as log lines.
+//! [`LogMessages`] reads them as events instead and attaches the topic and remote node they mention where possible,
+//! so tooling can show e.g. a type mismatch next to the topic it happened on.
+
+use std::time::Duration;
+
+use ntcore_sys::{
+    wpiutil::WPI_WaitForObjectTimeout, NT_AddPolledLogger, NT_CreateListenerPoller,
+    NT_DestroyListenerPoller, NT_EventFlags, NT_Listener, NT_ListenerPoller, NT_LogLevel,
+    NT_LogMessage, NT_ReadListenerQueue, NT_RemoveListener, NtEventArray,
+};
+
+use crate::{shutdown::InstanceGuard, Instance};
+
+/// How severe a log message is.
+///
+/// These mirror the levels of the `log` crate, which they convert into with the `log` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    /// Converts an ntcore log level into the closest level.
+    ///
+    /// Returns `None` for the most verbose debug levels, which have no equivalent.
+    pub fn from_raw(level: u32) -> Option<Self> {
+        if level >= NT_LogLevel::NT_LOG_ERROR.bits() {
+            Some(Self::Error)
+        } else if level >= NT_LogLevel::NT_LOG_WARNING.bits() {
+            Some(Self::Warn)
+        } else if level >= NT_LogLevel::NT_LOG_INFO.bits() {
+            Some(Self::Info)
+        } else if level >= NT_LogLevel::NT_LOG_DEBUG2.bits() {
+            Some(Self::Debug)
+        } else if level >= NT_LogLevel::NT_LOG_DEBUG3.bits() {
+            Some(Self::Trace)
+        } else {
+            None
+        }
+    }
+
+    /// The lowest ntcore log level that [`LogLevel::from_raw`] converts into this level.
+    pub fn to_raw(self) -> u32 {
+        match self {
+            Self::Error => NT_LogLevel::NT_LOG_ERROR.bits(),
+            Self::Warn => NT_LogLevel::NT_LOG_WARNING.bits(),
+            Self::Info => NT_LogLevel::NT_LOG_INFO.bits(),
+            Self::Debug => NT_LogLevel::NT_LOG_DEBUG2.bits(),
+            Self::Trace => NT_LogLevel::NT_LOG_DEBUG3.bits(),
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+impl From<LogLevel> for log::Level {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => Self::Error,
+            LogLevel::Warn => Self::Warn,
+            LogLevel::Info => Self::Info,
+            LogLevel::Debug => Self::Debug,
+            LogLevel::Trace => Self::Trace,
+        }
+    }
+}
+
+/// What a log message is about, as far as it can be told from the message.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct LogContext {
+    /// The topic the message mentions, e.g. the topic of a type mismatch.
+    pub topic: Option<String>,
+    /// The identity of the remote node the message mentions, e.g. a client that connected.
+    pub remote_id: Option<String>,
+}
+
+impl LogContext {
+    /// Finds the topic and remote node mentioned in an ntcore log message.
+    ///
+    /// ntcore quotes names, so the first quoted name starting with `/` is taken as the topic,
+    /// and a quoted name or word following `client` as the remote node.
+    pub fn parse(message: &str) -> Self {
+        let topic = quoted(message)
+            .find(|quoted| quoted.starts_with('/'))
+            .map(str::to_owned);
+
+        let remote_id = message.find("client ").and_then(|start| {
+            let rest = &message[start + "client ".len()..];
+            let id = match rest.strip_prefix('\'') {
+                Some(rest) => rest.split('\'').next(),
+                None => rest.split([' ', ':', ',']).next(),
+            };
+            id.filter(|id| !id.is_empty()).map(str::to_owned)
+        });
+
+        Self { topic, remote_id }
+    }
+}
+
+/// Returns every single-quoted part of `message`.
+fn quoted(message: &str) -> impl Iterator<Item = &str> {
+    message.split('\'').skip(1).step_by(2)
+}
+
+/// A message ntcore logged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LogMessage {
+    /// The ntcore log level, from `NT_LOG_DEBUG4` (6) to `NT_LOG_CRITICAL` (50).
+    pub raw_level: u32,
+    /// The ntcore source file that logged the message.
+    pub file: String,
+    pub line: u32,
+    pub message: String,
+    pub context: LogContext,
+}
+
+impl LogMessage {
+    /// The closest [`LogLevel`] to the ntcore level of this message.
+    pub fn level(&self) -> Option<LogLevel> {
+        LogLevel::from_raw(self.raw_level)
+    }
+}
+
+impl From<&NT_LogMessage> for LogMessage {
+    fn from(message: &NT_LogMessage) -> Self {
+        let text = unsafe { message.message.to_str_lossy() }.into_owned();
+        Self {
+            raw_level: message.level,
+            file: unsafe { message.filename.to_str_lossy() }.into_owned(),
+            line: message.line,
+            context: LogContext::parse(&text),
+            message: text,
+        }
+    }
+}
+
+/// A queue of the messages an instance logged.
+#[derive(Debug)]
+pub struct LogMessages<'a, I: Instance + ?Sized> {
+    instance: &'a I,
+    guard: InstanceGuard,
+    poller: NT_ListenerPoller,
+    listener: NT_Listener,
+}
+
+impl<'a, I: Instance + ?Sized> LogMessages<'a, I> {
+    pub(crate) fn new(instance: &'a I, min_level: LogLevel) -> Self {
+        let (poller, listener) = unsafe {
+            let poller = NT_CreateListenerPoller(instance.handle());
+            let listener = NT_AddPolledLogger(poller, min_level.to_raw(), u32::MAX);
+            (poller, listener)
+        };
+
+        Self {
+            instance,
+            guard: InstanceGuard::new(unsafe { instance.handle() }),
+            poller,
+            listener,
+        }
+    }
+
+    /// Returns all of the messages logged since the last read.
+    ///
+    /// If there have been no new messages, None is returned.
+    pub fn try_read_queue(&self) -> Option<Vec<LogMessage>> {
+        let mut messages = Vec::new();
+        (self.try_read_queue_into(&mut messages) > 0).then_some(messages)
+    }
+
+    /// Appends all of the messages logged since the last read to `messages`.
+    ///
+    /// Reusing the same `Vec` for every read avoids allocating a new one each time.
+    /// Returns the number of messages appended.
+    pub fn try_read_queue_into(&self, messages: &mut Vec<LogMessage>) -> usize {
+        let mut count = 0;
+        let raw_events = unsafe {
+            NtEventArray::from_raw(NT_ReadListenerQueue(self.poller, &raw mut count), count)
+        };
+
+        let before = messages.len();
+        messages.extend(
+            raw_events
+                .iter()
+                .filter(|event| event.flags & NT_EventFlags::NT_EVENT_LOGMESSAGE.bits() != 0)
+                .map(|event| LogMessage::from(unsafe { &event.data.logMessage })),
+        );
+        messages.len() - before
+    }
+
+    /// Like [`LogMessages::try_read_queue`], but waits up to `timeout` for a message if there are none yet.
+    pub fn read_queue_timeout(&self, timeout: Duration) -> Option<Vec<LogMessage>> {
+        let mut timed_out = 0;
+        unsafe { WPI_WaitForObjectTimeout(self.poller, timeout.as_secs_f64(), &raw mut timed_out) };
+        self.try_read_queue()
+    }
+
+    pub fn instance(&self) -> &'a I {
+        self.instance
+    }
+}
+
+impl<I: Instance + ?Sized> Drop for LogMessages<'_, I> {
+    fn drop(&mut self) {
+        self.guard.release(|| unsafe {
+            NT_RemoveListener(self.listener);
+            NT_DestroyListenerPoller(self.poller);
+        });
+    }
+}
@@ -0,0 +1,96 @@
+//! Copying the topics of one table to another, e.g. to rename a dashboard's table without losing its persistent settings.
+
+use crate::{entry::Entry, nt_types::Value, Instance, NetworkTablesError};
+
+/// Returns `prefix` with a single trailing slash, so it only matches whole table names.
+fn table_prefix(prefix: &str) -> String {
+    format!("{}/", prefix.trim_end_matches('/'))
+}
+
+/// Returns the name `name` has under the table prefix `to`, or `None` if it isn't under the table prefix `from`.
+fn destination(name: &str, from: &str, to: &str) -> Option<String> {
+    name.strip_prefix(from).map(|rest| format!("{to}{rest}"))
+}
+
+/// Copies the value and properties of every topic of the table `from` to the same topic of the table `to`.
+///
+/// See [`Instance::migrate`].
+pub(crate) fn migrate<'a, I: Instance + ?Sized>(
+    instance: &'a I,
+    from: &str,
+    to: &str,
+    delete_source: bool,
+) -> Result<Vec<Entry<'a, I>>, NetworkTablesError> {
+    // Without the trailing slash, migrating `/Old` would also move `/Older`.
+    let (from, to) = (table_prefix(from), table_prefix(to));
+    if from == to {
+        return Ok(Vec::new());
+    }
+    let nt4 = instance.version().has_properties();
+
+    let mut copies = Vec::new();
+    // The topics are fetched up front, so copies under `from` (if `to` is under it) aren't migrated again.
+    for info in instance.topics_iter(&from) {
+        let Some(name) = destination(&info.name, &from, &to) else {
+            continue;
+        };
+        let value = instance.entry(&*info.name).value();
+        if value == Value::Unassigned {
+            continue;
+        }
+
+        let copy = instance.entry(&name);
+        copy.set_value(value)?;
+        let source = instance.topic(&*info.name);
+        let topic = instance.topic(&name);
+        if nt4 {
+            // The properties include the persistent, cached and retained flags.
+            if !info.properties.is_empty() {
+                topic.update_properties(&info.properties)?;
+            }
+        } else {
+            topic.set_persistent(source.is_persistent());
+        }
+
+        if delete_source {
+            source.set_persistent(false);
+            if nt4 {
+                source.set_retained(false)?;
+            }
+        }
+        copies.push(copy);
+    }
+    Ok(copies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{destination, table_prefix};
+
+    #[test]
+    fn names_move_to_the_new_prefix() {
+        assert_eq!(
+            destination(
+                "/SmartDashboard/Old/Auto/delay",
+                "/SmartDashboard/Old/",
+                "/Dashboard/"
+            ),
+            Some("/Dashboard/Auto/delay".to_string())
+        );
+        assert_eq!(destination("/Other/speed", "/Old/", "/New/"), None);
+    }
+
+    #[test]
+    fn sibling_tables_are_left_alone() {
+        assert_eq!(table_prefix("/Old"), "/Old/");
+        assert_eq!(table_prefix("/Old//"), "/Old/");
+        assert_eq!(
+            destination("/Old/speed", &table_prefix("/Old"), &table_prefix("/New")),
+            Some("/New/speed".to_string())
+        );
+        assert_eq!(
+            destination("/Older/speed", &table_prefix("/Old"), &table_prefix("/New")),
+            None
+        );
+    }
+}
@@ -1,18 +1,30 @@
 use std::{
+    borrow::Cow,
+    fmt::{self, Debug},
     ops::{Add, AddAssign, Sub, SubAssign},
     slice,
+    sync::atomic::{AtomicU64, Ordering},
     time::Duration,
 };
 
 use bitflags::bitflags;
-use ntcore_sys::{NT_Now, NT_PubSubOptions, NT_Type, NT_Value};
+#[cfg(feature = "derive")]
+pub use lagan_derive::StringEnum;
+use ntcore_sys::{
+    wpiutil::{WPI_Now, WPI_SetNowImpl},
+    NT_Bool, NT_Now, NT_PubSubOptions, NT_Publisher, NT_Type, NT_Value, NT_ValueData,
+    NT_ValueDataArray, WPI_String,
+};
+#[cfg(feature = "builder")]
 use typed_builder::TypedBuilder;
 
+use crate::{NetworkTablesError, ProtocolSnafu};
+
 /// A monotonic clock timestamp that is used to timestamp network tables values.
 /// Instants have microsecond precision.
 ///
 /// This API matches the [`std::time::Instant`] API.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct NetworkTablesInstant {
     micros: u64,
 }
@@ -22,18 +34,35 @@ impl NetworkTablesInstant {
         Self { micros }
     }
 
-    pub(crate) fn from_micros(micros: u64) -> Self {
+    /// Creates an instant from a timestamp in microseconds, e.g. one read from a data log.
+    pub fn from_micros(micros: u64) -> Self {
         Self { micros }
     }
     pub(crate) fn as_micros(&self) -> u64 {
         self.micros
     }
 
+    /// Sets the time returned by [`Self::now`] and used to timestamp values that are set without a timestamp.
+    ///
+    /// This enables [simulated time](set_simulated_time) if it isn't already,
+    /// so the clock stays at `instant` until `set_now` is called again or [`Self::reset_now`] is called.
+    /// The NetworkTables clock is shared by every instance in the process.
+    pub fn set_now(instant: Self) {
+        SIMULATED_MICROS.store(instant.micros, Ordering::Relaxed);
+        unsafe { WPI_SetNowImpl(Some(simulated_now)) };
+    }
+
+    /// Makes the clock follow the system clock again after [`Self::set_now`], disabling simulated time.
+    pub fn reset_now() {
+        set_simulated_time(false);
+    }
+
     pub fn elapsed(&self) -> Duration {
-        self.duration_since(Self::now())
+        Self::now().duration_since(*self)
     }
+    /// Returns the time elapsed from `earlier` to this instant, or zero if `earlier` is later.
     pub fn duration_since(&self, earlier: Self) -> Duration {
-        self.checked_duration_since(earlier).unwrap()
+        self.saturating_duration_since(earlier)
     }
 
     pub fn checked_add(&self, duration: std::time::Duration) -> Option<Self> {
@@ -53,10 +82,35 @@ impl NetworkTablesInstant {
     }
 }
 
+static SIMULATED_MICROS: AtomicU64 = AtomicU64::new(0);
+
+unsafe extern "C" fn simulated_now() -> u64 {
+    SIMULATED_MICROS.load(Ordering::Relaxed)
+}
+
+/// Switches the NetworkTables clock between the system clock and simulated time.
+///
+/// While simulated time is enabled, time only advances when [`NetworkTablesInstant::set_now`] is called,
+/// which lets simulation and replay tools timestamp values deterministically.
+/// Simulated time starts at the time it was enabled.
+///
+/// The clock is shared by every instance in the process, as well as any other wpiutil-based code.
+pub fn set_simulated_time(enabled: bool) {
+    if enabled {
+        SIMULATED_MICROS.store(unsafe { WPI_Now() }, Ordering::Relaxed);
+        unsafe { WPI_SetNowImpl(Some(simulated_now)) };
+    } else {
+        unsafe { WPI_SetNowImpl(None) };
+    }
+}
+
 impl Add<Duration> for NetworkTablesInstant {
     type Output = Self;
+    /// Saturates at the latest representable instant instead of overflowing.
     fn add(self, duration: Duration) -> Self {
-        self.checked_add(duration).unwrap()
+        Self {
+            micros: self.micros.saturating_add(duration.as_micros() as u64),
+        }
     }
 }
 impl AddAssign<Duration> for NetworkTablesInstant {
@@ -73,8 +127,11 @@ impl Sub for NetworkTablesInstant {
 }
 impl Sub<Duration> for NetworkTablesInstant {
     type Output = Self;
+    /// Saturates at the start of the clock instead of underflowing.
     fn sub(self, duration: Duration) -> Self {
-        self.checked_sub(duration).unwrap()
+        Self {
+            micros: self.micros.saturating_sub(duration.as_micros() as u64),
+        }
     }
 }
 impl SubAssign<Duration> for NetworkTablesInstant {
@@ -85,6 +142,7 @@ impl SubAssign<Duration> for NetworkTablesInstant {
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ValueType {
+    /// No value, or an NT3 RPC entry. ntcore dropped RPC support when it moved to NetworkTables 4.
     Unassigned,
     Bool,
     I64,
@@ -97,10 +155,33 @@ pub enum ValueType {
     F32Array,
     I64Array,
     StringArray,
+    /// A type this version of lagan doesn't know, e.g. one added by a newer ntcore, with its raw `NT_Type` bits.
+    Unknown(u32),
+}
+impl ValueType {
+    /// Returns the type string ntcore uses for this type by default.
+    pub fn type_string(&self) -> &'static str {
+        match self {
+            Self::Unassigned => "",
+            Self::Bool => "boolean",
+            Self::I64 => "int",
+            Self::F32 => "float",
+            Self::F64 => "double",
+            Self::String => "string",
+            Self::Raw => "raw",
+            Self::BoolArray => "boolean[]",
+            Self::F64Array => "double[]",
+            Self::F32Array => "float[]",
+            Self::I64Array => "int[]",
+            Self::StringArray => "string[]",
+            Self::Unknown(_) => "",
+        }
+    }
 }
 impl From<NT_Type> for ValueType {
     fn from(value: NT_Type) -> Self {
         match value {
+            // RPC entries were removed from ntcore along with the rest of NT3 RPC support.
             NT_Type::NT_UNASSIGNED | NT_Type::NT_RPC => Self::Unassigned,
             NT_Type::NT_BOOLEAN => Self::Bool,
             NT_Type::NT_INTEGER => Self::I64,
@@ -113,7 +194,7 @@ impl From<NT_Type> for ValueType {
             NT_Type::NT_FLOAT_ARRAY => Self::F32Array,
             NT_Type::NT_INTEGER_ARRAY => Self::I64Array,
             NT_Type::NT_STRING_ARRAY => Self::StringArray,
-            _ => unreachable!("Invalid NT_Type"),
+            other => Self::Unknown(other.bits()),
         }
     }
 }
@@ -132,6 +213,7 @@ impl From<ValueType> for NT_Type {
             ValueType::F32Array => NT_Type::NT_FLOAT_ARRAY,
             ValueType::I64Array => NT_Type::NT_INTEGER_ARRAY,
             ValueType::StringArray => NT_Type::NT_STRING_ARRAY,
+            ValueType::Unknown(bits) => NT_Type::from_bits(bits),
         }
     }
 }
@@ -150,8 +232,24 @@ pub enum Value {
     F32Array(Vec<f32>),
     I64Array(Vec<i64>),
     StringArray(Vec<String>),
+    /// A value of a type this version of lagan doesn't know.
+    ///
+    /// Only its type is kept, since the layout of its data is unknown. Unknown values can't be published.
+    Unknown {
+        type_bits: u32,
+    },
 }
 impl Value {
+    /// Parses a string value as a variant of `T`.
+    ///
+    /// Returns `None` if the value is not a string or does not name a variant of `T`.
+    pub fn parse_enum<T: StringEnum>(&self) -> Option<T> {
+        match self {
+            Self::String(name) => T::from_name(name),
+            _ => None,
+        }
+    }
+
     pub fn value_type(&self) -> ValueType {
         match self {
             Self::Unassigned => ValueType::Unassigned,
@@ -166,6 +264,222 @@ impl Value {
             Self::F32Array(_) => ValueType::F32Array,
             Self::I64Array(_) => ValueType::I64Array,
             Self::StringArray(_) => ValueType::StringArray,
+            Self::Unknown { type_bits, .. } => ValueType::Unknown(*type_bits),
+        }
+    }
+
+    /// Returns the number held by an integer, float or double value as a double.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::I64(value) => Some(*value as f64),
+            Self::F32(value) => Some(f64::from(*value)),
+            Self::F64(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Interpolates linearly from `a` to `b`, where a `t` of 0 gives `a` and 1 gives `b`.
+    ///
+    /// Number arrays are interpolated element by element, and integers are rounded to the nearest integer.
+    /// `t` isn't clamped, so values outside of `0.0..=1.0` extrapolate.
+    /// Returns `None` unless both values are numbers, or number arrays of the same length, of the same type.
+    pub fn lerp_between(a: &Value, b: &Value, t: f64) -> Option<Value> {
+        fn lerp(a: f64, b: f64, t: f64) -> f64 {
+            a + (b - a) * t
+        }
+        fn lerp_arrays<T: Copy>(a: &[T], b: &[T], lerp: impl Fn(T, T) -> T) -> Option<Vec<T>> {
+            (a.len() == b.len()).then(|| a.iter().zip(b).map(|(a, b)| lerp(*a, *b)).collect())
+        }
+        let lerp_i64 = |a: i64, b: i64| lerp(a as f64, b as f64, t).round() as i64;
+        let lerp_f32 = |a: f32, b: f32| lerp(f64::from(a), f64::from(b), t) as f32;
+
+        match (a, b) {
+            (Self::I64(a), Self::I64(b)) => Some(Self::I64(lerp_i64(*a, *b))),
+            (Self::F32(a), Self::F32(b)) => Some(Self::F32(lerp_f32(*a, *b))),
+            (Self::F64(a), Self::F64(b)) => Some(Self::F64(lerp(*a, *b, t))),
+            (Self::I64Array(a), Self::I64Array(b)) => {
+                lerp_arrays(a, b, lerp_i64).map(Self::I64Array)
+            }
+            (Self::F32Array(a), Self::F32Array(b)) => {
+                lerp_arrays(a, b, lerp_f32).map(Self::F32Array)
+            }
+            (Self::F64Array(a), Self::F64Array(b)) => {
+                lerp_arrays(a, b, |a, b| lerp(a, b, t)).map(Self::F64Array)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A Rust type that maps to a single NetworkTables [`ValueType`].
+pub trait NetworkTablesValue: Sized {
+    /// The NetworkTables type values of this type are stored as.
+    fn value_type() -> ValueType;
+    fn into_value(self) -> Value;
+    /// Returns `None` if the value is of a different type.
+    fn from_value(value: Value) -> Option<Self>;
+}
+
+macro_rules! network_tables_value {
+    {$($ty:ty => $variant:ident),*} => {
+        $(
+            impl NetworkTablesValue for $ty {
+                fn value_type() -> ValueType {
+                    ValueType::$variant
+                }
+                fn into_value(self) -> Value {
+                    Value::$variant(self)
+                }
+                fn from_value(value: Value) -> Option<Self> {
+                    match value {
+                        Value::$variant(value) => Some(value),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+network_tables_value! {
+    bool => Bool,
+    i64 => I64,
+    f32 => F32,
+    f64 => F64,
+    String => String,
+    Vec<u8> => Raw,
+    Vec<bool> => BoolArray,
+    Vec<f64> => F64Array,
+    Vec<f32> => F32Array,
+    Vec<i64> => I64Array,
+    Vec<String> => StringArray
+}
+
+/// A fieldless enum stored in NetworkTables as the name of one of its variants.
+///
+/// This is usually derived with `#[derive(StringEnum)]`, which requires the `derive` feature.
+/// Variants are named after their identifier unless renamed with `#[nt(rename = "name")]`.
+pub trait StringEnum: Sized {
+    /// The names of every variant.
+    const VARIANTS: &'static [&'static str];
+
+    /// Returns the name of this variant.
+    fn name(&self) -> &'static str;
+    /// Returns the variant with the given name.
+    fn from_name(name: &str) -> Option<Self>;
+}
+
+impl<T: StringEnum> NetworkTablesValue for T {
+    fn value_type() -> ValueType {
+        ValueType::String
+    }
+    fn into_value(self) -> Value {
+        Value::String(self.name().to_owned())
+    }
+    fn from_value(value: Value) -> Option<Self> {
+        value.parse_enum()
+    }
+}
+
+/// Fails for [`Value::Unknown`], which can't be handed back to ntcore.
+pub(crate) fn ensure_known(value: &Value) -> Result<(), NetworkTablesError> {
+    match value {
+        Value::Unknown { type_bits, .. } => ProtocolSnafu {
+            message: format!("can't publish a value of unknown type {type_bits:#x}"),
+        }
+        .fail(),
+        _ => Ok(()),
+    }
+}
+
+/// Calls `f` with a borrowed [`NT_Value`] holding `value`.
+///
+/// The pointers inside the [`NT_Value`] are only valid for the duration of `f`.
+pub(crate) fn with_nt_value<R>(
+    value: &Value,
+    last_change: i64,
+    server_time: i64,
+    f: impl FnOnce(&NT_Value) -> R,
+) -> R {
+    let data = NtValueData::new(value);
+    let raw_value = NT_Value {
+        r#type: value.value_type().into(),
+        last_change,
+        server_time,
+        data: data.data(),
+    };
+    f(&raw_value)
+}
+
+/// The data of a [`Value`] laid out the way ntcore expects it.
+///
+/// Booleans and strings have a different layout in ntcore, so arrays of them are converted and kept here.
+/// Every other type is borrowed directly from the value.
+struct NtValueData<'v> {
+    value: &'v Value,
+    bools: Vec<NT_Bool>,
+    strings: Vec<WPI_String>,
+}
+
+impl<'v> NtValueData<'v> {
+    fn new(value: &'v Value) -> Self {
+        let bools = match value {
+            Value::BoolArray(values) => values.iter().map(|b| (*b).into()).collect(),
+            _ => Vec::new(),
+        };
+        let strings = match value {
+            Value::StringArray(strings) => strings
+                .iter()
+                .map(|s| WPI_String::from(s.as_str()))
+                .collect(),
+            _ => Vec::new(),
+        };
+        Self {
+            value,
+            bools,
+            strings,
+        }
+    }
+
+    /// Returns the union holding the value.
+    ///
+    /// Pointers in the returned union are only valid while `self` is alive.
+    fn data(&self) -> NT_ValueData {
+        fn array<T>(array: &[T]) -> NT_ValueDataArray<T> {
+            NT_ValueDataArray {
+                arr: array.as_ptr(),
+                size: array.len(),
+            }
+        }
+
+        match self.value {
+            // Unknown values are rejected before they reach ntcore, see `Value::Unknown`.
+            Value::Unassigned | Value::Unknown { .. } => unsafe { std::mem::zeroed() },
+            Value::Bool(value) => NT_ValueData {
+                v_boolean: (*value).into(),
+            },
+            Value::I64(value) => NT_ValueData { v_int: *value },
+            Value::F32(value) => NT_ValueData { v_float: *value },
+            Value::F64(value) => NT_ValueData { v_double: *value },
+            Value::String(string) => NT_ValueData {
+                v_string: WPI_String::from(string.as_str()),
+            },
+            Value::Raw(data) => NT_ValueData { v_raw: array(data) },
+            Value::F64Array(values) => NT_ValueData {
+                arr_double: array(values),
+            },
+            Value::F32Array(values) => NT_ValueData {
+                arr_float: array(values),
+            },
+            Value::I64Array(values) => NT_ValueData {
+                arr_int: array(values),
+            },
+            Value::BoolArray(_) => NT_ValueData {
+                arr_boolean: array(&self.bools),
+            },
+            Value::StringArray(_) => NT_ValueData {
+                arr_string: array(&self.strings),
+            },
         }
     }
 }
@@ -177,92 +491,205 @@ pub struct RawValue {
     pub server_time: NetworkTablesInstant,
 }
 
-impl From<NT_Value> for RawValue {
-    // Oh boy, this is going to be a fun one
-    fn from(value: NT_Value) -> Self {
-        let last_change = NetworkTablesInstant::from_micros(value.last_change as _);
-        let server_time = NetworkTablesInstant::from_micros(value.server_time as _);
-        let data = match value.r#type {
+/// Borrows the elements of an array stored in an [`NT_Value`].
+///
+/// # Safety
+///
+/// `array.arr` must either be null or point to `array.size` elements that outlive the returned slice.
+unsafe fn array_slice<'v, T>(array: NT_ValueDataArray<T>) -> &'v [T] {
+    // ntcore may use null pointers for empty arrays, which `slice::from_raw_parts` doesn't allow.
+    if array.arr.is_null() || array.size == 0 {
+        return &[];
+    }
+    unsafe { slice::from_raw_parts(array.arr, array.size) }
+}
+
+/// Reads the field of `data` that holds values of type `r#type`.
+/// Values of types this version of lagan doesn't know are read as [`Value::Unknown`].
+///
+/// # Safety
+///
+/// `data` must have been written as `r#type`, and any pointers in it must be valid as described in [`array_slice`].
+unsafe fn read_value_data(r#type: NT_Type, data: &NT_ValueData) -> Value {
+    unsafe {
+        match r#type {
             NT_Type::NT_UNASSIGNED | NT_Type::NT_RPC => Value::Unassigned,
-            NT_Type::NT_BOOLEAN => Value::Bool(unsafe { value.data.v_boolean == 1 }),
-            NT_Type::NT_INTEGER => Value::I64(unsafe { value.data.v_int }),
-            NT_Type::NT_FLOAT => Value::F32(unsafe { value.data.v_float }),
-            NT_Type::NT_DOUBLE => Value::F64(unsafe { value.data.v_double }),
-            NT_Type::NT_STRING => {
-                let string = unsafe {
-                    String::from_utf8_lossy(slice::from_raw_parts(
-                        value.data.v_string.str.cast(),
-                        value.data.v_string.len,
-                    ))
-                }
-                .into_owned();
-                Value::String(string)
-            }
-            NT_Type::NT_RAW => {
-                let data = unsafe {
-                    slice::from_raw_parts(value.data.v_raw.arr, value.data.v_raw.size as _)
-                }
-                .to_vec();
-                Value::Raw(data)
-            }
-            NT_Type::NT_BOOLEAN_ARRAY => {
-                let data = unsafe {
-                    slice::from_raw_parts(
-                        value.data.arr_boolean.arr,
-                        value.data.arr_boolean.size as _,
-                    )
-                }
-                .iter()
-                .map(|b| *b == 1)
-                .collect::<Vec<_>>();
-                Value::BoolArray(data)
-            }
-            NT_Type::NT_DOUBLE_ARRAY => {
-                let data = unsafe {
-                    slice::from_raw_parts(
-                        value.data.arr_double.arr,
-                        value.data.arr_double.size as _,
-                    )
-                }
-                .to_vec();
-                Value::F64Array(data)
-            }
-            NT_Type::NT_FLOAT_ARRAY => {
-                let data = unsafe {
-                    slice::from_raw_parts(value.data.arr_float.arr, value.data.arr_float.size as _)
-                }
-                .to_vec();
-                Value::F32Array(data)
-            }
-            NT_Type::NT_INTEGER_ARRAY => {
-                let data = unsafe {
-                    slice::from_raw_parts(value.data.arr_int.arr, value.data.arr_int.size as _)
-                }
-                .to_vec();
-                Value::I64Array(data)
-            }
-            NT_Type::NT_STRING_ARRAY => {
-                let data = unsafe {
-                    slice::from_raw_parts(
-                        value.data.arr_string.arr,
-                        value.data.arr_string.size as _,
-                    )
+            NT_Type::NT_BOOLEAN => Value::Bool(data.v_boolean == 1),
+            NT_Type::NT_INTEGER => Value::I64(data.v_int),
+            NT_Type::NT_FLOAT => Value::F32(data.v_float),
+            NT_Type::NT_DOUBLE => Value::F64(data.v_double),
+            NT_Type::NT_STRING => Value::String(data.v_string.to_str_lossy().into_owned()),
+            NT_Type::NT_RAW => Value::Raw(array_slice(data.v_raw).to_vec()),
+            NT_Type::NT_BOOLEAN_ARRAY => Value::BoolArray(
+                array_slice(data.arr_boolean)
+                    .iter()
+                    .map(|b| *b == 1)
+                    .collect(),
+            ),
+            NT_Type::NT_DOUBLE_ARRAY => Value::F64Array(array_slice(data.arr_double).to_vec()),
+            NT_Type::NT_FLOAT_ARRAY => Value::F32Array(array_slice(data.arr_float).to_vec()),
+            NT_Type::NT_INTEGER_ARRAY => Value::I64Array(array_slice(data.arr_int).to_vec()),
+            NT_Type::NT_STRING_ARRAY => Value::StringArray(
+                array_slice(data.arr_string)
+                    .iter()
+                    .map(|s| s.to_str_lossy().into_owned())
+                    .collect(),
+            ),
+            other => Value::Unknown {
+                type_bits: other.bits(),
+            },
+        }
+    }
+}
+
+/// A value borrowed from ntcore instead of copied into a [`Value`], see [`Entry::with_value`](crate::Entry::with_value).
+///
+/// Strings and arrays point into ntcore's copy of the value, so reading them allocates nothing
+/// unless a string isn't valid UTF-8. Booleans have a different layout in ntcore, so boolean arrays are converted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueRef<'v> {
+    Unassigned,
+    Bool(bool),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    String(Cow<'v, str>),
+    Raw(&'v [u8]),
+    BoolArray(Vec<bool>),
+    F64Array(&'v [f64]),
+    F32Array(&'v [f32]),
+    I64Array(&'v [i64]),
+    StringArray(StringArrayRef<'v>),
+    /// A value of a type this version of lagan doesn't know, see [`Value::Unknown`].
+    Unknown {
+        type_bits: u32,
+    },
+}
+
+impl<'v> ValueRef<'v> {
+    /// # Safety
+    ///
+    /// `value` must have been written by ntcore, or be valid as described in [`read_value_data`].
+    pub(crate) unsafe fn from_raw(value: &'v NT_Value) -> Self {
+        unsafe {
+            match value.r#type {
+                NT_Type::NT_UNASSIGNED | NT_Type::NT_RPC => Self::Unassigned,
+                NT_Type::NT_BOOLEAN => Self::Bool(value.data.v_boolean == 1),
+                NT_Type::NT_INTEGER => Self::I64(value.data.v_int),
+                NT_Type::NT_FLOAT => Self::F32(value.data.v_float),
+                NT_Type::NT_DOUBLE => Self::F64(value.data.v_double),
+                NT_Type::NT_STRING => Self::String(value.data.v_string.to_str_lossy()),
+                NT_Type::NT_RAW => Self::Raw(array_slice(value.data.v_raw)),
+                NT_Type::NT_BOOLEAN_ARRAY => Self::BoolArray(
+                    array_slice(value.data.arr_boolean)
+                        .iter()
+                        .map(|b| *b == 1)
+                        .collect(),
+                ),
+                NT_Type::NT_DOUBLE_ARRAY => Self::F64Array(array_slice(value.data.arr_double)),
+                NT_Type::NT_FLOAT_ARRAY => Self::F32Array(array_slice(value.data.arr_float)),
+                NT_Type::NT_INTEGER_ARRAY => Self::I64Array(array_slice(value.data.arr_int)),
+                NT_Type::NT_STRING_ARRAY => {
+                    Self::StringArray(StringArrayRef(array_slice(value.data.arr_string)))
                 }
-                .iter()
-                .map(|s| {
-                    unsafe { String::from_utf8_lossy(slice::from_raw_parts(s.str.cast(), s.len)) }
-                        .into_owned()
-                })
-                .collect::<Vec<_>>();
-                Value::StringArray(data)
+                other => Self::Unknown {
+                    type_bits: other.bits(),
+                },
             }
-            _ => unreachable!("Invalid NT_Type"),
-        };
+        }
+    }
 
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Self::Unassigned => ValueType::Unassigned,
+            Self::Bool(_) => ValueType::Bool,
+            Self::I64(_) => ValueType::I64,
+            Self::F32(_) => ValueType::F32,
+            Self::F64(_) => ValueType::F64,
+            Self::String(_) => ValueType::String,
+            Self::Raw(_) => ValueType::Raw,
+            Self::BoolArray(_) => ValueType::BoolArray,
+            Self::F64Array(_) => ValueType::F64Array,
+            Self::F32Array(_) => ValueType::F32Array,
+            Self::I64Array(_) => ValueType::I64Array,
+            Self::StringArray(_) => ValueType::StringArray,
+            Self::Unknown { type_bits, .. } => ValueType::Unknown(*type_bits),
+        }
+    }
+
+    /// Copies the value into an owned [`Value`].
+    pub fn to_value(&self) -> Value {
+        match self {
+            Self::Unassigned => Value::Unassigned,
+            Self::Bool(value) => Value::Bool(*value),
+            Self::I64(value) => Value::I64(*value),
+            Self::F32(value) => Value::F32(*value),
+            Self::F64(value) => Value::F64(*value),
+            Self::String(value) => Value::String(value.clone().into_owned()),
+            Self::Raw(bytes) => Value::Raw(bytes.to_vec()),
+            Self::BoolArray(values) => Value::BoolArray(values.clone()),
+            Self::F64Array(values) => Value::F64Array(values.to_vec()),
+            Self::F32Array(values) => Value::F32Array(values.to_vec()),
+            Self::I64Array(values) => Value::I64Array(values.to_vec()),
+            Self::StringArray(values) => Value::StringArray(values.to_vec()),
+            Self::Unknown { type_bits } => Value::Unknown {
+                type_bits: *type_bits,
+            },
+        }
+    }
+}
+
+/// The strings of a borrowed string array, converted to `str`s as they are read.
+#[derive(Clone, Copy)]
+pub struct StringArrayRef<'v>(&'v [WPI_String]);
+
+impl<'v> StringArrayRef<'v> {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the string at `index`, with invalid UTF-8 replaced by `U+FFFD`.
+    pub fn get(&self, index: usize) -> Option<Cow<'v, str>> {
+        // Safety: the strings were written by ntcore and outlive `'v`.
+        self.0
+            .get(index)
+            .map(|string| unsafe { string.to_str_lossy() })
+    }
+
+    /// Returns an iterator over the strings, with invalid UTF-8 replaced by `U+FFFD`.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Cow<'v, str>> + 'v {
+        // Safety: the strings were written by ntcore and outlive `'v`.
+        self.0.iter().map(|string| unsafe { string.to_str_lossy() })
+    }
+
+    pub fn to_vec(&self) -> Vec<String> {
+        self.iter().map(Cow::into_owned).collect()
+    }
+}
+
+impl Debug for StringArrayRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for StringArrayRef<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl From<NT_Value> for RawValue {
+    fn from(value: NT_Value) -> Self {
         Self {
-            last_change,
-            server_time,
-            data,
+            last_change: NetworkTablesInstant::from_micros(value.last_change as _),
+            server_time: NetworkTablesInstant::from_micros(value.server_time as _),
+            // Safety: ntcore always writes the field matching the type of the value.
+            data: unsafe { read_value_data(value.r#type, &value.data) },
         }
     }
 }
@@ -276,24 +703,43 @@ bitflags! {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, TypedBuilder)]
+/// Identifies a [`TopicPublisher`](crate::topic::TopicPublisher) in [`PubSubOptions::exclude_publisher`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PublisherId(pub(crate) NT_Publisher);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "builder", derive(TypedBuilder))]
 pub struct PubSubOptions {
     /// Defaults to 1 if [`Self::send_all_updates`] is true, 20 otherwise.
-    #[builder(default = None, setter(strip_option))]
+    #[cfg_attr(feature = "builder", builder(default = None, setter(strip_option)))]
     pub queue_length: Option<u32>,
     /// How frequently changes should be sent over the network.
-    #[builder(default = Duration::from_millis(100))]
+    #[cfg_attr(feature = "builder", builder(default = Duration::from_millis(100)))]
     pub update_interval: Duration,
     /// Send all value changes over the network
-    #[builder(default)]
+    #[cfg_attr(feature = "builder", builder(default))]
     pub send_all_updates: bool,
     /// If true, duplicate value changes will be ignored.
-    #[builder(default = true)]
+    #[cfg_attr(feature = "builder", builder(default = true))]
     pub ignore_duplicates: bool,
+    /// Ignore value changes made by this publisher.
+    /// Pass a [`TopicPublisher`](crate::topic::TopicPublisher) to suppress echoes of your own writes.
+    #[cfg_attr(feature = "builder", builder(default = None, setter(into, strip_option)))]
+    pub exclude_publisher: Option<PublisherId>,
+    /// For entries, ignore value changes made through the entry itself.
+    #[cfg_attr(feature = "builder", builder(default))]
+    pub exclude_self: bool,
 }
 impl Default for PubSubOptions {
     fn default() -> Self {
-        Self::builder().build()
+        Self {
+            queue_length: None,
+            update_interval: Duration::from_millis(100),
+            send_all_updates: false,
+            ignore_duplicates: true,
+            exclude_publisher: None,
+            exclude_self: false,
+        }
     }
 }
 impl PubSubOptions {
@@ -303,6 +749,8 @@ impl PubSubOptions {
             update_interval,
             send_all_updates,
             ignore_duplicates,
+            exclude_publisher: None,
+            exclude_self: false,
         }
     }
 }
@@ -318,14 +766,14 @@ impl From<PubSubOptions> for NT_PubSubOptions {
             structSize: std::mem::size_of::<NT_PubSubOptions>() as _,
             pollStorage: queue_length,
             periodic: update_interval,
-            excludePublisher: 0,
+            excludePublisher: options.exclude_publisher.map_or(0, |id| id.0),
             sendAll: send_all_updates,
             topicsOnly: 0,
             prefixMatch: 0,
             keepDuplicates: keep_duplicates,
             disableRemote: 0,
             disableLocal: 0,
-            excludeSelf: 0,
+            excludeSelf: options.exclude_self.into(),
             hidden: 0,
         }
     }
@@ -337,6 +785,322 @@ impl From<NT_PubSubOptions> for PubSubOptions {
             update_interval: Duration::from_secs_f64(options.periodic),
             send_all_updates: options.sendAll != 0,
             ignore_duplicates: options.keepDuplicates == 0,
+            exclude_publisher: match options.excludePublisher {
+                0 => None,
+                publisher => Some(PublisherId(publisher)),
+            },
+            exclude_self: options.excludeSelf != 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn f64s() -> impl Strategy<Value = f64> {
+        use proptest::num::f64::*;
+        NORMAL | SUBNORMAL | ZERO | INFINITE | NEGATIVE | POSITIVE
+    }
+    fn f32s() -> impl Strategy<Value = f32> {
+        use proptest::num::f32::*;
+        NORMAL | SUBNORMAL | ZERO | INFINITE | NEGATIVE | POSITIVE
+    }
+    /// Strings including embedded NULs and other control characters.
+    fn strings() -> impl Strategy<Value = String> {
+        prop_oneof![any::<String>(), "[\0a-z]{0,64}"]
+    }
+
+    fn values() -> impl Strategy<Value = Value> {
+        prop_oneof![
+            Just(Value::Unassigned),
+            any::<bool>().prop_map(Value::Bool),
+            any::<i64>().prop_map(Value::I64),
+            f32s().prop_map(Value::F32),
+            f64s().prop_map(Value::F64),
+            strings().prop_map(Value::String),
+            prop::collection::vec(any::<u8>(), 0..4096).prop_map(Value::Raw),
+            prop::collection::vec(any::<bool>(), 0..256).prop_map(Value::BoolArray),
+            prop::collection::vec(f64s(), 0..256).prop_map(Value::F64Array),
+            prop::collection::vec(f32s(), 0..256).prop_map(Value::F32Array),
+            prop::collection::vec(any::<i64>(), 0..256).prop_map(Value::I64Array),
+            prop::collection::vec(strings(), 0..64).prop_map(Value::StringArray),
+        ]
+    }
+
+    fn round_trip(value: &Value, last_change: i64, server_time: i64) -> RawValue {
+        with_nt_value(value, last_change, server_time, |raw| RawValue::from(*raw))
+    }
+
+    /// One value of every type, including edge cases, small enough to check under Miri.
+    fn samples() -> Vec<Value> {
+        vec![
+            Value::Unassigned,
+            Value::Bool(true),
+            Value::Bool(false),
+            Value::I64(i64::MIN),
+            Value::F32(-1.5),
+            Value::F64(f64::INFINITY),
+            Value::String(String::new()),
+            Value::String("embedded\0nul".to_string()),
+            Value::Raw(Vec::new()),
+            Value::Raw(vec![0, 1, 255]),
+            Value::BoolArray(vec![true, false, true]),
+            Value::F64Array(vec![0.0, -0.0, 1e300]),
+            Value::F32Array(vec![f32::MIN_POSITIVE]),
+            Value::I64Array(Vec::new()),
+            Value::I64Array(vec![i64::MAX, 0]),
+            Value::StringArray(Vec::new()),
+            Value::StringArray(vec![
+                String::new(),
+                "a".to_string(),
+                "\u{1F980}".to_string(),
+            ]),
+        ]
+    }
+
+    #[test]
+    fn samples_round_trip() {
+        for value in samples() {
+            let raw = round_trip(&value, 1, 2);
+            assert_eq!(raw.data, value);
+            assert_eq!(raw.last_change.as_micros(), 1);
+            assert_eq!(raw.server_time.as_micros(), 2);
+        }
+    }
+
+    #[test]
+    fn reads_the_field_of_the_type() {
+        let bytes = [1u8, 2, 3];
+        let raw = NT_ValueData {
+            v_raw: NT_ValueDataArray {
+                arr: bytes.as_ptr(),
+                size: bytes.len(),
+            },
+        };
+        assert_eq!(
+            unsafe { read_value_data(NT_Type::NT_RAW, &raw) },
+            Value::Raw(bytes.to_vec())
+        );
+
+        let bools = [1, 0];
+        let bools = NT_ValueData {
+            arr_boolean: NT_ValueDataArray {
+                arr: bools.as_ptr(),
+                size: bools.len(),
+            },
+        };
+        assert_eq!(
+            unsafe { read_value_data(NT_Type::NT_BOOLEAN_ARRAY, &bools) },
+            Value::BoolArray(vec![true, false])
+        );
+
+        let double = NT_ValueData { v_double: 0.25 };
+        assert_eq!(
+            unsafe { read_value_data(NT_Type::NT_DOUBLE, &double) },
+            Value::F64(0.25)
+        );
+    }
+
+    #[test]
+    fn samples_borrow_like_they_read() {
+        for value in samples() {
+            let borrowed = with_nt_value(&value, 0, 0, |raw| {
+                unsafe { ValueRef::from_raw(raw) }.to_value()
+            });
+            assert_eq!(borrowed, value);
+        }
+
+        let value = Value::StringArray(vec!["a".to_string(), "b".to_string()]);
+        with_nt_value(&value, 0, 0, |raw| {
+            let ValueRef::StringArray(strings) = (unsafe { ValueRef::from_raw(raw) }) else {
+                panic!("expected a string array");
+            };
+            assert!(strings.iter().all(|string| matches!(string, Cow::Borrowed(_))));
+            assert_eq!(strings.get(1).as_deref(), Some("b"));
+        });
+    }
+
+    #[test]
+    fn empty_arrays_are_not_read() {
+        let array = NT_ValueDataArray::<i64> {
+            arr: std::ptr::NonNull::dangling().as_ptr(),
+            size: 0,
+        };
+        assert!(unsafe { array_slice(array) }.is_empty());
+    }
+
+    #[test]
+    fn numbers_interpolate() {
+        assert_eq!(
+            Value::lerp_between(&Value::F64(1.0), &Value::F64(3.0), 0.25),
+            Some(Value::F64(1.5))
+        );
+        assert_eq!(
+            Value::lerp_between(&Value::I64(0), &Value::I64(3), 0.5),
+            Some(Value::I64(2))
+        );
+        assert_eq!(
+            Value::lerp_between(
+                &Value::F32Array(vec![0.0, 10.0]),
+                &Value::F32Array(vec![2.0, 0.0]),
+                1.5
+            ),
+            Some(Value::F32Array(vec![3.0, -5.0]))
+        );
+
+        assert_eq!(
+            Value::lerp_between(&Value::F64(1.0), &Value::I64(3), 0.5),
+            None
+        );
+        assert_eq!(
+            Value::lerp_between(
+                &Value::I64Array(vec![1]),
+                &Value::I64Array(vec![1, 2]),
+                0.5
+            ),
+            None
+        );
+        assert_eq!(
+            Value::lerp_between(&Value::Bool(false), &Value::Bool(true), 0.5),
+            None
+        );
+    }
+
+    // Proptest is far too slow under Miri, and can't persist failures there.
+    proptest! {
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn values_round_trip(value in values(), last_change in 0..i64::MAX, server_time in 0..i64::MAX) {
+            let raw = round_trip(&value, last_change, server_time);
+            prop_assert_eq!(raw.data, value);
+            prop_assert_eq!(raw.last_change.as_micros(), last_change as u64);
+            prop_assert_eq!(raw.server_time.as_micros(), server_time as u64);
+        }
+
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn strings_round_trip(string in strings()) {
+            let wpi_string = WPI_String::from(string.as_str());
+            prop_assert_eq!(unsafe { wpi_string.as_bytes() }, string.as_bytes());
+            prop_assert_eq!(unsafe { wpi_string.to_str_lossy() }, string.as_str());
+        }
+
+        #[test]
+        #[cfg_attr(miri, ignore)]
+        fn invalid_utf8_is_replaced(bytes in prop::collection::vec(any::<u8>(), 0..256)) {
+            let wpi_string = WPI_String {
+                str: bytes.as_ptr().cast(),
+                len: bytes.len(),
+            };
+            let expected = String::from_utf8_lossy(&bytes).into_owned();
+            let value = NT_Value {
+                r#type: NT_Type::NT_STRING,
+                last_change: 0,
+                server_time: 0,
+                data: NT_ValueData { v_string: wpi_string },
+            };
+            prop_assert_eq!(RawValue::from(value).data, Value::String(expected));
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn huge_raw_values_round_trip() {
+        let value = Value::Raw((0..16 * 1024 * 1024).map(|i| i as u8).collect());
+        assert_eq!(round_trip(&value, 0, 0).data, value);
+    }
+
+    #[test]
+    fn null_arrays_are_empty() {
+        fn null_array<T>() -> NT_ValueDataArray<T> {
+            NT_ValueDataArray {
+                arr: std::ptr::null(),
+                size: 0,
+            }
+        }
+        let cases = [
+            (
+                NT_Type::NT_STRING,
+                NT_ValueData {
+                    v_string: WPI_String::empty(),
+                },
+                Value::String(String::new()),
+            ),
+            (
+                NT_Type::NT_RAW,
+                NT_ValueData {
+                    v_raw: null_array(),
+                },
+                Value::Raw(Vec::new()),
+            ),
+            (
+                NT_Type::NT_BOOLEAN_ARRAY,
+                NT_ValueData {
+                    arr_boolean: null_array(),
+                },
+                Value::BoolArray(Vec::new()),
+            ),
+            (
+                NT_Type::NT_DOUBLE_ARRAY,
+                NT_ValueData {
+                    arr_double: null_array(),
+                },
+                Value::F64Array(Vec::new()),
+            ),
+            (
+                NT_Type::NT_FLOAT_ARRAY,
+                NT_ValueData {
+                    arr_float: null_array(),
+                },
+                Value::F32Array(Vec::new()),
+            ),
+            (
+                NT_Type::NT_INTEGER_ARRAY,
+                NT_ValueData {
+                    arr_int: null_array(),
+                },
+                Value::I64Array(Vec::new()),
+            ),
+            (
+                NT_Type::NT_STRING_ARRAY,
+                NT_ValueData {
+                    arr_string: null_array(),
+                },
+                Value::StringArray(Vec::new()),
+            ),
+        ];
+
+        for (r#type, data, expected) in cases {
+            let value = NT_Value {
+                r#type,
+                last_change: 0,
+                server_time: 0,
+                data,
+            };
+            assert_eq!(RawValue::from(value).data, expected);
+        }
+    }
+
+    #[test]
+    fn unknown_types_are_kept() {
+        let value = NT_Value {
+            r#type: NT_Type::from_bits(0x1000),
+            last_change: 0,
+            server_time: 0,
+            data: NT_ValueData {
+                arr_int: NT_ValueDataArray {
+                    arr: std::ptr::null(),
+                    size: 42,
+                },
+            },
+        };
+
+        let value = RawValue::from(value).data;
+        assert_eq!(value.value_type(), ValueType::Unknown(0x1000));
+        assert_eq!(value, Value::Unknown { type_bits: 0x1000 });
+        assert_eq!(NT_Type::from(ValueType::Unknown(0x1000)).bits(), 0x1000);
+    }
+}
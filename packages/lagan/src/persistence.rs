@@ -0,0 +1,138 @@
+//! Checking and writing ntcore's persistent file.
+//!
+//! ntcore loads and saves persistent values on its own, but its C API can neither trigger a save nor report
+//! a file it failed to load other than through its log. [`Server::save_persistent`](crate::server::Server::save_persistent)
+//! writes the same JSON file right away, and [`Server::new`](crate::server::Server::new) checks the file before starting.
+
+use std::{fmt::Debug, fs, io};
+
+use crate::{json, nt_types::Value, Instance, NetworkTablesError, PersistenceSnafu};
+
+/// The header of NetworkTables 3 persistent files, which ntcore converts when it loads them.
+const NT3_HEADER: &str = "[NetworkTables Storage 3.0]";
+
+/// Checks that ntcore can load `filename`, which is fine if it doesn't exist yet since ntcore creates it.
+///
+/// ntcore starts without the values of a file it can't load and overwrites it on its next save,
+/// so a corrupted file is reported before the server starts instead.
+pub(crate) fn check(filename: &str) -> Result<(), NetworkTablesError> {
+    let contents = match fs::read_to_string(filename) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => {
+            return PersistenceSnafu {
+                filename,
+                message: error.to_string(),
+            }
+            .fail()
+        }
+    };
+    if contents.trim().is_empty() || contents.starts_with(NT3_HEADER) {
+        return Ok(());
+    }
+
+    let message = match json::validate(&contents) {
+        Ok(()) if contents.trim_start().starts_with('[') => return Ok(()),
+        Ok(()) => "expected an array of topics".to_string(),
+        Err(message) => message,
+    };
+    PersistenceSnafu { filename, message }.fail()
+}
+
+/// Returns every persistent topic of `instance` and its current value as the contents of a persistent file.
+///
+/// Topics without a value, and values JSON can't represent such as NaN, are left out.
+pub(crate) fn snapshot<I: Instance>(instance: &I) -> String {
+    let mut file = String::from("[");
+    for info in instance.topics_iter("") {
+        if !instance.topic(&*info.name).is_persistent() {
+            continue;
+        }
+        let Some(value) = json_value(&instance.entry(&*info.name).value()) else {
+            continue;
+        };
+        let properties = if info.properties.is_empty() {
+            "{}"
+        } else {
+            &info.properties
+        };
+
+        if file.len() > 1 {
+            file.push(',');
+        }
+        file.push_str(&format!(
+            "\n  {{\n    \"name\": {},\n    \"type\": {},\n    \"value\": {value},\n    \"properties\": {properties}\n  }}",
+            json::encode_string(&info.name),
+            json::encode_string(&info.type_string),
+        ));
+    }
+    file.push_str("\n]\n");
+    file
+}
+
+/// Replaces `filename` with `contents`.
+///
+/// The contents are written to a temporary file first, so a crash never leaves a truncated persistent file behind.
+pub(crate) fn save(filename: &str, contents: &str) -> Result<(), NetworkTablesError> {
+    let temporary = format!("{filename}.lagan.tmp");
+    fs::write(&temporary, contents)
+        .and_then(|()| fs::rename(&temporary, filename))
+        .map_err(|error| {
+            PersistenceSnafu {
+                filename,
+                message: error.to_string(),
+            }
+            .build()
+        })
+}
+
+/// Encodes `value` the way ntcore does in persistent files.
+fn json_value(value: &Value) -> Option<String> {
+    Some(match value {
+        Value::Bool(value) => value.to_string(),
+        Value::I64(value) => value.to_string(),
+        Value::F32(value) => json_number(*value)?,
+        Value::F64(value) => json_number(*value)?,
+        Value::String(value) => json::encode_string(value),
+        Value::Raw(value) => json::encode_string(&base64(value)),
+        Value::BoolArray(values) => json_array(values.iter().map(|value| Some(value.to_string())))?,
+        Value::I64Array(values) => json_array(values.iter().map(|value| Some(value.to_string())))?,
+        Value::F32Array(values) => json_array(values.iter().map(|value| json_number(*value)))?,
+        Value::F64Array(values) => json_array(values.iter().map(|value| json_number(*value)))?,
+        Value::StringArray(values) => {
+            json_array(values.iter().map(|value| Some(json::encode_string(value))))?
+        }
+        Value::Unassigned | Value::Unknown { .. } => return None,
+    })
+}
+
+fn json_number<T: Into<f64> + Copy + Debug>(value: T) -> Option<String> {
+    value.into().is_finite().then(|| format!("{value:?}"))
+}
+
+fn json_array(values: impl Iterator<Item = Option<String>>) -> Option<String> {
+    let values = values.collect::<Option<Vec<_>>>()?;
+    Some(format!("[{}]", values.join(",")))
+}
+
+/// Standard base64 with padding, which ntcore uses for raw values in persistent files.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | (u32::from(*byte) << (16 - 8 * index))
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(char::from(
+                    ALPHABET[((group >> (18 - 6 * index)) & 0x3f) as usize],
+                ));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
@@ -0,0 +1,67 @@
+//! Reusable buffers for draining queues.
+//!
+//! Reads that fill a `Vec` take it from a pool and give it back afterwards,
+//! so reading at a high rate doesn't allocate a new buffer every time.
+//! Buffers handed to the caller only come back if the caller recycles them, e.g. with
+//! [`TopicSubscriber::recycle`](crate::topic::TopicSubscriber::recycle).
+//! Every thread has its own buffers, so reads on different threads don't contend for them.
+
+use std::{cell::RefCell, thread::LocalKey};
+
+use crate::nt_types::RawValue;
+
+/// The most buffers a pool keeps per thread.
+const MAX_POOLED: usize = 8;
+/// Buffers that have grown beyond this many elements are dropped instead of pooled,
+/// so one unusually large read doesn't keep its memory alive forever.
+const MAX_POOLED_CAPACITY: usize = 4096;
+
+thread_local! {
+    static RAW_VALUE_BUFFERS: RefCell<Vec<Vec<RawValue>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Buffers of raw values used while reading subscriber and entry queues.
+pub(crate) static RAW_VALUES: BufferPool<RawValue> = BufferPool::new(&RAW_VALUE_BUFFERS);
+
+pub(crate) struct BufferPool<T: 'static> {
+    buffers: &'static LocalKey<RefCell<Vec<Vec<T>>>>,
+}
+
+impl<T> BufferPool<T> {
+    pub(crate) const fn new(buffers: &'static LocalKey<RefCell<Vec<Vec<T>>>>) -> Self {
+        Self { buffers }
+    }
+
+    /// Takes an empty buffer, reusing one that was given back on this thread if possible.
+    pub(crate) fn take(&self) -> Vec<T> {
+        // The buffers are gone while the thread is exiting, in which case a new buffer is fine.
+        self.buffers
+            .try_with(|buffers| buffers.borrow_mut().pop())
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    /// Gives a buffer back to the pool, clearing it.
+    pub(crate) fn give(&self, mut buffer: Vec<T>) {
+        if buffer.capacity() == 0 || buffer.capacity() > MAX_POOLED_CAPACITY {
+            return;
+        }
+        buffer.clear();
+
+        let _ = self.buffers.try_with(|buffers| {
+            let mut buffers = buffers.borrow_mut();
+            if buffers.len() < MAX_POOLED {
+                buffers.push(buffer);
+            }
+        });
+    }
+
+    /// Calls `f` with an empty buffer from the pool and gives it back afterwards.
+    pub(crate) fn with<R>(&self, f: impl FnOnce(&mut Vec<T>) -> R) -> R {
+        let mut buffer = self.take();
+        let result = f(&mut buffer);
+        self.give(buffer);
+        result
+    }
+}
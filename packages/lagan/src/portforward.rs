@@ -0,0 +1,84 @@
+//! TCP port forwarding, used to reach a roboRIO over USB the same way WPILib dashboards do.
+
+use std::{
+    collections::HashSet,
+    sync::{Mutex, OnceLock},
+};
+
+use ntcore_sys::{
+    wpinet::{WPI_AddPortForwarder, WPI_RemovePortForwarder},
+    WPI_String,
+};
+use snafu::ensure;
+
+use crate::{lock, NetworkTablesError, PortForwardedSnafu};
+
+/// The address of a roboRIO connected over USB.
+pub const ROBORIO_USB_ADDRESS: &str = "172.22.11.2";
+
+/// The local ports a [`PortForward`] currently forwards.
+fn forwarded() -> &'static Mutex<HashSet<u16>> {
+    static FORWARDED: OnceLock<Mutex<HashSet<u16>>> = OnceLock::new();
+    FORWARDED.get_or_init(Default::default)
+}
+
+/// Forwards a local TCP port to a remote host and port until dropped.
+///
+/// wpinet keeps one forward per local port, so only one [`PortForward`] can exist for each port at a time.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct PortForward {
+    port: u16,
+}
+
+impl PortForward {
+    /// Starts forwarding connections on `port` to `remote_host:remote_port`.
+    ///
+    /// # Parameters
+    ///
+    /// - `port`: The local port to listen on.
+    ///   Ports less than 1024 won't work as a normal user.
+    /// - `remote_host`: The IP address or DNS name to forward to.
+    /// - `remote_port`: The port on the remote host to forward to.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::PortForwarded`] if another [`PortForward`] already forwards `port`.
+    pub fn new(
+        port: u16,
+        remote_host: impl AsRef<str>,
+        remote_port: u16,
+    ) -> Result<Self, NetworkTablesError> {
+        ensure!(lock(forwarded()).insert(port), PortForwardedSnafu { port });
+        let remote_host = WPI_String::from(remote_host.as_ref());
+
+        unsafe {
+            WPI_AddPortForwarder(port as _, &raw const remote_host, remote_port as _);
+        }
+
+        Ok(Self { port })
+    }
+
+    /// Forwards `port` to the same port on a roboRIO connected over USB.
+    ///
+    /// # Errors
+    ///
+    /// See [`PortForward::new`].
+    pub fn roborio_usb(port: u16) -> Result<Self, NetworkTablesError> {
+        Self::new(port, ROBORIO_USB_ADDRESS, port)
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+}
+
+impl Drop for PortForward {
+    fn drop(&mut self) {
+        // Held until the forward is removed, so a new one on the same port can't be added in the meantime.
+        let mut forwarded = lock(forwarded());
+        unsafe {
+            WPI_RemovePortForwarder(self.port as _);
+        }
+        forwarded.remove(&self.port);
+    }
+}
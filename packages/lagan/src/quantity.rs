@@ -0,0 +1,310 @@
+//! Publishing and subscribing to physical quantities with their unit checked at compile time.
+//!
+//! Quantities are published as doubles holding the value in SI base units,
+//! and the unit of those values is stored in the topic's `unit` property (see [`crate::annotation`]), e.g. `"m/s^2"`.
+//! Subscribers refuse to read topics whose unit is a different one,
+//! so a length can't be read from a topic that a different version of the robot code publishes angles to.
+
+use std::{any::TypeId, fmt::Debug, marker::PhantomData};
+
+use uom::{
+    si::{
+        marker::{AngleKind, SolidAngleKind},
+        Dimension, Quantity, SI,
+    },
+    typenum::Integer,
+};
+
+use crate::{
+    nt_types::{PubSubOptions, Value, ValueType},
+    topic::{Topic, TopicPublisher, TopicSubscriber},
+    Instance, NetworkTablesError,
+};
+
+/// The symbols of the SI base units, in the order of the dimensions of [`Dimension`].
+const BASE_UNITS: [&str; 7] = ["m", "kg", "s", "A", "K", "mol", "cd"];
+
+/// A quantity that can be stored as a double in SI base units.
+///
+/// Implemented for every `f64` quantity of `uom`'s SI system, e.g. [`uom::si::f64::Length`].
+pub trait SiQuantity: Sized {
+    /// The value in SI base units.
+    fn to_si(&self) -> f64;
+
+    /// Creates a quantity from a value in SI base units.
+    fn from_si(value: f64) -> Self;
+
+    /// The unit of values in SI base units, e.g. `"m/s"`.
+    fn unit() -> String;
+}
+
+impl<D: Dimension + ?Sized> SiQuantity for Quantity<D, SI<f64>, f64>
+where
+    D::Kind: 'static,
+{
+    fn to_si(&self) -> f64 {
+        self.value
+    }
+
+    fn from_si(value: f64) -> Self {
+        Self {
+            dimension: PhantomData,
+            units: PhantomData,
+            value,
+        }
+    }
+
+    fn unit() -> String {
+        let kind = TypeId::of::<D::Kind>();
+        let dimensionless = if kind == TypeId::of::<dyn AngleKind>() {
+            Some("rad")
+        } else if kind == TypeId::of::<dyn SolidAngleKind>() {
+            Some("sr")
+        } else {
+            None
+        };
+        unit_string(
+            [
+                D::L::I32,
+                D::M::I32,
+                D::T::I32,
+                D::I::I32,
+                D::Th::I32,
+                D::N::I32,
+                D::J::I32,
+            ],
+            dimensionless,
+        )
+    }
+}
+
+/// Formats the unit with the given exponents of the SI base units, e.g. `"kg*m/s^2"` for a force.
+///
+/// `dimensionless` is the unit used in place of `1`, for quantities like angles which are dimensionless in SI.
+fn unit_string(exponents: [i32; 7], dimensionless: Option<&str>) -> String {
+    let term = |symbol: &str, exponent: i32| match exponent {
+        1 => symbol.to_string(),
+        exponent => format!("{symbol}^{exponent}"),
+    };
+    // Mass goes first, so a force reads like the usual `kg*m/s^2`.
+    let order = [1, 0, 2, 3, 4, 5, 6];
+
+    let mut numerator: Vec<String> = dimensionless.map(str::to_string).into_iter().collect();
+    numerator.extend(
+        order
+            .iter()
+            .filter(|&&index| exponents[index] > 0)
+            .map(|&index| term(BASE_UNITS[index], exponents[index])),
+    );
+    let denominator: Vec<String> = order
+        .iter()
+        .filter(|&&index| exponents[index] < 0)
+        .map(|&index| term(BASE_UNITS[index], -exponents[index]))
+        .collect();
+
+    let numerator = if numerator.is_empty() {
+        "1".to_string()
+    } else {
+        numerator.join("*")
+    };
+    match denominator.len() {
+        0 => numerator,
+        1 => format!("{numerator}/{}", denominator[0]),
+        _ => format!("{numerator}/({})", denominator.join("*")),
+    }
+}
+
+impl<I: Instance + ?Sized> Topic<'_, I> {
+    /// Starts publishing quantities of type `Q` to the topic, e.g. `topic.publish_quantity::<Length>(options)`.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the topic already exists with a type other than double.
+    pub fn publish_quantity<Q: SiQuantity>(
+        &self,
+        options: PubSubOptions,
+    ) -> Result<QuantityPublisher<'_, I, Q>, NetworkTablesError> {
+        QuantityPublisher::new(self, options)
+    }
+
+    /// Subscribes to quantities of type `Q` on the topic, e.g. `topic.subscribe_quantity::<Length>(options)`.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the topic already exists with a type other than double.
+    pub fn subscribe_quantity<Q: SiQuantity>(
+        &self,
+        options: PubSubOptions,
+    ) -> Result<QuantitySubscriber<'_, I, Q>, NetworkTablesError> {
+        QuantitySubscriber::new(self, options)
+    }
+}
+
+/// A double publisher that publishes quantities of type `Q` in SI base units.
+pub struct QuantityPublisher<'a, I: Instance + ?Sized, Q: SiQuantity> {
+    publisher: TopicPublisher<'a, I>,
+    _quantity: PhantomData<fn(Q)>,
+}
+
+impl<'a, I: Instance + ?Sized, Q: SiQuantity> QuantityPublisher<'a, I, Q> {
+    /// Starts publishing quantities to `topic` and sets its unit.
+    ///
+    /// NetworkTables 3 clients have no topic properties, so there the unit is not stored.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the topic already exists with a type other than double.
+    pub fn new(
+        topic: &'a Topic<'a, I>,
+        options: PubSubOptions,
+    ) -> Result<Self, NetworkTablesError> {
+        let publisher = topic.publish(ValueType::F64, "double", options)?;
+        if topic.instance.version().has_properties() {
+            topic.set_unit(Q::unit())?;
+        }
+        Ok(Self {
+            publisher,
+            _quantity: PhantomData,
+        })
+    }
+
+    pub fn set(&self, quantity: &Q) -> Result<(), NetworkTablesError> {
+        self.publisher.set_value(Value::F64(quantity.to_si()))
+    }
+
+    /// Like [`Self::set`], but without checking the type of the topic, see [`TopicPublisher::set_f64_fast`].
+    pub fn set_fast(&self, quantity: &Q) -> bool {
+        self.publisher.set_f64_fast(quantity.to_si())
+    }
+
+    pub fn publisher(&self) -> &TopicPublisher<'a, I> {
+        &self.publisher
+    }
+}
+
+impl<I: Instance + ?Sized + Debug, Q: SiQuantity> Debug for QuantityPublisher<'_, I, Q> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuantityPublisher")
+            .field("publisher", &self.publisher)
+            .field("unit", &Q::unit())
+            .finish()
+    }
+}
+
+/// A double subscriber that reads values in SI base units as quantities of type `Q`.
+///
+/// Values are only read while the topic's unit is the unit of `Q` or not set.
+pub struct QuantitySubscriber<'a, I: Instance + ?Sized, Q: SiQuantity> {
+    subscriber: TopicSubscriber<'a, I>,
+    unit: String,
+    _quantity: PhantomData<fn() -> Q>,
+}
+
+impl<'a, I: Instance + ?Sized, Q: SiQuantity> QuantitySubscriber<'a, I, Q> {
+    /// Subscribes to quantities on `topic`.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the topic already exists with a type other than double.
+    pub fn new(
+        topic: &'a Topic<'a, I>,
+        options: PubSubOptions,
+    ) -> Result<Self, NetworkTablesError> {
+        let subscriber = topic.subscribe(ValueType::F64, "double", options)?;
+        Ok(Self {
+            subscriber,
+            unit: Q::unit(),
+            _quantity: PhantomData,
+        })
+    }
+
+    /// Checks that the topic's unit is the unit of `Q`.
+    ///
+    /// Topics without a unit pass, since they may be published by code that doesn't set one.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::UnitMismatch`] if the topic has a different unit.
+    pub fn check_unit(&self) -> Result<(), NetworkTablesError> {
+        match self.subscriber.topic().unit() {
+            Some(found) if found != self.unit => Err(NetworkTablesError::UnitMismatch {
+                expected: self.unit.clone(),
+                found,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns all of the new quantities since the last read.
+    ///
+    /// If there have been no new updates, None is returned.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::UnitMismatch`] if the topic has a different unit than `Q`.
+    ///   The values are left in the queue.
+    pub fn try_read_update_queue(&self) -> Result<Option<Vec<Q>>, NetworkTablesError> {
+        self.check_unit()?;
+        let Some(values) = self
+            .subscriber
+            .try_read_update_queue_of_type(ValueType::F64)
+        else {
+            return Ok(None);
+        };
+
+        let quantities = values
+            .into_iter()
+            .filter_map(|value| match value {
+                Value::F64(value) => Some(Q::from_si(value)),
+                _ => None,
+            })
+            .collect();
+        Ok(Some(quantities))
+    }
+
+    /// Returns the latest quantity since the last read.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::UnitMismatch`] if the topic has a different unit than `Q`.
+    pub fn try_read_latest(&self) -> Result<Option<Q>, NetworkTablesError> {
+        Ok(self
+            .try_read_update_queue()?
+            .and_then(|quantities| quantities.into_iter().last()))
+    }
+
+    /// The unit of `Q`, which the topic's unit is checked against.
+    pub fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    pub fn subscriber(&self) -> &TopicSubscriber<'a, I> {
+        &self.subscriber
+    }
+}
+
+impl<I: Instance + ?Sized + Debug, Q: SiQuantity> Debug for QuantitySubscriber<'_, I, Q> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuantitySubscriber")
+            .field("subscriber", &self.subscriber)
+            .field("unit", &self.unit)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::unit_string;
+
+    #[test]
+    fn units_are_formatted() {
+        assert_eq!(unit_string([1, 0, 0, 0, 0, 0, 0], None), "m");
+        assert_eq!(unit_string([1, 0, -2, 0, 0, 0, 0], None), "m/s^2");
+        assert_eq!(unit_string([1, 1, -2, 0, 0, 0, 0], None), "kg*m/s^2");
+        assert_eq!(unit_string([-3, 1, 0, 0, 0, 0, 0], None), "kg/m^3");
+        assert_eq!(unit_string([0, 0, -1, 0, 0, 0, 0], None), "1/s");
+        assert_eq!(unit_string([0, 0, 0, 0, 0, 0, 0], None), "1");
+        assert_eq!(unit_string([0, 0, -1, 0, 0, 0, 0], Some("rad")), "rad/s");
+        assert_eq!(unit_string([2, 1, -3, -1, 0, 0, 0], None), "kg*m^2/(s^3*A)");
+    }
+}
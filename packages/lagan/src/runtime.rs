@@ -0,0 +1,222 @@
+//! Running periodic work without depending on a particular async runtime.
+//!
+//! Periodic work such as publishing statistics or delivering coalesced values is scheduled through a [`Runtime`].
+//! A thread-based runtime is always available; the `tokio` and `async-std` features add runtimes
+//! that spawn tasks onto those executors instead.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, ThreadId},
+    time::Duration,
+};
+
+use crate::lock;
+
+#[derive(Debug, Default)]
+struct TaskState {
+    stopped: AtomicBool,
+    /// Held while the work runs, so stopping can wait for a run in progress.
+    running: Mutex<()>,
+    /// The thread the work is running on, if it is running.
+    runner: Mutex<Option<ThreadId>>,
+}
+
+impl TaskState {
+    /// Runs `task` once, unless the work was stopped. Returns `false` once it was.
+    fn run(&self, task: &mut impl FnMut()) -> bool {
+        let _running = lock(&self.running);
+        if self.stopped.load(Ordering::Acquire) {
+            return false;
+        }
+        *lock(&self.runner) = Some(thread::current().id());
+        task();
+        *lock(&self.runner) = None;
+        true
+    }
+}
+
+/// A handle to periodic work that stops it when dropped.
+#[derive(Debug)]
+pub struct PeriodicTask {
+    state: Arc<TaskState>,
+}
+
+impl PeriodicTask {
+    fn new() -> (Self, Arc<TaskState>) {
+        let state = Arc::new(TaskState::default());
+        (
+            Self {
+                state: state.clone(),
+            },
+            state,
+        )
+    }
+
+    /// Stops the work. It will not run again, and a run that is already in progress is waited for,
+    /// unless the work stops itself from within that run.
+    pub fn stop(&self) {
+        self.state.stopped.store(true, Ordering::Release);
+        if *lock(&self.state.runner) != Some(thread::current().id()) {
+            drop(lock(&self.state.running));
+        }
+    }
+
+    /// Returns `true` if the work was stopped, or could not be started in the first place.
+    pub fn is_stopped(&self) -> bool {
+        self.state.stopped.load(Ordering::Acquire)
+    }
+
+    /// Lets the work run until the program exits.
+    pub fn detach(self) {
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for PeriodicTask {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Something that can run work periodically.
+pub trait Runtime {
+    /// Runs `task` every `period` until the returned handle is dropped.
+    fn spawn_periodic(
+        &self,
+        period: Duration,
+        task: impl FnMut() + Send + 'static,
+    ) -> PeriodicTask;
+}
+
+/// Runs each piece of periodic work on its own thread.
+///
+/// If the thread can't be spawned, the returned task is already stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ThreadRuntime;
+
+impl Runtime for ThreadRuntime {
+    fn spawn_periodic(
+        &self,
+        period: Duration,
+        mut task: impl FnMut() + Send + 'static,
+    ) -> PeriodicTask {
+        let (handle, state) = PeriodicTask::new();
+        let spawned = thread::Builder::new()
+            .name("lagan-periodic".to_string())
+            .spawn({
+                let state = state.clone();
+                move || {
+                    while state.run(&mut task) {
+                        thread::sleep(period);
+                    }
+                }
+            });
+        if spawned.is_err() {
+            state.stopped.store(true, Ordering::Release);
+        }
+        handle
+    }
+}
+
+/// Spawns periodic work onto the current tokio runtime.
+///
+/// Outside of a tokio runtime, the returned task is already stopped.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TokioRuntime;
+
+#[cfg(feature = "tokio")]
+impl Runtime for TokioRuntime {
+    fn spawn_periodic(
+        &self,
+        period: Duration,
+        mut task: impl FnMut() + Send + 'static,
+    ) -> PeriodicTask {
+        let (handle, state) = PeriodicTask::new();
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            state.stopped.store(true, Ordering::Release);
+            return handle;
+        };
+        runtime.spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if !state.run(&mut task) {
+                    break;
+                }
+            }
+        });
+        handle
+    }
+}
+
+/// Spawns periodic work onto the async-std executor.
+#[cfg(feature = "async-std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct AsyncStdRuntime;
+
+#[cfg(feature = "async-std")]
+impl Runtime for AsyncStdRuntime {
+    fn spawn_periodic(
+        &self,
+        period: Duration,
+        mut task: impl FnMut() + Send + 'static,
+    ) -> PeriodicTask {
+        let (handle, state) = PeriodicTask::new();
+        async_std::task::spawn(async move {
+            while state.run(&mut task) {
+                async_std::task::sleep(period).await;
+            }
+        });
+        handle
+    }
+}
+
+/// The runtime lagan uses for its own periodic work, such as delivering coalesced values.
+///
+/// This is always [`ThreadRuntime`], since it works from any thread. The tokio and async-std runtimes
+/// are never picked by enabling their features, as the tokio one only works inside a tokio runtime.
+/// Pass them explicitly where a [`Runtime`] is taken, e.g. to [`Stats::publish_periodically`](crate::stats::Stats::publish_periodically).
+pub type DefaultRuntime = ThreadRuntime;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use super::*;
+
+    #[test]
+    fn stopping_waits_for_the_run_in_progress() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let task = ThreadRuntime.spawn_periodic(Duration::from_millis(5), {
+            let runs = runs.clone();
+            move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(20));
+            }
+        });
+        thread::sleep(Duration::from_millis(60));
+        drop(task);
+
+        let stopped_at = runs.load(Ordering::SeqCst);
+        assert!(stopped_at > 0);
+        thread::sleep(Duration::from_millis(60));
+        assert_eq!(runs.load(Ordering::SeqCst), stopped_at);
+    }
+
+    #[test]
+    fn work_can_stop_itself() {
+        let slot = Arc::new(Mutex::new(None::<PeriodicTask>));
+        let task = ThreadRuntime.spawn_periodic(Duration::from_millis(5), {
+            let slot = slot.clone();
+            move || drop(lock(&slot).take())
+        });
+        *lock(&slot) = Some(task);
+
+        thread::sleep(Duration::from_millis(60));
+        assert!(lock(&slot).is_none());
+    }
+}
@@ -0,0 +1,134 @@
+//! Classifying topics by the schema of their data.
+//!
+//! WPILib publishes structs and protobuf messages as raw topics with type strings like `struct:Pose2d`,
+//! and publishes the schema describing each of them to a topic under [`SCHEMA_PREFIX`].
+//! [`TopicKind`] does the string matching once, so tools can group and decode such topics without it.
+
+use std::{collections::HashMap, marker::PhantomData};
+
+use ntcore_sys::{NT_MultiSubscriber, NT_SubscribeMultiple, NT_UnsubscribeMultiple, WPI_String};
+
+use crate::{
+    nt_types::{PubSubOptions, Value},
+    shutdown::InstanceGuard,
+    Instance,
+};
+
+/// The prefix of the topics schemas are published to.
+pub const SCHEMA_PREFIX: &str = "/.schema/";
+
+/// What kind of data a topic holds, as told by its name and type string.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum TopicKind {
+    /// A topic of a plain NetworkTables type, or a raw topic of an unknown format.
+    #[default]
+    Plain,
+    /// A raw topic holding WPILib structs.
+    Struct(StructType),
+    /// A raw topic holding a protobuf message of the named type.
+    Protobuf(String),
+    /// A topic under [`SCHEMA_PREFIX`], describing how to decode other topics.
+    Schema,
+}
+
+/// The struct type of a topic, from a type string like `struct:Pose2d` or `struct:Pose2d[]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StructType {
+    /// The name of the struct, e.g. `Pose2d`.
+    pub name: String,
+    /// Whether the topic holds an array of structs instead of a single one.
+    pub array: bool,
+    /// The schema of the struct, e.g. `Translation2d translation;Rotation2d rotation`,
+    /// or `None` if no schema was registered or announced for it.
+    pub schema: Option<String>,
+}
+
+impl TopicKind {
+    /// Classifies a topic without resolving the schema of structs.
+    pub fn parse(name: &str, type_string: &str) -> Self {
+        if name.starts_with(SCHEMA_PREFIX) {
+            Self::Schema
+        } else if let Some(struct_type) = type_string.strip_prefix("struct:") {
+            let (name, array) = match struct_type.strip_suffix("[]") {
+                Some(name) => (name, true),
+                None => (struct_type, false),
+            };
+            Self::Struct(StructType {
+                name: name.to_string(),
+                array,
+                schema: None,
+            })
+        } else if let Some(message) = type_string.strip_prefix("proto:") {
+            Self::Protobuf(message.to_string())
+        } else {
+            Self::Plain
+        }
+    }
+}
+
+/// Looks up struct schemas in the schema topics of an instance, remembering every lookup.
+#[derive(Debug, Default)]
+pub(crate) struct SchemaCache {
+    schemas: HashMap<String, Option<String>>,
+}
+
+impl SchemaCache {
+    /// Classifies a topic, resolving the schema of structs against the schema topics of `instance`.
+    pub(crate) fn classify<I: Instance + ?Sized>(
+        &mut self,
+        instance: &I,
+        name: &str,
+        type_string: &str,
+    ) -> TopicKind {
+        let mut kind = TopicKind::parse(name, type_string);
+        if let TopicKind::Struct(struct_type) = &mut kind {
+            struct_type.schema = self
+                .schemas
+                .entry(struct_type.name.clone())
+                .or_insert_with(|| {
+                    let topic = format!("{SCHEMA_PREFIX}struct:{}", struct_type.name);
+                    match instance.entry(topic).value() {
+                        Value::Raw(schema) => String::from_utf8(schema).ok(),
+                        _ => None,
+                    }
+                })
+                .clone();
+        }
+        kind
+    }
+}
+
+/// Keeps the values of every schema topic available locally until dropped.
+///
+/// Clients only receive the values of topics something subscribed to,
+/// so without this the schemas of structs are unknown to [`TopicKind`] on clients.
+#[derive(Debug)]
+#[must_use = "the schemas are unsubscribed from when this is dropped"]
+pub struct SchemaSubscription<'a, I: Instance + ?Sized> {
+    subscriber: NT_MultiSubscriber,
+    guard: InstanceGuard,
+    _instance: PhantomData<&'a I>,
+}
+
+impl<'a, I: Instance + ?Sized> SchemaSubscription<'a, I> {
+    pub(crate) fn new(instance: &'a I) -> Self {
+        let prefix = WPI_String::from(SCHEMA_PREFIX);
+        let options = PubSubOptions::default().into();
+
+        let subscriber = unsafe {
+            NT_SubscribeMultiple(instance.handle(), &raw const prefix, 1, &raw const options)
+        };
+        Self {
+            subscriber,
+            guard: InstanceGuard::new(unsafe { instance.handle() }),
+            _instance: PhantomData,
+        }
+    }
+}
+
+impl<I: Instance + ?Sized> Drop for SchemaSubscription<'_, I> {
+    fn drop(&mut self) {
+        self.guard
+            .release(|| unsafe { NT_UnsubscribeMultiple(self.subscriber) });
+    }
+}
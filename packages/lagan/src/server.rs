@@ -1,16 +1,37 @@
-use std::{ffi::CString, net::SocketAddr};
+use std::{
+    hash::{Hash, Hasher},
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 use ntcore_sys::{
-    NT_AddLogger, NT_DestroyInstance, NT_GetDefaultInstance, NT_Inst, NT_StartServer,
-    NT_StopServer, WPI_String,
+    NT_GetDefaultInstance, NT_Inst, NT_StartServer, NT_StopServer, WPI_String,
 };
+use snafu::{ensure, OptionExt};
+#[cfg(feature = "builder")]
 use typed_builder::TypedBuilder;
 
-use crate::Instance;
+use crate::{
+    connection::{ConnectionEvents, ConnectionInfo},
+    persistence, shutdown, Instance, InstanceInUseSnafu, NetworkTablesError, ServerRunningSnafu,
+    UnknownServerOptionsSnafu,
+};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A NetworkTables server on the default instance.
+///
+/// Only one server can be started at a time. Clones and handles from [`Server::shared`] share its instance,
+/// which is destroyed once every one of them is dropped.
+#[derive(Debug)]
 pub struct Server {
     instance: NT_Inst,
+    /// Shared with clones, so stopping any of them stops them all.
+    stopped: Arc<AtomicBool>,
+    /// The options the server was started with, or `None` if it was adopted with [`Instance::from_handle`]
+    /// or shared with [`Server::shared`].
+    options: Option<ServerOptions>,
 }
 
 impl Server {
@@ -25,52 +46,167 @@ impl Server {
     ///   If `None`, the server will listen on all addresses.
     /// - `nt3_port`: The port to listen for NetworkTables V3 clients on.
     /// - `nt4_port`: The port to listen for NetworkTables V4 clients on.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InstanceInUse`] if another server already runs on the default instance.
+    ///   Use [`Server::shared`] to get another handle to it instead.
+    /// - [`NetworkTablesError::Persistence`] if the persistent file exists but can't be loaded, e.g. because it is corrupted.
+    ///   ntcore would start without its values and overwrite it, so the server isn't started.
+    ///   A missing file is not an error, since ntcore creates it.
     pub fn new(
         persist_filename: impl AsRef<str>,
         listen_address: Option<SocketAddr>,
         nt3_port: u16,
         nt4_port: u16,
-    ) -> Self {
+    ) -> Result<Self, NetworkTablesError> {
+        let options = ServerOptions {
+            persist_filename: persist_filename.as_ref().to_string(),
+            listen_address,
+            nt3_port,
+            nt4_port,
+        };
+        persistence::check(&options.persist_filename)?;
         let instance = unsafe { NT_GetDefaultInstance() };
+        ensure!(
+            shutdown::acquire_unowned_instance(instance),
+            InstanceInUseSnafu
+        );
+        crate::add_default_logger(instance);
+        start(instance, &options);
+
+        Ok(Self {
+            instance,
+            stopped: Arc::default(),
+            options: Some(options),
+        })
+    }
 
-        //TODO: Are these WPI_String pointers supposed to be static?
-        unsafe {
-            NT_AddLogger(
-                instance,
-                0,
-                u32::MAX,
-                std::ptr::null_mut(),
-                crate::default_log_callback,
-            );
-
-            let persist_filename = CString::new(persist_filename.as_ref()).unwrap();
-            let persist_filename = WPI_String::from(persist_filename.as_c_str());
-
-            let listen_address = listen_address.map(|address| {
-                let address = CString::new(address.ip().to_string()).unwrap();
-                WPI_String::from(address.as_c_str())
-            });
-
-            NT_StartServer(
-                instance,
-                &raw const persist_filename,
-                listen_address
-                    .map(|la| &raw const la)
-                    .unwrap_or(std::ptr::null()),
-                nt3_port as _,
-                nt4_port as _,
-            );
+    /// Returns another handle to the server that already runs on the default instance,
+    /// or `None` if no server was started there.
+    ///
+    /// Like clones, the handles share the instance, which is destroyed once every one of them is dropped.
+    /// The options the server was started with are unknown to the new handle,
+    /// so it behaves like a server adopted with [`Instance::from_handle`].
+    pub fn shared() -> Option<Self> {
+        let instance = unsafe { NT_GetDefaultInstance() };
+        shutdown::acquire_owned_instance(instance).then_some(Self {
+            instance,
+            stopped: Arc::default(),
+            options: None,
+        })
+    }
+
+    /// The file the server persists its data to, or `None` if the server was adopted with [`Instance::from_handle`].
+    ///
+    /// ntcore saves this file on its own about a second after a persistent value changes,
+    /// use [`Server::save_persistent`] to save it right away.
+    pub fn persist_filename(&self) -> Option<&str> {
+        self.options
+            .as_ref()
+            .map(|options| options.persist_filename.as_str())
+    }
+
+    /// Switches the server to another persistent file, which it uses once it is restarted with [`Server::restart`].
+    ///
+    /// ntcore only reads the persist filename when the server starts, and restarting disconnects every client,
+    /// so the server has to be stopped first. Values in the new file are loaded on top of the current ones.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::ServerRunning`] if the server hasn't been stopped with [`Server::stop`].
+    /// - [`NetworkTablesError::UnknownServerOptions`] if the server was adopted with [`Instance::from_handle`],
+    ///   since its other options are unknown and it can't be restarted.
+    pub fn set_persist_filename(
+        &mut self,
+        persist_filename: impl AsRef<str>,
+    ) -> Result<(), NetworkTablesError> {
+        ensure!(self.is_stopped(), ServerRunningSnafu);
+        let options = self.options.as_mut().context(UnknownServerOptionsSnafu)?;
+        options.persist_filename = persist_filename.as_ref().to_string();
+        Ok(())
+    }
+
+    /// Starts a stopped server again with the options it was created with.
+    ///
+    /// Restarting a running server does nothing.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::Persistence`] if the persistent file exists but can't be loaded, see [`Server::new`].
+    /// - [`NetworkTablesError::UnknownServerOptions`] if the server was adopted with [`Instance::from_handle`],
+    ///   since its options are unknown.
+    pub fn restart(&mut self) -> Result<(), NetworkTablesError> {
+        let options = self.options.as_ref().context(UnknownServerOptionsSnafu)?;
+        if self.is_stopped() {
+            persistence::check(&options.persist_filename)?;
+            start(self.instance, options);
+            self.stopped.store(false, Ordering::SeqCst);
         }
+        Ok(())
+    }
 
-        Self { instance }
+    /// Writes every persistent value to the persistent file right away.
+    ///
+    /// ntcore saves persistent values on its own about a second after they change,
+    /// but there's no way to tell when it did. Use this when a value must be on disk before moving on,
+    /// e.g. to confirm that an autonomous selection survives a reboot.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::Persistence`] if the file could not be written.
+    /// - [`NetworkTablesError::UnknownServerOptions`] if the server was adopted with [`Instance::from_handle`],
+    ///   since its persistent file is unknown.
+    pub fn save_persistent(&self) -> Result<(), NetworkTablesError> {
+        let filename = self.persist_filename().context(UnknownServerOptionsSnafu)?;
+        persistence::save(filename, &persistence::snapshot(self))
     }
 
+    /// Stops the server without destroying the instance.
+    ///
+    /// Entries and topics obtained from this server remain valid, but no longer communicate over the network.
+    /// Clones of the server are stopped as well. Stopping an already stopped server does nothing.
+    pub fn stop(&mut self) {
+        if !self.stopped.swap(true, Ordering::SeqCst) {
+            unsafe { NT_StopServer(self.instance) };
+        }
+    }
+
+    /// Returns `true` if [`Server::stop`] has been called on this server or one of its clones.
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Returns every client currently connected to this server.
+    ///
+    /// The [`ConnectionInfo::remote_id`] of each client is the identity it was started with,
+    /// which makes it possible to tell dashboards and co-processors apart.
+    pub fn clients(&self) -> Vec<ConnectionInfo> {
+        self.connections()
+    }
+
+    /// Creates a queue of events for clients connecting to and disconnecting from this server.
+    ///
+    /// Clients that are already connected are reported as connected the first time the queue is read.
+    pub fn client_events(&self) -> ConnectionEvents<'_, Self> {
+        self.connection_events()
+    }
+
+    #[cfg(feature = "builder")]
     pub fn builder() -> ServerOptionsBuilder {
         ServerOptions::builder()
     }
 }
 
 impl Instance for Server {
+    unsafe fn from_handle(handle: NT_Inst) -> Self {
+        shutdown::acquire_instance(handle);
+        Self {
+            instance: handle,
+            stopped: Arc::default(),
+            options: None,
+        }
+    }
     unsafe fn handle(&self) -> NT_Inst {
         self.instance
     }
@@ -79,28 +215,51 @@ impl Instance for Server {
     }
 }
 
+impl Clone for Server {
+    fn clone(&self) -> Self {
+        shutdown::acquire_instance(self.instance);
+        Self {
+            instance: self.instance,
+            stopped: Arc::clone(&self.stopped),
+            options: self.options.clone(),
+        }
+    }
+}
+
+impl PartialEq for Server {
+    fn eq(&self, other: &Self) -> bool {
+        self.instance == other.instance && self.options == other.options
+    }
+}
+impl Eq for Server {}
+impl Hash for Server {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.instance.hash(state);
+        self.options.hash(state);
+    }
+}
+
 impl Drop for Server {
     fn drop(&mut self) {
-        unsafe {
-            NT_StopServer(self.instance);
-            NT_DestroyInstance(self.instance);
-        }
+        // Listeners, subscribers and publishers that outlive the instance stop releasing their handles first.
+        let instance = self.instance;
+        shutdown::release_instance(instance, || self.stop());
     }
 }
 
-#[derive(Debug, Clone, TypedBuilder)]
-#[builder(build_method(into = Server))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "builder", derive(TypedBuilder), builder(build_method(into = Result<Server, NetworkTablesError>)))]
 pub struct ServerOptions {
-    #[builder(setter(transform = |name: impl AsRef<str>| name.as_ref().to_string()))]
+    #[cfg_attr(feature = "builder", builder(setter(transform = |name: impl AsRef<str>| name.as_ref().to_string())))]
     pub persist_filename: String,
-    #[builder(default = None, setter(strip_option))]
+    #[cfg_attr(feature = "builder", builder(default = None, setter(strip_option)))]
     pub listen_address: Option<SocketAddr>,
-    #[builder(default = 1735)]
+    #[cfg_attr(feature = "builder", builder(default = 1735))]
     pub nt3_port: u16,
-    #[builder(default = 5810)]
+    #[cfg_attr(feature = "builder", builder(default = 5810))]
     pub nt4_port: u16,
 }
-impl From<ServerOptions> for Server {
+impl From<ServerOptions> for Result<Server, NetworkTablesError> {
     fn from(options: ServerOptions) -> Self {
         Server::new(
             options.persist_filename,
@@ -110,3 +269,26 @@ impl From<ServerOptions> for Server {
         )
     }
 }
+
+/// Starts the server of `instance`.
+fn start(instance: NT_Inst, options: &ServerOptions) {
+    //TODO: Are these WPI_String pointers supposed to be static?
+    let persist_filename = WPI_String::from(options.persist_filename.as_str());
+
+    let listen_address = options
+        .listen_address
+        .map(|address| address.ip().to_string());
+    let listen_address = listen_address.as_deref().map(WPI_String::from);
+
+    unsafe {
+        NT_StartServer(
+            instance,
+            &raw const persist_filename,
+            listen_address
+                .as_ref()
+                .map_or(std::ptr::null(), |la| la as *const WPI_String),
+            options.nt3_port as _,
+            options.nt4_port as _,
+        );
+    }
+}
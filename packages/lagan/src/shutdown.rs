@@ -0,0 +1,124 @@
+//! Keeping handles from being released after the instance they belong to is destroyed.
+//!
+//! ntcore hands out the same handle values again when an instance is destroyed and another one created in its place,
+//! which always happens with the default instance. Releasing a handle after its instance was destroyed
+//! could therefore release a listener or subscriber of the new instance instead.
+//!
+//! Everything that releases handles when dropped holds an [`InstanceGuard`] and only releases them through it,
+//! and destroying an instance invalidates every guard of it before `NT_DestroyInstance` runs,
+//! which releases the handles of the instance itself.
+//!
+//! Several [`Client`](crate::Client)s and [`Server`](crate::Server)s may own the same instance,
+//! e.g. clones, or two servers on the default instance. The instance is only destroyed once the last of them is dropped.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+use ntcore_sys::{NT_DestroyInstance, NT_Handle, NT_Inst};
+
+use crate::{instance_of, intern, lock};
+
+/// How many times each instance handle was destroyed.
+type Generations = Mutex<HashMap<NT_Inst, u64>>;
+/// How many clients and servers own each instance.
+type Owners = Mutex<HashMap<NT_Inst, usize>>;
+
+fn generations() -> &'static Generations {
+    static GENERATIONS: OnceLock<Generations> = OnceLock::new();
+    GENERATIONS.get_or_init(Default::default)
+}
+
+fn owners() -> &'static Owners {
+    static OWNERS: OnceLock<Owners> = OnceLock::new();
+    OWNERS.get_or_init(Default::default)
+}
+
+/// Remembers which instance a handle was created in, to tell whether that instance still exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct InstanceGuard {
+    instance: NT_Inst,
+    generation: u64,
+}
+
+impl InstanceGuard {
+    /// Returns a guard for handles created in `instance` from now on.
+    pub(crate) fn new(instance: NT_Inst) -> Self {
+        let generation = lock(generations()).get(&instance).copied().unwrap_or(0);
+        Self {
+            instance,
+            generation,
+        }
+    }
+
+    /// Returns a guard for the instance that owns `handle`.
+    ///
+    /// If `handle` is invalid, the guard never releases anything.
+    pub(crate) fn of(handle: NT_Handle) -> Self {
+        Self::new(instance_of(handle).unwrap_or(0))
+    }
+
+    /// Calls `release` unless the instance was destroyed since the guard was created,
+    /// and keeps it from being destroyed until `release` returns.
+    ///
+    /// Returns `false` if `release` wasn't called.
+    /// `release` must not wait for listener callbacks, which may be dropping handles themselves.
+    pub(crate) fn release(&self, release: impl FnOnce()) -> bool {
+        let generations = lock(generations());
+        let generation = generations.get(&self.instance).copied().unwrap_or(0);
+        let alive = self.instance != 0 && generation == self.generation;
+        if alive {
+            release();
+        }
+        alive
+    }
+}
+
+/// Registers another owner of `instance`, and returns `true` if it is the only one.
+pub(crate) fn acquire_instance(instance: NT_Inst) -> bool {
+    let mut owners = lock(owners());
+    let count = owners.entry(instance).or_default();
+    *count += 1;
+    *count == 1
+}
+
+/// Registers the first owner of `instance`, and returns `false` without registering it if there already is one.
+pub(crate) fn acquire_unowned_instance(instance: NT_Inst) -> bool {
+    let mut owners = lock(owners());
+    if owners.contains_key(&instance) {
+        return false;
+    }
+    owners.insert(instance, 1);
+    true
+}
+
+/// Registers another owner of `instance`, and returns `false` without registering it if there is no owner yet.
+pub(crate) fn acquire_owned_instance(instance: NT_Inst) -> bool {
+    let mut owners = lock(owners());
+    let Some(count) = owners.get_mut(&instance) else {
+        return false;
+    };
+    *count += 1;
+    true
+}
+
+/// Unregisters an owner of `instance`.
+///
+/// The last owner calls `stop`, invalidates every [`InstanceGuard`] of the instance, then destroys it.
+pub(crate) fn release_instance(instance: NT_Inst, stop: impl FnOnce()) {
+    // Held until the instance is destroyed, so a new owner can't acquire it in the meantime.
+    let mut owners = lock(owners());
+    if let Some(count) = owners.get_mut(&instance) {
+        *count -= 1;
+        if *count > 0 {
+            return;
+        }
+    }
+    owners.remove(&instance);
+
+    stop();
+    *lock(generations()).entry(instance).or_default() += 1;
+    unsafe { NT_DestroyInstance(instance) };
+    intern::forget_instance(instance);
+}
@@ -0,0 +1,423 @@
+//! Per-topic update rate and bandwidth statistics.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
+#[cfg(feature = "log")]
+use log::warn;
+use ntcore_sys::{
+    NT_AddPolledListenerSingle, NT_CreateListenerPoller, NT_DestroyListenerPoller, NT_EventFlags,
+    NT_GetTopicName, NT_Listener, NT_ListenerPoller, NT_ReadListenerQueue, NT_RemoveListener,
+    NT_Topic, NtEventArray, WpiStringBuf, WPI_String,
+};
+
+use crate::{
+    intern,
+    nt_types::{NetworkTablesInstant, RawValue, Value},
+    runtime::{PeriodicTask, Runtime},
+    shutdown::InstanceGuard,
+    Instance, NetworkTablesError,
+};
+
+/// The prefix [`Stats::publish`] publishes statistics under.
+pub const STATS_PREFIX: &str = "/lagan/stats";
+
+/// The window statistics are calculated over by default.
+pub const DEFAULT_STATS_WINDOW: Duration = Duration::from_secs(5);
+
+/// Returns the approximate number of bytes a value takes up on the wire, not counting protocol overhead.
+pub fn payload_size(value: &Value) -> usize {
+    match value {
+        Value::Unassigned => 0,
+        Value::Bool(_) => 1,
+        Value::I64(_) | Value::F64(_) => 8,
+        Value::F32(_) => 4,
+        Value::String(string) => string.len(),
+        Value::Raw(data) => data.len(),
+        Value::BoolArray(array) => array.len(),
+        Value::F64Array(array) => array.len() * 8,
+        Value::F32Array(array) => array.len() * 4,
+        Value::I64Array(array) => array.len() * 8,
+        Value::StringArray(array) => array.iter().map(String::len).sum(),
+        Value::Unknown { .. } => 0,
+    }
+}
+
+/// Statistics for a single topic over the collector's window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopicStats {
+    pub name: Arc<str>,
+    /// Value updates per second.
+    pub update_rate: f64,
+    /// Payload bytes per second.
+    pub byte_rate: f64,
+    /// Time since the last update.
+    pub last_update_age: Duration,
+    /// Updates seen since the collector was created.
+    pub total_updates: u64,
+    /// Payload bytes seen since the collector was created.
+    pub total_bytes: u64,
+}
+
+/// A limit on the aggregate payload bytes per second of every topic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BandwidthBudget {
+    /// The maximum number of payload bytes per second.
+    pub bytes_per_second: f64,
+    /// How many of the busiest topics are reported when the budget is exceeded.
+    pub top_offenders: usize,
+}
+impl BandwidthBudget {
+    pub fn new(bytes_per_second: f64) -> Self {
+        Self {
+            bytes_per_second,
+            top_offenders: 3,
+        }
+    }
+}
+
+/// Reported when the aggregate byte rate exceeds a [`BandwidthBudget`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetExceeded {
+    /// The aggregate payload bytes per second of every topic.
+    pub byte_rate: f64,
+    pub budget: BandwidthBudget,
+    /// The busiest topics, busiest first.
+    pub offenders: Vec<TopicStats>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TopicWindow {
+    /// The time and payload size of every update inside the window.
+    updates: VecDeque<(NetworkTablesInstant, usize)>,
+    last_update: Option<NetworkTablesInstant>,
+    total_updates: u64,
+    total_bytes: u64,
+}
+
+impl TopicWindow {
+    fn push(&mut self, time: NetworkTablesInstant, size: usize) {
+        self.updates.push_back((time, size));
+        self.last_update = Some(time);
+        self.total_updates += 1;
+        self.total_bytes += size as u64;
+    }
+
+    fn trim(&mut self, now: NetworkTablesInstant, window: Duration) {
+        while let Some((time, _)) = self.updates.front() {
+            if now.saturating_duration_since(*time) <= window {
+                break;
+            }
+            self.updates.pop_front();
+        }
+    }
+}
+
+/// Per-topic update statistics over a sliding window, fed with updates by hand.
+///
+/// [`Stats`] feeds one from a listener poller. Use it directly when updates already arrive another way,
+/// such as through an [`EventReceiver`](crate::channel::EventReceiver).
+#[derive(Debug, Clone)]
+pub struct UpdateStats {
+    window: Duration,
+    topics: HashMap<Arc<str>, TopicWindow>,
+}
+
+impl Default for UpdateStats {
+    fn default() -> Self {
+        Self::new(DEFAULT_STATS_WINDOW)
+    }
+}
+
+impl UpdateStats {
+    /// Creates empty statistics calculated over the last `window`.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            topics: HashMap::new(),
+        }
+    }
+
+    /// Counts an update of the topic `name`, at the time the value last changed.
+    pub fn record(&mut self, name: Arc<str>, value: &RawValue) {
+        self.topics
+            .entry(name)
+            .or_default()
+            .push(value.last_change, payload_size(&value.data));
+    }
+
+    /// Forgets the updates that have left the window.
+    pub fn trim(&mut self) {
+        let now = NetworkTablesInstant::now();
+        for window in self.topics.values_mut() {
+            window.trim(now, self.window);
+        }
+    }
+
+    /// Returns the aggregate payload bytes per second of every topic.
+    pub fn byte_rate(&self) -> f64 {
+        let bytes = self
+            .topics
+            .values()
+            .flat_map(|window| window.updates.iter().map(|(_, size)| *size))
+            .sum::<usize>();
+        bytes as f64 / self.window.as_secs_f64()
+    }
+
+    /// Returns statistics for a single topic, if it has been updated.
+    pub fn topic(&self, name: &str) -> Option<TopicStats> {
+        let (name, window) = self.topics.get_key_value(name)?;
+        Some(self.stats(name, window, NetworkTablesInstant::now()))
+    }
+
+    /// Returns statistics for every topic that has been updated, busiest first.
+    pub fn report(&self) -> Vec<TopicStats> {
+        let now = NetworkTablesInstant::now();
+        let mut report = self
+            .topics
+            .iter()
+            .map(|(name, window)| self.stats(name, window, now))
+            .collect::<Vec<_>>();
+        report.sort_by(|a, b| b.byte_rate.total_cmp(&a.byte_rate));
+        report
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    fn stats(
+        &self,
+        name: &Arc<str>,
+        window: &TopicWindow,
+        now: NetworkTablesInstant,
+    ) -> TopicStats {
+        let seconds = self.window.as_secs_f64();
+        let bytes = window.updates.iter().map(|(_, size)| *size).sum::<usize>();
+
+        TopicStats {
+            name: name.clone(),
+            update_rate: window.updates.len() as f64 / seconds,
+            byte_rate: bytes as f64 / seconds,
+            last_update_age: window
+                .last_update
+                .map(|time| now.saturating_duration_since(time))
+                .unwrap_or_default(),
+            total_updates: window.total_updates,
+            total_bytes: window.total_bytes,
+        }
+    }
+}
+
+/// An opt-in collector of per-topic update statistics.
+///
+/// Every value update seen by the instance is counted, remote and local.
+/// Call [`Stats::update`] regularly to process new updates.
+pub struct Stats<'a, I: Instance + ?Sized> {
+    instance: &'a I,
+    guard: InstanceGuard,
+    poller: NT_ListenerPoller,
+    listener: NT_Listener,
+    names: HashMap<NT_Topic, Arc<str>>,
+    updates: UpdateStats,
+    budget: Option<BandwidthBudget>,
+    budget_callback: Option<Box<dyn FnMut(&BudgetExceeded) + Send + 'a>>,
+    last_budget_warning: Option<NetworkTablesInstant>,
+}
+
+impl<'a, I: Instance + ?Sized> Stats<'a, I> {
+    /// Starts collecting statistics for every topic, calculated over the last `window`.
+    pub fn new(instance: &'a I, window: Duration) -> Self {
+        let prefix = WPI_String::from("");
+        let (poller, listener) = unsafe {
+            let poller = NT_CreateListenerPoller(instance.handle());
+            let listener = NT_AddPolledListenerSingle(
+                poller,
+                &raw const prefix,
+                NT_EventFlags::NT_EVENT_VALUE_ALL.bits(),
+            );
+            (poller, listener)
+        };
+
+        Self {
+            instance,
+            guard: InstanceGuard::new(unsafe { instance.handle() }),
+            poller,
+            listener,
+            names: HashMap::new(),
+            updates: UpdateStats::new(window),
+            budget: None,
+            budget_callback: None,
+            last_budget_warning: None,
+        }
+    }
+
+    /// Warns when the aggregate byte rate of every topic exceeds `budget`.
+    ///
+    /// Without a callback set with [`Self::on_budget_exceeded`], a warning naming the busiest topics is logged if the `log` feature is enabled.
+    /// Warnings are issued at most once per window.
+    pub fn set_budget(&mut self, budget: Option<BandwidthBudget>) {
+        self.budget = budget;
+    }
+
+    /// Calls `callback` instead of logging a warning when the budget is exceeded.
+    pub fn on_budget_exceeded(&mut self, callback: impl FnMut(&BudgetExceeded) + Send + 'a) {
+        self.budget_callback = Some(Box::new(callback));
+    }
+
+    /// Returns the aggregate payload bytes per second of every topic.
+    pub fn byte_rate(&self) -> f64 {
+        self.updates.byte_rate()
+    }
+
+    /// Processes every update since the last call.
+    pub fn update(&mut self) {
+        let mut count = 0;
+        let events = unsafe {
+            NtEventArray::from_raw(NT_ReadListenerQueue(self.poller, &raw mut count), count)
+        };
+
+        for event in events.iter() {
+            if event.flags & NT_EventFlags::NT_EVENT_VALUE_ALL.bits() == 0 {
+                continue;
+            }
+            let data = unsafe { event.data.valueData };
+
+            let instance = unsafe { self.instance.handle() };
+            let name = self.names.entry(data.topic).or_insert_with(|| {
+                let mut name = WpiStringBuf::new();
+                unsafe { NT_GetTopicName(data.topic, name.as_mut_ptr()) };
+                intern::intern(instance, &name.to_str_lossy())
+            });
+            // Don't count the statistics we publish ourselves.
+            if name.starts_with(STATS_PREFIX) {
+                continue;
+            }
+
+            self.updates.record(name.clone(), &RawValue::from(data.value));
+        }
+
+        self.updates.trim();
+        self.check_budget(NetworkTablesInstant::now());
+    }
+
+    fn check_budget(&mut self, now: NetworkTablesInstant) {
+        let Some(budget) = self.budget else {
+            return;
+        };
+        let byte_rate = self.byte_rate();
+        if byte_rate <= budget.bytes_per_second {
+            return;
+        }
+        if self
+            .last_budget_warning
+            .is_some_and(|last| now.saturating_duration_since(last) < self.updates.window())
+        {
+            return;
+        }
+        self.last_budget_warning = Some(now);
+
+        let mut offenders = self.report();
+        offenders.truncate(budget.top_offenders);
+        let exceeded = BudgetExceeded {
+            byte_rate,
+            budget,
+            offenders,
+        };
+
+        match &mut self.budget_callback {
+            Some(callback) => callback(&exceeded),
+            #[cfg(feature = "log")]
+            None => {
+                let offenders = exceeded
+                    .offenders
+                    .iter()
+                    .map(|stats| format!("{} ({:.0} B/s)", stats.name, stats.byte_rate))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                warn!(
+                    "NetworkTables bandwidth budget exceeded: {:.0} B/s of {:.0} B/s. Busiest topics: {}",
+                    byte_rate, budget.bytes_per_second, offenders
+                );
+            }
+            #[cfg(not(feature = "log"))]
+            None => {}
+        }
+    }
+
+    /// Returns statistics for a single topic, if it has been updated.
+    pub fn topic(&self, name: &str) -> Option<TopicStats> {
+        self.updates.topic(name)
+    }
+
+    /// Returns statistics for every topic that has been updated, busiest first.
+    pub fn report(&self) -> Vec<TopicStats> {
+        self.updates.report()
+    }
+
+    /// Publishes the current report to the instance under [`STATS_PREFIX`].
+    ///
+    /// Each topic gets `update_rate`, `byte_rate` and `last_update_age` (in seconds) entries
+    /// under `/lagan/stats/<topic name>`.
+    pub fn publish(&self) -> Result<(), NetworkTablesError> {
+        for stats in self.report() {
+            let table = format!("{STATS_PREFIX}/{}", stats.name.trim_start_matches('/'));
+            self.instance
+                .entry(format!("{table}/update_rate"))
+                .set_value_f64(stats.update_rate)?;
+            self.instance
+                .entry(format!("{table}/byte_rate"))
+                .set_value_f64(stats.byte_rate)?;
+            self.instance
+                .entry(format!("{table}/last_update_age"))
+                .set_value_f64(stats.last_update_age.as_secs_f64())?;
+        }
+        Ok(())
+    }
+
+    pub fn window(&self) -> Duration {
+        self.updates.window()
+    }
+
+    /// Calls [`Stats::update`] and [`Stats::publish`] every `period` on `runtime` until the returned task is dropped.
+    ///
+    /// Failures to publish are logged if the `log` feature is enabled, and publishing is tried again next period.
+    pub fn publish_periodically(mut self, runtime: &impl Runtime, period: Duration) -> PeriodicTask
+    where
+        Self: Send + 'static,
+    {
+        runtime.spawn_periodic(period, move || {
+            self.update();
+            let result = self.publish();
+            #[cfg(feature = "log")]
+            if let Err(error) = result {
+                warn!("Failed to publish NetworkTables statistics: {error}");
+            }
+            #[cfg(not(feature = "log"))]
+            let _ = result;
+        })
+    }
+}
+
+impl<I: Instance + ?Sized + std::fmt::Debug> std::fmt::Debug for Stats<'_, I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Stats")
+            .field("instance", &self.instance)
+            .field("window", &self.updates.window)
+            .field("topics", &self.updates.topics.len())
+            .field("budget", &self.budget)
+            .finish()
+    }
+}
+
+impl<I: Instance + ?Sized> Drop for Stats<'_, I> {
+    fn drop(&mut self) {
+        self.guard.release(|| unsafe {
+            NT_RemoveListener(self.listener);
+            NT_DestroyListenerPoller(self.poller);
+        });
+    }
+}
@@ -0,0 +1,34 @@
+//! Mapping Rust structs to groups of topics.
+
+use crate::{Instance, NetworkTablesError};
+
+#[cfg(feature = "derive")]
+pub use lagan_derive::NtTable;
+
+/// A struct whose fields are stored as child topics of a table.
+///
+/// This is usually derived with `#[derive(NtTable)]`, which requires the `derive` feature.
+/// Every field must implement [`NetworkTablesValue`](crate::nt_types::NetworkTablesValue) and [`Clone`].
+/// Fields can be renamed with `#[nt(rename = "name")]` and skipped with `#[nt(skip)]`,
+/// in which case they are set to their [`Default`] value when read.
+/// Raw identifiers such as `r#type` are published without the `r#`.
+///
+/// The derive also generates a `tunables` method that returns a [`Tunable`](crate::tunable::Tunable) for every field.
+pub trait NtTable: Sized {
+    /// Publishes every field to its child topic of `table`.
+    fn publish_all<I: Instance + ?Sized>(
+        &self,
+        instance: &I,
+        table: &str,
+    ) -> Result<(), NetworkTablesError>;
+
+    /// Reads every field from its child topic of `table`.
+    ///
+    /// Returns `None` if any of the topics is unassigned or of the wrong type.
+    fn read_all<I: Instance + ?Sized>(instance: &I, table: &str) -> Option<Self>;
+}
+
+/// Returns the name of the child topic `name` of `table`.
+pub fn child_name(table: &str, name: &str) -> String {
+    format!("{}/{}", table.trim_end_matches('/'), name)
+}
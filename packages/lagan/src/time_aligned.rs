@@ -0,0 +1,102 @@
+//! Pairing updates of related topics by timestamp.
+
+use std::{collections::VecDeque, time::Duration};
+
+use crate::{
+    nt_types::{NetworkTablesInstant, RawValue},
+    pool,
+    topic::TopicSubscriber,
+    Instance,
+};
+
+/// The number of updates buffered per subscriber by default.
+pub const DEFAULT_ALIGNMENT_CAPACITY: usize = 64;
+
+/// Combines several subscribers into samples whose server timestamps are within a window of each other.
+///
+/// This is useful for consumers that need consistent samples of values published together,
+/// such as the target offsets and latency of a vision pipeline.
+/// Updates that can't be matched with updates of every other subscriber are dropped.
+#[derive(Debug)]
+pub struct TimeAligned<'s, 'a, I: Instance + ?Sized> {
+    subscribers: Vec<&'s TopicSubscriber<'a, I>>,
+    buffers: Vec<VecDeque<RawValue>>,
+    window: Duration,
+    capacity: usize,
+}
+
+impl<'s, 'a, I: Instance + ?Sized> TimeAligned<'s, 'a, I> {
+    /// Creates a combinator that pairs updates whose server timestamps are at most `window` apart.
+    pub fn new(
+        subscribers: impl IntoIterator<Item = &'s TopicSubscriber<'a, I>>,
+        window: Duration,
+    ) -> Self {
+        let subscribers = subscribers.into_iter().collect::<Vec<_>>();
+        Self {
+            buffers: subscribers.iter().map(|_| VecDeque::new()).collect(),
+            subscribers,
+            window,
+            capacity: DEFAULT_ALIGNMENT_CAPACITY,
+        }
+    }
+
+    /// Sets how many unmatched updates are buffered per subscriber before the oldest are dropped.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    fn read(&mut self) {
+        for (subscriber, buffer) in self.subscribers.iter().zip(&mut self.buffers) {
+            pool::RAW_VALUES.with(|values| {
+                subscriber.try_read_update_queue_into(values);
+                buffer.extend(values.drain(..));
+            });
+            while buffer.len() > self.capacity {
+                buffer.pop_front();
+            }
+        }
+    }
+
+    fn next_aligned(&mut self) -> Option<Vec<RawValue>> {
+        loop {
+            let fronts = self
+                .buffers
+                .iter()
+                .map(|buffer| buffer.front().map(|value| value.server_time))
+                .collect::<Option<Vec<NetworkTablesInstant>>>()?;
+
+            let (oldest, oldest_time) = fronts
+                .iter()
+                .copied()
+                .enumerate()
+                .min_by_key(|(_, time)| *time)?;
+            let newest_time = fronts.iter().copied().max()?;
+
+            if newest_time.saturating_duration_since(oldest_time) <= self.window {
+                return self.buffers.iter_mut().map(VecDeque::pop_front).collect();
+            }
+
+            // The oldest update can't be matched with anything newer, so it never will be.
+            self.buffers[oldest].pop_front();
+        }
+    }
+
+    /// Returns the next aligned sample, with one value per subscriber in the order they were given.
+    ///
+    /// If no sample is available yet, None is returned.
+    pub fn try_next(&mut self) -> Option<Vec<RawValue>> {
+        self.read();
+        self.next_aligned()
+    }
+
+    /// Returns every aligned sample available, oldest first.
+    pub fn try_next_all(&mut self) -> Vec<Vec<RawValue>> {
+        self.read();
+        std::iter::from_fn(|| self.next_aligned()).collect()
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+}
@@ -1,26 +1,58 @@
-use std::{ffi::CString, future::Future, task::Poll};
+use std::{
+    mem::MaybeUninit,
+    hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicU32, AtomicU64, AtomicU8, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+#[cfg(feature = "async")]
+use std::{future::Future, task::Poll};
 
 use ntcore_sys::{
-    NT_DisposeValueArray, NT_GetTopicCached, NT_GetTopicExists, NT_GetTopicPersistent, NT_GetTopicRetained, NT_GetTopicType, NT_GetTopicTypeString, NT_Publish, NT_Publisher, NT_ReadQueueValue, NT_Release, NT_SetBoolean, NT_SetBooleanArray, NT_SetDouble, NT_SetDoubleArray, NT_SetFloat, NT_SetFloatArray, NT_SetInteger, NT_SetIntegerArray, NT_SetRaw, NT_SetString, NT_SetStringArray, NT_SetTopicCached, NT_SetTopicPersistent, NT_SetTopicRetained, NT_Subscribe, NT_Subscriber, NT_Topic, WPI_String
+    NT_Bool, NT_DeleteTopicProperty, NT_DisposeTopicInfo, NT_GetTopicInfo, NT_GetTopics, NT_Inst, NT_TopicInfo, NtHandleArray, NT_GetTopicExists, NT_GetTopicPersistent, NT_GetTopicProperties, NT_GetTopicProperty, NT_GetTopicRetained, NT_GetTopicType, NT_GetTopicTypeString, NT_Publish, NT_Publisher, NT_ReadQueueValue, NT_Release, NT_Type, NT_SetBoolean, NT_SetBooleanArray, NT_SetDouble, NT_SetDoubleArray, NT_SetFloat, NT_SetFloatArray, NT_SetInteger, NT_SetIntegerArray, NT_SetRaw, NT_SetString, NT_SetStringArray, NT_SetTopicPersistent, NT_SetTopicProperties, NT_SetTopicProperty, NT_SetTopicRetained, NT_Subscribe, NT_Subscriber, NT_Topic, NtValueArray, WpiStringBuf, WPI_String, probe::optional_functions
 };
-use snafu::ensure;
+use snafu::{ensure, OptionExt};
 
 use crate::{
-    nt_types::{PubSubOptions, RawValue, Value, ValueFlags, ValueType}, Instance, InvalidTypeSnafu, NetworkTablesError, SetToUnassignedSnafu
+    access::ReadOnlyTopic,
+    listener::ListenerHandle,
+    schema::{SchemaCache, TopicKind},
+    shutdown::InstanceGuard,
+    nt_types::{PubSubOptions, PublisherId, RawValue, Value, ValueFlags, ValueType}, intern, lock, pool, Instance, InvalidPropertiesSnafu, InvalidTypeSnafu, NetworkTablesError, Nt4OnlySnafu, ProtocolSnafu, SetToUnassignedSnafu, UnsupportedSnafu
 };
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct Topic<'a, I: Instance + ?Sized> {
     pub(crate) instance: &'a I,
+    pub(crate) guard: InstanceGuard,
     pub(crate) handle: NT_Topic,
-    pub(crate) name: String,
+    pub(crate) name: Arc<str>,
 }
 
-impl<I: Instance + ?Sized> Topic<'_, I> {
-    pub fn subscribe(&self, expected_type: ValueType, expected_type_string: impl AsRef<str>, options: PubSubOptions) -> TopicSubscriber<'_, I> {
-        let type_str = CString::new(expected_type_string.as_ref()).unwrap();
-        let raw_type_str = WPI_String::from(type_str.as_c_str());
-        
+impl<'a, I: Instance + ?Sized> Topic<'a, I> {
+    /// Subscribes to the topic.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the topic already exists with a different type.
+    /// - [`NetworkTablesError::Nt4Only`] if `expected_type` is an integer or float type on a NetworkTables 3 client.
+    /// - [`NetworkTablesError::ProtocolError`] if ntcore refused to create the subscriber.
+    pub fn subscribe(&self, expected_type: ValueType, expected_type_string: impl AsRef<str>, options: PubSubOptions) -> Result<TopicSubscriber<'_, I>, NetworkTablesError> {
+        self.ensure_type(&expected_type)?;
+        let subscriber = self.subscribe_unchecked(expected_type, expected_type_string, options);
+        ensure!(subscriber.handle != 0, ProtocolSnafu { message: format!("ntcore refused to subscribe to {}", self.name) });
+        Ok(subscriber)
+    }
+
+    /// Subscribes to the topic without checking its current type.
+    ///
+    /// Subscribing with [`ValueType::Unassigned`] receives values of any type.
+    pub(crate) fn subscribe_unchecked(&self, expected_type: ValueType, expected_type_string: impl AsRef<str>, options: PubSubOptions) -> TopicSubscriber<'_, I> {
+        let type_string = Arc::<str>::from(expected_type_string.as_ref());
+        let raw_type_str = WPI_String::from(&*type_string);
+
         let raw_options = options.into();
         let handle = unsafe {
             NT_Subscribe(self.handle(), expected_type.into(), &raw const raw_type_str, &raw const raw_options)
@@ -29,22 +61,59 @@ impl<I: Instance + ?Sized> Topic<'_, I> {
         TopicSubscriber {
             handle,
             topic: self,
+            type_string,
         }
     }
 
-    pub fn publish(&self, expected_type: ValueType, expected_type_string: impl AsRef<str>, options: PubSubOptions) -> TopicPublisher<'_, I> {
-        let type_str = CString::new(expected_type_string.as_ref()).unwrap();
-        let raw_type_str = WPI_String::from(type_str.as_c_str());
-        
+    /// Starts publishing to the topic.
+    ///
+    /// NetworkTables 3 has no type strings, so `expected_type_string` is ignored on NetworkTables 3 clients.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::InvalidType`] if the topic already exists with a different type.
+    /// - [`NetworkTablesError::Nt4Only`] if `expected_type` is an integer or float type on a NetworkTables 3 client.
+    /// - [`NetworkTablesError::ProtocolError`] if ntcore refused to create the publisher.
+    pub fn publish(&self, expected_type: ValueType, expected_type_string: impl AsRef<str>, options: PubSubOptions) -> Result<TopicPublisher<'_, I>, NetworkTablesError> {
+        self.ensure_type(&expected_type)?;
+
+        let type_string = Arc::<str>::from(expected_type_string.as_ref());
+        let raw_type_str = WPI_String::from(&*type_string);
+
         let raw_options = options.into();
         let handle = unsafe {
             NT_Publish(self.handle(), expected_type.into(), &raw const raw_type_str, &raw const raw_options)
         };
+        ensure!(handle != 0, ProtocolSnafu { message: format!("ntcore refused to publish to {}", self.name) });
+
+        let value_type = AtomicU32::new(unsafe { NT_GetTopicType(self.handle()) }.bits());
 
-        TopicPublisher {
+        Ok(TopicPublisher {
             handle,
             topic: self,
-        }
+            type_string,
+            value_type,
+            last: AtomicU8::new(LAST_UNKNOWN),
+            last_value: Mutex::new(None),
+            last_f64: AtomicU64::new(0),
+            buffers: Mutex::default(),
+        })
+    }
+
+    /// Fails if the topic already has a type other than `expected_type`.
+    /// Topics without a type yet accept any type.
+    ///
+    /// NetworkTables 3 clients only accept types that exist in NetworkTables 3.
+    fn ensure_type(&self, expected_type: &ValueType) -> Result<(), NetworkTablesError> {
+        let numeric = matches!(expected_type, ValueType::I64 | ValueType::F32 | ValueType::I64Array | ValueType::F32Array);
+        ensure!(!numeric || self.instance.version().has_numeric_types(), Nt4OnlySnafu { feature: "Integer and float topics" });
+
+        let current_type = self.value_type();
+        ensure!(current_type == ValueType::Unassigned || current_type == *expected_type, InvalidTypeSnafu {
+            current_type,
+            given_type: expected_type.clone(),
+        });
+        Ok(())
     }
 
     pub fn value_type(&self) -> ValueType {
@@ -64,37 +133,89 @@ impl<I: Instance + ?Sized> Topic<'_, I> {
             return None;
         }
 
-        let mut raw_string = unsafe { std::mem::zeroed() };
+        let mut raw_string = WpiStringBuf::new();
         unsafe {
-            NT_GetTopicTypeString(self.handle(), &raw mut raw_string);
+            NT_GetTopicTypeString(self.handle(), raw_string.as_mut_ptr());
         }
 
-        // Safety: NT should only return a nullptr when the topic does not exist.
-        Some(
-            String::from_utf8_lossy(unsafe {
-                std::slice::from_raw_parts(raw_string.str.cast(), raw_string.len)
-            })
-            .into_owned(),
-        )
+        Some(raw_string.to_str_lossy().into_owned())
     }
 
-    pub fn set_flags(&self, flags: ValueFlags) {
-        let persist = flags.contains(ValueFlags::PERSISTENT).into();
-        let cache = (!flags.contains(ValueFlags::UNCACHED)).into();
-        let retain = flags.contains(ValueFlags::RETAINED).into();
-
-        unsafe {
-            NT_SetTopicPersistent(self.handle(), persist);
-            NT_SetTopicCached(self.handle(), cache);
-            NT_SetTopicRetained(self.handle(), retain);
+    /// Sets every flag of the topic at once.
+    ///
+    /// Flags that aren't in `flags` are cleared.
+    /// Use [`Self::set_persistent`], [`Self::set_cached`] or [`Self::set_retained`] to change a single flag.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::Nt4Only`] if `flags` contains anything but [`ValueFlags::PERSISTENT`] on a NetworkTables 3 client.
+    pub fn set_flags(&self, flags: ValueFlags) -> Result<(), NetworkTablesError> {
+        if !self.instance.version().has_properties() {
+            ensure!(flags.difference(ValueFlags::PERSISTENT).is_empty(), Nt4OnlySnafu { feature: "The cached and retained flags" });
+            self.set_persistent(flags.contains(ValueFlags::PERSISTENT));
+            return Ok(());
         }
+
+        self.set_persistent(flags.contains(ValueFlags::PERSISTENT));
+        self.set_cached(!flags.contains(ValueFlags::UNCACHED))?;
+        self.set_retained(flags.contains(ValueFlags::RETAINED))
+    }
+
+    /// Sets whether the topic's value is saved to the server's persistent storage.
+    pub fn set_persistent(&self, persistent: bool) {
+        unsafe { NT_SetTopicPersistent(self.handle(), persistent.into()) }
+    }
+    /// Sets whether the server caches the topic's value for new subscribers.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::Nt4Only`] on a NetworkTables 3 client.
+    /// - [`NetworkTablesError::Unsupported`] if the linked ntcore has no cached property.
+    pub fn set_cached(&self, cached: bool) -> Result<(), NetworkTablesError> {
+        self.ensure_nt4_properties("The cached flag")?;
+        let set_cached = optional_functions()
+            .NT_SetTopicCached
+            .context(UnsupportedSnafu { feature: "The cached flag" })?;
+        unsafe { set_cached(self.handle(), cached.into()) };
+        Ok(())
+    }
+    /// Sets whether the server keeps the topic around after it has no publishers.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::Nt4Only`] on a NetworkTables 3 client.
+    pub fn set_retained(&self, retained: bool) -> Result<(), NetworkTablesError> {
+        self.ensure_nt4_properties("The retained flag")?;
+        unsafe { NT_SetTopicRetained(self.handle(), retained.into()) };
+        Ok(())
+    }
+
+    /// Fails on NetworkTables 3 clients, which have no topic properties, so `feature` would silently do nothing.
+    fn ensure_nt4_properties(&self, feature: &'static str) -> Result<(), NetworkTablesError> {
+        ensure!(self.instance.version().has_properties(), Nt4OnlySnafu { feature });
+        Ok(())
+    }
+
+    pub fn is_persistent(&self) -> bool {
+        unsafe { NT_GetTopicPersistent(self.handle()) == 1 }
+    }
+    /// Always `false` if the linked ntcore has no cached property.
+    pub fn is_cached(&self) -> bool {
+        optional_functions()
+            .NT_GetTopicCached
+            .is_some_and(|get_cached| unsafe { get_cached(self.handle()) == 1 })
+    }
+    pub fn is_retained(&self) -> bool {
+        unsafe { NT_GetTopicRetained(self.handle()) == 1 }
     }
 
     pub fn flags(&self) -> ValueFlags {
         let (persist, cache, retain) = unsafe {
             (
                 NT_GetTopicPersistent(self.handle()) == 1,
-                NT_GetTopicCached(self.handle()) == 1,
+                optional_functions()
+                    .NT_GetTopicCached
+                    .is_some_and(|get_cached| get_cached(self.handle()) == 1),
                 NT_GetTopicRetained(self.handle()) == 1,
             )
         };
@@ -112,6 +233,87 @@ impl<I: Instance + ?Sized> Topic<'_, I> {
         flags
     }
 
+    /// Returns the value of a topic property as a JSON string.
+    ///
+    /// Returns `None` if the property is not set.
+    pub fn property(&self, name: impl AsRef<str>) -> Option<String> {
+        let raw_name = WPI_String::from(name.as_ref());
+
+        let mut raw_property = WpiStringBuf::new();
+        unsafe {
+            NT_GetTopicProperty(self.handle(), &raw const raw_name, raw_property.as_mut_ptr());
+        }
+
+        match raw_property.to_str_lossy() {
+            property if property.is_empty() || property == "null" => None,
+            property => Some(property.into_owned()),
+        }
+    }
+
+    /// Sets a topic property to the given JSON value.
+    /// Properties are sent to the server and shared with every other client.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::Nt4Only`] on a NetworkTables 3 client.
+    /// - [`NetworkTablesError::InvalidProperties`] if `json` is not valid JSON.
+    pub fn set_property(&self, name: impl AsRef<str>, json: impl AsRef<str>) -> Result<(), NetworkTablesError> {
+        self.ensure_nt4_properties("Topic properties")?;
+        let raw_name = WPI_String::from(name.as_ref());
+        let raw_value = WPI_String::from(json.as_ref());
+
+        let result = unsafe { NT_SetTopicProperty(self.handle(), &raw const raw_name, &raw const raw_value) };
+        ensure!(result == 1, InvalidPropertiesSnafu);
+
+        Ok(())
+    }
+
+    /// Deletes a topic property. Does nothing if the property is not set.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::Nt4Only`] on a NetworkTables 3 client.
+    pub fn delete_property(&self, name: impl AsRef<str>) -> Result<(), NetworkTablesError> {
+        self.ensure_nt4_properties("Topic properties")?;
+        let raw_name = WPI_String::from(name.as_ref());
+
+        unsafe { NT_DeleteTopicProperty(self.handle(), &raw const raw_name) };
+        Ok(())
+    }
+
+    /// Returns every property of the topic as a JSON object.
+    /// This includes the `persistent`, `cached` and `retained` flags as well as any other properties.
+    pub fn properties(&self) -> String {
+        let mut raw_properties = WpiStringBuf::new();
+        unsafe {
+            NT_GetTopicProperties(self.handle(), raw_properties.as_mut_ptr());
+        }
+
+        raw_properties.to_str_lossy().into_owned()
+    }
+
+    /// Updates the properties in the given JSON object, leaving every other property untouched.
+    /// Properties set to `null` are deleted.
+    ///
+    /// # Errors
+    ///
+    /// - [`NetworkTablesError::Nt4Only`] on a NetworkTables 3 client.
+    /// - [`NetworkTablesError::InvalidProperties`] if `json` is not a valid JSON object.
+    pub fn update_properties(&self, json: impl AsRef<str>) -> Result<(), NetworkTablesError> {
+        self.ensure_nt4_properties("Topic properties")?;
+        let raw_properties = WPI_String::from(json.as_ref());
+
+        let result = unsafe { NT_SetTopicProperties(self.handle(), &raw const raw_properties) };
+        ensure!(result == 1, InvalidPropertiesSnafu);
+
+        Ok(())
+    }
+
+    /// Returns a view of this topic that can't be published to or reconfigured.
+    pub fn read_only(&self) -> ReadOnlyTopic<'_, 'a, I> {
+        ReadOnlyTopic::new(self)
+    }
+
     /// Returns true if the topic has at least one publisher
     pub fn is_existant(&self) -> bool {
         (unsafe { NT_GetTopicExists(self.handle()) } == 1)
@@ -125,6 +327,29 @@ impl<I: Instance + ?Sized> Topic<'_, I> {
         &self.name
     }
 
+    /// Returns the name of this topic, shared with every other handle to it.
+    ///
+    /// Cloning the returned name doesn't allocate, which makes it cheap to use as e.g. a map key.
+    pub fn name_arc(&self) -> Arc<str> {
+        self.name.clone()
+    }
+
+    /// Calls `callback` with every new value of this topic, local or remote, until the returned handle is dropped.
+    ///
+    /// The callback runs on ntcore's listener thread, so it should return quickly.
+    pub fn on_change(&self, callback: impl Fn(RawValue) + Send + Sync + 'static) -> ListenerHandle {
+        ListenerHandle::on_value(self.handle, callback)
+    }
+
+    /// Like [`Self::on_change`], but calls `callback` at most once per `interval` with the latest value
+    /// and the number of values skipped before it, e.g. to repaint a GUI for a 250 Hz topic.
+    ///
+    /// The first value after a quiet period is delivered right away, later ones once `interval` has passed
+    /// since the last delivery, from a background thread.
+    pub fn on_change_coalesced(&self, interval: Duration, callback: impl Fn(RawValue, u64) + Send + Sync + 'static) -> ListenerHandle {
+        ListenerHandle::on_value_coalesced(self.handle, interval, callback)
+    }
+
     /// # Safety
     ///
     /// Caller must ensure that the returned handle is only used while the topic is valid.
@@ -135,15 +360,15 @@ impl<I: Instance + ?Sized> Topic<'_, I> {
 
 impl<I: Instance + ?Sized> Drop for Topic<'_, I> {
     fn drop(&mut self) {
-        unsafe {
-            NT_Release(self.handle());
-        }
+        self.guard.release(|| unsafe { NT_Release(self.handle) });
     }
 }
 
+#[cfg(feature = "async")]
 pub struct TopicSubscriberReadQueueRawFuture<'a, I: Instance + ?Sized> {
     subscriber: &'a TopicSubscriber<'a, I>,
 }
+#[cfg(feature = "async")]
 impl<I: Instance + ?Sized> Future for TopicSubscriberReadQueueRawFuture<'_, I> {
     type Output = Vec<RawValue>;
 
@@ -164,8 +389,10 @@ impl<I: Instance + ?Sized> Future for TopicSubscriberReadQueueRawFuture<'_, I> {
 pub struct TopicSubscriber<'a, I: Instance + ?Sized> {
     handle: NT_Subscriber,
     topic: &'a Topic<'a, I>,
+    type_string: Arc<str>,
 }
 
+#[cfg(feature = "async")]
 macro_rules! typed_reader {
     {$($ident:ident: $variant:ident => $ty:ty),*} => {
         $(
@@ -181,47 +408,130 @@ macro_rules! typed_reader {
     };
 }
 
-impl<I: Instance + ?Sized> TopicSubscriber<'_, I> {
+impl<'a, I: Instance + ?Sized> TopicSubscriber<'a, I> {
     /// Returns all of the new topic values since the last read in their raw form (timestamps included).
     ///
+    /// The returned `Vec` is taken from a pool of buffers on this thread. Give it back with [`Self::recycle`]
+    /// once done with it, so later reads reuse its capacity, or use [`Self::try_read_update_queue_into`] instead.
     /// If there have been no new updates, None is returned.
     pub fn try_read_update_queue_raw(&self) -> Option<Vec<RawValue>> {
-        let mut count = 0;
-        let raw_values = unsafe { NT_ReadQueueValue(self.handle(), &raw mut count) };
-        if count == 0 {
-            return None;
+        let mut values = pool::RAW_VALUES.take();
+        if self.try_read_update_queue_into(&mut values) > 0 {
+            Some(values)
+        } else {
+            pool::RAW_VALUES.give(values);
+            None
         }
+    }
 
-        let values = unsafe { std::slice::from_raw_parts(raw_values, count) };
-        let values = values
-            .iter()
-            .map(|v| (*v).into())
-            .collect::<Vec<RawValue>>();
-        unsafe {
-            NT_DisposeValueArray(raw_values, count);
-        }
+    /// Gives a `Vec` returned by one of the raw reads back to the pool, so later reads on this thread reuse its capacity.
+    ///
+    /// The values still in it are dropped.
+    pub fn recycle(&self, values: Vec<RawValue>) {
+        pool::RAW_VALUES.give(values);
+    }
 
-        Some(values)
+    /// Appends all of the new topic values since the last read to `values` in their raw form.
+    ///
+    /// Reusing the same `Vec` for every read avoids allocating a new one each time.
+    /// Returns the number of values appended.
+    pub fn try_read_update_queue_into(&self, values: &mut Vec<RawValue>) -> usize {
+        let mut count = 0;
+        let raw_values = unsafe {
+            NtValueArray::from_raw(NT_ReadQueueValue(self.handle(), &raw mut count), count)
+        };
+        values.extend(raw_values.iter().map(|v| RawValue::from(*v)));
+        raw_values.len()
     }
 
     pub fn try_read_update_queue(&self) -> Option<Vec<Value>> {
-        let values = self.try_read_update_queue_raw()?;
-        Some(values.into_iter().map(|v| v.data).collect())
+        let mut count = 0;
+        let raw_values = unsafe {
+            NtValueArray::from_raw(NT_ReadQueueValue(self.handle(), &raw mut count), count)
+        };
+        (!raw_values.is_empty()).then(|| raw_values.iter().map(|v| RawValue::from(*v).data).collect())
+    }
+
+    /// Returns all of the new topic values of the given type since the last read in their raw form.
+    ///
+    /// Values of other types are skipped without being decoded,
+    /// which avoids wasted work when the type of a topic changes during reconnects.
+    /// Passing [`ValueType::Unassigned`] accepts values of any type.
+    ///
+    /// If there have been no new updates of the given type, None is returned.
+    /// Like with [`Self::try_read_update_queue_raw`], give the `Vec` back with [`Self::recycle`] once done with it.
+    pub fn try_read_update_queue_raw_of_type(
+        &self,
+        value_type: ValueType,
+    ) -> Option<Vec<RawValue>> {
+        let mut values = pool::RAW_VALUES.take();
+        if self.try_read_update_queue_of_type_into(value_type, &mut values) > 0 {
+            Some(values)
+        } else {
+            pool::RAW_VALUES.give(values);
+            None
+        }
+    }
+
+    /// Like [`Self::try_read_update_queue_into`], but only appends values of the given type.
+    pub fn try_read_update_queue_of_type_into(
+        &self,
+        value_type: ValueType,
+        values: &mut Vec<RawValue>,
+    ) -> usize {
+        let before = values.len();
+        self.read_update_queue_of_type(value_type, |value| values.push(value));
+        values.len() - before
+    }
+
+    pub fn try_read_update_queue_of_type(&self, value_type: ValueType) -> Option<Vec<Value>> {
+        let mut values = Vec::new();
+        self.read_update_queue_of_type(value_type, |value| values.push(value.data));
+        (!values.is_empty()).then_some(values)
+    }
+
+    /// Calls `f` with every new value of the given type since the last read.
+    fn read_update_queue_of_type(&self, value_type: ValueType, mut f: impl FnMut(RawValue)) {
+        let mut count = 0;
+        let Some(read_queue_of_type) = optional_functions().NT_ReadQueueValueType else {
+            // Older ntcore can only read values of every type, so the others are decoded and dropped.
+            let raw_values = unsafe {
+                NtValueArray::from_raw(NT_ReadQueueValue(self.handle(), &raw mut count), count)
+            };
+            raw_values
+                .iter()
+                .map(|v| RawValue::from(*v))
+                .filter(|v| value_type == ValueType::Unassigned || v.data.value_type() == value_type)
+                .for_each(f);
+            return;
+        };
+        let types = NT_Type::from(value_type).bits();
+        let raw_values = unsafe {
+            NtValueArray::from_raw(read_queue_of_type(self.handle(), types, &raw mut count), count)
+        };
+        for v in raw_values.iter() {
+            f(RawValue::from(*v));
+        }
     }
 
+    #[cfg(feature = "async")]
     pub fn update_queue_raw(&self) -> TopicSubscriberReadQueueRawFuture<'_, I> {
         TopicSubscriberReadQueueRawFuture { subscriber: self }
     }
+    #[cfg(feature = "async")]
     pub async fn update_queue(&self) -> Vec<Value> {
         let values = self.update_queue_raw().await;
         values.into_iter().map(|v| v.data).collect()
     }
 
+    #[cfg(feature = "async")]
     pub async fn value(&self) -> Value {
-        let updates = self.update_queue().await;
-        updates.last().unwrap().clone()
+        let mut updates = self.update_queue().await;
+        // The queue future only resolves once there is at least one update.
+        updates.pop().unwrap_or(Value::Unassigned)
     }
 
+    #[cfg(feature = "async")]
     typed_reader!{
         value_bool: Bool => bool,
         value_i64: I64 => i64,
@@ -236,29 +546,88 @@ impl<I: Instance + ?Sized> TopicSubscriber<'_, I> {
         value_string_array: StringArray => Vec<String>
     }
 
+    pub fn topic(&self) -> &'a Topic<'a, I> {
+        self.topic
+    }
+
+    /// The type string this subscriber was created with.
+    pub fn type_string(&self) -> &str {
+        &self.type_string
+    }
+
     /// # Safety
     ///
     /// Caller must ensure that the returned handle is only used while the topic and subscriber is valid.
     pub unsafe fn handle(&self) -> NT_Subscriber {
         self.handle
     }
+
+    /// Gives up ownership of the subscriber handle, which the caller then has to release.
+    #[cfg(all(feature = "async", feature = "tokio"))]
+    pub(crate) fn into_raw(self) -> NT_Subscriber {
+        let subscriber = std::mem::ManuallyDrop::new(self);
+        // Safety: the subscriber is never used again, so its type string is dropped exactly once.
+        drop(unsafe { std::ptr::read(&subscriber.type_string) });
+        subscriber.handle
+    }
 }
 
 impl<I: Instance + ?Sized> Drop for TopicSubscriber<'_, I> {
     fn drop(&mut self) {
-        unsafe {
-            NT_Release(self.handle());
-        }
+        self.topic.guard.release(|| unsafe { NT_Release(self.handle) });
     }
 }
 
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug)]
 pub struct TopicPublisher<'a, I: Instance + ?Sized> {
     handle: NT_Publisher,
     topic: &'a Topic<'a, I>,
+    type_string: Arc<str>,
+    /// The raw `NT_Type` of the topic, cached so publishing doesn't have to ask ntcore.
+    /// Refreshed when a value doesn't match it, in case the topic was published again with another type.
+    value_type: AtomicU32,
+    /// Where the last value published is remembered for [`Self::set_if_changed`], one of the `LAST_*` constants.
+    last: AtomicU8,
+    /// The last value published through [`Self::set_if_changed`] or [`Self::set_if_changed_within`].
+    /// Other publishes don't clone the value to remember it.
+    last_value: Mutex<Option<Value>>,
+    /// The bits of the last double published, which are remembered without locking or allocating.
+    last_f64: AtomicU64,
+    buffers: Mutex<PublishBuffers>,
 }
 
+/// Storage for converted array elements, reused across publishes.
+///
+/// The strings only point into the value being published while the publish is in progress,
+/// and the buffers are cleared afterwards.
+#[derive(Debug, Default)]
+struct PublishBuffers {
+    bools: Vec<NT_Bool>,
+    strings: Vec<WPI_String>,
+}
+
+// Safety: The string pointers are never used outside of a publish, see above.
+unsafe impl Send for PublishBuffers {}
+
+impl<I: Instance + ?Sized> PartialEq for TopicPublisher<'_, I> {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+impl<I: Instance + ?Sized> Eq for TopicPublisher<'_, I> {}
+impl<I: Instance + ?Sized> Hash for TopicPublisher<'_, I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.handle.hash(state);
+    }
+}
+
+/// The last value published is unknown, so [`TopicPublisher::set_if_changed`] publishes.
+const LAST_UNKNOWN: u8 = 0;
+/// The last value published is in `last_value`.
+const LAST_VALUE: u8 = 1;
+/// The last value published is the double in `last_f64`.
+const LAST_F64: u8 = 2;
 
 macro_rules! typed_setter {
     {$($ident:ident: $ty:ty => $variant:ident),*} => {
@@ -271,49 +640,97 @@ macro_rules! typed_setter {
     };
 }
 
-impl<I: Instance + ?Sized> TopicPublisher<'_, I> {
+impl<'a, I: Instance + ?Sized> TopicPublisher<'a, I> {
     pub fn set_value(&self, value: Value) -> Result<(), NetworkTablesError> {
-        ensure!(value.value_type() == self.topic.value_type(), InvalidTypeSnafu {
-            current_type: self.topic.value_type(),
+        self.set_value_at(value, 0)
+    }
+
+    /// Publishes a double without checking the type of the topic or going through [`Value`].
+    ///
+    /// Use this for doubles published at high rates from robot code.
+    /// Returns `false` if ntcore rejected the value, e.g. because this publisher wasn't created for doubles.
+    /// The value is remembered for [`Self::set_if_changed`] without locking or allocating.
+    #[inline]
+    pub fn set_f64_fast(&self, value: f64) -> bool {
+        let published = unsafe { NT_SetDouble(self.handle(), 0, value) } == 1;
+        if published {
+            self.remember_f64(value);
+        }
+        published
+    }
+
+    pub(crate) fn ensure_type(&self, value: &Value) -> Result<(), NetworkTablesError> {
+        let mut current_type = self.value_type();
+        if value.value_type() != current_type {
+            // The topic may have been published again with another type since the type was cached.
+            current_type = self.refresh_type();
+        }
+        ensure!(value.value_type() == current_type, InvalidTypeSnafu {
+            current_type,
             given_type: value.value_type(),
         });
+        Ok(())
+    }
+
+    /// Returns the cached type of the topic.
+    ///
+    /// The type is cached when the publisher is created and refreshed whenever a value doesn't match it,
+    /// so unlike [`Topic::value_type`] this doesn't call into ntcore.
+    pub fn value_type(&self) -> ValueType {
+        NT_Type::from_bits(self.value_type.load(Ordering::Relaxed)).into()
+    }
+
+    /// Reads the type of the topic from ntcore again, in case the cached type missed a change, and returns it.
+    pub fn refresh_type(&self) -> ValueType {
+        let raw_type = unsafe { NT_GetTopicType(self.topic.handle()) };
+        self.value_type.store(raw_type.bits(), Ordering::Relaxed);
+        raw_type.into()
+    }
+
+    /// Sets the value with the given timestamp in microseconds, where 0 means now.
+    pub(crate) fn set_value_at(&self, value: Value, time: i64) -> Result<(), NetworkTablesError> {
+        self.ensure_type(&value)?;
+        let last_f64 = match value {
+            Value::F64(value) => Some(value),
+            _ => None,
+        };
 
         macro_rules! set_simple_array {
             ($name:ident($field:ident)) => {{
                 let len = $field.len() as _;
                 let raw = $field.as_ptr();
-                unsafe { $name(self.handle(), 0, raw, len) }
+                unsafe { $name(self.handle(), time, raw, len) }
             }};
         }
 
         // ntcore only has typed topic publisher setters so we have to match
         let result = match value {
             Value::Unassigned => return SetToUnassignedSnafu.fail(),
+            Value::Unknown { type_bits, .. } => return ProtocolSnafu { message: format!("can't publish a value of unknown type {type_bits:#x}") }.fail(),
             Value::Bool(value) => {
                 unsafe {
-                    NT_SetBoolean(self.handle(), 0, value.into())
+                    NT_SetBoolean(self.handle(), time, value.into())
                 }
             },
             Value::I64(value) => {
                 unsafe {
-                    NT_SetInteger(self.handle(), 0, value)
+                    NT_SetInteger(self.handle(), time, value)
                 }
             },
             Value::F32(value) => {
                 unsafe {
-                    NT_SetFloat(self.handle(), 0, value)
+                    NT_SetFloat(self.handle(), time, value)
                 }
             },
             Value::F64(value) => {
                 unsafe {
-                    NT_SetDouble(self.handle(), 0, value)
+                    NT_SetDouble(self.handle(), time, value)
                 }
             },
             Value::String(string) => {
-                let string = CString::new(string).unwrap();
-                let wpi_string = WPI_String::from(string.as_c_str());
+                let wpi_string = WPI_String::from(string.as_str());
                 unsafe {
-                    NT_SetString(self.handle(), 0, &raw const wpi_string)
+                    NT_SetString(self.handle(), time, &raw const wpi_string)
                 }
             },
             Value::Raw(value) => set_simple_array!(NT_SetRaw(value)),
@@ -321,31 +738,109 @@ impl<I: Instance + ?Sized> TopicPublisher<'_, I> {
             Value::F32Array(value) => set_simple_array!(NT_SetFloatArray(value)),
             Value::I64Array(value) => set_simple_array!(NT_SetIntegerArray(value)),
             Value::BoolArray(value) => {
-                let bools = value.into_iter().map(|b| b.into()).collect::<Vec<_>>();
-                let len = bools.len() as _;
-                let raw = bools.as_ptr();
-                unsafe { NT_SetBooleanArray(self.handle(), 0, raw, len) }
+                let mut buffers = lock(&self.buffers);
+                buffers.bools.extend(value.into_iter().map(NT_Bool::from));
+                let result = unsafe {
+                    NT_SetBooleanArray(self.handle(), time, buffers.bools.as_ptr(), buffers.bools.len() as _)
+                };
+                buffers.bools.clear();
+                result
             },
             Value::StringArray(value) => {
-                let c_strings = value
-                    .into_iter()
-                    .map(|s| CString::new(s).unwrap())
-                    .collect::<Vec<_>>();
-                let wpi_strings = c_strings
-                    .iter()
-                    .map(|s| WPI_String::from(s.as_c_str()))
-                    .collect::<Vec<_>>();
-                let len = wpi_strings.len() as _;
-                let raw = wpi_strings.as_ptr();
-                unsafe { NT_SetStringArray(self.handle(), 0, raw, len) }
+                let mut buffers = lock(&self.buffers);
+                buffers.strings.extend(value.iter().map(|s| WPI_String::from(s.as_str())));
+                let result = unsafe {
+                    NT_SetStringArray(self.handle(), time, buffers.strings.as_ptr(), buffers.strings.len() as _)
+                };
+                buffers.strings.clear();
+                result
             },
         } == 1;
 
-        debug_assert!(result);
+        ensure!(result, ProtocolSnafu { message: format!("ntcore rejected a value published to {}", self.topic.name) });
+        // Only values that were published count for `set_if_changed`.
+        match last_f64 {
+            Some(value) => self.remember_f64(value),
+            None => self.last.store(LAST_UNKNOWN, Ordering::Release),
+        }
 
         Ok(())
     }
 
+    fn remember_f64(&self, value: f64) {
+        self.last_f64.store(value.to_bits(), Ordering::Relaxed);
+        self.last.store(LAST_F64, Ordering::Release);
+    }
+
+    /// Publishes `value` and remembers it for [`Self::set_if_changed`].
+    ///
+    /// Doubles are remembered by every publish, other values are cloned and stored here.
+    fn set_value_remembered(&self, value: Value) -> Result<(), NetworkTablesError> {
+        if matches!(value, Value::F64(_)) {
+            return self.set_value(value);
+        }
+        let mut last_value = lock(&self.last_value);
+        self.set_value(value.clone())?;
+        *last_value = Some(value);
+        self.last.store(LAST_VALUE, Ordering::Release);
+        Ok(())
+    }
+
+    /// Publishes the value only if it differs from the last value published by this publisher.
+    ///
+    /// Unlike [`PubSubOptions::ignore_duplicates`], this skips the publish entirely instead of relying on ntcore to drop it.
+    /// Returns `true` if the value was published.
+    ///
+    /// Only values published through this method or [`Self::set_if_changed_within`] are remembered, except doubles,
+    /// so other setters don't pay for cloning the value. After publishing anything else, the next call publishes.
+    pub fn set_if_changed(&self, value: Value) -> Result<bool, NetworkTablesError> {
+        if self.with_last_value(|last| last == Some(&value)) {
+            return Ok(false);
+        }
+        self.set_value_remembered(value)?;
+        Ok(true)
+    }
+
+    /// Like [`Self::set_if_changed`], but floating point values (and arrays of them) are considered unchanged
+    /// if every element differs from the last published value by at most `epsilon`.
+    pub fn set_if_changed_within(&self, value: Value, epsilon: f64) -> Result<bool, NetworkTablesError> {
+        let unchanged = self.with_last_value(|last| match (last, &value) {
+            (Some(Value::F64(last)), Value::F64(new)) => (last - new).abs() <= epsilon,
+            (Some(Value::F32(last)), Value::F32(new)) => ((last - new).abs() as f64) <= epsilon,
+            (Some(Value::F64Array(last)), Value::F64Array(new)) => {
+                last.len() == new.len() && last.iter().zip(new).all(|(last, new)| (last - new).abs() <= epsilon)
+            }
+            (Some(Value::F32Array(last)), Value::F32Array(new)) => {
+                last.len() == new.len() && last.iter().zip(new).all(|(last, new)| ((last - new).abs() as f64) <= epsilon)
+            }
+            (last, value) => last == Some(value),
+        });
+        if unchanged {
+            return Ok(false);
+        }
+        self.set_value_remembered(value)?;
+        Ok(true)
+    }
+
+    /// Calls `f` with the last value published by this publisher, or `None` if it isn't known.
+    fn with_last_value<R>(&self, f: impl FnOnce(Option<&Value>) -> R) -> R {
+        match self.last.load(Ordering::Acquire) {
+            LAST_F64 => {
+                let last = Value::F64(f64::from_bits(self.last_f64.load(Ordering::Relaxed)));
+                f(Some(&last))
+            }
+            LAST_VALUE => {
+                let last_value = lock(&self.last_value);
+                // Another publish may have happened while waiting for the lock.
+                match self.last.load(Ordering::Acquire) {
+                    LAST_VALUE => f(last_value.as_ref()),
+                    _ => f(None),
+                }
+            }
+            _ => f(None),
+        }
+    }
+
     typed_setter! {
         set_value_bool: bool => Bool,
         set_value_i64: i64 => I64,
@@ -360,6 +855,20 @@ impl<I: Instance + ?Sized> TopicPublisher<'_, I> {
         set_value_string_array: Vec<String> => StringArray
     }
 
+    pub fn topic(&self) -> &'a Topic<'a, I> {
+        self.topic
+    }
+
+    /// The type string this publisher was created with.
+    pub fn type_string(&self) -> &str {
+        &self.type_string
+    }
+
+    /// Returns an identifier that can be passed to [`PubSubOptions::exclude_publisher`].
+    pub fn id(&self) -> PublisherId {
+        PublisherId(self.handle)
+    }
+
     /// # Safety
     ///
     /// Caller must ensure that the returned handle is only used while the topic and publisher is valid.
@@ -368,10 +877,93 @@ impl<I: Instance + ?Sized> TopicPublisher<'_, I> {
     }
 }
 
+impl<I: Instance + ?Sized> From<&TopicPublisher<'_, I>> for PublisherId {
+    fn from(publisher: &TopicPublisher<'_, I>) -> Self {
+        publisher.id()
+    }
+}
+
 impl<I: Instance + ?Sized> Drop for TopicPublisher<'_, I> {
     fn drop(&mut self) {
+        self.topic.guard.release(|| unsafe { NT_Release(self.handle) });
+    }
+}
+
+/// Information about a topic, as returned by [`Instance::topics_iter`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TopicInfo {
+    pub name: Arc<str>,
+    pub value_type: ValueType,
+    pub type_string: String,
+    /// The properties of the topic as a JSON object.
+    pub properties: String,
+    /// What kind of data the topic holds, with the schema of struct topics resolved
+    /// against the schemas registered on or announced to the instance.
+    pub kind: TopicKind,
+}
+
+impl TopicInfo {
+    /// # Safety
+    ///
+    /// The strings in `info` must be valid.
+    pub(crate) unsafe fn from_raw(instance: NT_Inst, info: &NT_TopicInfo) -> Self {
         unsafe {
-            NT_Release(self.handle());
+            Self {
+                name: intern::intern(instance, &info.name.to_str_lossy()),
+                value_type: info.r#type.into(),
+                type_string: info.type_str.to_str_lossy().into_owned(),
+                properties: info.properties.to_str_lossy().into_owned(),
+                kind: TopicKind::Plain,
+            }
+        }
+    }
+}
+
+/// A lazy iterator over the topics of an instance, created with [`Instance::topics_iter`].
+///
+/// Only the topic handles are fetched up front; the information about each topic is fetched as it is reached.
+/// The schema of every struct type is looked up once per iterator.
+/// Topics that are removed while iterating are skipped.
+#[derive(Debug)]
+pub struct TopicsIter<'a, I: Instance + ?Sized> {
+    instance: &'a I,
+    topics: NtHandleArray,
+    next: usize,
+    schemas: SchemaCache,
+}
+
+impl<'a, I: Instance + ?Sized> TopicsIter<'a, I> {
+    pub(crate) fn new(instance: &'a I, prefix: &str) -> Self {
+        let prefix = WPI_String::from(prefix);
+        let mut count = 0;
+        let topics = unsafe {
+            NtHandleArray::from_raw(NT_GetTopics(instance.handle(), &raw const prefix, 0, &raw mut count), count)
+        };
+        Self { instance, topics, next: 0, schemas: SchemaCache::default() }
+    }
+}
+
+impl<I: Instance + ?Sized> Iterator for TopicsIter<'_, I> {
+    type Item = TopicInfo;
+
+    fn next(&mut self) -> Option<TopicInfo> {
+        while let Some(topic) = self.topics.get(self.next) {
+            self.next += 1;
+
+            let mut info = MaybeUninit::<NT_TopicInfo>::uninit();
+            if unsafe { NT_GetTopicInfo(*topic, info.as_mut_ptr()) } == 0 {
+                continue;
+            }
+            let mut info = unsafe { info.assume_init() };
+            let mut topic_info = unsafe { TopicInfo::from_raw(self.instance.handle(), &info) };
+            unsafe { NT_DisposeTopicInfo(&raw mut info) };
+            topic_info.kind = self.schemas.classify(self.instance, &topic_info.name, &topic_info.type_string);
+            return Some(topic_info);
         }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.topics.len() - self.next))
     }
 }
@@ -0,0 +1,107 @@
+//! Values that can be changed remotely, e.g. from a dashboard.
+
+use std::fmt::Debug;
+
+use crate::{
+    entry::Entry,
+    nt_types::{NetworkTablesValue, PubSubOptions},
+    Instance, NetworkTablesError,
+};
+
+/// A value that is published to NetworkTables and updated whenever it is changed remotely.
+///
+/// This is most commonly used to live-tune constants such as PID gains from a dashboard.
+/// Local writes made with [`Tunable::set`] are not reported back as remote changes.
+pub struct Tunable<'a, I: Instance + ?Sized, T: NetworkTablesValue> {
+    entry: Entry<'a, I>,
+    value: T,
+    callbacks: Vec<Box<dyn FnMut(&T) + 'a>>,
+}
+
+impl<'a, I: Instance + ?Sized, T: NetworkTablesValue + Clone> Tunable<'a, I, T> {
+    /// Creates a tunable value.
+    ///
+    /// If the topic already has a value of the right type, e.g. because it is persistent,
+    /// that value is used. Otherwise `default` is published.
+    pub fn new(instance: &'a I, name: impl AsRef<str>, default: T) -> Self {
+        let options = PubSubOptions {
+            send_all_updates: true,
+            exclude_self: true,
+            ..Default::default()
+        };
+        let entry = instance.entry_with_options(name, T::value_type(), options);
+
+        let value = match entry.value_of_type(T::value_type()).and_then(T::from_value) {
+            Some(value) => value,
+            None => {
+                // The entry is either unassigned or of another type, in which case there is nothing to publish.
+                let _ = entry.set_value(default.clone().into_value());
+                default
+            }
+        };
+
+        Self {
+            entry,
+            value,
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Applies every remote change since the last update, calling the change callbacks for each one.
+    ///
+    /// Returns `true` if the value changed.
+    pub fn update(&mut self) -> bool {
+        let Some(values) = self.entry.try_read_update_queue_of_type(T::value_type()) else {
+            return false;
+        };
+
+        let mut changed = false;
+        for value in values.into_iter().filter_map(T::from_value) {
+            for callback in &mut self.callbacks {
+                callback(&value);
+            }
+            self.value = value;
+            changed = true;
+        }
+        changed
+    }
+
+    /// Returns the latest value, applying any remote changes first.
+    pub fn get(&mut self) -> &T {
+        self.update();
+        &self.value
+    }
+
+    /// Returns the cached value without checking for remote changes.
+    pub fn cached(&self) -> &T {
+        &self.value
+    }
+
+    /// Sets the value locally and publishes it.
+    ///
+    /// Change callbacks are not called for local writes.
+    pub fn set(&mut self, value: T) -> Result<(), NetworkTablesError> {
+        self.entry.set_value(value.clone().into_value())?;
+        self.value = value;
+        Ok(())
+    }
+
+    /// Registers a callback that is called with every remote change applied by [`Self::update`].
+    pub fn on_change(&mut self, callback: impl FnMut(&T) + 'a) {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    pub fn entry(&self) -> &Entry<'a, I> {
+        &self.entry
+    }
+}
+
+impl<I: Instance + ?Sized + Debug, T: NetworkTablesValue + Debug> Debug for Tunable<'_, I, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tunable")
+            .field("entry", &self.entry)
+            .field("value", &self.value)
+            .field("callbacks", &self.callbacks.len())
+            .finish()
+    }
+}
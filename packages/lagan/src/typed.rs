@@ -0,0 +1,125 @@
+//! Subscribing to topics as Rust types, with handling for topics whose type changes.
+
+use std::fmt::Debug;
+
+use crate::{
+    nt_types::{NetworkTablesValue, PubSubOptions, Value, ValueType},
+    topic::{Topic, TopicSubscriber},
+    Instance,
+};
+
+/// The type of a topic's values changed, e.g. because a different version of the robot code published it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypeChanged {
+    /// The type of the previous value, or the expected type if this is the first value.
+    pub from: ValueType,
+    pub to: ValueType,
+    /// The type string of the topic after the change.
+    pub type_string: Option<String>,
+}
+
+/// An update read by a [`TypedSubscriber`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedUpdate<T> {
+    Value(T),
+    /// Reported before the first value of the new type.
+    /// Values of the new type are only reported if a fallback converted them.
+    TypeChanged(TypeChanged),
+}
+
+/// A subscriber that reads values as `T`.
+///
+/// Values of any type are received, so a change of the topic's type is reported as a [`TypeChanged`]
+/// update instead of values silently no longer arriving.
+/// With [`TypedSubscriber::with_fallback`], values of other types can still be converted to `T`.
+pub struct TypedSubscriber<'a, I: Instance + ?Sized, T: NetworkTablesValue> {
+    subscriber: TopicSubscriber<'a, I>,
+    fallback: Option<Box<dyn Fn(Value) -> Option<T> + Send + Sync + 'a>>,
+    /// The type of the last value read.
+    current_type: ValueType,
+}
+
+impl<'a, I: Instance + ?Sized, T: NetworkTablesValue> TypedSubscriber<'a, I, T> {
+    pub fn new(topic: &'a Topic<'a, I>, options: PubSubOptions) -> Self {
+        Self {
+            subscriber: topic.subscribe_unchecked(ValueType::Unassigned, "", options),
+            fallback: None,
+            current_type: T::value_type(),
+        }
+    }
+
+    /// Converts values that are not of type `T` with `fallback` instead of skipping them.
+    ///
+    /// For example, a fallback can read an integer topic as `f64` after the robot code changed its type.
+    /// Values the fallback returns `None` for are skipped.
+    pub fn with_fallback(
+        mut self,
+        fallback: impl Fn(Value) -> Option<T> + Send + Sync + 'a,
+    ) -> Self {
+        self.fallback = Some(Box::new(fallback));
+        self
+    }
+
+    /// Returns every update since the last read.
+    ///
+    /// If there have been no new updates, None is returned.
+    pub fn try_read_updates(&mut self) -> Option<Vec<TypedUpdate<T>>> {
+        let values = self.subscriber.try_read_update_queue()?;
+
+        let mut updates = Vec::with_capacity(values.len());
+        for value in values {
+            let value_type = value.value_type();
+            if value_type != self.current_type {
+                updates.push(TypedUpdate::TypeChanged(TypeChanged {
+                    from: std::mem::replace(&mut self.current_type, value_type.clone()),
+                    to: value_type.clone(),
+                    type_string: self.subscriber.topic().value_type_string(),
+                }));
+            }
+
+            let value = if value_type == T::value_type() {
+                T::from_value(value)
+            } else {
+                self.fallback.as_ref().and_then(|fallback| fallback(value))
+            };
+            if let Some(value) = value {
+                updates.push(TypedUpdate::Value(value));
+            }
+        }
+        Some(updates)
+    }
+
+    /// Returns the latest value since the last read, skipping type changes.
+    pub fn try_read_latest(&mut self) -> Option<T> {
+        self.try_read_updates()?
+            .into_iter()
+            .rev()
+            .find_map(|update| match update {
+                TypedUpdate::Value(value) => Some(value),
+                TypedUpdate::TypeChanged(_) => None,
+            })
+    }
+
+    /// The type of the last value read, or `T`'s type if nothing has been read yet.
+    pub fn current_type(&self) -> &ValueType {
+        &self.current_type
+    }
+
+    /// Returns `true` if the last value read could not be read as `T` without a fallback.
+    pub fn is_type_mismatched(&self) -> bool {
+        self.current_type != T::value_type()
+    }
+
+    pub fn subscriber(&self) -> &TopicSubscriber<'a, I> {
+        &self.subscriber
+    }
+}
+
+impl<I: Instance + ?Sized + Debug, T: NetworkTablesValue> Debug for TypedSubscriber<'_, I, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TypedSubscriber")
+            .field("subscriber", &self.subscriber)
+            .field("current_type", &self.current_type)
+            .finish_non_exhaustive()
+    }
+}
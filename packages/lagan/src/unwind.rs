@@ -0,0 +1,34 @@
+//! Catching panics in user callbacks.
+//!
+//! Listener callbacks are called by ntcore, and unwinding into C++ is undefined behavior.
+//! Callbacks on lagan's own threads would take the thread down with them, silently stopping every later callback.
+
+use std::{
+    any::Any,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+#[cfg(feature = "log")]
+use log::error;
+
+/// Calls `f`, reporting a panic as an error mentioning `context` instead of unwinding.
+///
+/// Returns `None` if `f` panicked.
+pub(crate) fn catch<R>(context: &str, f: impl FnOnce() -> R) -> Option<R> {
+    // A callback may be left in a broken state by the panic, so callers decide whether to call it again.
+    catch_unwind(AssertUnwindSafe(f))
+        .map_err(|panic| report(context, &*panic))
+        .ok()
+}
+
+fn report(context: &str, panic: &(dyn Any + Send)) {
+    let message = panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>");
+    #[cfg(feature = "log")]
+    error!("{context} panicked: {message}");
+    #[cfg(not(feature = "log"))]
+    eprintln!("{context} panicked: {message}");
+}
@@ -0,0 +1,249 @@
+//! Checking the values other nodes publish against the types and ranges a program expects of them.
+//!
+//! A robot program and its dashboards only agree on the type of each topic by convention.
+//! When one side changes, typed subscribers on the other side quietly stop receiving values.
+//! A [`Validator`] reports such contract drift as a [`Violation`] as soon as a mismatching value arrives.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Display},
+    ops::RangeInclusive,
+    sync::{Arc, Mutex},
+};
+
+#[cfg(feature = "log")]
+use log::warn;
+
+use crate::{
+    listener::ListenerHandle,
+    lock,
+    nt_types::{Value, ValueType},
+    watch, Instance,
+};
+
+/// What a topic is expected to hold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expectation {
+    pub value_type: ValueType,
+    /// The range numbers, or every element of number arrays, must fall in.
+    pub range: Option<RangeInclusive<f64>>,
+}
+
+impl Expectation {
+    pub fn new(value_type: ValueType) -> Self {
+        Self {
+            value_type,
+            range: None,
+        }
+    }
+
+    /// Also expects numbers to fall in `range`.
+    pub fn with_range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.range = Some(range);
+        self
+    }
+}
+
+/// A value that didn't match the [`Expectation`] of its topic.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    WrongType {
+        topic: Arc<str>,
+        expected: ValueType,
+        actual: ValueType,
+    },
+    OutOfRange {
+        topic: Arc<str>,
+        /// The first number outside the range.
+        value: f64,
+        range: RangeInclusive<f64>,
+    },
+}
+
+impl Violation {
+    /// The name of the topic the value was published to.
+    pub fn topic(&self) -> &Arc<str> {
+        match self {
+            Self::WrongType { topic, .. } | Self::OutOfRange { topic, .. } => topic,
+        }
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongType {
+                topic,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{topic} received a value of type {actual:?} instead of {expected:?}"
+            ),
+            Self::OutOfRange {
+                topic,
+                value,
+                range,
+            } => write!(
+                f,
+                "{topic} received {value}, outside of {}..={}",
+                range.start(),
+                range.end()
+            ),
+        }
+    }
+}
+
+/// The expected types and ranges of a set of topics.
+#[derive(Debug, Clone, Default)]
+pub struct Validator {
+    expectations: HashMap<String, Expectation>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets what the topic named `topic` is expected to hold, replacing any earlier expectation.
+    pub fn expect(&mut self, topic: impl Into<String>, expectation: Expectation) {
+        self.expectations.insert(topic.into(), expectation);
+    }
+
+    /// Checks a value of the topic named `topic`.
+    ///
+    /// Returns `None` if the value matches, or the topic has no expectation.
+    pub fn check(&self, topic: &str, value: &Value) -> Option<Violation> {
+        let expectation = self.expectations.get(topic)?;
+        let actual = value.value_type();
+        if actual != expectation.value_type {
+            return Some(Violation::WrongType {
+                topic: topic.into(),
+                expected: expectation.value_type.clone(),
+                actual,
+            });
+        }
+
+        let range = expectation.range.as_ref()?;
+        let value = match value {
+            Value::I64(value) => (!range.contains(&(*value as f64))).then_some(*value as f64),
+            Value::F32(value) => (!range.contains(&f64::from(*value))).then_some(f64::from(*value)),
+            Value::F64(value) => (!range.contains(value)).then_some(*value),
+            Value::I64Array(values) => values
+                .iter()
+                .map(|value| *value as f64)
+                .find(|value| !range.contains(value)),
+            Value::F32Array(values) => values
+                .iter()
+                .map(|value| f64::from(*value))
+                .find(|value| !range.contains(value)),
+            Value::F64Array(values) => values.iter().copied().find(|value| !range.contains(value)),
+            _ => None,
+        }?;
+        Some(Violation::OutOfRange {
+            topic: topic.into(),
+            value,
+            range: range.clone(),
+        })
+    }
+
+    /// Checks every new value of the expected topics, local or remote, until the returned handle is dropped.
+    ///
+    /// `callback` is called with the first violation of a topic, and again only after the topic
+    /// received a matching value in between, so a peer publishing the wrong type at 50 Hz reports once.
+    /// It runs on ntcore's listener thread.
+    pub fn watch<I: Instance + ?Sized>(
+        self,
+        instance: &I,
+        callback: impl Fn(Violation) + Send + Sync + 'static,
+    ) -> ListenerHandle {
+        let names: Vec<String> = self.expectations.keys().cloned().collect();
+        let violating = Mutex::new(HashSet::<Arc<str>>::new());
+        // Names are used as prefixes, so topics with longer names reach the callback too, and `check` passes them.
+        watch::watch(unsafe { instance.handle() }, names, move |name, value| {
+            match self.check(&name, &value.data) {
+                Some(violation) => {
+                    if lock(&violating).insert(name) {
+                        callback(violation);
+                    }
+                }
+                None => {
+                    lock(&violating).remove(&name);
+                }
+            }
+        })
+    }
+
+    /// Like [`Validator::watch`], but logs every violation as a warning.
+    #[cfg(feature = "log")]
+    pub fn watch_logged<I: Instance + ?Sized>(self, instance: &I) -> ListenerHandle {
+        self.watch(instance, |violation| {
+            warn!("NetworkTables contract violated: {violation}")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validator() -> Validator {
+        let mut validator = Validator::new();
+        validator.expect(
+            "/drive/speed",
+            Expectation::new(ValueType::F64).with_range(-5.0..=5.0),
+        );
+        validator.expect(
+            "/drive/modules",
+            Expectation::new(ValueType::I64Array).with_range(0.0..=3.0),
+        );
+        validator.expect("/auto/mode", Expectation::new(ValueType::String));
+        validator
+    }
+
+    #[test]
+    fn matching_values_pass() {
+        let validator = validator();
+        assert_eq!(validator.check("/drive/speed", &Value::F64(4.5)), None);
+        assert_eq!(
+            validator.check("/drive/modules", &Value::I64Array(vec![0, 3])),
+            None
+        );
+        assert_eq!(
+            validator.check("/auto/mode", &Value::String("Left".to_string())),
+            None
+        );
+        assert_eq!(validator.check("/unexpected", &Value::Bool(true)), None);
+    }
+
+    #[test]
+    fn wrong_types_are_reported() {
+        assert_eq!(
+            validator().check("/drive/speed", &Value::F32(1.0)),
+            Some(Violation::WrongType {
+                topic: "/drive/speed".into(),
+                expected: ValueType::F64,
+                actual: ValueType::F32,
+            })
+        );
+    }
+
+    #[test]
+    fn numbers_out_of_range_are_reported() {
+        let validator = validator();
+        assert_eq!(
+            validator.check("/drive/speed", &Value::F64(-7.5)),
+            Some(Violation::OutOfRange {
+                topic: "/drive/speed".into(),
+                value: -7.5,
+                range: -5.0..=5.0,
+            })
+        );
+        assert_eq!(
+            validator
+                .check("/drive/modules", &Value::I64Array(vec![1, 4, 5]))
+                .map(|violation| violation.to_string()),
+            Some("/drive/modules received 4, outside of 0..=3".to_string())
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! The version of the linked ntcore.
+//!
+//! ntcore can't report its own version, so it is determined when ntcore-sys is built:
+//! from the release downloaded by the `prebuilt` feature, the tag of the vendored allwpilib,
+//! or pkg-config for preinstalled libraries.
+
+use std::fmt::{self, Display};
+
+/// A WPILib release version, such as `2025.3.2` or `2025.1.1-beta-3`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NtcoreVersion {
+    /// The year of the release.
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    /// Everything after the first `-`, e.g. `beta-3`, or `14-g1a2b3c4` for a commit after a release.
+    pub suffix: Option<String>,
+}
+
+impl NtcoreVersion {
+    /// Parses a version like `2025.3.2`, allowing a leading `v` and a `-` suffix.
+    /// Missing minor and patch numbers are zero.
+    ///
+    /// Returns `None` if `version` doesn't start with numbers separated by dots.
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.trim();
+        let version = version.strip_prefix('v').unwrap_or(version);
+        let (numbers, suffix) = match version.split_once('-') {
+            Some((numbers, suffix)) => (numbers, Some(suffix.to_string())),
+            None => (version, None),
+        };
+
+        let mut numbers = numbers.split('.').map(str::parse::<u32>);
+        let major = numbers.next()?.ok()?;
+        let minor = numbers.next().transpose().ok()?.unwrap_or(0);
+        let patch = numbers.next().transpose().ok()?.unwrap_or(0);
+        if numbers.next().is_some() {
+            return None;
+        }
+
+        Some(Self {
+            major,
+            minor,
+            patch,
+            suffix,
+        })
+    }
+
+    /// Whether this version is the given release or a later one, ignoring the suffix.
+    pub fn at_least(&self, major: u32, minor: u32, patch: u32) -> bool {
+        (self.major, self.minor, self.patch) >= (major, minor, patch)
+    }
+}
+
+impl Display for NtcoreVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(suffix) = &self.suffix {
+            write!(f, "-{suffix}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the version of the linked ntcore, or `None` if it couldn't be determined when building.
+pub fn ntcore_version() -> Option<NtcoreVersion> {
+    ntcore_sys::NTCORE_VERSION.and_then(NtcoreVersion::parse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_release_and_describe_versions() {
+        let release = NtcoreVersion::parse("2025.3.2").unwrap();
+        assert_eq!((release.major, release.minor, release.patch), (2025, 3, 2));
+        assert_eq!(release.suffix, None);
+
+        let describe = NtcoreVersion::parse("v2025.3.2-14-g1a2b3c4").unwrap();
+        assert_eq!(describe.suffix.as_deref(), Some("14-g1a2b3c4"));
+        assert_eq!(describe.to_string(), "2025.3.2-14-g1a2b3c4");
+
+        assert!(NtcoreVersion::parse("2025.x").is_none());
+        assert!(NtcoreVersion::parse("").is_none());
+    }
+
+    #[test]
+    fn compares_without_suffix() {
+        let version = NtcoreVersion::parse("2025.1.1-beta-3").unwrap();
+        assert!(version.at_least(2025, 1, 1));
+        assert!(version.at_least(2024, 3, 2));
+        assert!(!version.at_least(2025, 2, 0));
+    }
+}
@@ -0,0 +1,146 @@
+//! Watching the values of every topic under a set of prefixes with a single listener.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+#[cfg(feature = "async")]
+use std::{
+    collections::VecDeque,
+    future::poll_fn,
+    task::{Poll, Waker},
+};
+
+use ntcore_sys::{NT_EventFlags, NT_GetTopicName, NT_Inst, NT_Topic, WPI_String, WpiStringBuf};
+
+use crate::{
+    coalesce::Coalescer,
+    intern,
+    listener::{ListenerHandle, PanicGuard},
+    lock,
+    nt_types::RawValue,
+};
+
+/// Adds one listener for the values of every topic under `prefixes` and calls `callback` with the name and value of each.
+pub(crate) fn watch<P: AsRef<str>>(
+    instance: NT_Inst,
+    prefixes: impl IntoIterator<Item = P>,
+    callback: impl Fn(Arc<str>, RawValue) + Send + Sync + 'static,
+) -> ListenerHandle {
+    let prefixes: Vec<P> = prefixes.into_iter().collect();
+    let raw_prefixes: Vec<WPI_String> = prefixes
+        .iter()
+        .map(|prefix| WPI_String::from(prefix.as_ref()))
+        .collect();
+
+    let names = Mutex::new(HashMap::<NT_Topic, Arc<str>>::new());
+    ListenerHandle::multiple(
+        instance,
+        &raw_prefixes,
+        NT_EventFlags::NT_EVENT_VALUE_ALL,
+        move |event| {
+            if event.flags & NT_EventFlags::NT_EVENT_VALUE_ALL.bits() == 0 {
+                return;
+            }
+            let data = unsafe { event.data.valueData };
+
+            let name = lock(&names)
+                .entry(data.topic)
+                .or_insert_with(|| {
+                    let mut name = WpiStringBuf::new();
+                    unsafe { NT_GetTopicName(data.topic, name.as_mut_ptr()) };
+                    intern::intern(instance, &name.to_str_lossy())
+                })
+                .clone();
+            callback(name, RawValue::from(data.value));
+        },
+    )
+}
+
+/// Like [`watch`], but calls `callback` at most once per topic per `interval`
+/// with the latest value and the number of values skipped before it.
+pub(crate) fn watch_coalesced<P: AsRef<str>>(
+    instance: NT_Inst,
+    prefixes: impl IntoIterator<Item = P>,
+    interval: Duration,
+    callback: impl Fn(Arc<str>, RawValue, u64) + Send + Sync + 'static,
+) -> ListenerHandle {
+    let guard = Arc::new(PanicGuard::default());
+    let (coalescer, flusher) = Coalescer::new(interval, {
+        let guard = guard.clone();
+        move |name: &Arc<str>, value, skipped| guard.call(|| callback(name.clone(), value, skipped))
+    });
+    watch(instance, prefixes, move |name, value| {
+        coalescer.push(name, value)
+    })
+    .with_flusher(flusher, guard)
+}
+
+#[cfg(feature = "async")]
+#[derive(Debug, Default)]
+struct Shared {
+    values: VecDeque<(Arc<str>, RawValue)>,
+    waker: Option<Waker>,
+}
+
+/// The values of every topic under a set of prefixes, read asynchronously.
+///
+/// Created with [`Instance::watch_stream`](crate::Instance::watch_stream).
+/// Values are queued until they are read, so read them regularly.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct WatchStream {
+    shared: Arc<Mutex<Shared>>,
+    _listener: ListenerHandle,
+}
+
+#[cfg(feature = "async")]
+impl WatchStream {
+    pub(crate) fn new<P: AsRef<str>>(
+        instance: NT_Inst,
+        prefixes: impl IntoIterator<Item = P>,
+    ) -> Self {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let listener = watch(instance, prefixes, {
+            let shared = shared.clone();
+            move |name, value| {
+                let mut shared = lock(&shared);
+                shared.values.push_back((name, value));
+                if let Some(waker) = shared.waker.take() {
+                    waker.wake();
+                }
+            }
+        });
+
+        Self {
+            shared,
+            _listener: listener,
+        }
+    }
+
+    /// Waits for the next value and returns it with the name of its topic.
+    pub async fn next(&mut self) -> (Arc<str>, RawValue) {
+        poll_fn(|cx| {
+            let mut shared = lock(&self.shared);
+            match shared.values.pop_front() {
+                Some(value) => Poll::Ready(value),
+                None => {
+                    shared.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+
+    /// Returns the next value if one has already arrived.
+    pub fn try_next(&mut self) -> Option<(Arc<str>, RawValue)> {
+        lock(&self.shared).values.pop_front()
+    }
+
+    /// Returns every value that has arrived since the last read.
+    pub fn drain(&mut self) -> Vec<(Arc<str>, RawValue)> {
+        lock(&self.shared).values.drain(..).collect()
+    }
+}
@@ -0,0 +1,373 @@
+//! Reading WPILib data logs (`.wpilog` files), e.g. ones written by a [`DataLog`](crate::datalog::DataLog).
+//!
+//! Unlike writing, reading is done in Rust, so logs can be read without starting an instance.
+
+use std::{collections::HashMap, fs, path::Path, time::Duration};
+
+use crate::{NetworkTablesError, Value};
+
+const MAGIC: &[u8] = b"WPILOG";
+/// The format version this reader understands, 1.0.
+const VERSION: u16 = 0x0100;
+
+const CONTROL_START: u8 = 0;
+const CONTROL_FINISH: u8 = 1;
+const CONTROL_SET_METADATA: u8 = 2;
+
+/// A stream of data in a log, e.g. a NetworkTables topic logged with the name `NT:/Shooter/Speed`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LogEntry {
+    pub name: String,
+    /// The type of the data, e.g. `double` or `struct:Pose2d`.
+    pub type_string: String,
+    pub metadata: String,
+}
+
+/// A value of an entry, timestamped relative to the robot's clock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    /// The index of the entry in [`WpiLog::entries`].
+    pub entry: usize,
+    pub timestamp: Duration,
+    /// The value, decoded by the type of the entry.
+    ///
+    /// Values of other types, such as `raw` and `struct:` entries, and values whose size doesn't match
+    /// their type are kept as [`Value::Raw`].
+    pub value: Value,
+}
+
+/// The contents of a data log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WpiLog {
+    extra_header: String,
+    entries: Vec<LogEntry>,
+    /// Ordered by timestamp.
+    records: Vec<LogRecord>,
+}
+
+impl WpiLog {
+    /// Reads a data log file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, NetworkTablesError> {
+        let bytes = fs::read(path.as_ref()).map_err(|err| NetworkTablesError::InvalidDataLog {
+            message: format!("{}: {err}", path.as_ref().display()),
+        })?;
+        Self::parse(&bytes)
+    }
+
+    /// Parses the contents of a data log.
+    ///
+    /// A record cut off at the end of the log, e.g. because the robot lost power, ends the log.
+    pub fn parse(bytes: &[u8]) -> Result<Self, NetworkTablesError> {
+        let mut reader = Reader(bytes);
+        if reader.take(MAGIC.len()) != Some(MAGIC) {
+            return Err(invalid("missing the WPILOG header"));
+        }
+        let version = reader
+            .uint(2)
+            .ok_or_else(|| invalid("missing the format version"))?;
+        if version != u64::from(VERSION) {
+            return Err(invalid(format!(
+                "format version {}.{} isn't supported",
+                version >> 8,
+                version & 0xff
+            )));
+        }
+        let extra_header = reader
+            .string()
+            .ok_or_else(|| invalid("missing the extra header"))?;
+
+        let mut log = Self {
+            extra_header,
+            entries: Vec::new(),
+            records: Vec::new(),
+        };
+        // Entry ids can be reused once their entry is finished, so they map to the latest entry started with them.
+        let mut active = HashMap::new();
+        while let Some((id, timestamp, payload)) = reader.record() {
+            if id == 0 {
+                log.control(payload, &mut active);
+            } else if let Some(&entry) = active.get(&id) {
+                let value = decode(&log.entries[entry].type_string, payload);
+                log.records.push(LogRecord {
+                    entry,
+                    timestamp: Duration::from_micros(timestamp),
+                    value,
+                });
+            }
+        }
+        // Stable, so records with the same timestamp stay in the order they were logged.
+        log.records.sort_by_key(|record| record.timestamp);
+        Ok(log)
+    }
+
+    /// Applies a record of the control entry. Malformed control records are ignored.
+    fn control(&mut self, payload: &[u8], active: &mut HashMap<u32, usize>) {
+        let mut reader = Reader(payload);
+        let (Some(kind), Some(id)) = (reader.uint(1), reader.uint(4)) else {
+            return;
+        };
+        let id = id as u32;
+        match kind as u8 {
+            CONTROL_START => {
+                let (Some(name), Some(type_string), Some(metadata)) =
+                    (reader.string(), reader.string(), reader.string())
+                else {
+                    return;
+                };
+                active.insert(id, self.entries.len());
+                self.entries.push(LogEntry {
+                    name,
+                    type_string,
+                    metadata,
+                });
+            }
+            CONTROL_FINISH => {
+                active.remove(&id);
+            }
+            CONTROL_SET_METADATA => {
+                if let (Some(&entry), Some(metadata)) = (active.get(&id), reader.string()) {
+                    self.entries[entry].metadata = metadata;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The header the log was created with, often describing the program that wrote it.
+    pub fn extra_header(&self) -> &str {
+        &self.extra_header
+    }
+
+    /// Returns every entry started in the log, in the order they were started.
+    pub fn entries(&self) -> &[LogEntry] {
+        &self.entries
+    }
+
+    /// Returns every record in the log, ordered by timestamp.
+    pub fn records(&self) -> &[LogRecord] {
+        &self.records
+    }
+
+    /// Returns the records with timestamps up to and including `time`.
+    pub fn records_until(&self, time: Duration) -> &[LogRecord] {
+        let end = self
+            .records
+            .partition_point(|record| record.timestamp <= time);
+        &self.records[..end]
+    }
+
+    /// Returns the entry a record belongs to.
+    pub fn entry(&self, record: &LogRecord) -> Option<&LogEntry> {
+        self.entries.get(record.entry)
+    }
+
+    /// The timestamps of the first and last records, or `None` if the log has no records.
+    pub fn time_range(&self) -> Option<(Duration, Duration)> {
+        Some((
+            self.records.first()?.timestamp,
+            self.records.last()?.timestamp,
+        ))
+    }
+}
+
+fn invalid(message: impl Into<String>) -> NetworkTablesError {
+    NetworkTablesError::InvalidDataLog {
+        message: message.into(),
+    }
+}
+
+/// Reads little-endian fields from the front of a byte slice.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(taken)
+    }
+
+    /// Reads an unsigned integer of `len` bytes, at most 8.
+    fn uint(&mut self, len: usize) -> Option<u64> {
+        let bytes = self.take(len)?;
+        Some(
+            bytes
+                .iter()
+                .rev()
+                .fold(0, |value, byte| (value << 8) | u64::from(*byte)),
+        )
+    }
+
+    /// Reads a string prefixed by its length as a `u32`.
+    fn string(&mut self) -> Option<String> {
+        let len = self.uint(4)?;
+        let bytes = self.take(usize::try_from(len).ok()?)?;
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    /// Reads the entry id, timestamp in microseconds and payload of a record.
+    ///
+    /// The header byte of a record holds the lengths of the variable length fields after it, minus one:
+    /// the entry id in bits 0-1, the payload size in bits 2-3 and the timestamp in bits 4-6.
+    fn record(&mut self) -> Option<(u32, u64, &'a [u8])> {
+        let header = *self.take(1)?.first()?;
+        let id_len = usize::from(header & 0b11) + 1;
+        let size_len = usize::from((header >> 2) & 0b11) + 1;
+        let timestamp_len = usize::from((header >> 4) & 0b111) + 1;
+
+        let id = self.uint(id_len)? as u32;
+        let size = self.uint(size_len)?;
+        let timestamp = self.uint(timestamp_len)?;
+        let payload = self.take(usize::try_from(size).ok()?)?;
+        Some((id, timestamp, payload))
+    }
+}
+
+/// Decodes a payload by the type string of its entry.
+fn decode(type_string: &str, payload: &[u8]) -> Value {
+    fn array<const N: usize, T>(payload: &[u8], from_bytes: fn([u8; N]) -> T) -> Option<Vec<T>> {
+        let chunks = payload.chunks_exact(N);
+        if !chunks.remainder().is_empty() {
+            return None;
+        }
+        Some(
+            chunks
+                .filter_map(|chunk| chunk.try_into().ok().map(from_bytes))
+                .collect(),
+        )
+    }
+    fn single<const N: usize, T>(payload: &[u8], from_bytes: fn([u8; N]) -> T) -> Option<T> {
+        payload.try_into().ok().map(from_bytes)
+    }
+    fn strings(payload: &[u8]) -> Option<Vec<String>> {
+        let mut reader = Reader(payload);
+        let count = reader.uint(4)?;
+        (0..count).map(|_| reader.string()).collect()
+    }
+
+    let value = match type_string {
+        "boolean" => single(payload, |[byte]: [u8; 1]| byte != 0).map(Value::Bool),
+        "int64" => single(payload, i64::from_le_bytes).map(Value::I64),
+        "float" => single(payload, f32::from_le_bytes).map(Value::F32),
+        "double" => single(payload, f64::from_le_bytes).map(Value::F64),
+        "string" | "json" => Some(Value::String(String::from_utf8_lossy(payload).into_owned())),
+        "boolean[]" => Some(Value::BoolArray(
+            payload.iter().map(|byte| *byte != 0).collect(),
+        )),
+        "int64[]" => array(payload, i64::from_le_bytes).map(Value::I64Array),
+        "float[]" => array(payload, f32::from_le_bytes).map(Value::F32Array),
+        "double[]" => array(payload, f64::from_le_bytes).map(Value::F64Array),
+        "string[]" => strings(payload).map(Value::StringArray),
+        _ => None,
+    };
+    value.unwrap_or_else(|| Value::Raw(payload.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> Vec<u8> {
+        let mut bytes = b"WPILOG".to_vec();
+        bytes.extend(VERSION.to_le_bytes());
+        bytes.extend(4u32.to_le_bytes());
+        bytes.extend(b"test");
+        bytes
+    }
+
+    /// Appends a record with 4 byte ids and sizes and 8 byte timestamps.
+    fn record(log: &mut Vec<u8>, id: u32, timestamp: u64, payload: &[u8]) {
+        log.push(0b0111_1111);
+        log.extend(id.to_le_bytes());
+        log.extend((payload.len() as u32).to_le_bytes());
+        log.extend(timestamp.to_le_bytes());
+        log.extend(payload);
+    }
+
+    fn string(text: &str) -> Vec<u8> {
+        let mut bytes = (text.len() as u32).to_le_bytes().to_vec();
+        bytes.extend(text.as_bytes());
+        bytes
+    }
+
+    fn start(log: &mut Vec<u8>, id: u32, name: &str, type_string: &str) {
+        let mut payload = vec![CONTROL_START];
+        payload.extend(id.to_le_bytes());
+        payload.extend(string(name));
+        payload.extend(string(type_string));
+        payload.extend(string(""));
+        record(log, 0, 0, &payload);
+    }
+
+    #[test]
+    fn reads_entries_and_values() {
+        let mut bytes = header();
+        start(&mut bytes, 1, "NT:/Shooter/Speed", "double");
+        start(&mut bytes, 2, "NT:/Alerts/errors", "string[]");
+        record(&mut bytes, 1, 2_000, &4500.0f64.to_le_bytes());
+        record(&mut bytes, 1, 1_000, &3000.0f64.to_le_bytes());
+        let mut alerts = 2u32.to_le_bytes().to_vec();
+        alerts.extend(string("Brownout"));
+        alerts.extend(string("Camera disconnected"));
+        record(&mut bytes, 2, 1_500, &alerts);
+
+        let log = WpiLog::parse(&bytes).unwrap();
+        assert_eq!(log.extra_header(), "test");
+        assert_eq!(log.entries().len(), 2);
+        assert_eq!(log.entries()[0].name, "NT:/Shooter/Speed");
+
+        let values: Vec<_> = log.records().iter().map(|record| &record.value).collect();
+        assert_eq!(
+            values,
+            [
+                &Value::F64(3000.0),
+                &Value::StringArray(vec!["Brownout".into(), "Camera disconnected".into()]),
+                &Value::F64(4500.0),
+            ]
+        );
+        assert_eq!(log.records_until(Duration::from_micros(1_500)).len(), 2);
+        assert_eq!(
+            log.time_range(),
+            Some((Duration::from_micros(1_000), Duration::from_micros(2_000)))
+        );
+    }
+
+    #[test]
+    fn reused_ids_refer_to_the_latest_entry() {
+        let mut bytes = header();
+        start(&mut bytes, 1, "first", "int64");
+        record(&mut bytes, 0, 0, &[CONTROL_FINISH, 1, 0, 0, 0]);
+        record(&mut bytes, 1, 10, &5i64.to_le_bytes());
+        start(&mut bytes, 1, "second", "boolean");
+        record(&mut bytes, 1, 20, &[1]);
+
+        let log = WpiLog::parse(&bytes).unwrap();
+        assert_eq!(log.records().len(), 1);
+        let record = &log.records()[0];
+        assert_eq!(log.entry(record).unwrap().name, "second");
+        assert_eq!(record.value, Value::Bool(true));
+    }
+
+    #[test]
+    fn truncated_records_end_the_log() {
+        let mut bytes = header();
+        start(&mut bytes, 1, "speed", "double");
+        record(&mut bytes, 1, 10, &1.0f64.to_le_bytes());
+        record(&mut bytes, 1, 20, &2.0f64.to_le_bytes());
+        bytes.truncate(bytes.len() - 3);
+
+        let log = WpiLog::parse(&bytes).unwrap();
+        assert_eq!(log.records().len(), 1);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(WpiLog::parse(b"{\"type\": \"json\"}").is_err());
+
+        let mut bytes = b"WPILOG".to_vec();
+        bytes.extend(0x0200u16.to_le_bytes());
+        assert!(WpiLog::parse(&bytes).is_err());
+    }
+}
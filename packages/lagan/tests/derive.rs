@@ -0,0 +1,110 @@
+//! Expands `#[derive(NtTable)]` and `#[derive(StringEnum)]` against the real traits, so changes to either side that
+//! break the generated code fail to compile here.
+//!
+//! Values are published to a client that never connects, which keeps them local to its own instance.
+
+use std::net::{Ipv4Addr, SocketAddr};
+
+use lagan::{
+    nt_types::{NetworkTablesValue, StringEnum, Value},
+    prelude::*,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, StringEnum)]
+enum Auto {
+    Left,
+    #[nt(rename = "Center Line")]
+    Center,
+}
+
+#[derive(Debug, Clone, PartialEq, NtTable)]
+pub struct Drive {
+    max_speed: f64,
+    #[nt(rename = "Gear Ratios")]
+    gear_ratios: Vec<f64>,
+    r#type: String,
+    auto: Auto,
+    #[nt(skip)]
+    cache: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, NtTable)]
+struct Cache {
+    #[nt(skip)]
+    values: Vec<f64>,
+}
+
+fn client() -> Client {
+    Client::new(
+        NetworkTablesVersion::V4,
+        SocketAddr::from((Ipv4Addr::LOCALHOST, 1)),
+        None::<&str>,
+    )
+}
+
+#[test]
+fn string_enums_use_their_names() {
+    assert_eq!(Auto::VARIANTS, ["Left", "Center Line"]);
+    assert_eq!(Auto::Center.name(), "Center Line");
+    assert_eq!(Auto::from_name("Left"), Some(Auto::Left));
+    assert_eq!(Auto::from_name("Center"), None);
+    assert_eq!(
+        Auto::Center.into_value(),
+        Value::String("Center Line".to_string())
+    );
+}
+
+#[test]
+fn tables_round_trip() {
+    let client = client();
+    let drive = Drive {
+        max_speed: 4.5,
+        gear_ratios: vec![8.14, 6.75],
+        r#type: "swerve".to_string(),
+        auto: Auto::Center,
+        cache: vec![1.0],
+    };
+    drive.publish_all(&client, "/Drive").unwrap();
+
+    assert_eq!(
+        client.entry("/Drive/Gear Ratios").value(),
+        Value::F64Array(vec![8.14, 6.75])
+    );
+    assert_eq!(
+        client.entry("/Drive/type").value(),
+        Value::String("swerve".to_string())
+    );
+    assert!(client.entry("/Drive/cache").is_unassigned());
+
+    let read = Drive::read_all(&client, "/Drive").unwrap();
+    assert_eq!(
+        read,
+        Drive {
+            cache: Vec::new(),
+            ..drive
+        }
+    );
+    assert_eq!(Drive::read_all(&client, "/Missing"), None);
+}
+
+#[test]
+fn tunables_start_from_the_current_values() {
+    let client = client();
+    let drive = Drive {
+        max_speed: 4.5,
+        gear_ratios: Vec::new(),
+        r#type: "tank".to_string(),
+        auto: Auto::Left,
+        cache: Vec::new(),
+    };
+    let mut tunables = drive.tunables(&client, "/Tuned");
+    assert_eq!(*tunables.max_speed.get(), 4.5);
+    assert!(!tunables.update());
+
+    let mut empty = Cache { values: vec![1.0] }.tunables(&client, "/Cache");
+    assert!(!empty.update());
+    assert_eq!(
+        Cache::read_all(&client, "/Cache"),
+        Some(Cache { values: Vec::new() })
+    );
+}
@@ -0,0 +1,1007 @@
+//! Behavioral tests of the safe layer against a real server and client talking over loopback.
+//!
+//! These bind local ports, so they only build with the `integration-tests` feature:
+//!
+//! ```sh
+//! cargo test -p lagan --features integration-tests
+//! ```
+//!
+//! The server always runs on the default instance, so tests hold [`LOCK`] and run one at a time.
+//! Instead of sleeping, every test waits on listener pollers for the event it expects.
+
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use lagan::{
+    channel::{Event, EventMask, TopicEventKind},
+    conventions::{
+        alerts::{AlertGroup, AlertLevel, AlertReader, Alerts},
+        sim::{Joystick, SimInput},
+    },
+    nt_types::{PubSubOptions, Value, ValueType},
+    prelude::*,
+    schema::{StructType, TopicKind},
+    validation::{Expectation, Validator, Violation},
+    NetworkTablesError,
+};
+use ntcore_sys::{
+    wpiutil::WPI_WaitForObjectTimeout, NT_AddPolledListener, NT_CreateListenerPoller,
+    NT_DestroyListenerPoller, NT_EventFlags, NT_Handle, NT_ReadListenerQueue, NtEventArray,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// A server and a client connected to it.
+struct Pair {
+    server: Server,
+    client: Client,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl Pair {
+    /// Starts a server on `port` and connects a client to it.
+    ///
+    /// Every test uses its own port so that a client of a previous test can't connect by accident.
+    fn new(port: u16) -> Self {
+        Self::with_persist_file(port, persist_file(&format!("pair-{port}")))
+    }
+
+    /// Starts a server on `port` and connects a NetworkTables 3 client to its NetworkTables 3 port.
+    fn nt3(port: u16) -> Self {
+        Self::start(
+            port,
+            persist_file(&format!("pair-{port}")),
+            NetworkTablesVersion::V3,
+        )
+    }
+
+    fn with_persist_file(port: u16, persist_filename: PathBuf) -> Self {
+        Self::start(port, persist_filename, NetworkTablesVersion::V4)
+    }
+
+    fn start(port: u16, persist_filename: PathBuf, version: NetworkTablesVersion) -> Self {
+        let guard = LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let server = start_server(port, &persist_filename);
+        let client_port = match version {
+            NetworkTablesVersion::V4 => port,
+            NetworkTablesVersion::V3 => port + 1000,
+        };
+        let client = Client::builder()
+            .address(address(client_port))
+            .version(version)
+            .build();
+        assert!(wait_for_connection(&client), "client never connected");
+        Self {
+            server,
+            client,
+            _guard: guard,
+        }
+    }
+}
+
+fn address(port: u16) -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], port))
+}
+
+fn start_server(port: u16, persist_filename: &Path) -> Server {
+    Server::builder()
+        .persist_filename(persist_filename.to_str().unwrap())
+        .listen_address(address(port))
+        .nt3_port(port + 1000)
+        .nt4_port(port)
+        .build()
+        .unwrap()
+}
+
+fn persist_file(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("lagan-{}-{name}.json", std::process::id()))
+}
+
+/// Waits until `condition` holds, re-checking it whenever `handle` has one of the events in `mask`.
+///
+/// Returns `false` if [`TIMEOUT`] elapses first.
+fn wait_until(
+    instance: &impl Instance,
+    handle: NT_Handle,
+    mask: NT_EventFlags,
+    mut condition: impl FnMut() -> bool,
+) -> bool {
+    let deadline = Instant::now() + TIMEOUT;
+    unsafe {
+        let poller = NT_CreateListenerPoller(instance.handle());
+        NT_AddPolledListener(poller, handle, mask.bits());
+
+        let satisfied = loop {
+            if condition() {
+                break true;
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break false;
+            }
+
+            let mut timed_out = 0;
+            WPI_WaitForObjectTimeout(poller, remaining.as_secs_f64(), &raw mut timed_out);
+            let mut count = 0;
+            drop(NtEventArray::from_raw(
+                NT_ReadListenerQueue(poller, &raw mut count),
+                count,
+            ));
+        };
+
+        NT_DestroyListenerPoller(poller);
+        satisfied
+    }
+}
+
+fn wait_for_connection(client: &Client) -> bool {
+    wait_until(
+        client,
+        unsafe { client.handle() },
+        NT_EventFlags::NT_EVENT_CONNECTED,
+        || !client.connections().is_empty(),
+    )
+}
+
+fn wait_for_disconnection(client: &Client) -> bool {
+    wait_until(
+        client,
+        unsafe { client.handle() },
+        NT_EventFlags::NT_EVENT_DISCONNECTED,
+        || client.connections().is_empty(),
+    )
+}
+
+#[test]
+fn server_to_client_round_trip() {
+    let pair = Pair::new(5901);
+
+    let client_topic = pair.client.topic("/integration/server_to_client");
+    let subscriber = client_topic
+        .subscribe(ValueType::F64, "double", PubSubOptions::default())
+        .unwrap();
+
+    let server_topic = pair.server.topic("/integration/server_to_client");
+    let publisher = server_topic
+        .publish(ValueType::F64, "double", PubSubOptions::default())
+        .unwrap();
+    publisher.set_value_f64(1.5).unwrap();
+
+    assert_eq!(
+        subscriber.next_value_blocking(Some(TIMEOUT)),
+        Some(Value::F64(1.5))
+    );
+}
+
+#[test]
+fn client_to_server_round_trip() {
+    let pair = Pair::new(5902);
+
+    let client_topic = pair.client.topic("/integration/client_to_server");
+    let publisher = client_topic
+        .publish(ValueType::String, "string", PubSubOptions::default())
+        .unwrap();
+    publisher.set_value_string("hello").unwrap();
+
+    let entry = pair.server.entry("/integration/client_to_server");
+    assert_eq!(
+        entry.wait_for(|value| *value != Value::Unassigned, Some(TIMEOUT)),
+        Some(Value::String("hello".to_string()))
+    );
+}
+
+#[test]
+fn every_update_is_queued() {
+    let pair = Pair::new(5903);
+
+    let options = PubSubOptions::builder()
+        .send_all_updates(true)
+        .queue_length(16)
+        .build();
+    let client_topic = pair.client.topic("/integration/queued");
+    let subscriber = client_topic
+        .subscribe(ValueType::I64, "int", options)
+        .unwrap();
+
+    let server_topic = pair.server.topic("/integration/queued");
+    let publisher = server_topic
+        .publish(ValueType::I64, "int", options)
+        .unwrap();
+    for value in 0..10 {
+        publisher.set_value_i64(value).unwrap();
+    }
+
+    let received: Vec<_> = subscriber.changes_iter().take(10).collect();
+    assert_eq!(received, (0..10).map(Value::I64).collect::<Vec<_>>());
+}
+
+#[test]
+fn mismatched_types_are_rejected() {
+    let pair = Pair::new(5904);
+
+    let topic = pair.server.topic("/integration/mismatched");
+    let _publisher = topic
+        .publish(ValueType::Bool, "boolean", PubSubOptions::default())
+        .unwrap();
+
+    assert!(topic
+        .publish(ValueType::F64, "double", PubSubOptions::default())
+        .is_err());
+    assert!(topic
+        .subscribe(ValueType::String, "string", PubSubOptions::default())
+        .is_err());
+}
+
+#[test]
+fn flags_reach_clients() {
+    let pair = Pair::new(5905);
+
+    let server_topic = pair.server.topic("/integration/flags");
+    let _publisher = server_topic
+        .publish(ValueType::Bool, "boolean", PubSubOptions::default())
+        .unwrap();
+    server_topic.set_retained(true).unwrap();
+
+    let client_topic = pair.client.topic("/integration/flags");
+    let _subscriber = client_topic
+        .subscribe(ValueType::Bool, "boolean", PubSubOptions::default())
+        .unwrap();
+
+    assert!(wait_until(
+        &pair.client,
+        unsafe { client_topic.handle() },
+        NT_EventFlags::NT_EVENT_TOPIC,
+        || client_topic.is_retained(),
+    ));
+    assert!(!client_topic.is_persistent());
+}
+
+#[test]
+fn properties_reach_clients() {
+    let pair = Pair::new(5906);
+
+    let server_topic = pair.server.topic("/integration/properties");
+    let _publisher = server_topic
+        .publish(ValueType::F64, "double", PubSubOptions::default())
+        .unwrap();
+    server_topic.set_property("unit", "\"m\"").unwrap();
+
+    let client_topic = pair.client.topic("/integration/properties");
+    let _subscriber = client_topic
+        .subscribe(ValueType::F64, "double", PubSubOptions::default())
+        .unwrap();
+
+    assert!(wait_until(
+        &pair.client,
+        unsafe { client_topic.handle() },
+        NT_EventFlags::NT_EVENT_TOPIC,
+        || client_topic.property("unit").as_deref() == Some("\"m\""),
+    ));
+
+    server_topic.delete_property("unit").unwrap();
+    assert!(wait_until(
+        &pair.client,
+        unsafe { client_topic.handle() },
+        NT_EventFlags::NT_EVENT_TOPIC,
+        || client_topic.property("unit").is_none(),
+    ));
+}
+
+#[test]
+fn persistent_values_are_saved() {
+    let persist_filename = persist_file("persistence");
+    let _ = std::fs::remove_file(&persist_filename);
+
+    let mut pair = Pair::with_persist_file(5907, persist_filename.clone());
+    let entry = pair.server.entry("/integration/persistent");
+    assert_eq!(entry.get_or_init(Value::F64(2.5)).unwrap(), Value::F64(2.5));
+
+    // The server writes the file in the background, and there is no event for when it's done.
+    let deadline = Instant::now() + TIMEOUT;
+    let saved = loop {
+        let saved = std::fs::read_to_string(&persist_filename).unwrap_or_default();
+        if saved.contains("/integration/persistent") || Instant::now() > deadline {
+            break saved;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    pair.server.stop();
+    assert!(saved.contains("/integration/persistent"));
+    assert!(saved.contains("2.5"));
+
+    let _ = std::fs::remove_file(&persist_filename);
+}
+
+#[test]
+fn client_reconnects_after_server_restart() {
+    let port = 5908;
+    let Pair {
+        server,
+        client,
+        _guard,
+    } = Pair::new(port);
+
+    // The old server has to be gone before the new one starts, since both use the default instance.
+    drop(server);
+    assert!(wait_for_disconnection(&client), "client never disconnected");
+
+    let server = start_server(port, &persist_file(&format!("pair-{port}")));
+    assert!(wait_for_connection(&client), "client never reconnected");
+
+    let client_topic = client.topic("/integration/reconnect");
+    let subscriber = client_topic
+        .subscribe(ValueType::Bool, "boolean", PubSubOptions::default())
+        .unwrap();
+    let server_topic = server.topic("/integration/reconnect");
+    let publisher = server_topic
+        .publish(ValueType::Bool, "boolean", PubSubOptions::default())
+        .unwrap();
+    publisher.set_value_bool(true).unwrap();
+
+    assert_eq!(
+        subscriber.next_value_blocking(Some(TIMEOUT)),
+        Some(Value::Bool(true))
+    );
+}
+
+#[test]
+fn nt3_client_round_trip() {
+    let pair = Pair::nt3(5909);
+    assert_eq!(pair.client.version(), NetworkTablesVersion::V3);
+
+    let client_topic = pair.client.topic("/integration/nt3");
+    let publisher = client_topic
+        .publish(ValueType::F64, "double", PubSubOptions::default())
+        .unwrap();
+    publisher.set_value_f64(4.5).unwrap();
+
+    let entry = pair.server.entry("/integration/nt3");
+    assert_eq!(
+        entry.wait_for(|value| *value != Value::Unassigned, Some(TIMEOUT)),
+        Some(Value::F64(4.5))
+    );
+}
+
+#[test]
+fn nt3_client_rejects_nt4_features() {
+    let pair = Pair::nt3(5910);
+
+    let topic = pair.client.topic("/integration/nt3-only");
+    assert!(matches!(
+        topic.publish(ValueType::I64, "int", PubSubOptions::default()),
+        Err(NetworkTablesError::Nt4Only { .. })
+    ));
+    assert!(matches!(
+        topic.set_property("unit", "\"m\""),
+        Err(NetworkTablesError::Nt4Only { .. })
+    ));
+    assert!(matches!(
+        topic.set_retained(true),
+        Err(NetworkTablesError::Nt4Only { .. })
+    ));
+    topic.set_flags(ValueFlags::PERSISTENT).unwrap();
+}
+
+#[test]
+fn persistent_values_are_saved_on_demand() {
+    let persist_filename = persist_file("save-on-demand");
+    let moved_filename = persist_file("save-on-demand-moved");
+    let _ = std::fs::remove_file(&persist_filename);
+    let _ = std::fs::remove_file(&moved_filename);
+
+    let mut pair = Pair::with_persist_file(5911, persist_filename.clone());
+    let entry = pair.server.entry("/integration/saved");
+    entry
+        .get_or_init(Value::String("left".to_string()))
+        .unwrap();
+
+    pair.server.save_persistent().unwrap();
+    let saved = std::fs::read_to_string(&persist_filename).unwrap();
+    assert!(saved.contains("/integration/saved"));
+    assert!(saved.contains("\"left\""));
+
+    // The persistent file can only be changed while the server is stopped.
+    assert!(matches!(
+        pair.server.set_persist_filename(moved_filename.to_str().unwrap()),
+        Err(NetworkTablesError::ServerRunning)
+    ));
+    pair.server.stop();
+    pair.server
+        .set_persist_filename(moved_filename.to_str().unwrap())
+        .unwrap();
+    pair.server.restart().unwrap();
+    assert_eq!(pair.server.persist_filename(), moved_filename.to_str());
+    pair.server.save_persistent().unwrap();
+    assert!(std::fs::read_to_string(&moved_filename)
+        .unwrap()
+        .contains("/integration/saved"));
+
+    pair.server.stop();
+    let _ = std::fs::remove_file(&persist_filename);
+    let _ = std::fs::remove_file(&moved_filename);
+}
+
+#[test]
+fn corrupt_persistent_file_is_reported() {
+    let _guard = LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let persist_filename = persist_file("corrupt");
+    std::fs::write(&persist_filename, "[{\"name\": \"/integration/corrupt\",").unwrap();
+
+    let result = Server::new(
+        persist_filename.to_str().unwrap(),
+        Some(address(5912)),
+        6912,
+        5912,
+    );
+    assert!(matches!(
+        result,
+        Err(NetworkTablesError::Persistence { .. })
+    ));
+    // The file is left alone for the user to fix.
+    assert!(std::fs::read_to_string(&persist_filename)
+        .unwrap()
+        .ends_with(','));
+
+    let _ = std::fs::remove_file(&persist_filename);
+}
+
+#[test]
+fn annotations_reach_clients() {
+    let pair = Pair::new(5913);
+
+    let server_topic = pair.server.topic("/integration/annotated");
+    let _publisher = server_topic
+        .publish(ValueType::F64, "double", PubSubOptions::default())
+        .unwrap();
+    server_topic.set_unit("m/s").unwrap();
+    server_topic
+        .set_description("Drivetrain \"forward\" speed")
+        .unwrap();
+
+    let client_topic = pair.client.topic("/integration/annotated");
+    let _subscriber = client_topic
+        .subscribe(ValueType::F64, "double", PubSubOptions::default())
+        .unwrap();
+
+    assert!(wait_until(
+        &pair.client,
+        unsafe { client_topic.handle() },
+        NT_EventFlags::NT_EVENT_TOPIC,
+        || client_topic.description().is_some(),
+    ));
+    assert_eq!(client_topic.unit().as_deref(), Some("m/s"));
+    assert_eq!(
+        client_topic.description().as_deref(),
+        Some("Drivetrain \"forward\" speed")
+    );
+}
+
+#[test]
+fn struct_topics_resolve_their_schema() {
+    let pair = Pair::new(5914);
+    let _schemas = pair.client.subscribe_schemas();
+
+    // The point is announced first, so the schema value is the last thing the client receives.
+    let point_topic = pair.server.topic("/integration/point");
+    let _point_publisher = point_topic
+        .publish(ValueType::Raw, "struct:Point[]", PubSubOptions::default())
+        .unwrap();
+    let schema_topic = pair.server.topic("/.schema/struct:Point");
+    let schema_publisher = schema_topic
+        .publish(ValueType::Raw, "structschema", PubSubOptions::default())
+        .unwrap();
+    schema_publisher
+        .set_value_raw(b"double x;double y".to_vec())
+        .unwrap();
+
+    let client_schema_topic = pair.client.topic("/.schema/struct:Point");
+    let point_kind = || {
+        pair.client
+            .topics_iter("/integration/point")
+            .next()
+            .map(|info| info.kind)
+    };
+    assert!(wait_until(
+        &pair.client,
+        unsafe { client_schema_topic.handle() },
+        NT_EventFlags::NT_EVENT_VALUE_ALL,
+        || matches!(
+            point_kind(),
+            Some(TopicKind::Struct(StructType {
+                schema: Some(_),
+                ..
+            }))
+        ),
+    ));
+    assert_eq!(
+        point_kind(),
+        Some(TopicKind::Struct(StructType {
+            name: "Point".to_string(),
+            array: true,
+            schema: Some("double x;double y".to_string()),
+        }))
+    );
+}
+
+#[test]
+fn coalesced_listeners_deliver_the_latest_value() {
+    let pair = Pair::new(5915);
+
+    let topic = pair.server.topic("/integration/coalesced");
+    let publisher = topic
+        .publish(ValueType::I64, "int", PubSubOptions::default())
+        .unwrap();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let sender = Mutex::new(sender);
+    let _listener = topic.on_change_coalesced(Duration::from_millis(200), move |value, skipped| {
+        let _ = sender.lock().unwrap().send((value.data, skipped));
+    });
+
+    for i in 0..50 {
+        publisher.set_value_i64(i).unwrap();
+    }
+
+    let mut deliveries = Vec::new();
+    while let Ok(delivery) = receiver.recv_timeout(Duration::from_secs(1)) {
+        let done = delivery.0 == Value::I64(49);
+        deliveries.push(delivery);
+        if done {
+            break;
+        }
+    }
+    assert_eq!(
+        deliveries.last().map(|(value, _)| value),
+        Some(&Value::I64(49))
+    );
+    assert!(deliveries.len() < 50);
+    let seen = deliveries
+        .iter()
+        .map(|(_, skipped)| skipped + 1)
+        .sum::<u64>();
+    assert_eq!(seen, 50);
+}
+
+#[test]
+fn events_arrive_over_a_channel() {
+    let pair = Pair::new(5916);
+    let events = pair
+        .client
+        .event_channel(EventMask::VALUE, ["/integration/channel"]);
+
+    let topic = pair.server.topic("/integration/channel");
+    let publisher = topic
+        .publish(ValueType::Bool, "boolean", PubSubOptions::default())
+        .unwrap();
+    publisher.set_value_bool(true).unwrap();
+
+    let event = events.recv_timeout(TIMEOUT).unwrap();
+    let Event::Value { name, value } = event else {
+        panic!("expected a value event, got {event:?}");
+    };
+    assert_eq!(&*name, "/integration/channel");
+    assert_eq!(value.data, Value::Bool(true));
+}
+
+#[cfg(all(feature = "async", feature = "tokio"))]
+#[test]
+fn topics_plug_into_tokio_channels() {
+    let pair = Pair::new(5917);
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let client_topic = pair.client.topic("/integration/dataflow");
+    let server_topic = pair.server.topic("/integration/dataflow");
+    let publisher = server_topic
+        .publish(ValueType::F64, "double", PubSubOptions::default())
+        .unwrap();
+
+    runtime.block_on(async {
+        let mut values = client_topic
+            .subscribe(ValueType::F64, "double", PubSubOptions::default())
+            .unwrap()
+            .into_watch();
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(8);
+        sender.send(Value::F64(1.5)).await.unwrap();
+        drop(sender);
+        publisher.publish_from(receiver).await.unwrap();
+
+        tokio::time::timeout(TIMEOUT, values.wait_for(|value| *value == Value::F64(1.5)))
+            .await
+            .unwrap()
+            .unwrap();
+    });
+}
+
+#[test]
+fn clients_receive_time_sync_events() {
+    let pair = Pair::new(5918);
+    let events = pair
+        .client
+        .event_channel(EventMask::TIME_SYNC, std::iter::empty::<&str>());
+
+    // The client measures the round trip time periodically, not only when connecting.
+    let event = events.recv_timeout(TIMEOUT * 3).unwrap();
+    let Event::TimeSync(time_sync) = event else {
+        panic!("expected a time sync event, got {event:?}");
+    };
+    assert!(time_sync.valid);
+}
+
+#[test]
+fn auto_subscriptions_follow_announcements() {
+    let pair = Pair::new(5919);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let sender = Mutex::new(sender);
+    let subscription = pair.client.auto_subscribe(
+        "/integration/auto/",
+        PubSubOptions::default(),
+        move |kind, info| {
+            let _ = sender.lock().unwrap().send((kind, info.name));
+        },
+    );
+
+    let topic = pair.server.topic("/integration/auto/value");
+    let publisher = topic
+        .publish(ValueType::I64, "int", PubSubOptions::default())
+        .unwrap();
+    publisher.set_value_i64(3).unwrap();
+
+    let (kind, name) = receiver.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(kind, TopicEventKind::Published);
+    assert_eq!(&*name, "/integration/auto/value");
+    assert_eq!(subscription.topics(), vec![name]);
+
+    drop(publisher);
+    let (kind, _) = receiver.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(kind, TopicEventKind::Unpublished);
+    assert!(subscription.topics().is_empty());
+}
+
+#[test]
+fn validators_report_mismatching_values_once() {
+    let pair = Pair::new(5920);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let sender = Mutex::new(sender);
+    let mut validator = Validator::new();
+    validator.expect(
+        "/integration/validated",
+        Expectation::new(ValueType::F64).with_range(0.0..=1.0),
+    );
+    let _listener = validator.watch(&pair.client, move |violation| {
+        let _ = sender.lock().unwrap().send(violation);
+    });
+
+    let topic = pair.server.topic("/integration/validated");
+    let publisher = topic
+        .publish(ValueType::I64, "int", PubSubOptions::default())
+        .unwrap();
+    publisher.set_value_i64(1).unwrap();
+    publisher.set_value_i64(2).unwrap();
+
+    assert_eq!(
+        receiver.recv_timeout(TIMEOUT).unwrap(),
+        Violation::WrongType {
+            topic: "/integration/validated".into(),
+            expected: ValueType::F64,
+            actual: ValueType::I64,
+        }
+    );
+    assert!(receiver.recv_timeout(Duration::from_millis(500)).is_err());
+}
+
+#[test]
+fn publishers_cache_the_topic_type() {
+    let pair = Pair::new(5921);
+
+    let topic = pair.server.topic("/integration/cached_type");
+    let publisher = topic
+        .publish(ValueType::F64, "double", PubSubOptions::default())
+        .unwrap();
+
+    assert_eq!(publisher.value_type(), ValueType::F64);
+    assert_eq!(publisher.refresh_type(), ValueType::F64);
+    assert!(publisher.set_value_i64(1).is_err());
+    publisher.set_value_f64(1.0).unwrap();
+}
+
+#[test]
+fn panicking_listeners_can_be_removed() {
+    let pair = Pair::new(5922);
+
+    let topic = pair.server.topic("/integration/panicking");
+    let publisher = topic
+        .publish(ValueType::I64, "int", PubSubOptions::default())
+        .unwrap();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let sender = Mutex::new(sender);
+    let listener = topic
+        .on_change(move |value| {
+            let _ = sender.lock().unwrap().send(value.data);
+            panic!("listener failed");
+        })
+        .remove_on_panic();
+
+    publisher.set_value_i64(1).unwrap();
+    assert_eq!(receiver.recv_timeout(TIMEOUT), Ok(Value::I64(1)));
+    publisher.set_value_i64(2).unwrap();
+    assert!(receiver.recv_timeout(Duration::from_millis(500)).is_err());
+    assert!(listener.has_panicked());
+}
+
+#[test]
+fn listeners_outliving_their_instance_leave_new_instances_alone() {
+    let pair = Pair::new(5923);
+    let outlived = pair.server.watch(["/integration/outlived"], |_, _| {});
+    drop(pair);
+
+    // ntcore hands out the same handles to the new instances, so the old listener must not remove the new one.
+    let pair = Pair::new(5924);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let sender = Mutex::new(sender);
+    let _listener = pair.server.watch(["/integration/outlived"], move |_, value| {
+        let _ = sender.lock().unwrap().send(value.data);
+    });
+    drop(outlived);
+
+    let topic = pair.server.topic("/integration/outlived");
+    let publisher = topic
+        .publish(ValueType::Bool, "boolean", PubSubOptions::default())
+        .unwrap();
+    publisher.set_value_bool(true).unwrap();
+    assert_eq!(receiver.recv_timeout(TIMEOUT), Ok(Value::Bool(true)));
+}
+
+#[test]
+fn servers_on_the_default_instance_share_it() {
+    let pair = Pair::new(5925);
+
+    assert!(matches!(
+        Server::new("unused.json", Some(address(5926)), 6926, 5926),
+        Err(NetworkTablesError::InstanceInUse)
+    ));
+    let shared = Server::shared().unwrap();
+    assert_eq!(shared.persist_filename(), None);
+    drop(shared);
+
+    // Dropping the second server neither stopped nor destroyed the first one.
+    let topic = pair.server.topic("/integration/shared");
+    let publisher = topic
+        .publish(ValueType::I64, "int", PubSubOptions::default())
+        .unwrap();
+    publisher.set_value_i64(3).unwrap();
+    let entry = pair.client.entry("/integration/shared");
+    assert_eq!(
+        entry.wait_for(|value| *value != Value::Unassigned, Some(TIMEOUT)),
+        Some(Value::I64(3))
+    );
+}
+
+#[test]
+fn sim_inputs_reach_the_simulation() {
+    let pair = Pair::new(5927);
+
+    let driver = SimInput::with_prefix(&pair.client, "/integration/sim/");
+    let joystick = Joystick {
+        axes: vec![0.5, -1.0],
+        buttons: vec![true, false, true],
+        povs: vec![90],
+    };
+    driver.set_joystick(0, &joystick).unwrap();
+    driver.set_bool("Enabled", true).unwrap();
+
+    let robot = SimInput::with_prefix(&pair.server, "/integration/sim");
+    let enabled = pair.server.entry("/integration/sim/Enabled");
+    assert_eq!(
+        enabled.wait_for(|value| *value != Value::Unassigned, Some(TIMEOUT)),
+        Some(Value::Bool(true))
+    );
+    assert_eq!(robot.bool("Enabled"), Some(true));
+    assert_eq!(robot.joystick(0), Some(joystick));
+    assert_eq!(robot.joystick(1), None);
+    assert_eq!(robot.number("Enabled"), None);
+}
+
+#[test]
+fn alerts_reach_dashboards() {
+    let pair = Pair::new(5928);
+
+    let mut group = AlertGroup::new(&pair.server, "/integration/Alerts").unwrap();
+    group.raise(AlertLevel::Error, "Arm encoder unplugged").unwrap();
+    group.raise(AlertLevel::Warning, "Battery low").unwrap();
+    group.raise(AlertLevel::Warning, "Camera disconnected").unwrap();
+    group.clear(AlertLevel::Warning, "Battery low").unwrap();
+
+    let reader = AlertReader::new(&pair.client, "/integration/Alerts/");
+    let expected = Alerts {
+        errors: vec!["Arm encoder unplugged".to_string()],
+        warnings: vec!["Camera disconnected".to_string()],
+        infos: Vec::new(),
+    };
+    assert_eq!(group.alerts(), expected);
+    let entry = pair.client.entry("/integration/Alerts/warnings");
+    assert_eq!(
+        entry.wait_for(|value| *value != Value::Unassigned, Some(TIMEOUT)),
+        Some(Value::StringArray(expected.warnings.clone()))
+    );
+    assert_eq!(reader.alerts(), expected);
+    assert_eq!(
+        pair.client.entry("/integration/Alerts/.type").value_string(),
+        Some("Alerts".to_string())
+    );
+}
+
+#[test]
+fn migrations_keep_persistent_settings() {
+    let pair = Pair::new(5929);
+
+    let old = pair.server.entry("/integration/OldDashboard/Auto/delay");
+    old.set_value_f64(2.5).unwrap();
+    let old_topic = pair.server.topic("/integration/OldDashboard/Auto/delay");
+    old_topic.set_persistent(true);
+    old_topic.set_property("unit", "\"s\"").unwrap();
+
+    let _copies = pair
+        .server
+        .migrate("/integration/OldDashboard/", "/integration/Dashboard/", true)
+        .unwrap();
+
+    let new_topic = pair.server.topic("/integration/Dashboard/Auto/delay");
+    assert!(new_topic.is_persistent());
+    assert_eq!(new_topic.property("unit"), Some("\"s\"".to_string()));
+    assert!(!old_topic.is_persistent());
+
+    let new = pair.client.entry("/integration/Dashboard/Auto/delay");
+    assert_eq!(
+        new.wait_for(|value| *value != Value::Unassigned, Some(TIMEOUT)),
+        Some(Value::F64(2.5))
+    );
+}
+
+#[test]
+fn listeners_can_be_dropped_from_their_own_callback() {
+    let pair = Pair::new(5930);
+
+    let topic = pair.server.topic("/integration/self_removing");
+    let publisher = topic
+        .publish(ValueType::I64, "int", PubSubOptions::default())
+        .unwrap();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let sender = Mutex::new(sender);
+    let slot = std::sync::Arc::new(Mutex::new(None));
+    let listener = topic.on_change({
+        let slot = slot.clone();
+        move |value| {
+            let _ = sender.lock().unwrap().send(value.data);
+            drop(slot.lock().unwrap().take());
+        }
+    });
+    *slot.lock().unwrap() = Some(listener);
+
+    publisher.set_value_i64(1).unwrap();
+    assert_eq!(receiver.recv_timeout(TIMEOUT), Ok(Value::I64(1)));
+    publisher.set_value_i64(2).unwrap();
+    assert!(receiver.recv_timeout(Duration::from_millis(500)).is_err());
+    assert!(slot.lock().unwrap().is_none());
+}
+
+#[test]
+fn set_if_changed_skips_repeated_values() {
+    let pair = Pair::new(5931);
+
+    let topic = pair.server.topic("/integration/deduplicated");
+    let publisher = topic
+        .publish(ValueType::String, "string", PubSubOptions::default())
+        .unwrap();
+    let left = || Value::String("left".to_string());
+
+    assert!(publisher.set_if_changed(left()).unwrap());
+    assert!(!publisher.set_if_changed(left()).unwrap());
+    // Values published otherwise aren't remembered, so the next value is published again.
+    publisher.set_value(left()).unwrap();
+    assert!(publisher.set_if_changed(left()).unwrap());
+    assert!(!publisher.set_if_changed(left()).unwrap());
+
+    let doubles = pair.server.topic("/integration/deduplicated_doubles");
+    let publisher = doubles
+        .publish(ValueType::F64, "double", PubSubOptions::default())
+        .unwrap();
+    publisher.set_value_f64(1.0).unwrap();
+    assert!(!publisher.set_if_changed(Value::F64(1.0)).unwrap());
+    assert!(publisher.set_f64_fast(2.0));
+    assert!(!publisher
+        .set_if_changed_within(Value::F64(2.05), 0.1)
+        .unwrap());
+    assert!(publisher
+        .set_if_changed_within(Value::F64(2.5), 0.1)
+        .unwrap());
+}
+
+#[test]
+fn coalesced_listeners_report_panics_of_held_back_values() {
+    let pair = Pair::new(5932);
+
+    let topic = pair.server.topic("/integration/coalesced_panics");
+    let publisher = topic
+        .publish(ValueType::I64, "int", PubSubOptions::default())
+        .unwrap();
+    let calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let listener = topic
+        .on_change_coalesced(Duration::from_millis(100), {
+            let calls = calls.clone();
+            move |_, skipped| {
+                calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                // Only values that were held back are skipped over, and those are delivered by the flusher.
+                assert_eq!(skipped, 0);
+            }
+        })
+        .remove_on_panic();
+
+    for i in 0..10 {
+        publisher.set_value_i64(i).unwrap();
+    }
+    let deadline = Instant::now() + TIMEOUT;
+    while !listener.has_panicked() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(listener.has_panicked());
+
+    let calls_after_panic = calls.load(std::sync::atomic::Ordering::SeqCst);
+    for i in 10..20 {
+        publisher.set_value_i64(i).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    std::thread::sleep(Duration::from_millis(300));
+    assert_eq!(
+        calls.load(std::sync::atomic::Ordering::SeqCst),
+        calls_after_panic
+    );
+}
+
+#[test]
+fn chunked_payloads_can_shrink() {
+    let pair = Pair::new(5933);
+    let name = "/integration/chunked";
+
+    let large = (0..=255).collect::<Vec<u8>>();
+    lagan::chunked::publish_chunked(&pair.server, name, &large, 100).unwrap();
+    assert_eq!(
+        lagan::chunked::read_chunked(&pair.server, name),
+        Ok(Some(large))
+    );
+
+    let small = vec![1, 2, 3];
+    lagan::chunked::publish_chunked(&pair.server, name, &small, 100).unwrap();
+    assert_eq!(
+        lagan::chunked::read_chunked(&pair.server, name),
+        Ok(Some(small))
+    );
+
+    assert_eq!(
+        lagan::chunked::publish_chunked(&pair.server, name, &[1], 0),
+        Err(NetworkTablesError::InvalidChunkSize)
+    );
+}
+
+#[test]
+fn clones_share_whether_they_are_stopped() {
+    let pair = Pair::new(5934);
+
+    let mut client = pair.client.clone();
+    client.stop();
+    assert!(pair.client.is_stopped());
+
+    let mut server = pair.server.clone();
+    server.stop();
+    assert!(pair.server.is_stopped());
+    server.restart().unwrap();
+    assert!(!pair.server.is_stopped());
+}
@@ -1,12 +1,213 @@
-use std::process::Command;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
 
+#[cfg(feature = "prebuilt")]
+#[path = "build/prebuilt.rs"]
+mod prebuilt;
+#[cfg(feature = "system")]
+#[path = "build/system.rs"]
+mod system;
+
+/// Where the ntcore, wpinet, and wpiutil libraries come from.
+pub struct Libraries {
+    /// Directories to search for the libraries in.
+    pub search_dirs: Vec<PathBuf>,
+    /// Include directories needed to build the shim.
+    pub include_dirs: Vec<PathBuf>,
+    /// Appended to every library name, e.g. `d` for debug builds of allwpilib.
+    pub suffix: &'static str,
+    /// The WPILib version of the libraries, e.g. `2025.3.2`, if it is known.
+    pub version: Option<String>,
+}
+
+/// The platform being compiled for.
+pub struct Target {
+    /// The target triple, e.g. `arm-unknown-linux-gnueabi`.
+    pub triple: String,
+    /// Whether the target differs from the host.
+    pub cross: bool,
+    pub os: String,
+    pub env: String,
+    /// Whether the C runtime is linked statically (`-C target-feature=+crt-static`).
+    pub static_crt: bool,
+}
+impl Target {
+    fn from_env() -> Self {
+        let features = std::env::var("CARGO_CFG_TARGET_FEATURE").unwrap_or_default();
+
+        let triple = std::env::var("TARGET").unwrap();
+        let host = std::env::var("HOST").unwrap();
+
+        Self {
+            cross: triple != host,
+            triple,
+            os: std::env::var("CARGO_CFG_TARGET_OS").unwrap(),
+            env: std::env::var("CARGO_CFG_TARGET_ENV").unwrap(),
+            static_crt: features.split(',').any(|feature| feature == "crt-static"),
+        }
+    }
+
+    pub fn is_msvc(&self) -> bool {
+        self.env == "msvc"
+    }
+    pub fn is_macos(&self) -> bool {
+        self.os == "macos"
+    }
+    pub fn is_roborio(&self) -> bool {
+        self.triple == "arm-unknown-linux-gnueabi"
+    }
+
+    /// The prefix of the roboRIO compiler executables.
+    /// Can be overridden with the `NTCORE_SYS_ROBORIO_TOOLCHAIN` environment variable.
+    pub fn roborio_toolchain_prefix(&self) -> String {
+        println!("cargo:rerun-if-env-changed=NTCORE_SYS_ROBORIO_TOOLCHAIN");
+        std::env::var("NTCORE_SYS_ROBORIO_TOOLCHAIN")
+            .unwrap_or_else(|_| "arm-frc2025-linux-gnueabi-".to_string())
+    }
+
+    /// Whether to build universal (x86_64 and arm64) macOS libraries.
+    /// Enabled by setting `NTCORE_SYS_UNIVERSAL`.
+    pub fn universal(&self) -> bool {
+        println!("cargo:rerun-if-env-changed=NTCORE_SYS_UNIVERSAL");
+        self.is_macos() && std::env::var_os("NTCORE_SYS_UNIVERSAL").is_some()
+    }
+}
+
+// Some of these are only needed when building allwpilib.
+#[cfg_attr(feature = "system", allow(unused_variables))]
 fn main() {
     let out_dir = std::env::var("OUT_DIR").unwrap();
     let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
     let release = std::env::var("PROFILE").unwrap() == "release";
-    let maybe_d = if release { "" } else { "d" };
+    let target = Target::from_env();
+    // Rust always links the release CRT on MSVC, so debug builds of allwpilib (which use the debug CRT) can't be linked.
+    let debug = !release && !target.is_msvc();
+
+    // Preinstalled libraries take priority over downloading them, which takes priority over building them.
+    #[cfg(feature = "system")]
+    let libraries = system::locate();
+    #[cfg(all(feature = "prebuilt", not(feature = "system")))]
+    let libraries = prebuilt::download(&out_dir, debug);
+    #[cfg(not(any(feature = "prebuilt", feature = "system")))]
+    let libraries = build_vendored(&manifest_dir, &out_dir, &target, debug);
+
+    // wpinet has no C API for the port forwarder, so a small shim provides one,
+    // and resolves the ntcore functions older releases don't export (see `probe.rs`).
+    let mut shim = cc::Build::new();
+    shim.cpp(true)
+        .std("c++20")
+        .static_crt(target.static_crt)
+        .file("src/shim/port_forwarder.cpp")
+        .file("src/shim/optional.cpp")
+        .includes(&libraries.include_dirs);
+    if target.is_roborio() {
+        shim.compiler(format!("{}g++", target.roborio_toolchain_prefix()));
+    }
+    if target.universal() {
+        shim.flag("-arch")
+            .flag("arm64")
+            .flag("-arch")
+            .flag("x86_64");
+    }
+    shim.compile("lagan_shim");
+    println!("cargo:rerun-if-changed=src/shim");
+    println!("cargo:rerun-if-changed=build/toolchains");
+
+    #[cfg(feature = "bindgen")]
+    generate_bindings(&manifest_dir, &out_dir);
+
+    // Exposed as `ntcore_sys::NTCORE_VERSION`, since ntcore can't report its own version.
+    if let Some(version) = &libraries.version {
+        println!("cargo:rustc-env=NTCORE_SYS_NTCORE_VERSION={version}");
+    }
+
+    link_platform_libraries(&target);
+    for dir in &libraries.search_dirs {
+        println!("cargo:rustc-link-search=native={}", dir.display());
+        // Preinstalled libraries may be dylibs, which have to be found again at runtime.
+        #[cfg(feature = "system")]
+        if target.is_macos() {
+            println!("cargo:rustc-link-arg=-Wl,-rpath,{}", dir.display());
+        }
+    }
+    for library in ["ntcore", "wpinet", "wpiutil"] {
+        println!("cargo:rustc-link-lib={library}{}", libraries.suffix);
+    }
+}
 
-    cmake::Config::new(format!("{manifest_dir}/allwpilib"))
+/// Links the C++ standard library and the system libraries that ntcore, wpinet, and wpiutil depend on.
+fn link_platform_libraries(target: &Target) {
+    if target.is_msvc() {
+        // The MSVC C++ runtime is linked automatically, but libuv (used by wpinet) needs these.
+        for library in [
+            "ws2_32", "iphlpapi", "userenv", "psapi", "advapi32", "user32", "shell32", "ole32",
+            "dbghelp",
+        ] {
+            println!("cargo:rustc-link-lib={library}");
+        }
+    } else if target.is_macos() {
+        println!("cargo:rustc-link-lib=c++");
+        println!("cargo:rustc-link-lib=framework=CoreFoundation");
+    } else {
+        println!("cargo:rustc-link-lib=stdc++");
+    }
+}
+
+/// Builds the vendored allwpilib submodule with CMake.
+///
+/// The build is skipped entirely if the submodule is at the same revision as the last build.
+#[cfg_attr(any(feature = "prebuilt", feature = "system"), allow(dead_code))]
+fn build_vendored(manifest_dir: &str, out_dir: &str, target: &Target, debug: bool) -> Libraries {
+    let allwpilib = Path::new(manifest_dir).join("allwpilib");
+    let mut search_dirs = vec![Path::new(out_dir).join("lib")];
+    if target.is_msvc() {
+        // Visual Studio generators are multi-config and put libraries in a per-configuration directory.
+        search_dirs.push(Path::new(out_dir).join("build/lib/Release"));
+    }
+    let libraries = Libraries {
+        search_dirs,
+        include_dirs: vec![
+            allwpilib.join("wpinet/src/main/native/include"),
+            allwpilib.join("wpiutil/src/main/native/include"),
+            allwpilib.join("wpiutil/src/main/native/thirdparty/llvm/include"),
+            allwpilib.join("wpiutil/src/main/native/thirdparty/fmtlib/include"),
+        ],
+        suffix: if debug { "d" } else { "" },
+        version: submodule_version(&allwpilib),
+    };
+
+    let stamp_path = Path::new(out_dir).join("allwpilib.stamp");
+    let stamp = submodule_revision(&allwpilib).map(|revision| format!("{revision} {debug}"));
+    if stamp.is_some() && std::fs::read_to_string(&stamp_path).ok() == stamp {
+        return libraries;
+    }
+
+    let mut config = cmake::Config::new(&allwpilib);
+    if target.universal() {
+        config.define("CMAKE_OSX_ARCHITECTURES", "arm64;x86_64");
+    }
+    if target.is_roborio() {
+        config
+            .define(
+                "CMAKE_TOOLCHAIN_FILE",
+                format!("{manifest_dir}/build/toolchains/roborio.cmake"),
+            )
+            .define(
+                "ROBORIO_TOOLCHAIN_PREFIX",
+                target.roborio_toolchain_prefix(),
+            );
+    }
+    if target.cross {
+        // Executables built for the target can't be run while building.
+        config.define("WITH_JAVA", "OFF");
+    }
+
+    // The cmake crate builds and installs in one step, using `NUM_JOBS` parallel jobs.
+    config
+        .profile(if debug { "Debug" } else { "Release" })
+        .static_crt(target.static_crt)
         .define("WITH_CSCORE", "OFF")
         .define("WITH_GUI", "OFF")
         .define("WITH_SIMULATION_MODULES", "OFF")
@@ -15,18 +216,69 @@ fn main() {
         .define("WITH_WPIMATH", "OFF")
         .define("WITH_PROTOBUF", "OFF")
         .define("BUILD_SHARED_LIBS", "OFF")
+        // Some distributions default to lib64, especially when cross-compiling.
+        .define("CMAKE_INSTALL_LIBDIR", "lib")
         .build();
 
-    Command::new("cmake")
-        .args(["--build", &out_dir, "-j", "8"])
-        .spawn()
-        .unwrap()
-        .wait()
-        .unwrap();
-
-    println!("cargo:rustc-link-lib=stdc++");
-    println!("cargo:rustc-link-search=native={out_dir}/lib");
-    println!("cargo:rustc-link-lib=ntcore{maybe_d}");
-    println!("cargo:rustc-link-lib=wpiutil{maybe_d}");
-    println!("cargo:rustc-link-lib=wpinet{maybe_d}");
+    if let Some(stamp) = stamp {
+        std::fs::write(stamp_path, stamp).unwrap();
+    }
+
+    libraries
+}
+
+/// Runs git in the allwpilib submodule, returning its trimmed output if it succeeds.
+fn git(allwpilib: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(allwpilib)
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Returns the commit the allwpilib submodule is checked out at and
+/// tells cargo to rerun the build script when it changes.
+///
+/// Returns `None` if the submodule isn't a git checkout (e.g. when building from crates.io).
+fn submodule_revision(allwpilib: &Path) -> Option<String> {
+    let git_dir = allwpilib.join(git(allwpilib, &["rev-parse", "--git-dir"])?);
+    println!("cargo:rerun-if-changed={}", git_dir.join("HEAD").display());
+
+    git(allwpilib, &["rev-parse", "HEAD"])
+}
+
+/// Returns the WPILib release the allwpilib submodule is at, e.g. `2025.3.2`,
+/// or `2025.3.2-14-g1a2b3c4` for commits after a release.
+fn submodule_version(allwpilib: &Path) -> Option<String> {
+    let tag = git(allwpilib, &["describe", "--tags", "--match", "v*"])?;
+    tag.strip_prefix('v').map(str::to_string)
+}
+
+#[cfg(feature = "bindgen")]
+fn generate_bindings(manifest_dir: &str, out_dir: &str) {
+    let ntcore_include = format!("{manifest_dir}/allwpilib/ntcore/src/main/native/include");
+    let wpiutil_include = format!("{manifest_dir}/allwpilib/wpiutil/src/main/native/include");
+    let generated_include =
+        format!("{manifest_dir}/allwpilib/ntcore/src/generated/main/native/include");
+
+    bindgen::Builder::default()
+        .header(format!("{ntcore_include}/ntcore_c.h"))
+        .header(format!("{generated_include}/ntcore_c_types.h"))
+        .clang_arg(format!("-I{ntcore_include}"))
+        .clang_arg(format!("-I{wpiutil_include}"))
+        .clang_arg(format!("-I{generated_include}"))
+        .allowlist_function("NT_.*")
+        .allowlist_function("WPI_.*String.*")
+        .allowlist_type("NT_.*")
+        .allowlist_type("WPI_String")
+        .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
+        .generate()
+        .expect("Unable to generate ntcore bindings")
+        .write_to_file(format!("{out_dir}/ntcore_bindings.rs"))
+        .expect("Unable to write ntcore bindings");
 }
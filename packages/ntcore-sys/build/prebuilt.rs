@@ -0,0 +1,120 @@
+//! Downloads the official WPILib maven artifacts instead of building allwpilib.
+
+use std::{
+    io::{Cursor, Read},
+    path::Path,
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::Libraries;
+
+/// The WPILib release that artifacts are downloaded from by default.
+/// Can be overridden with the `NTCORE_SYS_WPILIB_VERSION` environment variable.
+const DEFAULT_WPILIB_VERSION: &str = "2025.3.2";
+const WPILIB_MAVEN: &str = "https://frcmaven.wpi.edu/artifactory/release/edu/wpi/first";
+
+/// The maven classifier and library directory of a platform.
+struct Platform {
+    classifier: &'static str,
+    lib_dir: &'static str,
+}
+
+fn platform() -> Platform {
+    let os = std::env::var("CARGO_CFG_TARGET_OS").unwrap();
+    let arch = std::env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+    let triple = std::env::var("TARGET").unwrap();
+
+    let (classifier, lib_dir) = match (os.as_str(), arch.as_str()) {
+        _ if triple == "arm-unknown-linux-gnueabi" => ("linuxathena", "linux/athena"),
+        ("linux", "x86_64") => ("linuxx86-64", "linux/x86-64"),
+        ("linux", "aarch64") => ("linuxarm64", "linux/arm64"),
+        ("linux", "arm") => ("linuxarm32", "linux/arm32"),
+        ("windows", "x86_64") => ("windowsx86-64", "windows/x86-64"),
+        ("windows", "aarch64") => ("windowsarm64", "windows/arm64"),
+        ("macos", _) => ("osxuniversal", "osx/universal"),
+        _ => panic!("No prebuilt WPILib artifacts are available for {arch}-{os}"),
+    };
+
+    Platform {
+        classifier,
+        lib_dir,
+    }
+}
+
+/// Downloads, verifies, and extracts the static ntcore, wpinet, and wpiutil libraries
+/// along with the headers needed to build the shim.
+pub fn download(out_dir: &str, debug: bool) -> Libraries {
+    println!("cargo:rerun-if-env-changed=NTCORE_SYS_WPILIB_VERSION");
+    let version = std::env::var("NTCORE_SYS_WPILIB_VERSION")
+        .unwrap_or_else(|_| DEFAULT_WPILIB_VERSION.to_string());
+    let platform = platform();
+    let variant = if debug { "staticdebug" } else { "static" };
+
+    let root = Path::new(out_dir)
+        .join("prebuilt")
+        .join(&version)
+        .join(format!("{}{variant}", platform.classifier));
+    let libraries = Libraries {
+        search_dirs: vec![root.join(platform.lib_dir).join("static")],
+        include_dirs: vec![root.join("include")],
+        suffix: if debug { "d" } else { "" },
+        version: Some(version.clone()),
+    };
+
+    // Artifacts are only downloaded once per version and platform.
+    let marker = root.join(".complete");
+    if marker.exists() {
+        return libraries;
+    }
+
+    for artifact in ["ntcore", "wpinet", "wpiutil"] {
+        let base =
+            format!("{WPILIB_MAVEN}/{artifact}/{artifact}-cpp/{version}/{artifact}-cpp-{version}");
+        extract(
+            &fetch_verified(&format!("{base}-{}{variant}.zip", platform.classifier)),
+            &root,
+        );
+        extract(
+            &fetch_verified(&format!("{base}-headers.zip")),
+            &root.join("include"),
+        );
+    }
+
+    std::fs::write(marker, "").unwrap();
+
+    libraries
+}
+
+fn fetch(url: &str) -> Vec<u8> {
+    let mut data = Vec::new();
+    ureq::get(url)
+        .call()
+        .unwrap_or_else(|err| panic!("Failed to download {url}: {err}"))
+        .into_reader()
+        .read_to_end(&mut data)
+        .unwrap();
+    data
+}
+
+/// Downloads an artifact and checks it against the SHA-256 checksum published alongside it.
+fn fetch_verified(url: &str) -> Vec<u8> {
+    let data = fetch(url);
+
+    let expected = String::from_utf8(fetch(&format!("{url}.sha256"))).unwrap();
+    let actual = format!("{:x}", Sha256::digest(&data));
+    assert!(
+        expected.trim().eq_ignore_ascii_case(&actual),
+        "Checksum mismatch for {url}: expected {}, got {actual}",
+        expected.trim()
+    );
+
+    data
+}
+
+fn extract(data: &[u8], destination: &Path) {
+    zip::ZipArchive::new(Cursor::new(data))
+        .unwrap()
+        .extract(destination)
+        .unwrap();
+}
@@ -0,0 +1,60 @@
+//! Locates preinstalled ntcore, wpinet, and wpiutil libraries instead of building allwpilib.
+
+use std::path::{Path, PathBuf};
+
+use crate::Libraries;
+
+/// Finds the libraries in `NTCORE_DIR` if it is set, falling back to pkg-config.
+///
+/// `NTCORE_DIR` should point to an allwpilib install prefix,
+/// i.e. a directory containing `lib` and `include/{ntcore,wpinet,wpiutil}`.
+pub fn locate() -> Libraries {
+    println!("cargo:rerun-if-env-changed=NTCORE_DIR");
+
+    match std::env::var_os("NTCORE_DIR") {
+        Some(dir) => from_prefix(Path::new(&dir)),
+        None => from_pkg_config(),
+    }
+}
+
+fn from_prefix(prefix: &Path) -> Libraries {
+    let include = prefix.join("include");
+
+    Libraries {
+        search_dirs: vec![prefix.join("lib")],
+        include_dirs: vec![include.join("wpinet"), include.join("wpiutil")],
+        suffix: "",
+        version: None,
+    }
+}
+
+fn from_pkg_config() -> Libraries {
+    let mut search_dirs = Vec::<PathBuf>::new();
+    let mut include_dirs = Vec::<PathBuf>::new();
+    let mut version = None;
+
+    for library in ["ntcore", "wpinet", "wpiutil"] {
+        let library = pkg_config::Config::new()
+            .cargo_metadata(false)
+            .probe(library)
+            .unwrap_or_else(|err| {
+                panic!("Could not find {library} with pkg-config. Set NTCORE_DIR to its install prefix instead.\n{err}")
+            });
+        // ntcore is probed first, so this is its version.
+        if version.is_none() {
+            version = Some(library.version);
+        }
+        search_dirs.extend(library.link_paths);
+        include_dirs.extend(library.include_paths);
+    }
+
+    search_dirs.dedup();
+    include_dirs.dedup();
+
+    Libraries {
+        search_dirs,
+        include_dirs,
+        suffix: "",
+        version,
+    }
+}
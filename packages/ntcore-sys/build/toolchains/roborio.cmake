@@ -0,0 +1,14 @@
+# Cross-compiles for the roboRIO using the WPILib compiler toolchain (https://github.com/wpilibsuite/opensdk).
+# ROBORIO_TOOLCHAIN_PREFIX is passed in by build.rs, e.g. `arm-frc2025-linux-gnueabi-`.
+
+set(CMAKE_SYSTEM_NAME Linux)
+set(CMAKE_SYSTEM_PROCESSOR arm)
+list(APPEND CMAKE_TRY_COMPILE_PLATFORM_VARIABLES ROBORIO_TOOLCHAIN_PREFIX)
+
+set(CMAKE_C_COMPILER ${ROBORIO_TOOLCHAIN_PREFIX}gcc)
+set(CMAKE_CXX_COMPILER ${ROBORIO_TOOLCHAIN_PREFIX}g++)
+
+set(CMAKE_FIND_ROOT_PATH_MODE_PROGRAM NEVER)
+set(CMAKE_FIND_ROOT_PATH_MODE_LIBRARY ONLY)
+set(CMAKE_FIND_ROOT_PATH_MODE_INCLUDE ONLY)
+set(CMAKE_FIND_ROOT_PATH_MODE_PACKAGE ONLY)
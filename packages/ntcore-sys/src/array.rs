@@ -0,0 +1,94 @@
+//! Owned wrappers for arrays allocated by ntcore.
+
+use std::{fmt::Debug, ops::Deref};
+
+use crate::{
+    NT_ConnectionInfo, NT_DisposeConnectionInfoArray, NT_DisposeEventArray,
+    NT_DisposeTopicInfoArray, NT_DisposeValueArray, NT_Event, NT_FreeBooleanArray, NT_Handle,
+    NT_TopicInfo, NT_Value,
+};
+
+/// Frees an array of handles returned by e.g. `NT_GetTopics`.
+///
+/// ntcore allocates these with `malloc` but has no function to free them;
+/// every `NT_Free*Array` function simply calls `free`, so one of those is used.
+unsafe fn free_handle_array(arr: *mut NT_Handle, _count: usize) {
+    unsafe { NT_FreeBooleanArray(arr.cast()) };
+}
+
+macro_rules! disposable_array {
+    {$(
+        $(#[$meta:meta])*
+        $name:ident($element:ty) => $dispose:ident
+    ),* $(,)?} => {
+        $(
+            $(#[$meta])*
+            pub struct $name {
+                ptr: *mut $element,
+                len: usize,
+            }
+
+            impl $name {
+                #[doc = concat!("Takes ownership of an array returned by ntcore that can be disposed of with `", stringify!($dispose), "`.")]
+                ///
+                /// # Safety
+                ///
+                /// `ptr` must either be null or point to `len` elements allocated by ntcore,
+                /// and the array must not be disposed of elsewhere.
+                pub unsafe fn from_raw(ptr: *mut $element, len: usize) -> Self {
+                    Self { ptr, len }
+                }
+
+                /// Releases ownership of the array without disposing of it.
+                pub fn into_raw(self) -> (*mut $element, usize) {
+                    let raw = (self.ptr, self.len);
+                    std::mem::forget(self);
+                    raw
+                }
+            }
+
+            impl Deref for $name {
+                type Target = [$element];
+
+                fn deref(&self) -> &Self::Target {
+                    if self.ptr.is_null() {
+                        return &[];
+                    }
+                    unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+                }
+            }
+
+            impl Debug for $name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.debug_struct(stringify!($name))
+                        .field("len", &self.len())
+                        .finish()
+                }
+            }
+
+            impl Drop for $name {
+                fn drop(&mut self) {
+                    if !self.ptr.is_null() {
+                        unsafe { $dispose(self.ptr, self.len) };
+                    }
+                }
+            }
+
+            // Safety: The array is uniquely owned heap memory.
+            unsafe impl Send for $name {}
+        )*
+    };
+}
+
+disposable_array! {
+    /// An array of values returned by e.g. `NT_ReadQueueValue`.
+    NtValueArray(NT_Value) => NT_DisposeValueArray,
+    /// An array of events returned by e.g. `NT_ReadListenerQueue`.
+    NtEventArray(NT_Event) => NT_DisposeEventArray,
+    /// An array of connections returned by `NT_GetConnections`.
+    NtConnectionInfoArray(NT_ConnectionInfo) => NT_DisposeConnectionInfoArray,
+    /// An array of topic infos returned by e.g. `NT_GetTopicInfos`.
+    NtTopicInfoArray(NT_TopicInfo) => NT_DisposeTopicInfoArray,
+    /// An array of handles returned by e.g. `NT_GetTopics`.
+    NtHandleArray(NT_Handle) => free_handle_array,
+}
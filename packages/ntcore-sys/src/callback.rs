@@ -0,0 +1,82 @@
+//! Converts Rust closures into ntcore listener callbacks.
+
+use std::{
+    ffi::c_void,
+    fmt::Debug,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use crate::{NT_Event, NT_ListenerCallback};
+
+/// A Rust closure that can be passed to ntcore as an [`NT_ListenerCallback`] and data pointer,
+/// e.g. to `NT_AddListener` or `NT_AddLogger`.
+///
+/// The closure is freed when the trampoline is dropped, so the trampoline must outlive the listener.
+/// Remove the listener with `NT_RemoveListener` (or destroy the instance) before dropping it.
+///
+/// Panics inside the closure are caught and printed to stderr,
+/// since unwinding into ntcore is undefined behavior.
+pub struct Trampoline {
+    data: *mut c_void,
+    callback: NT_ListenerCallback,
+    drop_data: unsafe fn(*mut c_void),
+}
+
+impl Trampoline {
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: Fn(&NT_Event) + Send + Sync + 'static,
+    {
+        unsafe extern "C" fn call<F: Fn(&NT_Event)>(data: *mut c_void, event: *const NT_Event) {
+            // Safety: data was created from a Box<F> in Trampoline::new and is valid until the trampoline is dropped.
+            let callback = unsafe { &*data.cast::<F>() };
+            let event = unsafe { &*event };
+
+            if let Err(panic) = catch_unwind(AssertUnwindSafe(|| callback(event))) {
+                let message = panic
+                    .downcast_ref::<&str>()
+                    .copied()
+                    .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+                    .unwrap_or("Box<dyn Any>");
+                eprintln!("ntcore listener callback panicked: {message}");
+            }
+        }
+        unsafe fn drop_data<F>(data: *mut c_void) {
+            drop(unsafe { Box::from_raw(data.cast::<F>()) });
+        }
+
+        Self {
+            data: Box::into_raw(Box::new(callback)).cast(),
+            callback: call::<F>,
+            drop_data: drop_data::<F>,
+        }
+    }
+
+    /// The function to pass to ntcore.
+    pub fn callback(&self) -> NT_ListenerCallback {
+        self.callback
+    }
+
+    /// The data pointer to pass to ntcore alongside [`Self::callback`].
+    pub fn data(&self) -> *mut c_void {
+        self.data
+    }
+}
+
+impl Debug for Trampoline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Trampoline")
+            .field("data", &self.data)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Trampoline {
+    fn drop(&mut self) {
+        unsafe { (self.drop_data)(self.data) };
+    }
+}
+
+// Safety: The closure is required to be Send + Sync.
+unsafe impl Send for Trampoline {}
+unsafe impl Sync for Trampoline {}
@@ -3,6 +3,30 @@
 use bitflags::bitflags;
 use std::{ffi::CStr, fmt::Debug};
 
+mod array;
+pub mod callback;
+pub mod probe;
+mod string;
+pub mod wpinet;
+pub mod wpiutil;
+
+pub use array::{NtConnectionInfoArray, NtEventArray, NtHandleArray, NtTopicInfoArray, NtValueArray};
+pub use string::{read_wpi_string_owned, WpiStringBuf};
+
+/// The WPILib version of the linked ntcore, e.g. `2025.3.2`, as determined when building.
+///
+/// `None` if it couldn't be determined, e.g. for libraries found through `NTCORE_DIR`.
+pub const NTCORE_VERSION: Option<&str> = option_env!("NTCORE_SYS_NTCORE_VERSION");
+
+/// Bindings generated by bindgen from the vendored ntcore headers.
+/// These exist to verify the hand-written bindings and should not be used directly.
+#[cfg(feature = "bindgen")]
+#[doc(hidden)]
+#[allow(non_upper_case_globals, dead_code, clippy::all)]
+pub mod generated {
+    include!(concat!(env!("OUT_DIR"), "/ntcore_bindings.rs"));
+}
+
 #[repr(C)]
 #[derive(Debug, Copy, Clone, Hash)]
 pub struct WPI_String {
@@ -19,6 +43,7 @@ impl From<&CStr> for WPI_String {
     }
 }
 
+/// Opaque DataLog object. See [`wpiutil`] for functions that create and use it.
 pub type WPI_DataLog = std::ffi::c_void;
 
 pub type NT_Bool = i32;
@@ -58,6 +83,11 @@ macro_rules! c_enum {
                     self.0
                 }
 
+                /// Creates a value from raw bits, which may not be one of the known constants.
+                pub const fn from_bits(bits: $type) -> Self {
+                    Self(bits)
+                }
+
                 $(
                     $(#[$memmeta])*
                     pub const $var: $name = $name($val);
@@ -78,6 +108,7 @@ c_enum! {
         NT_BOOLEAN_ARRAY = 0x10,
         NT_DOUBLE_ARRAY = 0x20,
         NT_STRING_ARRAY = 0x40,
+        /// NT3 remote procedure calls. ntcore no longer implements RPCs, so no values have this type.
         NT_RPC = 0x80,
         NT_INTEGER = 0x100,
         NT_FLOAT = 0x200,
@@ -369,11 +400,11 @@ pub struct NT_PubSubOptions {
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct NT_TimestampedBoolean {
     /// Time in local time base
-    time: i64,
+    pub time: i64,
     /// Time in server time base. May be 0 or 1 for locally set values.
-    serverTime: i64,
+    pub serverTime: i64,
     /// Value.
-    value: NT_Bool,
+    pub value: NT_Bool,
 }
 
 /// Timestamped Integer.
@@ -381,11 +412,11 @@ pub struct NT_TimestampedBoolean {
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct NT_TimestampedInteger {
     /// Time in local time base
-    time: i64,
+    pub time: i64,
     /// Time in server time base. May be 0 or 1 for locally set values.
-    serverTime: i64,
+    pub serverTime: i64,
     /// Value.
-    value: i64,
+    pub value: i64,
 }
 
 /// Timestamped Float.
@@ -393,11 +424,11 @@ pub struct NT_TimestampedInteger {
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct NT_TimestampedFloat {
     /// Time in local time base
-    time: i64,
+    pub time: i64,
     /// Time in server time base. May be 0 or 1 for locally set values.
-    serverTime: i64,
+    pub serverTime: i64,
     /// Value.
-    value: f32,
+    pub value: f32,
 }
 
 /// Timestamped Double.
@@ -405,11 +436,11 @@ pub struct NT_TimestampedFloat {
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct NT_TimestampedDouble {
     /// Time in local time base
-    time: i64,
+    pub time: i64,
     /// Time in server time base. May be 0 or 1 for locally set values.
-    serverTime: i64,
+    pub serverTime: i64,
     /// Value.
-    value: f32,
+    pub value: f64,
 }
 
 /// Timestamped String.
@@ -417,26 +448,25 @@ pub struct NT_TimestampedDouble {
 #[derive(Debug, Copy, Clone, Hash)]
 pub struct NT_TimestampedString {
     /// Time in local time base
-    time: i64,
+    pub time: i64,
     /// Time in server time base. May be 0 or 1 for locally set values.
-    serverTime: i64,
+    pub serverTime: i64,
     /// Value.
-    value: WPI_String,
+    pub value: WPI_String,
 }
 
-
 /// Timestamped Raw.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct NT_TimestampedRaw {
     /// Time in local time base
-    time: i64,
+    pub time: i64,
     /// Time in server time base. May be 0 or 1 for locally set values.
-    serverTime: i64,
+    pub serverTime: i64,
     /// Value.
-    value: *mut u8,
+    pub value: *mut u8,
     /// Value length.
-    len: usize,
+    pub len: usize,
 }
 
 /// Timestamped Boolean Array.
@@ -444,13 +474,13 @@ pub struct NT_TimestampedRaw {
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct NT_TimestampedBooleanArray {
     /// Time in local time base
-    time: i64,
+    pub time: i64,
     /// Time in server time base. May be 0 or 1 for locally set values.
-    serverTime: i64,
+    pub serverTime: i64,
     /// Value.
-    value: *mut NT_Bool,
+    pub value: *mut NT_Bool,
     /// Value length.
-    len: usize,
+    pub len: usize,
 }
 
 /// Timestamped Integer Array.
@@ -458,58 +488,128 @@ pub struct NT_TimestampedBooleanArray {
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct NT_TimestampedIntegerArray {
     /// Time in local time base
-    time: i64,
+    pub time: i64,
     /// Time in server time base. May be 0 or 1 for locally set values.
-    serverTime: i64,
+    pub serverTime: i64,
     /// Value.
-    value: *mut i64,
+    pub value: *mut i64,
     /// Value length.
-    len: usize,
+    pub len: usize,
 }
 
-
 /// Timestamped Float Array.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct NT_TimestampedFloatArray {
     /// Time in local time base
-    time: i64,
+    pub time: i64,
     /// Time in server time base. May be 0 or 1 for locally set values.
-    serverTime: i64,
+    pub serverTime: i64,
     /// Value.
-    value: *mut f32,
+    pub value: *mut f32,
     /// Value length.
-    len: usize,
+    pub len: usize,
 }
 
-
 /// Timestamped Double Array.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct NT_TimestampedDoubleArray {
     /// Time in local time base
-    time: i64,
+    pub time: i64,
     /// Time in server time base. May be 0 or 1 for locally set values.
-    serverTime: i64,
+    pub serverTime: i64,
     /// Value.
-    value: *mut f64,
+    pub value: *mut f64,
     /// Value length.
-    len: usize,
+    pub len: usize,
 }
 
-
 /// Timestamped String Array.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct NT_TimestampedStringArray {
     /// Time in local time base
-    time: i64,
+    pub time: i64,
     /// Time in server time base. May be 0 or 1 for locally set values.
-    serverTime: i64,
+    pub serverTime: i64,
     /// Value.
-    value: *mut WPI_String,
+    pub value: *mut WPI_String,
     /// Value length.
-    len: usize,
+    pub len: usize,
+}
+
+/// Subscriber options. Different from PubSubOptions in this reflects only
+/// options that are sent over the network.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NT_Meta_SubscriberOptions {
+    /// How frequently changes will be sent over the network, in seconds.
+    pub periodic: f64,
+    /// For subscriptions, don't ask for value changes (only topic announcements).
+    pub topicsOnly: NT_Bool,
+    /// Send all value changes over the network.
+    pub sendAll: NT_Bool,
+    /// Perform prefix match on subscriber topic names.
+    pub prefixMatch: NT_Bool,
+}
+
+/// Topic publisher (as published via `$pub$<topic>`).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Hash)]
+pub struct NT_Meta_TopicPublisher {
+    /// Client name.
+    pub client: WPI_String,
+    /// Publisher ID.
+    pub pubuid: u64,
+}
+
+/// Topic subscriber (as published via `$sub$<topic>`).
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct NT_Meta_TopicSubscriber {
+    /// Client name.
+    pub client: WPI_String,
+    /// Subscriber ID.
+    pub subuid: u64,
+    /// Subscriber options.
+    pub options: NT_Meta_SubscriberOptions,
+}
+
+/// Client publisher (as published via `$clientpub$<client>` or `$serverpub`).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Hash)]
+pub struct NT_Meta_ClientPublisher {
+    /// Publisher ID.
+    pub uid: i64,
+    /// Topic name.
+    pub topic: WPI_String,
+}
+
+/// Client subscriber (as published via `$clientsub$<client>` or `$serversub`).
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct NT_Meta_ClientSubscriber {
+    /// Subscriber ID.
+    pub uid: i64,
+    /// Number of topics.
+    pub topicsCount: usize,
+    /// Topic names.
+    pub topics: *mut WPI_String,
+    /// Subscriber options.
+    pub options: NT_Meta_SubscriberOptions,
+}
+
+/// Client (as published via `$clients`).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Hash)]
+pub struct NT_Meta_Client {
+    /// Client name.
+    pub id: WPI_String,
+    /// Connection information.
+    pub conn: WPI_String,
+    /// Client protocol version.
+    pub version: u16,
 }
 
 extern "C" {
@@ -558,6 +658,8 @@ extern "C" {
     ///
     /// - `entry`: Entry handle.
     /// - `name`: Entry name (output parameter).
+    ///
+    /// The output string is allocated by ntcore and must be freed with `WPI_FreeString()`.
     pub fn NT_GetEntryName(entry: NT_Entry, name: *mut WPI_String);
 
     /// Gets the type for the specified key, or unassigned if non-existent.
@@ -600,25 +702,6 @@ extern "C" {
     /// purpose).
     pub fn NT_GetEntryValue(entry: NT_Entry, value: *mut NT_Value);
 
-    /// Get Entry Value.
-    ///
-    /// Returns copy of current entry value.
-    /// Note that one of the type options is "unassigned".
-    ///
-    /// # Parameters
-    ///
-    /// - entry: entry handle
-    /// - types: bitmask of NT_Type values; 0 is treated specially
-    ///   as a "don't care"
-    /// - value: storage for returned entry value
-    ///
-    /// # Note
-    ///
-    /// It is the caller's responsibility to free value once it's no longer
-    /// needed (the utility function NT_DisposeValue() is useful for this
-    /// purpose).
-    pub fn NT_GetEntryValueType(entry: NT_Entry, types: NT_Type, value: *mut NT_Value);
-
     /// Set Default Entry Value.
     ///
     /// Returns copy of current entry value if it exists.
@@ -684,27 +767,6 @@ extern "C" {
     /// entry value array; returns NULL and count=0 if no new values
     pub fn NT_ReadQueueValue(subentry: NT_Handle, count: *mut usize) -> *mut NT_Value;
 
-    /// Read Entry Queue.
-    ///
-    /// Returns new entry values since last call. The returned array must be freed
-    /// using NT_DisposeValueArray().
-    ///
-    /// # Parameters
-    ///
-    /// - subentry: subscriber or entry handle
-    /// - types: bitmask of NT_Type values; 0 is treated specially
-    ///   as a "don't care"
-    /// - count: count of items in returned array (output)
-    ///
-    /// # Returns
-    ///
-    /// entry value array; returns NULL and count=0 if no new values
-    pub fn NT_ReadQueueValueType(
-        subentry: NT_Handle,
-        types: u32,
-        count: *mut usize,
-    ) -> *mut NT_Value;
-
     /// Get Published Topic Handles.
     ///
     /// Returns an array of topic handles.  The results are optionally
@@ -840,6 +902,8 @@ extern "C" {
     /// - topic: topic handle
     /// - name: topic name (output); return length of 0 and nullptr if
     ///   handle is invalid.
+    ///
+    /// The output string is allocated by ntcore and must be freed with `WPI_FreeString()`.
     pub fn NT_GetTopicName(topic: NT_Topic, name: *mut WPI_String);
 
     /// Gets the type for the specified topic, or unassigned if non existent.
@@ -860,6 +924,8 @@ extern "C" {
     ///
     /// - topic: topic handle
     /// - type: topic type string (output)
+    ///
+    /// The output string is allocated by ntcore and must be freed with `WPI_FreeString()`.
     pub fn NT_GetTopicTypeString(topic: NT_Topic, r#type: *mut WPI_String);
 
     /// Sets the persistent property of a topic.  If true, the stored value is
@@ -902,27 +968,6 @@ extern "C" {
     /// retained property value
     pub fn NT_GetTopicRetained(topic: NT_Topic) -> NT_Bool;
 
-    /// Sets the cached property of a topic.  If true, the server and clients will
-    /// store the latest value, allowing the value to be read (and not just accessed
-    /// through event queues and listeners).
-    ///
-    /// # Parameters
-    ///
-    /// - topic: topic handle
-    /// - value: True for cached, false for not cached
-    pub fn NT_SetTopicCached(topic: NT_Topic, value: NT_Bool);
-
-    /// Gets the cached property of a topic.
-    ///
-    /// # Parameters
-    ///
-    /// - topic: topic handle
-    ///
-    /// # Return
-    ///
-    /// cached property value
-    pub fn NT_GetTopicCached(topic: NT_Topic) -> NT_Bool;
-
     /// Determine if topic exists (e.g. has at least one publisher).
     ///
     /// # Parameters
@@ -941,6 +986,8 @@ extern "C" {
     /// - topic: topic handle
     /// - name: property name
     /// - property: JSON string (output)
+    ///
+    /// The output string is allocated by ntcore and must be freed with `WPI_FreeString()`.
     pub fn NT_GetTopicProperty(topic: NT_Topic, name: *const WPI_String, property: *mut WPI_String);
 
     /// Sets a property value.
@@ -971,6 +1018,8 @@ extern "C" {
     ///
     /// - topic: topic handle
     /// - properties: JSON string (output)
+    ///
+    /// The output string is allocated by ntcore and must be freed with `WPI_FreeString()`.
     pub fn NT_GetTopicProperties(topic: NT_Topic, properties: *mut WPI_String);
 
     /// Updates multiple topic properties.  Each key in the passed-in JSON object is
@@ -1343,6 +1392,17 @@ extern "C" {
         mask: u32,
     ) -> NT_Listener;
 
+    /// Get the current network mode.
+    ///
+    /// # Parameters
+    ///
+    /// - `inst`: Instance handle.
+    ///
+    /// # Returns
+    ///
+    /// Bitmask of NT_NetworkMode.
+    pub fn NT_GetNetworkMode(inst: NT_Inst) -> u32;
+
     /// Starts local-only operation. Prevents calls to NT_StartServer or
     /// NT_StartClient from taking effect. Has no effect if NT_StartServer or
     /// NT_StartClient has already been called.
@@ -1724,43 +1784,6 @@ extern "C" {
         max_level: u32,
     ) -> NT_Listener;
 
-    /// Returns whether there is a data schema already registered with the given
-    /// name. This does NOT perform a check as to whether the schema has already
-    /// been published by another node on the network.
-    ///
-    /// # Parameters
-    ///
-    /// - `inst`: Instance handle.
-    /// - `name`: Name (the string passed as the data type for topics using this schema).
-    ///
-    /// # Returns
-    ///
-    /// True if schema already registered.
-    pub fn NT_HasSchema(inst: NT_Inst, name: *const WPI_String) -> NT_Bool;
-
-    /// Registers a data schema. Data schemas provide information for how a
-    /// certain data type string can be decoded. The type string of a data schema
-    /// indicates the type of the schema itself (e.g. "protobuf" for protobuf
-    /// schemas, "struct" for struct schemas, etc). In NetworkTables, schemas are
-    /// published just like normal topics, with the name being generated from the
-    /// provided name: "/.schema/<name>". Duplicate calls to this function with
-    /// the same name are silently ignored.
-    ///
-    /// # Parameters
-    ///
-    /// - `inst`: Instance handle.
-    /// - `name`: Name (the string passed as the data type for topics using this schema).
-    /// - `type`: Type of schema (e.g. "protobuf", "struct", etc).
-    /// - `schema`: Schema data.
-    /// - `schema_size`: Size of schema data.
-    pub fn NT_AddSchema(
-        inst: NT_Inst,
-        name: *const WPI_String,
-        type_: *const WPI_String,
-        schema: *const u8,
-        schema_size: usize,
-    );
-
     /// Allocates an array of chars.
     /// Note that the size is the number of elements, and not the
     /// specific number of bytes to allocate. That is calculated internally.
@@ -1787,7 +1810,7 @@ extern "C" {
     /// The allocated boolean array.
     ///
     /// After use, the array should be freed using the `NT_FreeBooleanArray()` function.
-    pub fn NT_AllocateBooleanArray(size: usize) -> *mut bool;
+    pub fn NT_AllocateBooleanArray(size: usize) -> *mut NT_Bool;
 
     /// Allocates an array of integers.
     /// Note that the size is the number of elements, and not the
@@ -1844,7 +1867,7 @@ extern "C" {
     /// # Parameters
     ///
     /// - `v_boolean`: Pointer to the boolean array to free.
-    pub fn NT_FreeBooleanArray(v_boolean: *mut bool);
+    pub fn NT_FreeBooleanArray(v_boolean: *mut NT_Bool);
 
     /// Frees an array of integers.
     ///
@@ -1894,7 +1917,7 @@ extern "C" {
     pub fn NT_GetValueBoolean(
         value: *const NT_Value,
         last_change: *mut u64,
-        v_boolean: *mut bool,
+        v_boolean: *mut NT_Bool,
     ) -> NT_Bool;
 
     /// Returns the int from the NT_Value.
@@ -2016,7 +2039,7 @@ extern "C" {
         value: *const NT_Value,
         last_change: *mut u64,
         arr_size: *mut usize,
-    ) -> *mut bool;
+    ) -> *mut NT_Bool;
 
     /// Returns a copy of the int array from the NT_Value.
     /// If the NT_Value is null, or is assigned to a different type, returns null.
@@ -2498,6 +2521,7 @@ extern "C" {
     /// - `defaultValue`: default value to return if no value has been published
     /// - `value`: returned value (output)
     ///
+    /// The output string is allocated by ntcore and must be freed with `WPI_FreeString()`.
     pub fn NT_GetString(
         subentry: NT_Handle,
         defaultValue: *const WPI_String,
@@ -3155,4 +3179,194 @@ extern "C" {
     /// - `arr`: array
     /// - `len`: length of array
     pub fn NT_FreeQueueStringArray(arr: *mut NT_TimestampedStringArray, len: usize);
+
+    /// Decodes the raw value of the `$pub$<topic>` meta-topic.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: Raw msgpack data of the meta-topic value.
+    /// - `size`: Size of `data` in bytes.
+    /// - `count`: Number of elements in the returned array (output).
+    ///
+    /// # Returns
+    ///
+    /// Array of publishers, or null if decoding failed.
+    ///
+    /// It is the caller's responsibility to free the array. The
+    /// `NT_Meta_FreeTopicPublishers` function is useful for this purpose.
+    pub fn NT_Meta_DecodeTopicPublishers(
+        data: *const u8,
+        size: usize,
+        count: *mut usize,
+    ) -> *mut NT_Meta_TopicPublisher;
+
+    /// Decodes the raw value of the `$sub$<topic>` meta-topic.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: Raw msgpack data of the meta-topic value.
+    /// - `size`: Size of `data` in bytes.
+    /// - `count`: Number of elements in the returned array (output).
+    ///
+    /// # Returns
+    ///
+    /// Array of subscribers, or null if decoding failed.
+    ///
+    /// It is the caller's responsibility to free the array. The
+    /// `NT_Meta_FreeTopicSubscribers` function is useful for this purpose.
+    pub fn NT_Meta_DecodeTopicSubscribers(
+        data: *const u8,
+        size: usize,
+        count: *mut usize,
+    ) -> *mut NT_Meta_TopicSubscriber;
+
+    /// Decodes the raw value of the `$clientpub$<client>` or `$serverpub` meta-topic.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: Raw msgpack data of the meta-topic value.
+    /// - `size`: Size of `data` in bytes.
+    /// - `count`: Number of elements in the returned array (output).
+    ///
+    /// # Returns
+    ///
+    /// Array of publishers, or null if decoding failed.
+    ///
+    /// It is the caller's responsibility to free the array. The
+    /// `NT_Meta_FreeClientPublishers` function is useful for this purpose.
+    pub fn NT_Meta_DecodeClientPublishers(
+        data: *const u8,
+        size: usize,
+        count: *mut usize,
+    ) -> *mut NT_Meta_ClientPublisher;
+
+    /// Decodes the raw value of the `$clientsub$<client>` or `$serversub` meta-topic.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: Raw msgpack data of the meta-topic value.
+    /// - `size`: Size of `data` in bytes.
+    /// - `count`: Number of elements in the returned array (output).
+    ///
+    /// # Returns
+    ///
+    /// Array of subscribers, or null if decoding failed.
+    ///
+    /// It is the caller's responsibility to free the array. The
+    /// `NT_Meta_FreeClientSubscribers` function is useful for this purpose.
+    pub fn NT_Meta_DecodeClientSubscribers(
+        data: *const u8,
+        size: usize,
+        count: *mut usize,
+    ) -> *mut NT_Meta_ClientSubscriber;
+
+    /// Decodes the raw value of the `$clients` meta-topic.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: Raw msgpack data of the meta-topic value.
+    /// - `size`: Size of `data` in bytes.
+    /// - `count`: Number of elements in the returned array (output).
+    ///
+    /// # Returns
+    ///
+    /// Array of clients, or null if decoding failed.
+    ///
+    /// It is the caller's responsibility to free the array. The
+    /// `NT_Meta_FreeClients` function is useful for this purpose.
+    pub fn NT_Meta_DecodeClients(
+        data: *const u8,
+        size: usize,
+        count: *mut usize,
+    ) -> *mut NT_Meta_Client;
+
+    /// Frees an array returned by `NT_Meta_DecodeTopicPublishers`.
+    ///
+    /// # Parameters
+    ///
+    /// - `arr`: Pointer to the array to free.
+    /// - `count`: Number of elements in the array.
+    pub fn NT_Meta_FreeTopicPublishers(arr: *mut NT_Meta_TopicPublisher, count: usize);
+
+    /// Frees an array returned by `NT_Meta_DecodeTopicSubscribers`.
+    ///
+    /// # Parameters
+    ///
+    /// - `arr`: Pointer to the array to free.
+    /// - `count`: Number of elements in the array.
+    pub fn NT_Meta_FreeTopicSubscribers(arr: *mut NT_Meta_TopicSubscriber, count: usize);
+
+    /// Frees an array returned by `NT_Meta_DecodeClientPublishers`.
+    ///
+    /// # Parameters
+    ///
+    /// - `arr`: Pointer to the array to free.
+    /// - `count`: Number of elements in the array.
+    pub fn NT_Meta_FreeClientPublishers(arr: *mut NT_Meta_ClientPublisher, count: usize);
+
+    /// Frees an array returned by `NT_Meta_DecodeClientSubscribers`.
+    ///
+    /// # Parameters
+    ///
+    /// - `arr`: Pointer to the array to free.
+    /// - `count`: Number of elements in the array.
+    pub fn NT_Meta_FreeClientSubscribers(arr: *mut NT_Meta_ClientSubscriber, count: usize);
+
+    /// Frees an array returned by `NT_Meta_DecodeClients`.
+    ///
+    /// # Parameters
+    ///
+    /// - `arr`: Pointer to the array to free.
+    /// - `count`: Number of elements in the array.
+    pub fn NT_Meta_FreeClients(arr: *mut NT_Meta_Client, count: usize);
+}
+
+extern "C" {
+    /// Allocates a buffer for a string of `length` bytes and points `wpiString` at it.
+    ///
+    /// # Parameters
+    ///
+    /// - `wpiString`: String to initialize (output).
+    /// - `length`: Length of the string in bytes, not including a null terminator.
+    ///
+    /// # Returns
+    ///
+    /// Pointer to the allocated buffer.
+    ///
+    /// After use, the string should be freed using the `WPI_FreeString()` function.
+    pub fn WPI_AllocateString(wpiString: *mut WPI_String, length: usize) -> *mut std::ffi::c_char;
+
+    /// Frees a string allocated by wpiutil or ntcore.
+    ///
+    /// This must be used for strings returned through output parameters,
+    /// e.g. by `NT_GetString()` or `NT_GetTopicName()`.
+    ///
+    /// # Parameters
+    ///
+    /// - `wpiString`: String to free.
+    pub fn WPI_FreeString(wpiString: *const WPI_String);
+
+    /// Allocates an array of strings.
+    ///
+    /// # Parameters
+    ///
+    /// - `length`: Number of strings in the array.
+    ///
+    /// # Returns
+    ///
+    /// Pointer to the allocated array.
+    ///
+    /// After use, the array should be freed using the `WPI_FreeStringArray()` function.
+    pub fn WPI_AllocateStringArray(length: usize) -> *mut WPI_String;
+
+    /// Frees an array of strings, including every string inside of it.
+    ///
+    /// This must be used for string arrays returned by ntcore,
+    /// e.g. by `NT_GetStringArray()` or `NT_GetValueStringArray()`.
+    ///
+    /// # Parameters
+    ///
+    /// - `wpiStringArray`: Array to free.
+    /// - `length`: Number of strings in the array.
+    pub fn WPI_FreeStringArray(wpiStringArray: *const WPI_String, length: usize);
 }
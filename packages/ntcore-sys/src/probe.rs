@@ -0,0 +1,85 @@
+//! Functions that older ntcore releases don't export, resolved at runtime instead of linked.
+//!
+//! With the `system` feature, ntcore may be a library older than the headers these bindings were written against.
+//! Linking these functions like every other one would then fail to link or load, so the shim references them weakly,
+//! and a function missing from the linked ntcore resolves to `None`.
+//!
+//! On Windows with a GNU toolchain, where weak references aren't available, they are linked like every other function.
+
+use std::{ffi::c_void, mem, sync::OnceLock};
+
+use crate::{NT_Bool, NT_Entry, NT_Handle, NT_Inst, NT_Topic, NT_Type, NT_Value, WPI_String};
+
+/// The optional functions of the linked ntcore, or `None` for those it doesn't export.
+#[derive(Debug, Clone, Copy)]
+pub struct OptionalFunctions {
+    /// Sets the cached property of a topic. If true, the server and clients will
+    /// store the latest value, allowing the value to be read (and not just accessed
+    /// through event queues and listeners).
+    pub NT_SetTopicCached: Option<unsafe extern "C" fn(topic: NT_Topic, value: NT_Bool)>,
+    /// Gets the cached property of a topic.
+    pub NT_GetTopicCached: Option<unsafe extern "C" fn(topic: NT_Topic) -> NT_Bool>,
+    /// Like `NT_GetEntryValue`, but only returns values whose type is in the bitmask `types`,
+    /// leaving `value` unassigned otherwise. A `types` of 0 accepts every type.
+    ///
+    /// The value must be freed with `NT_DisposeValue`.
+    pub NT_GetEntryValueType:
+        Option<unsafe extern "C" fn(entry: NT_Entry, types: NT_Type, value: *mut NT_Value)>,
+    /// Like `NT_ReadQueueValue`, but only returns values whose type is in the bitmask `types`.
+    /// A `types` of 0 accepts every type.
+    ///
+    /// The array must be freed with `NT_DisposeValueArray`.
+    pub NT_ReadQueueValueType: Option<
+        unsafe extern "C" fn(subentry: NT_Handle, types: u32, count: *mut usize) -> *mut NT_Value,
+    >,
+    /// Returns whether a data schema is already registered with the given name.
+    /// This does NOT check whether the schema has been published by another node on the network.
+    pub NT_HasSchema:
+        Option<unsafe extern "C" fn(inst: NT_Inst, name: *const WPI_String) -> NT_Bool>,
+    /// Registers a data schema, which is published as the topic `/.schema/<name>`.
+    /// Duplicate calls with the same name are silently ignored.
+    pub NT_AddSchema: Option<
+        unsafe extern "C" fn(
+            inst: NT_Inst,
+            name: *const WPI_String,
+            type_: *const WPI_String,
+            schema: *const u8,
+            schema_size: usize,
+        ),
+    >,
+}
+
+extern "C" {
+    fn lagan_optional_NT_SetTopicCached() -> *const c_void;
+    fn lagan_optional_NT_GetTopicCached() -> *const c_void;
+    fn lagan_optional_NT_GetEntryValueType() -> *const c_void;
+    fn lagan_optional_NT_ReadQueueValueType() -> *const c_void;
+    fn lagan_optional_NT_HasSchema() -> *const c_void;
+    fn lagan_optional_NT_AddSchema() -> *const c_void;
+}
+
+/// Converts the address of a function to a function pointer of type `F`, or `None` if it is null.
+///
+/// # Safety
+///
+/// `F` must be a function pointer type matching the function's signature.
+unsafe fn resolve<F: Copy>(address: *const c_void) -> Option<F> {
+    debug_assert_eq!(mem::size_of::<F>(), mem::size_of::<*const c_void>());
+    (!address.is_null()).then(|| unsafe { mem::transmute_copy(&address) })
+}
+
+/// Returns the optional functions of the linked ntcore. They are resolved once and then reused.
+pub fn optional_functions() -> &'static OptionalFunctions {
+    static FUNCTIONS: OnceLock<OptionalFunctions> = OnceLock::new();
+    // Safety: the shim returns the addresses of exactly these functions, declared in the ntcore headers.
+    FUNCTIONS.get_or_init(|| unsafe {
+        OptionalFunctions {
+            NT_SetTopicCached: resolve(lagan_optional_NT_SetTopicCached()),
+            NT_GetTopicCached: resolve(lagan_optional_NT_GetTopicCached()),
+            NT_GetEntryValueType: resolve(lagan_optional_NT_GetEntryValueType()),
+            NT_ReadQueueValueType: resolve(lagan_optional_NT_ReadQueueValueType()),
+            NT_HasSchema: resolve(lagan_optional_NT_HasSchema()),
+            NT_AddSchema: resolve(lagan_optional_NT_AddSchema()),
+        }
+    })
+}
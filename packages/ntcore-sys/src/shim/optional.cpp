@@ -0,0 +1,47 @@
+// References the ntcore functions that older releases don't export without requiring them,
+// so `probe.rs` can tell whether the linked ntcore has them.
+//
+// Weak references don't pull members out of static libraries, but every function here is defined
+// in ntcore's C API alongside functions lagan references normally, so they are linked whenever ntcore has them.
+//
+// The functions are declared without their parameters on purpose. Only their addresses are taken here,
+// and the ntcore headers aren't included, so the declarations can't conflict.
+
+#if defined(_MSC_VER)
+// MSVC has no weak references, but resolves a missing symbol to an alternate name instead.
+#if defined(_M_IX86)
+#define LAGAN_SYMBOL_PREFIX "_"
+#else
+#define LAGAN_SYMBOL_PREFIX ""
+#endif
+#define LAGAN_OPTIONAL(name)                                                  \
+  extern "C" void name();                                                     \
+  extern "C" void lagan_missing_##name() {}                                   \
+  __pragma(comment(linker, "/alternatename:" LAGAN_SYMBOL_PREFIX #name "="    \
+                           LAGAN_SYMBOL_PREFIX "lagan_missing_" #name));      \
+  extern "C" const void* lagan_optional_##name() {                            \
+    return &name == &lagan_missing_##name ? nullptr                           \
+                                          : reinterpret_cast<const void*>(&name); \
+  }
+#elif defined(_WIN32)
+// MinGW doesn't support weak references to functions of other libraries, so they are required.
+#define LAGAN_OPTIONAL(name)                                 \
+  extern "C" void name();                                    \
+  extern "C" const void* lagan_optional_##name() {           \
+    return reinterpret_cast<const void*>(&name);             \
+  }
+#else
+// A weak reference to a missing function is null instead of failing to link or load.
+#define LAGAN_OPTIONAL(name)                                 \
+  extern "C" void name() __attribute__((weak));              \
+  extern "C" const void* lagan_optional_##name() {           \
+    return reinterpret_cast<const void*>(&name);             \
+  }
+#endif
+
+LAGAN_OPTIONAL(NT_SetTopicCached)
+LAGAN_OPTIONAL(NT_GetTopicCached)
+LAGAN_OPTIONAL(NT_GetEntryValueType)
+LAGAN_OPTIONAL(NT_ReadQueueValueType)
+LAGAN_OPTIONAL(NT_HasSchema)
+LAGAN_OPTIONAL(NT_AddSchema)
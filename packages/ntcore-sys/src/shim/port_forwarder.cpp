@@ -0,0 +1,18 @@
+// wpinet only exposes the port forwarder through C++, so this provides the C API bound in `wpinet.rs`.
+
+#include <wpi/string.h>
+#include <wpinet/PortForwarder.h>
+
+extern "C" {
+
+void WPI_AddPortForwarder(unsigned int port, const struct WPI_String* remoteHost,
+                          unsigned int remotePort) {
+  wpi::PortForwarder::GetInstance().Add(port, wpi::to_string_view(remoteHost),
+                                        remotePort);
+}
+
+void WPI_RemovePortForwarder(unsigned int port) {
+  wpi::PortForwarder::GetInstance().Remove(port);
+}
+
+}  // extern "C"
@@ -0,0 +1,159 @@
+//! Helpers for converting between [`WPI_String`] and Rust strings.
+
+use std::{borrow::Cow, fmt::Debug};
+
+use crate::{WPI_AllocateString, WPI_FreeString, WPI_String};
+
+impl WPI_String {
+    /// An empty string that doesn't point to anything.
+    pub const fn empty() -> Self {
+        Self {
+            str: std::ptr::null(),
+            len: 0,
+        }
+    }
+
+    /// Returns the bytes of the string.
+    ///
+    /// # Safety
+    ///
+    /// `str` must either be null or point to `len` bytes that stay valid while the returned slice is used.
+    pub unsafe fn as_bytes(&self) -> &[u8] {
+        if self.str.is_null() || self.len == 0 {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(self.str.cast(), self.len) }
+    }
+
+    /// Converts the string to a Rust string, replacing invalid UTF-8 with `U+FFFD`.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Self::as_bytes`].
+    pub unsafe fn to_str_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(unsafe { self.as_bytes() })
+    }
+}
+
+/// Copies a string written to an output parameter by ntcore into a Rust string and frees the original.
+///
+/// # Safety
+///
+/// `string` must have been allocated by ntcore or wpiutil and must not be used or freed afterwards.
+pub unsafe fn read_wpi_string_owned(string: WPI_String) -> String {
+    unsafe { WpiStringBuf::from_raw(string) }
+        .to_str_lossy()
+        .into_owned()
+}
+
+/// Borrows a Rust string.
+/// WPI_Strings carry their length, so the string does not need to be NUL-terminated.
+impl From<&str> for WPI_String {
+    fn from(s: &str) -> Self {
+        Self {
+            str: s.as_ptr().cast(),
+            len: s.len(),
+        }
+    }
+}
+
+/// A string allocated by wpiutil that is freed with `WPI_FreeString` when dropped.
+///
+/// This should be used for every string that ntcore returns through an output parameter.
+pub struct WpiStringBuf {
+    inner: WPI_String,
+}
+
+impl WpiStringBuf {
+    /// Creates an empty string that can be passed to ntcore as an output parameter with [`Self::as_mut_ptr`].
+    pub const fn new() -> Self {
+        Self {
+            inner: WPI_String::empty(),
+        }
+    }
+
+    /// Takes ownership of a string allocated by wpiutil.
+    ///
+    /// # Safety
+    ///
+    /// `string` must have been allocated by wpiutil and must not be freed elsewhere.
+    pub unsafe fn from_raw(string: WPI_String) -> Self {
+        Self { inner: string }
+    }
+
+    /// Releases ownership of the string without freeing it.
+    pub fn into_raw(self) -> WPI_String {
+        let inner = self.inner;
+        std::mem::forget(self);
+        inner
+    }
+
+    pub fn as_ptr(&self) -> *const WPI_String {
+        &raw const self.inner
+    }
+
+    /// Returns a pointer that ntcore can write a newly allocated string to.
+    ///
+    /// Any string currently held is freed first, since ntcore overwrites output parameters without freeing them.
+    pub fn as_mut_ptr(&mut self) -> *mut WPI_String {
+        self.free();
+        &raw mut self.inner
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        // Safety: The string is owned by self, so it is valid for as long as self is.
+        unsafe { self.inner.as_bytes() }
+    }
+
+    pub fn to_str_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(self.as_bytes())
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.inner.len == 0
+    }
+
+    fn free(&mut self) {
+        if !self.inner.str.is_null() {
+            unsafe { WPI_FreeString(&raw const self.inner) };
+        }
+        self.inner = WPI_String::empty();
+    }
+}
+
+/// Copies a Rust string into a wpiutil allocated string.
+impl From<&str> for WpiStringBuf {
+    fn from(s: &str) -> Self {
+        let mut buf = Self::new();
+        unsafe {
+            let data = WPI_AllocateString(&raw mut buf.inner, s.len());
+            std::ptr::copy_nonoverlapping(s.as_ptr(), data.cast(), s.len());
+        }
+        buf
+    }
+}
+
+impl Default for WpiStringBuf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for WpiStringBuf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.to_str_lossy(), f)
+    }
+}
+
+impl Drop for WpiStringBuf {
+    fn drop(&mut self) {
+        self.free();
+    }
+}
+
+// Safety: The string is uniquely owned heap memory.
+unsafe impl Send for WpiStringBuf {}
+unsafe impl Sync for WpiStringBuf {}
@@ -0,0 +1,22 @@
+//! Bindings to wpinet utilities.
+
+use crate::WPI_String;
+
+extern "C" {
+    /// Forward a local TCP port to a remote host and port.
+    /// Note that local ports less than 1024 won't work as a normal user.
+    ///
+    /// # Parameters
+    ///
+    /// - `port`: Local port number.
+    /// - `remoteHost`: Remote IP address / DNS name.
+    /// - `remotePort`: Remote port number.
+    pub fn WPI_AddPortForwarder(port: u32, remoteHost: *const WPI_String, remotePort: u32);
+
+    /// Stop TCP forwarding on a port.
+    ///
+    /// # Parameters
+    ///
+    /// - `port`: Local port number.
+    pub fn WPI_RemovePortForwarder(port: u32);
+}
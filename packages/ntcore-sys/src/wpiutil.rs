@@ -0,0 +1,455 @@
+//! Bindings to the timestamp, synchronization and DataLog C APIs from wpiutil.
+
+use crate::{WPI_DataLog, WPI_String};
+
+/// Callback used by [`WPI_DataLog_CreateBackgroundWriter_Func`] to write log data.
+///
+/// # Parameters
+///
+/// - `ptr`: data pointer provided to the create function
+/// - `data`: data to write
+/// - `len`: length of `data` in bytes
+pub type WPI_DataLogWriteFunc = unsafe extern "C" fn(*mut std::ffi::c_void, *const u8, usize);
+
+/// Generic handle for all WPI handle-based interfaces.
+/// ntcore handles such as listener pollers can be waited on with [`WPI_WaitForObject`].
+pub type WPI_Handle = std::ffi::c_uint;
+
+/// Function returning the current time in microseconds, used by [`WPI_SetNowImpl`].
+pub type WPI_NowFunc = unsafe extern "C" fn() -> u64;
+
+extern "C" {
+    /// The default implementation used for `WPI_Now()`.
+    /// In general this is the time returned by the operating system's high resolution monotonic clock.
+    ///
+    /// # Returns
+    ///
+    /// Time in microseconds.
+    pub fn WPI_NowDefault() -> u64;
+
+    /// Sets the implementation used by `WPI_Now()`.
+    /// The intent is to use this for simulation or replay, where time should not follow the system clock.
+    ///
+    /// # Parameters
+    ///
+    /// - `func`: Function called by `WPI_Now()` to return the time, or null to restore the default.
+    pub fn WPI_SetNowImpl(func: Option<WPI_NowFunc>);
+
+    /// Returns a monotonic timestamp in microseconds.
+    ///
+    /// # Returns
+    ///
+    /// Time in microseconds.
+    pub fn WPI_Now() -> u64;
+
+    /// Waits for a handle to be signaled.
+    ///
+    /// # Parameters
+    ///
+    /// - `handle`: Handle to wait on.
+    ///
+    /// # Returns
+    ///
+    /// True if handle was signaled, false otherwise (e.g. object was destroyed).
+    pub fn WPI_WaitForObject(handle: WPI_Handle) -> std::ffi::c_int;
+
+    /// Waits for a handle to be signaled, with timeout.
+    ///
+    /// # Parameters
+    ///
+    /// - `handle`: Handle to wait on.
+    /// - `timeout`: Timeout in seconds.
+    /// - `timedOut`: Set to true if timeout reached without handle being signaled (output);
+    ///   set to false otherwise.
+    ///
+    /// # Returns
+    ///
+    /// True if handle was signaled, false otherwise (e.g. object was destroyed or timed out).
+    pub fn WPI_WaitForObjectTimeout(
+        handle: WPI_Handle,
+        timeout: f64,
+        timedOut: *mut std::ffi::c_int,
+    ) -> std::ffi::c_int;
+
+    /// Sets a handle's state to signaled, waking everything waiting on it.
+    ///
+    /// # Parameters
+    ///
+    /// - `handle`: Handle.
+    pub fn WPI_SetSignalObject(handle: WPI_Handle);
+}
+
+extern "C" {
+    /// Construct a new Data Log that writes to a file synchronously.
+    ///
+    /// # Parameters
+    ///
+    /// - `filename`: Filename to use.
+    /// - `errorCode`: Error code if file could not be opened (output).
+    /// - `extraHeader`: Extra header data.
+    ///
+    /// # Returns
+    ///
+    /// Data log. Must be released with `WPI_DataLog_Release()`.
+    pub fn WPI_DataLog_CreateWriter(
+        filename: *const WPI_String,
+        errorCode: *mut std::ffi::c_int,
+        extraHeader: *const WPI_String,
+    ) -> *mut WPI_DataLog;
+
+    /// Construct a new Data Log background writer. The log will be initially
+    /// created with a temporary filename.
+    ///
+    /// # Parameters
+    ///
+    /// - `dir`: Directory to store the log.
+    /// - `filename`: Filename to use; if none provided, a random filename is generated.
+    /// - `period`: Time between automatic flushes to disk, in seconds;
+    ///            this is a time/storage tradeoff.
+    /// - `extraHeader`: Extra header data.
+    ///
+    /// # Returns
+    ///
+    /// Data log. Must be released with `WPI_DataLog_Release()`.
+    pub fn WPI_DataLog_CreateBackgroundWriter(
+        dir: *const WPI_String,
+        filename: *const WPI_String,
+        period: f64,
+        extraHeader: *const WPI_String,
+    ) -> *mut WPI_DataLog;
+
+    /// Construct a new Data Log background writer that passes its output to the
+    /// provided function rather than a file.
+    ///
+    /// # Parameters
+    ///
+    /// - `period`: Time between automatic calls to `write`, in seconds;
+    ///            this is a time/storage tradeoff.
+    /// - `extraHeader`: Extra header data.
+    /// - `ptr`: Data pointer to pass to `write`.
+    /// - `write`: Write function. It is called with a zero length when the log is released.
+    ///
+    /// # Returns
+    ///
+    /// Data log. Must be released with `WPI_DataLog_Release()`.
+    pub fn WPI_DataLog_CreateBackgroundWriter_Func(
+        period: f64,
+        extraHeader: *const WPI_String,
+        ptr: *mut std::ffi::c_void,
+        write: WPI_DataLogWriteFunc,
+    ) -> *mut WPI_DataLog;
+
+    /// Releases a data log object. Closes the file and returns resources to the system.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    pub fn WPI_DataLog_Release(datalog: *mut WPI_DataLog);
+
+    /// Change log filename.
+    /// Only valid for background writers.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `filename`: Filename.
+    pub fn WPI_DataLog_SetFilename(datalog: *mut WPI_DataLog, filename: *const WPI_String);
+
+    /// Explicitly flushes the log data to disk.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    pub fn WPI_DataLog_Flush(datalog: *mut WPI_DataLog);
+
+    /// Pauses appending of data records to the log. While paused, no data records
+    /// are saved (e.g. AppendX is a no-op). Has no effect on entry starts / finishes /
+    /// metadata changes.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    pub fn WPI_DataLog_Pause(datalog: *mut WPI_DataLog);
+
+    /// Resumes appending of data records to the log. If called after Stop(),
+    /// opens a new file (with random name if SetFilename was not called after
+    /// Stop()) and appends Start records and schema data values for all previously
+    /// started entries and schemas.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    pub fn WPI_DataLog_Resume(datalog: *mut WPI_DataLog);
+
+    /// Stops appending all records to the log, and closes the log file.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    pub fn WPI_DataLog_Stop(datalog: *mut WPI_DataLog);
+
+    /// Start an entry. Duplicate names are allowed (with the same type), and
+    /// result in the same index being returned (Start/Finish are reference
+    /// counted). A duplicate name with a different type will result in an error
+    /// message being printed to the console and 0 being returned (which will be
+    /// ignored by the Append functions).
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `name`: Name.
+    /// - `type`: Data type.
+    /// - `metadata`: Initial metadata (e.g. data properties).
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    ///
+    /// # Returns
+    ///
+    /// Entry index.
+    pub fn WPI_DataLog_Start(
+        datalog: *mut WPI_DataLog,
+        name: *const WPI_String,
+        type_: *const WPI_String,
+        metadata: *const WPI_String,
+        timestamp: i64,
+    ) -> std::ffi::c_int;
+
+    /// Finish an entry.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `entry`: Entry index.
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    pub fn WPI_DataLog_Finish(datalog: *mut WPI_DataLog, entry: std::ffi::c_int, timestamp: i64);
+
+    /// Updates the metadata for an entry.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `entry`: Entry index.
+    /// - `metadata`: New metadata for the entry.
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    pub fn WPI_DataLog_SetMetadata(
+        datalog: *mut WPI_DataLog,
+        entry: std::ffi::c_int,
+        metadata: *const WPI_String,
+        timestamp: i64,
+    );
+
+    /// Appends a raw record to the log.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `entry`: Entry index, as returned by `WPI_DataLog_Start()`.
+    /// - `data`: Byte array to record.
+    /// - `len`: Length of byte array.
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    pub fn WPI_DataLog_AppendRaw(
+        datalog: *mut WPI_DataLog,
+        entry: std::ffi::c_int,
+        data: *const u8,
+        len: usize,
+        timestamp: i64,
+    );
+
+    /// Appends a boolean record to the log.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `entry`: Entry index, as returned by `WPI_DataLog_Start()`.
+    /// - `value`: Boolean value to record.
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    pub fn WPI_DataLog_AppendBoolean(
+        datalog: *mut WPI_DataLog,
+        entry: std::ffi::c_int,
+        value: std::ffi::c_int,
+        timestamp: i64,
+    );
+
+    /// Appends an integer record to the log.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `entry`: Entry index, as returned by `WPI_DataLog_Start()`.
+    /// - `value`: Integer value to record.
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    pub fn WPI_DataLog_AppendInteger(
+        datalog: *mut WPI_DataLog,
+        entry: std::ffi::c_int,
+        value: i64,
+        timestamp: i64,
+    );
+
+    /// Appends a float record to the log.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `entry`: Entry index, as returned by `WPI_DataLog_Start()`.
+    /// - `value`: Float value to record.
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    pub fn WPI_DataLog_AppendFloat(
+        datalog: *mut WPI_DataLog,
+        entry: std::ffi::c_int,
+        value: f32,
+        timestamp: i64,
+    );
+
+    /// Appends a double record to the log.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `entry`: Entry index, as returned by `WPI_DataLog_Start()`.
+    /// - `value`: Double value to record.
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    pub fn WPI_DataLog_AppendDouble(
+        datalog: *mut WPI_DataLog,
+        entry: std::ffi::c_int,
+        value: f64,
+        timestamp: i64,
+    );
+
+    /// Appends a string record to the log.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `entry`: Entry index, as returned by `WPI_DataLog_Start()`.
+    /// - `value`: String value to record.
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    pub fn WPI_DataLog_AppendString(
+        datalog: *mut WPI_DataLog,
+        entry: std::ffi::c_int,
+        value: *const WPI_String,
+        timestamp: i64,
+    );
+
+    /// Appends a boolean array record to the log.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `entry`: Entry index, as returned by `WPI_DataLog_Start()`.
+    /// - `arr`: Boolean array to record.
+    /// - `len`: Number of elements in array.
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    pub fn WPI_DataLog_AppendBooleanArray(
+        datalog: *mut WPI_DataLog,
+        entry: std::ffi::c_int,
+        arr: *const std::ffi::c_int,
+        len: usize,
+        timestamp: i64,
+    );
+
+    /// Appends an integer array record to the log.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `entry`: Entry index, as returned by `WPI_DataLog_Start()`.
+    /// - `arr`: Integer array to record.
+    /// - `len`: Number of elements in array.
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    pub fn WPI_DataLog_AppendIntegerArray(
+        datalog: *mut WPI_DataLog,
+        entry: std::ffi::c_int,
+        arr: *const i64,
+        len: usize,
+        timestamp: i64,
+    );
+
+    /// Appends a float array record to the log.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `entry`: Entry index, as returned by `WPI_DataLog_Start()`.
+    /// - `arr`: Float array to record.
+    /// - `len`: Number of elements in array.
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    pub fn WPI_DataLog_AppendFloatArray(
+        datalog: *mut WPI_DataLog,
+        entry: std::ffi::c_int,
+        arr: *const f32,
+        len: usize,
+        timestamp: i64,
+    );
+
+    /// Appends a double array record to the log.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `entry`: Entry index, as returned by `WPI_DataLog_Start()`.
+    /// - `arr`: Double array to record.
+    /// - `len`: Number of elements in array.
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    pub fn WPI_DataLog_AppendDoubleArray(
+        datalog: *mut WPI_DataLog,
+        entry: std::ffi::c_int,
+        arr: *const f64,
+        len: usize,
+        timestamp: i64,
+    );
+
+    /// Appends a string array record to the log.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `entry`: Entry index, as returned by `WPI_DataLog_Start()`.
+    /// - `arr`: String array to record.
+    /// - `len`: Number of elements in array.
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    pub fn WPI_DataLog_AppendStringArray(
+        datalog: *mut WPI_DataLog,
+        entry: std::ffi::c_int,
+        arr: *const WPI_String,
+        len: usize,
+        timestamp: i64,
+    );
+
+    /// Registers a data schema. Data schemas provide information for how a
+    /// certain data type string can be decoded. Duplicate calls to this function
+    /// with the same name are silently ignored.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `name`: Name (the string passed as the data type for records using this schema).
+    /// - `type`: Type of schema (e.g. "protobuf", "struct", etc).
+    /// - `schema`: Schema data.
+    /// - `schema_len`: Length of schema data.
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    pub fn WPI_DataLog_AddSchema(
+        datalog: *mut WPI_DataLog,
+        name: *const WPI_String,
+        type_: *const WPI_String,
+        schema: *const u8,
+        schema_len: usize,
+        timestamp: i64,
+    );
+
+    /// Registers a data schema whose data is a string.
+    /// See `WPI_DataLog_AddSchema()`.
+    ///
+    /// # Parameters
+    ///
+    /// - `datalog`: Data log.
+    /// - `name`: Name (the string passed as the data type for records using this schema).
+    /// - `type`: Type of schema (e.g. "protobuf", "struct", etc).
+    /// - `schema`: Schema data.
+    /// - `timestamp`: Time stamp (may be 0 to indicate now).
+    pub fn WPI_DataLog_AddSchemaString(
+        datalog: *mut WPI_DataLog,
+        name: *const WPI_String,
+        type_: *const WPI_String,
+        schema: *const WPI_String,
+        timestamp: i64,
+    );
+}
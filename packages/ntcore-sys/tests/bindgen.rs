@@ -0,0 +1,401 @@
+//! Compares the hand-written bindings against bindings generated by bindgen from the vendored
+//! ntcore headers, catching signature drift when the allwpilib submodule is updated.
+//!
+//! Run with `cargo test -p ntcore-sys --features bindgen`.
+#![cfg(feature = "bindgen")]
+
+use std::{any::type_name, mem};
+
+use ntcore_sys::{generated, probe::OptionalFunctions};
+
+#[derive(Debug)]
+struct TypeInfo {
+    name: &'static str,
+    size: usize,
+    align: usize,
+}
+impl TypeInfo {
+    fn of<T>() -> Self {
+        Self {
+            name: type_name::<T>(),
+            size: mem::size_of::<T>(),
+            align: mem::align_of::<T>(),
+        }
+    }
+
+    /// Strips module paths so that `ntcore_sys::WPI_String` and `ntcore_sys::generated::WPI_String` compare equal.
+    fn normalized_name(&self) -> String {
+        self.name
+            .split(' ')
+            .map(|token| token.rsplit("::").next().unwrap())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn is_primitive(&self) -> bool {
+        const PRIMITIVES: &[&str] = &[
+            "()", "bool", "i8", "u8", "i16", "u16", "i32", "u32", "i64", "u64", "isize", "usize",
+            "f32", "f64", "c_void",
+        ];
+        let name = self.normalized_name();
+        let pointee = name
+            .trim_start_matches("*const ")
+            .trim_start_matches("*mut ");
+        PRIMITIVES.contains(&pointee)
+    }
+
+    /// Types with the same name must match exactly, primitives must match by name
+    /// (catching e.g. `isize` vs `usize`), and everything else must at least have the same layout.
+    fn is_compatible_with(&self, other: &Self) -> bool {
+        if self.normalized_name() == other.normalized_name() {
+            true
+        } else if self.is_primitive() && other.is_primitive() {
+            false
+        } else {
+            self.size == other.size && self.align == other.align
+        }
+    }
+}
+
+trait Signature {
+    fn signature() -> Vec<TypeInfo>;
+}
+
+macro_rules! impl_signature {
+    ($($arg:ident),*) => {
+        impl<R, $($arg),*> Signature for unsafe extern "C" fn($($arg),*) -> R {
+            fn signature() -> Vec<TypeInfo> {
+                vec![TypeInfo::of::<R>() $(, TypeInfo::of::<$arg>())*]
+            }
+        }
+    };
+}
+impl_signature!();
+impl_signature!(A);
+impl_signature!(A, B);
+impl_signature!(A, B, C);
+impl_signature!(A, B, C, D);
+impl_signature!(A, B, C, D, E);
+impl_signature!(A, B, C, D, E, F);
+
+fn signature_of<F: Signature>(_: F) -> Vec<TypeInfo> {
+    F::signature()
+}
+
+/// The signature of a field of [`OptionalFunctions`], selected by `field`.
+fn optional_signature_of<F: Signature>(
+    _field: fn(&OptionalFunctions) -> Option<F>,
+) -> Vec<TypeInfo> {
+    F::signature()
+}
+
+macro_rules! compare_functions {
+    {$($name:ident($($arg:tt),*)),* $(,)?} => {{
+        let mut mismatches = Vec::new();
+        $(
+            let hand_written = signature_of(ntcore_sys::$name as unsafe extern "C" fn($($arg),*) -> _);
+            let generated = signature_of(generated::$name as unsafe extern "C" fn($($arg),*) -> _);
+            let compatible = hand_written.len() == generated.len()
+                && hand_written.iter().zip(&generated).all(|(a, b)| a.is_compatible_with(b));
+            if !compatible {
+                mismatches.push(format!("{}:\n  hand-written: {:?}\n  generated: {:?}", stringify!($name), hand_written, generated));
+            }
+        )*
+        mismatches
+    }};
+}
+
+macro_rules! compare_optional_functions {
+    {$($name:ident($($arg:tt),*)),* $(,)?} => {{
+        let mut mismatches = Vec::new();
+        $(
+            let hand_written = optional_signature_of(|functions| functions.$name);
+            let generated = signature_of(generated::$name as unsafe extern "C" fn($($arg),*) -> _);
+            let compatible = hand_written.len() == generated.len()
+                && hand_written.iter().zip(&generated).all(|(a, b)| a.is_compatible_with(b));
+            if !compatible {
+                mismatches.push(format!("{}:\n  hand-written: {:?}\n  generated: {:?}", stringify!($name), hand_written, generated));
+            }
+        )*
+        mismatches
+    }};
+}
+
+macro_rules! compare_layouts {
+    {$($name:ident),* $(,)?} => {{
+        let mut mismatches = Vec::new();
+        $(
+            let hand_written = TypeInfo::of::<ntcore_sys::$name>();
+            let generated = TypeInfo::of::<generated::$name>();
+            if hand_written.size != generated.size || hand_written.align != generated.align {
+                mismatches.push(format!("{}:\n  hand-written: {:?}\n  generated: {:?}", stringify!($name), hand_written, generated));
+            }
+        )*
+        mismatches
+    }};
+}
+
+#[test]
+fn function_signatures_match_headers() {
+    let mismatches = compare_functions! {
+        bits(_),
+        NT_GetDefaultInstance(),
+        NT_CreateInstance(),
+        NT_DestroyInstance(_),
+        NT_GetInstanceFromHandle(_),
+        NT_GetEntry(_, _),
+        NT_GetEntryName(_, _),
+        NT_GetEntryType(_),
+        NT_GetEntryLastChange(_),
+        NT_GetEntryValue(_, _),
+        NT_SetDefaultEntryValue(_, _),
+        NT_SetEntryValue(_, _),
+        NT_SetEntryFlags(_, _),
+        NT_GetEntryFlags(_),
+        NT_ReadQueueValue(_, _),
+        NT_GetTopics(_, _, _, _),
+        NT_GetTopicsStr(_, _, _, _, _),
+        NT_GetTopicInfos(_, _, _, _),
+        NT_GetTopicInfosStr(_, _, _, _, _),
+        NT_GetTopicInfo(_, _),
+        NT_GetTopic(_, _),
+        NT_GetTopicName(_, _),
+        NT_GetTopicType(_),
+        NT_GetTopicTypeString(_, _),
+        NT_SetTopicPersistent(_, _),
+        NT_GetTopicPersistent(_),
+        NT_SetTopicRetained(_, _),
+        NT_GetTopicRetained(_),
+        NT_GetTopicExists(_),
+        NT_GetTopicProperty(_, _, _),
+        NT_SetTopicProperty(_, _, _),
+        NT_DeleteTopicProperty(_, _),
+        NT_GetTopicProperties(_, _),
+        NT_SetTopicProperties(_, _),
+        NT_Subscribe(_, _, _, _),
+        NT_Unsubscribe(_),
+        NT_Publish(_, _, _, _),
+        NT_PublishEx(_, _, _, _, _),
+        NT_Unpublish(_),
+        NT_GetEntryEx(_, _, _, _),
+        NT_ReleaseEntry(_),
+        NT_Release(_),
+        NT_GetTopicFromHandle(_),
+        NT_SubscribeMultiple(_, _, _, _),
+        NT_UnsubscribeMultiple(_),
+        NT_CreateListenerPoller(_),
+        NT_DestroyListenerPoller(_),
+        NT_ReadListenerQueue(_, _),
+        NT_RemoveListener(_),
+        NT_WaitForListenerQueue(_, _),
+        NT_AddListenerSingle(_, _, _, _, _),
+        NT_AddListenerMultiple(_, _, _, _, _, _),
+        NT_AddListener(_, _, _, _),
+        NT_AddPolledListenerSingle(_, _, _),
+        NT_AddPolledListenerMultiple(_, _, _, _),
+        NT_AddPolledListener(_, _, _),
+        NT_StartLocal(_),
+        NT_StopLocal(_),
+        NT_StartServer(_, _, _, _, _),
+        NT_StopServer(_),
+        NT_StartClient3(_, _),
+        NT_StartClient4(_, _),
+        NT_StopClient(_),
+        NT_SetServer(_, _, _),
+        NT_SetServerMulti(_, _, _, _),
+        NT_SetServerTeam(_, _, _),
+        NT_Disconnect(_),
+        NT_StartDSClient(_, _),
+        NT_StopDSClient(_),
+        NT_FlushLocal(_),
+        NT_Flush(_),
+        NT_GetConnections(_, _),
+        NT_IsConnected(_),
+        NT_GetServerTimeOffset(_, _),
+        NT_DisposeValue(_),
+        NT_InitValue(_),
+        NT_DisposeValueArray(_, _),
+        NT_DisposeConnectionInfoArray(_, _),
+        NT_DisposeTopicInfoArray(_, _),
+        NT_DisposeTopicInfo(_),
+        NT_DisposeEventArray(_, _),
+        NT_DisposeEvent(_),
+        NT_Now(),
+        NT_SetNow(_),
+        NT_StartEntryDataLog(_, _, _, _),
+        NT_StopEntryDataLog(_),
+        NT_StartConnectionDataLog(_, _, _),
+        NT_StopConnectionDataLog(_),
+        NT_AddLogger(_, _, _, _, _),
+        NT_AddPolledLogger(_, _, _),
+        NT_AllocateCharArray(_),
+        NT_AllocateBooleanArray(_),
+        NT_AllocateIntegerArray(_),
+        NT_AllocateFloatArray(_),
+        NT_AllocateDoubleArray(_),
+        NT_FreeCharArray(_),
+        NT_FreeBooleanArray(_),
+        NT_FreeIntegerArray(_),
+        NT_FreeFloatArray(_),
+        NT_FreeDoubleArray(_),
+        NT_GetValueType(_),
+        NT_GetValueBoolean(_, _, _),
+        NT_GetValueInteger(_, _, _),
+        NT_GetValueFloat(_, _, _),
+        NT_GetValueDouble(_, _, _),
+        NT_GetValueString(_, _, _),
+        NT_GetValueRaw(_, _, _),
+        NT_GetValueBooleanArray(_, _, _),
+        NT_GetValueIntegerArray(_, _, _),
+        NT_GetValueFloatArray(_, _, _),
+        NT_GetValueDoubleArray(_, _, _),
+        NT_GetValueStringArray(_, _, _),
+        NT_SetBoolean(_, _, _),
+        NT_SetDefaultBoolean(_, _),
+        NT_GetBoolean(_, _),
+        NT_GetAtomicBoolean(_, _, _),
+        NT_DisposeTimestampedBoolean(_),
+        NT_ReadQueueBoolean(_, _),
+        NT_FreeQueueBoolean(_, _),
+        NT_ReadQueueValuesBoolean(_, _),
+        NT_SetInteger(_, _, _),
+        NT_SetDefaultInteger(_, _),
+        NT_GetInteger(_, _),
+        NT_GetAtomicInteger(_, _, _),
+        NT_DisposeTimestampedInteger(_),
+        NT_ReadQueueInteger(_, _),
+        NT_FreeQueueInteger(_, _),
+        NT_ReadQueueValuesInteger(_, _),
+        NT_SetFloat(_, _, _),
+        NT_SetDefaultFloat(_, _),
+        NT_GetFloat(_, _),
+        NT_GetAtomicFloat(_, _, _),
+        NT_DisposeTimestampedFloat(_),
+        NT_ReadQueueFloat(_, _),
+        NT_FreeQueueFloat(_, _),
+        NT_ReadQueueValuesFloat(_, _),
+        NT_SetDouble(_, _, _),
+        NT_SetDefaultDouble(_, _),
+        NT_GetDouble(_, _),
+        NT_GetAtomicDouble(_, _, _),
+        NT_DisposeTimestampedDouble(_),
+        NT_ReadQueueDouble(_, _),
+        NT_FreeQueueDouble(_, _),
+        NT_ReadQueueValuesDouble(_, _),
+        NT_SetString(_, _, _),
+        NT_SetDefaultString(_, _),
+        NT_GetString(_, _, _),
+        NT_GetAtomicString(_, _, _),
+        NT_DisposeTimestampedString(_),
+        NT_ReadQueueString(_, _),
+        NT_FreeQueueString(_, _),
+        NT_SetRaw(_, _, _, _),
+        NT_SetDefaultRaw(_, _, _),
+        NT_GetRaw(_, _, _, _),
+        NT_GetAtomicRaw(_, _, _, _),
+        NT_DisposeTimestampedRaw(_),
+        NT_ReadQueueRaw(_, _),
+        NT_FreeQueueRaw(_, _),
+        NT_SetBooleanArray(_, _, _, _),
+        NT_SetDefaultBooleanArray(_, _, _),
+        NT_GetBooleanArray(_, _, _, _),
+        NT_GetAtomicBooleanArray(_, _, _, _),
+        NT_DisposeTimestampedBooleanArray(_),
+        NT_ReadQueueBooleanArray(_, _),
+        NT_FreeQueueBooleanArray(_, _),
+        NT_SetIntegerArray(_, _, _, _),
+        NT_SetDefaultIntegerArray(_, _, _),
+        NT_GetIntegerArray(_, _, _, _),
+        NT_GetAtomicIntegerArray(_, _, _, _),
+        NT_DisposeTimestampedIntegerArray(_),
+        NT_ReadQueueIntegerArray(_, _),
+        NT_FreeQueueIntegerArray(_, _),
+        NT_SetFloatArray(_, _, _, _),
+        NT_SetDefaultFloatArray(_, _, _),
+        NT_GetFloatArray(_, _, _, _),
+        NT_GetAtomicFloatArray(_, _, _, _),
+        NT_DisposeTimestampedFloatArray(_),
+        NT_ReadQueueFloatArray(_, _),
+        NT_FreeQueueFloatArray(_, _),
+        NT_SetDoubleArray(_, _, _, _),
+        NT_SetDefaultDoubleArray(_, _, _),
+        NT_GetDoubleArray(_, _, _, _),
+        NT_GetAtomicDoubleArray(_, _, _, _),
+        NT_DisposeTimestampedDoubleArray(_),
+        NT_ReadQueueDoubleArray(_, _),
+        NT_FreeQueueDoubleArray(_, _),
+        NT_SetStringArray(_, _, _, _),
+        NT_SetDefaultStringArray(_, _, _),
+        NT_GetStringArray(_, _, _, _),
+        NT_GetAtomicStringArray(_, _, _, _),
+        NT_DisposeTimestampedStringArray(_),
+        NT_ReadQueueStringArray(_, _),
+        NT_FreeQueueStringArray(_, _),
+        NT_Meta_DecodeTopicPublishers(_, _, _),
+        NT_Meta_DecodeTopicSubscribers(_, _, _),
+        NT_Meta_DecodeClientPublishers(_, _, _),
+        NT_Meta_DecodeClientSubscribers(_, _, _),
+        NT_Meta_DecodeClients(_, _, _),
+        NT_Meta_FreeTopicPublishers(_, _),
+        NT_Meta_FreeTopicSubscribers(_, _),
+        NT_Meta_FreeClientPublishers(_, _),
+        NT_Meta_FreeClientSubscribers(_, _),
+        NT_Meta_FreeClients(_, _),
+        WPI_AllocateString(_, _),
+        WPI_FreeString(_),
+        WPI_AllocateStringArray(_),
+        WPI_FreeStringArray(_, _),
+    };
+
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+}
+
+#[test]
+fn optional_function_signatures_match_headers() {
+    let mismatches = compare_optional_functions! {
+        NT_SetTopicCached(_, _),
+        NT_GetTopicCached(_),
+        NT_GetEntryValueType(_, _, _),
+        NT_ReadQueueValueType(_, _, _),
+        NT_HasSchema(_, _),
+        NT_AddSchema(_, _, _, _, _),
+    };
+
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+}
+
+#[test]
+fn struct_layouts_match_headers() {
+    let mismatches = compare_layouts! {
+        WPI_String,
+        NT_EventFlags,
+        NT_EntryFlags,
+        NT_Value,
+        NT_TopicInfo,
+        NT_ConnectionInfo,
+        NT_ValueEventData,
+        NT_LogMessage,
+        NT_TimeSyncEventData,
+        NT_Event,
+        NT_PubSubOptions,
+        NT_TimestampedBoolean,
+        NT_TimestampedInteger,
+        NT_TimestampedFloat,
+        NT_TimestampedDouble,
+        NT_TimestampedString,
+        NT_TimestampedRaw,
+        NT_TimestampedBooleanArray,
+        NT_TimestampedIntegerArray,
+        NT_TimestampedFloatArray,
+        NT_TimestampedDoubleArray,
+        NT_TimestampedStringArray,
+        NT_Meta_SubscriberOptions,
+        NT_Meta_TopicPublisher,
+        NT_Meta_TopicSubscriber,
+        NT_Meta_ClientPublisher,
+        NT_Meta_ClientSubscriber,
+        NT_Meta_Client,
+    };
+
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+}